@@ -1,5 +1,136 @@
+#[doc(hidden)]
+pub mod __private {
+    pub use http::Method;
+    use vetis::{
+        errors::VetisError,
+        server::virtual_host::{
+            path::{HandlerPath, HandlerPathBuilder, HostPath},
+            Handler,
+        },
+    };
+
+    /// Routes collected by the `http!` macro, grouped by path.
+    #[derive(Default)]
+    pub struct Routes {
+        paths: Vec<(String, HandlerPathBuilder)>,
+    }
+
+    impl Routes {
+        pub fn route(mut self, method: Method, uri: &str, handler: impl Handler + 'static) -> Self {
+            match self
+                .paths
+                .iter_mut()
+                .find(|(path, _)| path == uri)
+            {
+                Some((_, builder)) => {
+                    let current = std::mem::replace(builder, HandlerPath::builder());
+                    *builder = current.method(method, handler);
+                }
+                None => self.paths.push((
+                    uri.to_string(),
+                    HandlerPath::builder()
+                        .uri(uri)
+                        .method(method, handler),
+                )),
+            }
+
+            self
+        }
+
+        pub fn into_paths(self) -> Result<Vec<HostPath>, VetisError> {
+            self.paths
+                .into_iter()
+                .map(|(_, builder)| builder.build())
+                .collect()
+        }
+    }
+}
+
+/// Turns the method name of an `http!` route into an `http::Method`, rejecting unknown names.
+///
+/// ```compile_fail
+/// let method = vetis_macros::__method!(GTE);
+/// ```
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __method {
+    (GET) => {
+        $crate::__private::Method::GET
+    };
+    (HEAD) => {
+        $crate::__private::Method::HEAD
+    };
+    (POST) => {
+        $crate::__private::Method::POST
+    };
+    (PUT) => {
+        $crate::__private::Method::PUT
+    };
+    (PATCH) => {
+        $crate::__private::Method::PATCH
+    };
+    (DELETE) => {
+        $crate::__private::Method::DELETE
+    };
+    (OPTIONS) => {
+        $crate::__private::Method::OPTIONS
+    };
+    (TRACE) => {
+        $crate::__private::Method::TRACE
+    };
+    (CONNECT) => {
+        $crate::__private::Method::CONNECT
+    };
+    ($method:ident) => {
+        compile_error!(concat!("Unknown HTTP method: ", stringify!($method)))
+    };
+}
+
 #[macro_export]
 macro_rules! http {
+    (hostname => $hostname:expr, root_directory => $root_directory:expr, port => $port:expr, interface => $interface:expr, routes => [$(($method:ident, $uri:expr, $handler:expr)),* $(,)?]) => {
+        async move {
+            use vetis::{
+                config::server::{virtual_host::VirtualHostConfig, ListenerConfig, ServerConfig},
+                errors::VetisError,
+                server::virtual_host::VirtualHost,
+                Vetis,
+            };
+
+            let listener = ListenerConfig::builder()
+                .port($port)
+                .interface($interface)
+                .build()?;
+
+            let config = ServerConfig::builder()
+                .add_listener(listener)
+                .build()?;
+
+            let virtual_host_config = VirtualHostConfig::builder()
+                .hostname($hostname)
+                .root_directory($root_directory)
+                .port($port)
+                .build()?;
+
+            let mut virtual_host = VirtualHost::new(virtual_host_config);
+
+            let routes = $crate::__private::Routes::default()
+                $(.route($crate::__method!($method), $uri, $handler))*;
+
+            for path in routes.into_paths()? {
+                virtual_host.add_path(path);
+            }
+
+            let mut vetis = Vetis::new(config);
+
+            vetis
+                .add_virtual_host(virtual_host)
+                .await;
+
+            Ok::<Vetis, VetisError>(vetis)
+        }
+    };
+
     (hostname => $hostname:expr, root_directory => $root_directory:expr, port => $port:expr, interface => $interface:expr, handler => $handler:ident) => {
         async move {
             use vetis::{
@@ -88,49 +219,55 @@ macro_rules! http {
 
 #[macro_export]
 macro_rules! https {
-    (hostname => &$hostname:ident, port => &$port:ident, interface => &$interface:ident, &cert => &$cert:ident, &key => &$key:ident) => {
-        use vetis::{
-            config::{ListenerConfig, ServerConfig, VirtualHostConfig},
-            errors::{ConfigError, VetisError},
-            server::{path::HandlerPath, virtual_host::VirtualHost},
-            Vetis,
-        };
-
-        let listener = ListenerConfig::builder()
-            .port($port)
-            .interface($interface)
-            .build()?;
-
-        let config = ServerConfig::builder()
-            .add_listener(listener)
-            .build()?;
-
-        let security_config = SecurityConfig::builder()
-            .cert_from_file($cert)
-            .key_from_file($key)
-            .build()?;
-
-        let virtual_host_config = VirtualHostConfig::builder()
-            .hostname($hostname)
-            .port($port)
-            .security(security_config)
-            .build()?;
-
-        let mut virtual_host = VirtualHost::new(virtual_host_config);
-
-        let root_path = HandlerPath::builder()
-            .uri("/")
-            .handler(Box::new($handler))
-            .build()?;
-
-        virtual_host.add_path(root_path);
-
-        let mut vetis = Vetis::new(config);
-
-        vetis
-            .add_virtual_host(virtual_host)
-            .await;
-
-        Ok::<Vetis, VetisError>(vetis)
+    (hostname => $hostname:expr, root_directory => $root_directory:expr, port => $port:expr, interface => $interface:expr, cert => $cert:expr, key => $key:expr, handler => $handler:expr) => {
+        async move {
+            use vetis::{
+                config::server::{
+                    virtual_host::{SecurityConfig, VirtualHostConfig},
+                    ListenerConfig, ServerConfig,
+                },
+                errors::VetisError,
+                server::virtual_host::{path::HandlerPath, VirtualHost},
+                Vetis,
+            };
+
+            let listener = ListenerConfig::builder()
+                .port($port)
+                .interface($interface)
+                .build()?;
+
+            let config = ServerConfig::builder()
+                .add_listener(listener)
+                .build()?;
+
+            let security_config = SecurityConfig::builder()
+                .cert_from_file($cert)
+                .key_from_file($key)
+                .build()?;
+
+            let virtual_host_config = VirtualHostConfig::builder()
+                .hostname($hostname)
+                .root_directory($root_directory)
+                .port($port)
+                .security(security_config)
+                .build()?;
+
+            let mut virtual_host = VirtualHost::new(virtual_host_config);
+
+            let root_path = HandlerPath::builder()
+                .uri("/")
+                .handler($handler)
+                .build()?;
+
+            virtual_host.add_path(root_path);
+
+            let mut vetis = Vetis::new(config);
+
+            vetis
+                .add_virtual_host(virtual_host)
+                .await;
+
+            Ok::<Vetis, VetisError>(vetis)
+        }
     };
 }
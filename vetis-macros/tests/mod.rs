@@ -1,6 +1,11 @@
-use deboa::request::get;
+use deboa::{
+    cert::{Certificate, ContentEncoding},
+    errors::{DeboaError, ResponseError},
+    request::{get, post},
+};
+use http::StatusCode;
 use vetis::server::virtual_host::handler_fn;
-use vetis_macros::http;
+use vetis_macros::{http, https};
 
 #[tokio::test]
 async fn test_http() -> Result<(), Box<dyn std::error::Error>> {
@@ -41,3 +46,113 @@ async fn test_http() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_http_routes() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = http!(
+        hostname => "localhost",
+        root_directory => "src",
+        port => 9135,
+        interface => "127.0.0.1",
+        routes => [
+            (GET, "/a", handler_fn(|_req| async move {
+                Ok(vetis::server::http::Response::builder().text("GET /a"))
+            })),
+            (POST, "/b", handler_fn(|_req| async move {
+                Ok(vetis::server::http::Response::builder().text("POST /b"))
+            })),
+        ]
+    )
+    .await?;
+
+    server
+        .start()
+        .await?;
+
+    let client = deboa::Client::default();
+
+    let response = get("http://localhost:9135/a")?
+        .send_with(&client)
+        .await?;
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response
+            .text()
+            .await?,
+        "GET /a"
+    );
+
+    let response = post("http://localhost:9135/b")?
+        .send_with(&client)
+        .await?;
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response
+            .text()
+            .await?,
+        "POST /b"
+    );
+
+    // Methods without a route are rejected
+    let response = get("http://localhost:9135/b")?
+        .send_with(&client)
+        .await;
+    assert!(matches!(
+        response.err(),
+        Some(DeboaError::Response(ResponseError::Receive { status_code, .. }))
+            if status_code == StatusCode::METHOD_NOT_ALLOWED
+    ));
+
+    server
+        .stop()
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_https() -> Result<(), Box<dyn std::error::Error>> {
+    let handler = handler_fn(|_req| async move {
+        Ok(vetis::server::http::Response::builder().text("Hello, TLS!"))
+    });
+
+    let mut server = https!(
+        hostname => "localhost",
+        root_directory => "src",
+        port => 9136,
+        interface => "127.0.0.1",
+        cert => "../vetis/src/tests/certs/server.der",
+        key => "../vetis/src/tests/certs/server.key.der",
+        handler => handler
+    )
+    .await?;
+
+    server
+        .start()
+        .await?;
+
+    let client = deboa::Client::builder()
+        .certificate(Certificate::from_slice(
+            include_bytes!("../../vetis/src/tests/certs/ca.der"),
+            ContentEncoding::DER,
+        ))
+        .build();
+
+    let response = get("https://localhost:9136")?
+        .send_with(&client)
+        .await?;
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response
+            .text()
+            .await?,
+        "Hello, TLS!"
+    );
+
+    server
+        .stop()
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,208 @@
+//! Runtime configuration for VeTiS server.
+//!
+//! Controls how many threads drive the async runtime and how many may be
+//! spawned for blocking work, such as the filesystem access done when
+//! serving static files.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! use vetis::config::runtime::RuntimeConfig;
+//!
+//! let runtime = RuntimeConfig::builder()
+//!     .worker_threads(4)
+//!     .max_blocking_threads(64)
+//!     .build()?;
+//!
+//! runtime.block_on(async {
+//!     // Create and run the server...
+//! })?;
+//! ```
+
+use std::future::Future;
+
+use serde::Deserialize;
+
+use crate::errors::{ConfigError, VetisError};
+
+/// Builder for creating `RuntimeConfig` instances.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use vetis::config::runtime::RuntimeConfig;
+///
+/// let config = RuntimeConfig::builder()
+///     .worker_threads(4)
+///     .build()?;
+/// ```
+#[derive(Clone, Default, Deserialize)]
+pub struct RuntimeConfigBuilder {
+    worker_threads: Option<usize>,
+    max_blocking_threads: Option<usize>,
+}
+
+impl RuntimeConfigBuilder {
+    /// Sets the number of worker threads driving the runtime.
+    ///
+    /// Defaults to the number of CPU cores. On Smol this sets `SMOL_THREADS`.
+    ///
+    /// # Arguments
+    ///
+    /// * `worker_threads` - The number of worker threads
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn worker_threads(mut self, worker_threads: usize) -> Self {
+        self.worker_threads = Some(worker_threads);
+        self
+    }
+
+    /// Sets the maximum number of threads spawned for blocking work.
+    ///
+    /// Defaults to the runtime's own limit. On Smol this sets `BLOCKING_MAX_THREADS`.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_blocking_threads` - The maximum number of blocking threads
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn max_blocking_threads(mut self, max_blocking_threads: usize) -> Self {
+        self.max_blocking_threads = Some(max_blocking_threads);
+        self
+    }
+
+    /// Creates the `RuntimeConfig` with the configured settings.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<RuntimeConfig, ConfigError>` - The runtime configuration.
+    pub fn build(self) -> Result<RuntimeConfig, ConfigError> {
+        if self.worker_threads == Some(0) {
+            return Err(ConfigError::Runtime("Worker threads cannot be 0".to_string()));
+        }
+
+        if self.max_blocking_threads == Some(0) {
+            return Err(ConfigError::Runtime("Max blocking threads cannot be 0".to_string()));
+        }
+
+        Ok(RuntimeConfig {
+            worker_threads: self.worker_threads,
+            max_blocking_threads: self.max_blocking_threads,
+        })
+    }
+}
+
+/// Configuration for the async runtime running the server.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use vetis::config::runtime::RuntimeConfig;
+///
+/// let config = RuntimeConfig::builder()
+///     .worker_threads(2)
+///     .max_blocking_threads(16)
+///     .build()?;
+///
+/// assert_eq!(config.worker_threads(), Some(2));
+/// ```
+#[derive(Clone, Default, Deserialize)]
+#[serde(try_from = "RuntimeConfigBuilder")]
+pub struct RuntimeConfig {
+    worker_threads: Option<usize>,
+    max_blocking_threads: Option<usize>,
+}
+
+impl TryFrom<RuntimeConfigBuilder> for RuntimeConfig {
+    type Error = ConfigError;
+
+    fn try_from(builder: RuntimeConfigBuilder) -> Result<Self, Self::Error> {
+        builder.build()
+    }
+}
+
+impl RuntimeConfig {
+    /// Creates a new `RuntimeConfigBuilder` with default settings.
+    ///
+    /// Default values:
+    /// - worker_threads: None (one per CPU core)
+    /// - max_blocking_threads: None (runtime default)
+    ///
+    /// # Returns
+    ///
+    /// * `RuntimeConfigBuilder` - The builder.
+    pub fn builder() -> RuntimeConfigBuilder {
+        RuntimeConfigBuilder::default()
+    }
+
+    /// Returns the number of worker threads, if set.
+    pub fn worker_threads(&self) -> Option<usize> {
+        self.worker_threads
+    }
+
+    /// Returns the maximum number of blocking threads, if set.
+    pub fn max_blocking_threads(&self) -> Option<usize> {
+        self.max_blocking_threads
+    }
+
+    /// Builds a multi-threaded Tokio runtime with the configured thread pools.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<tokio::runtime::Runtime, VetisError>` - The runtime.
+    #[cfg(feature = "tokio-rt")]
+    pub fn tokio_runtime(&self) -> Result<tokio::runtime::Runtime, VetisError> {
+        let mut builder = tokio::runtime::Builder::new_multi_thread();
+        builder.enable_all();
+
+        if let Some(worker_threads) = self.worker_threads {
+            builder.worker_threads(worker_threads);
+        }
+
+        if let Some(max_blocking_threads) = self.max_blocking_threads {
+            builder.max_blocking_threads(max_blocking_threads);
+        }
+
+        Ok(builder.build()?)
+    }
+
+    /// Runs a future to completion on a runtime built from this configuration.
+    ///
+    /// On Tokio a dedicated multi-threaded runtime is built. On Smol the
+    /// thread counts are exported to the global executor and blocking pool,
+    /// which only read them when first used, so this must be called before
+    /// anything is spawned.
+    ///
+    /// # Arguments
+    ///
+    /// * `future` - The future to run, usually creating and running the server
+    ///
+    /// # Returns
+    ///
+    /// * `Result<F::Output, VetisError>` - The output of the future.
+    pub fn block_on<F: Future>(&self, future: F) -> Result<F::Output, VetisError> {
+        #[cfg(feature = "tokio-rt")]
+        {
+            Ok(self
+                .tokio_runtime()?
+                .block_on(future))
+        }
+
+        #[cfg(feature = "smol-rt")]
+        {
+            if let Some(worker_threads) = self.worker_threads {
+                std::env::set_var("SMOL_THREADS", worker_threads.to_string());
+            }
+
+            if let Some(max_blocking_threads) = self.max_blocking_threads {
+                std::env::set_var("BLOCKING_MAX_THREADS", max_blocking_threads.to_string());
+            }
+
+            Ok(smol::block_on(future))
+        }
+    }
+}
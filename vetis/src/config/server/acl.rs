@@ -0,0 +1,251 @@
+//! Access control lists for listeners.
+
+use std::{net::IpAddr, str::FromStr};
+
+use serde::Deserialize;
+
+use crate::errors::ConfigError;
+
+/// An IPv4 or IPv6 network in CIDR notation, such as `10.0.0.0/8` or `::1/128`.
+///
+/// A bare address is treated as a single host network.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use vetis::config::server::acl::Cidr;
+///
+/// let cidr: Cidr = "192.168.0.0/16".parse()?;
+/// assert!(cidr.contains("192.168.1.10".parse()?));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    /// Returns the network address.
+    pub fn network(&self) -> IpAddr {
+        self.network
+    }
+
+    /// Returns the prefix length.
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    /// Checks whether the given address belongs to this network.
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - The address to check.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the address is inside the network.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = ConfigError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (address, prefix) = match value.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (value, None),
+        };
+
+        let network = address
+            .parse::<IpAddr>()
+            .map_err(|_| ConfigError::Acl(format!("Invalid network address: {}", value)))?;
+
+        let max_prefix = if network.is_ipv4() { 32 } else { 128 };
+
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix| *prefix <= max_prefix)
+                .ok_or_else(|| ConfigError::Acl(format!("Invalid prefix length: {}", value)))?,
+            None => max_prefix,
+        };
+
+        Ok(Cidr { network, prefix })
+    }
+}
+
+impl TryFrom<String> for Cidr {
+    type Error = ConfigError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Builder for creating `AclConfig` instances.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use vetis::config::server::acl::AclConfig;
+///
+/// let acl = AclConfig::builder()
+///     .allow("127.0.0.1/32")
+///     .deny("0.0.0.0/0")
+///     .build()?;
+/// ```
+#[derive(Clone, Default)]
+pub struct AclConfigBuilder {
+    allow: Vec<String>,
+    deny: Vec<String>,
+}
+
+impl AclConfigBuilder {
+    /// Adds a network whose clients are always allowed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::server::acl::AclConfig;
+    ///
+    /// let acl = AclConfig::builder()
+    ///     .allow("10.0.0.0/8")
+    ///     .build()?;
+    /// ```
+    pub fn allow(mut self, cidr: &str) -> Self {
+        self.allow
+            .push(cidr.to_string());
+        self
+    }
+
+    /// Adds a network whose clients are denied, unless also allowed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::server::acl::AclConfig;
+    ///
+    /// let acl = AclConfig::builder()
+    ///     .deny("192.168.0.0/16")
+    ///     .build()?;
+    /// ```
+    pub fn deny(mut self, cidr: &str) -> Self {
+        self.deny
+            .push(cidr.to_string());
+        self
+    }
+
+    /// Creates the `AclConfig`, parsing every configured network.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any network is not valid CIDR notation.
+    pub fn build(self) -> Result<AclConfig, ConfigError> {
+        let allow = self
+            .allow
+            .iter()
+            .map(|cidr| cidr.parse())
+            .collect::<Result<Vec<Cidr>, _>>()?;
+
+        let deny = self
+            .deny
+            .iter()
+            .map(|cidr| cidr.parse())
+            .collect::<Result<Vec<Cidr>, _>>()?;
+
+        Ok(AclConfig { allow, deny })
+    }
+}
+
+/// Client address allow and deny lists.
+///
+/// A client matching an `allow` entry is always accepted. Otherwise it is
+/// rejected when it matches a `deny` entry, or when the allow list is not
+/// empty.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use vetis::config::server::acl::AclConfig;
+///
+/// let acl = AclConfig::builder()
+///     .allow("127.0.0.1/32")
+///     .deny("0.0.0.0/0")
+///     .build()?;
+///
+/// assert!(acl.is_allowed("127.0.0.1".parse()?));
+/// assert!(!acl.is_allowed("10.0.0.1".parse()?));
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct AclConfig {
+    #[serde(default)]
+    allow: Vec<Cidr>,
+    #[serde(default)]
+    deny: Vec<Cidr>,
+}
+
+impl AclConfig {
+    /// Creates a new `AclConfigBuilder` with empty allow and deny lists.
+    pub fn builder() -> AclConfigBuilder {
+        AclConfigBuilder::default()
+    }
+
+    /// Returns the allowed networks.
+    pub fn allow(&self) -> &[Cidr] {
+        &self.allow
+    }
+
+    /// Returns the denied networks.
+    pub fn deny(&self) -> &[Cidr] {
+        &self.deny
+    }
+
+    /// Checks whether a client address may connect.
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - The client address.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the client is allowed.
+    pub fn is_allowed(&self, ip: IpAddr) -> bool {
+        if self
+            .allow
+            .iter()
+            .any(|cidr| cidr.contains(ip))
+        {
+            return true;
+        }
+
+        if !self
+            .allow
+            .is_empty()
+        {
+            return false;
+        }
+
+        !self
+            .deny
+            .iter()
+            .any(|cidr| cidr.contains(ip))
+    }
+}
@@ -0,0 +1,144 @@
+//! Settings of the internal admin listener.
+
+use serde::Deserialize;
+
+use crate::{
+    config::server::{ListenerConfig, Protocol},
+    errors::ConfigError,
+};
+
+fn default_interface() -> String {
+    "127.0.0.1".to_string()
+}
+
+/// Builder for creating `AdminConfig` instances.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use vetis::config::server::admin::AdminConfig;
+///
+/// let config = AdminConfig::builder()
+///     .port(9900)
+///     .build()?;
+/// ```
+#[derive(Clone)]
+pub struct AdminConfigBuilder {
+    port: Option<u16>,
+    interface: String,
+}
+
+impl AdminConfigBuilder {
+    /// Sets the port the admin listener binds to.
+    ///
+    /// The port must not be used by any other listener.
+    ///
+    /// # Arguments
+    ///
+    /// * `port` - The admin port
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// Sets the network interface the admin listener binds to.
+    ///
+    /// # Arguments
+    ///
+    /// * `interface` - The interface address, `127.0.0.1` by default
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn interface(mut self, interface: &str) -> Self {
+        self.interface = interface.to_string();
+        self
+    }
+
+    /// Creates the `AdminConfig` with the configured settings.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<AdminConfig, ConfigError>` - The admin configuration.
+    pub fn build(self) -> Result<AdminConfig, ConfigError> {
+        let port = match self.port {
+            Some(0) => return Err(ConfigError::Listener("Admin port cannot be 0".to_string())),
+            Some(port) => port,
+            None => return Err(ConfigError::Listener("Admin port is required".to_string())),
+        };
+
+        if self
+            .interface
+            .is_empty()
+        {
+            return Err(ConfigError::Listener("Admin interface cannot be empty".to_string()));
+        }
+
+        Ok(AdminConfig { port, interface: self.interface })
+    }
+}
+
+/// Internal control listener serving JSON introspection endpoints.
+///
+/// The admin listener speaks plain HTTP/1.1 on its own port and only binds
+/// to localhost unless another interface is configured. It serves:
+///
+/// - `GET /admin/vhosts` - The registered virtual hosts and their paths
+/// - `GET /admin/metrics` - Connection and request counts of every listener
+/// - `POST /admin/shutdown` - Asks [`Vetis::run`](crate::Vetis::run) to drain and stop the server
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use vetis::config::{server::admin::AdminConfig, ListenerConfig, ServerConfig};
+///
+/// let config = ServerConfig::builder()
+///     .add_listener(ListenerConfig::builder().port(8443).build()?)
+///     .admin(AdminConfig::builder().port(9900).build()?)
+///     .build()?;
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct AdminConfig {
+    port: u16,
+    #[serde(default = "default_interface")]
+    interface: String,
+}
+
+impl AdminConfig {
+    /// Creates a new `AdminConfigBuilder` with default settings.
+    ///
+    /// Default values:
+    /// - port: None (required)
+    /// - interface: "127.0.0.1"
+    ///
+    /// # Returns
+    ///
+    /// * `AdminConfigBuilder` - The builder.
+    pub fn builder() -> AdminConfigBuilder {
+        AdminConfigBuilder { port: None, interface: default_interface() }
+    }
+
+    /// Returns the admin port.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Returns the network interface.
+    pub fn interface(&self) -> &str {
+        &self.interface
+    }
+
+    /// Returns the configuration of the listener serving the admin endpoints.
+    pub(crate) fn listener(&self) -> Result<ListenerConfig, ConfigError> {
+        ListenerConfig::builder()
+            .name("admin")
+            .port(self.port)
+            .protocol(Protocol::Http1)
+            .interface(&self.interface)
+            .build()
+    }
+}
@@ -0,0 +1,158 @@
+//! QUIC transport settings for HTTP/3 listeners.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::errors::ConfigError;
+
+/// Builder for creating `Http3Config` instances.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use std::time::Duration;
+/// use vetis::config::server::http3::Http3Config;
+///
+/// let config = Http3Config::builder()
+///     .max_idle_timeout(Duration::from_secs(30))
+///     .max_concurrent_bidi_streams(100)
+///     .build()?;
+/// ```
+#[derive(Clone, Default)]
+pub struct Http3ConfigBuilder {
+    max_idle_timeout: Option<u64>,
+    max_concurrent_bidi_streams: Option<u32>,
+    receive_window: Option<u32>,
+}
+
+impl Http3ConfigBuilder {
+    /// Sets how long a connection may stay idle before it is closed.
+    ///
+    /// The effective timeout is the lowest of the values announced by both peers.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_idle_timeout` - The idle timeout, with millisecond precision
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn max_idle_timeout(mut self, max_idle_timeout: Duration) -> Self {
+        self.max_idle_timeout = Some(max_idle_timeout.as_millis() as u64);
+        self
+    }
+
+    /// Sets how many requests a client may have in flight on one connection.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_concurrent_bidi_streams` - The maximum number of concurrent bidirectional streams
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn max_concurrent_bidi_streams(mut self, max_concurrent_bidi_streams: u32) -> Self {
+        self.max_concurrent_bidi_streams = Some(max_concurrent_bidi_streams);
+        self
+    }
+
+    /// Sets how much data a client may send on a connection before it is acknowledged.
+    ///
+    /// # Arguments
+    ///
+    /// * `receive_window` - The connection receive window, in bytes
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn receive_window(mut self, receive_window: u32) -> Self {
+        self.receive_window = Some(receive_window);
+        self
+    }
+
+    /// Creates the `Http3Config` with the configured settings.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Http3Config, ConfigError>` - The HTTP/3 configuration.
+    pub fn build(self) -> Result<Http3Config, ConfigError> {
+        if self.max_idle_timeout == Some(0) {
+            return Err(ConfigError::Listener("Max idle timeout cannot be 0".to_string()));
+        }
+
+        if self.max_concurrent_bidi_streams == Some(0) {
+            return Err(ConfigError::Listener(
+                "Max concurrent bidi streams cannot be 0".to_string(),
+            ));
+        }
+
+        if self.receive_window == Some(0) {
+            return Err(ConfigError::Listener("Receive window cannot be 0".to_string()));
+        }
+
+        Ok(Http3Config {
+            max_idle_timeout: self.max_idle_timeout,
+            max_concurrent_bidi_streams: self.max_concurrent_bidi_streams,
+            receive_window: self.receive_window,
+        })
+    }
+}
+
+/// QUIC transport settings applied to the connections of an HTTP/3 listener.
+///
+/// Settings left unset keep the QUIC library defaults.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use std::time::Duration;
+/// use vetis::config::{server::http3::Http3Config, ListenerConfig, Protocol};
+///
+/// let http3 = Http3Config::builder()
+///     .max_idle_timeout(Duration::from_secs(10))
+///     .build()?;
+///
+/// let config = ListenerConfig::builder()
+///     .protocol(Protocol::Http3)
+///     .http3(http3)
+///     .build()?;
+/// ```
+#[derive(Clone, Default, Deserialize)]
+pub struct Http3Config {
+    max_idle_timeout: Option<u64>,
+    max_concurrent_bidi_streams: Option<u32>,
+    receive_window: Option<u32>,
+}
+
+impl Http3Config {
+    /// Creates a new `Http3ConfigBuilder` with default settings.
+    ///
+    /// Default values:
+    /// - max_idle_timeout: None (30 seconds)
+    /// - max_concurrent_bidi_streams: None (100)
+    /// - receive_window: None (unlimited)
+    ///
+    /// # Returns
+    ///
+    /// * `Http3ConfigBuilder` - The builder.
+    pub fn builder() -> Http3ConfigBuilder {
+        Http3ConfigBuilder::default()
+    }
+
+    /// Returns the idle timeout, if set.
+    pub fn max_idle_timeout(&self) -> Option<Duration> {
+        self.max_idle_timeout
+            .map(Duration::from_millis)
+    }
+
+    /// Returns the maximum number of concurrent bidirectional streams, if set.
+    pub fn max_concurrent_bidi_streams(&self) -> Option<u32> {
+        self.max_concurrent_bidi_streams
+    }
+
+    /// Returns the connection receive window, if set.
+    pub fn receive_window(&self) -> Option<u32> {
+        self.receive_window
+    }
+}
@@ -38,10 +38,25 @@
 //!     .build()?;
 //! ```
 
+#[cfg(unix)]
+use std::os::fd::{AsFd, BorrowedFd, OwnedFd};
+#[cfg(unix)]
+use std::sync::Arc;
+use std::time::Duration;
+
 use serde::Deserialize;
 
-use crate::errors::ConfigError;
+#[cfg(feature = "admin")]
+use crate::config::server::admin::AdminConfig;
+use crate::{
+    config::server::{acl::AclConfig, http3::Http3Config},
+    errors::ConfigError,
+};
 
+pub mod acl;
+#[cfg(feature = "admin")]
+pub mod admin;
+pub mod http3;
 pub mod virtual_host;
 
 /// Supported HTTP protocols.
@@ -74,6 +89,33 @@ pub enum Protocol {
     Http3,
 }
 
+/// How an HTTP/1 listener answers requests framed by both `content-length` and `transfer-encoding`.
+///
+/// Such requests are how request smuggling works: a proxy in front of the
+/// server may read the body with one header and the server with the other.
+/// Requests with conflicting `content-length` values are rejected either way.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum AmbiguousLengthPolicy {
+    /// Answer `400 Bad Request` and close the connection
+    #[default]
+    Reject,
+    /// Read the body by its `transfer-encoding` and drop `content-length`, as RFC 9112 permits
+    PreferTransferEncoding,
+}
+
+/// What happens to listeners once the last virtual host on their port is removed.
+///
+/// Hosts are removed at runtime with
+/// [`Vetis::remove_virtual_host`](crate::Vetis::remove_virtual_host).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum EmptyHostsPolicy {
+    /// Keep accepting connections, answering every request with `404 Not Found`
+    #[default]
+    NotFound,
+    /// Stop the listeners on the port, so connections are refused
+    StopListeners,
+}
+
 /// Builder for creating `ListenerConfig` instances.
 ///
 /// Provides a fluent API for configuring server listeners.
@@ -89,11 +131,33 @@ pub enum Protocol {
 ///     .interface("127.0.0.1")
 ///     .build();
 /// ```
-#[derive(Clone)]
+#[derive(Clone, Deserialize)]
 pub struct ListenerConfigBuilder {
     port: u16,
     protocol: Protocol,
     interface: String,
+    max_concurrent_connections: Option<usize>,
+    max_headers: Option<usize>,
+    max_header_bytes: Option<usize>,
+    acl: Option<AclConfig>,
+    http3: Option<Http3Config>,
+    name: Option<String>,
+    tcp_keepalive: Option<u64>,
+    tcp_keepalive_interval: Option<u64>,
+    tcp_keepalive_retries: Option<u32>,
+    #[serde(default = "default_tcp_nodelay")]
+    tcp_nodelay: bool,
+    #[serde(default = "default_http1_pipelining")]
+    http1_pipelining: bool,
+    log_sample_rate: Option<u64>,
+    #[serde(default)]
+    ambiguous_length: AmbiguousLengthPolicy,
+    #[cfg(unix)]
+    #[serde(skip)]
+    fd: Option<Arc<OwnedFd>>,
+    #[cfg(feature = "proxy-protocol")]
+    #[serde(default)]
+    proxy_protocol: bool,
 }
 
 impl ListenerConfigBuilder {
@@ -151,6 +215,305 @@ impl ListenerConfigBuilder {
         self
     }
 
+    /// Sets the maximum number of concurrent connections for this listener.
+    ///
+    /// New connections beyond this limit are refused: plaintext HTTP/1
+    /// connections with a `503 Service Unavailable`, others by closing them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::ListenerConfig;
+    ///
+    /// let config = ListenerConfig::builder()
+    ///     .max_concurrent_connections(1024)
+    ///     .build();
+    /// ```
+    pub fn max_concurrent_connections(mut self, max_concurrent_connections: usize) -> Self {
+        self.max_concurrent_connections = Some(max_concurrent_connections);
+        self
+    }
+
+    /// Sets the maximum number of headers a request may carry.
+    ///
+    /// Requests with more headers are answered with
+    /// `431 Request Header Fields Too Large`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::ListenerConfig;
+    ///
+    /// let config = ListenerConfig::builder()
+    ///     .max_headers(64)
+    ///     .build();
+    /// ```
+    pub fn max_headers(mut self, max_headers: usize) -> Self {
+        self.max_headers = Some(max_headers);
+        self
+    }
+
+    /// Sets the maximum total size of the request headers, in bytes.
+    ///
+    /// The size of each header is its name length plus its value length.
+    /// Requests with larger headers are answered with
+    /// `431 Request Header Fields Too Large`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::ListenerConfig;
+    ///
+    /// let config = ListenerConfig::builder()
+    ///     .max_header_bytes(16 * 1024)
+    ///     .build();
+    /// ```
+    pub fn max_header_bytes(mut self, max_header_bytes: usize) -> Self {
+        self.max_header_bytes = Some(max_header_bytes);
+        self
+    }
+
+    /// Sets the access control list checked for every new connection.
+    ///
+    /// Connections from denied clients are closed right after being accepted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::{server::acl::AclConfig, ListenerConfig};
+    ///
+    /// let acl = AclConfig::builder()
+    ///     .allow("127.0.0.1/32")
+    ///     .deny("0.0.0.0/0")
+    ///     .build()?;
+    ///
+    /// let config = ListenerConfig::builder()
+    ///     .acl(acl)
+    ///     .build();
+    /// ```
+    pub fn acl(mut self, acl: AclConfig) -> Self {
+        self.acl = Some(acl);
+        self
+    }
+
+    /// Sets the QUIC transport settings used when the protocol is HTTP/3.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use std::time::Duration;
+    /// use vetis::config::{server::http3::Http3Config, ListenerConfig, Protocol};
+    ///
+    /// let http3 = Http3Config::builder()
+    ///     .max_idle_timeout(Duration::from_secs(10))
+    ///     .build()?;
+    ///
+    /// let config = ListenerConfig::builder()
+    ///     .protocol(Protocol::Http3)
+    ///     .http3(http3)
+    ///     .build();
+    /// ```
+    pub fn http3(mut self, http3: Http3Config) -> Self {
+        self.http3 = Some(http3);
+        self
+    }
+
+    /// Sets a name telling this listener apart in logs.
+    ///
+    /// The name is shown in the startup log line and replaces the `%listener`
+    /// placeholder of access log formats.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::ListenerConfig;
+    ///
+    /// let config = ListenerConfig::builder()
+    ///     .port(9090)
+    ///     .name("admin")
+    ///     .build();
+    /// ```
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Enables TCP keepalive on accepted connections.
+    ///
+    /// Probes are sent once a connection has been idle for `idle`, so dead
+    /// peers are detected and NAT or firewall mappings are kept open.
+    /// Disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use std::time::Duration;
+    /// use vetis::config::ListenerConfig;
+    ///
+    /// let config = ListenerConfig::builder()
+    ///     .tcp_keepalive(Duration::from_secs(60))
+    ///     .tcp_keepalive_interval(Duration::from_secs(10))
+    ///     .tcp_keepalive_retries(5)
+    ///     .build();
+    /// ```
+    pub fn tcp_keepalive(mut self, idle: Duration) -> Self {
+        self.tcp_keepalive = Some(idle.as_millis() as u64);
+        self
+    }
+
+    /// Sets the time between TCP keepalive probes, once enabled with `tcp_keepalive`.
+    ///
+    /// Most platforms only honor whole seconds.
+    pub fn tcp_keepalive_interval(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive_interval = Some(interval.as_millis() as u64);
+        self
+    }
+
+    /// Sets how many unanswered TCP keepalive probes close a connection.
+    pub fn tcp_keepalive_retries(mut self, retries: u32) -> Self {
+        self.tcp_keepalive_retries = Some(retries);
+        self
+    }
+
+    /// Sets whether accepted connections disable Nagle's algorithm.
+    ///
+    /// Delay-free small writes suit request/response traffic; leaving Nagle
+    /// on can reduce packet overhead for bulk transfers. Enabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::ListenerConfig;
+    ///
+    /// let config = ListenerConfig::builder()
+    ///     .port(8080)
+    ///     .tcp_nodelay(false)
+    ///     .build();
+    /// ```
+    pub fn tcp_nodelay(mut self, tcp_nodelay: bool) -> Self {
+        self.tcp_nodelay = tcp_nodelay;
+        self
+    }
+
+    /// Sets whether HTTP/1.1 connections serve more than one request.
+    ///
+    /// Pipelined requests are always answered in the order they were sent,
+    /// one at a time. When disabled, every response carries
+    /// `connection: close` and requests pipelined behind it are discarded,
+    /// for handlers that can't tolerate another request on the same connection.
+    /// Enabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::ListenerConfig;
+    ///
+    /// let config = ListenerConfig::builder()
+    ///     .port(8080)
+    ///     .http1_pipelining(false)
+    ///     .build();
+    /// ```
+    pub fn http1_pipelining(mut self, http1_pipelining: bool) -> Self {
+        self.http1_pipelining = http1_pipelining;
+        self
+    }
+
+    /// Logs only one in every `log_sample_rate` requests served by the listener.
+    ///
+    /// Applies to the access log of every virtual host behind the listener.
+    /// Server errors (5xx) are always logged. Disabled by default, logging
+    /// every request.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::ListenerConfig;
+    ///
+    /// let config = ListenerConfig::builder()
+    ///     .port(8080)
+    ///     .log_sample_rate(100)
+    ///     .build();
+    /// ```
+    pub fn log_sample_rate(mut self, log_sample_rate: u64) -> Self {
+        self.log_sample_rate = Some(log_sample_rate);
+        self
+    }
+
+    /// Sets how requests with both `content-length` and `transfer-encoding` are answered.
+    ///
+    /// Rejected with `400 Bad Request` by default, which is the safe choice
+    /// behind proxies that may frame the request differently.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::{AmbiguousLengthPolicy, ListenerConfig};
+    ///
+    /// let config = ListenerConfig::builder()
+    ///     .port(8080)
+    ///     .ambiguous_length(AmbiguousLengthPolicy::PreferTransferEncoding)
+    ///     .build();
+    /// ```
+    pub fn ambiguous_length(mut self, ambiguous_length: AmbiguousLengthPolicy) -> Self {
+        self.ambiguous_length = ambiguous_length;
+        self
+    }
+
+    #[cfg(unix)]
+    /// Serves connections accepted on a socket already bound by a supervisor.
+    ///
+    /// With systemd socket activation, or when another process hands its
+    /// socket over for a zero-downtime restart, the listener accepts on this
+    /// file descriptor instead of binding `interface` and `port`. The port
+    /// must still match the one the socket is bound to, it selects the
+    /// virtual hosts. Only TCP listeners support it.
+    ///
+    /// The configuration owns the descriptor and closes it once the last
+    /// copy is dropped. It is duplicated when the listener starts, so the
+    /// server can be restarted on it. Config files can't set it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use std::os::fd::{FromRawFd, OwnedFd};
+    ///
+    /// use vetis::config::ListenerConfig;
+    ///
+    /// // SAFETY: systemd passes the first socket as descriptor 3, owned by this process
+    /// let socket = unsafe { OwnedFd::from_raw_fd(3) };
+    /// let config = ListenerConfig::builder()
+    ///     .port(443)
+    ///     .fd(socket)
+    ///     .build();
+    /// ```
+    pub fn fd(mut self, fd: OwnedFd) -> Self {
+        self.fd = Some(Arc::new(fd));
+        self
+    }
+
+    #[cfg(feature = "proxy-protocol")]
+    /// Expects a PROXY protocol header, v1 or v2, at the start of every TCP connection.
+    ///
+    /// Enable it when the listener sits behind an L4 load balancer, such as
+    /// HAProxy or AWS NLB, so requests are attributed to the client address
+    /// carried by the header instead of the load balancer's. Connections
+    /// without a valid header are closed. Disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::ListenerConfig;
+    ///
+    /// let config = ListenerConfig::builder()
+    ///     .port(8080)
+    ///     .proxy_protocol(true)
+    ///     .build();
+    /// ```
+    pub fn proxy_protocol(mut self, proxy_protocol: bool) -> Self {
+        self.proxy_protocol = proxy_protocol;
+        self
+    }
+
     /// Creates the `ListenerConfig` with the configured settings.
     pub fn build(self) -> Result<ListenerConfig, ConfigError> {
         if self.port == 0 {
@@ -164,7 +527,88 @@ impl ListenerConfigBuilder {
             return Err(ConfigError::Listener("Interface cannot be empty".to_string()));
         }
 
-        Ok(ListenerConfig { port: self.port, protocol: self.protocol, interface: self.interface })
+        if self.max_concurrent_connections == Some(0) {
+            return Err(ConfigError::Listener(
+                "Max concurrent connections cannot be 0".to_string(),
+            ));
+        }
+
+        if self.max_headers == Some(0) {
+            return Err(ConfigError::Listener("Max headers cannot be 0".to_string()));
+        }
+
+        if self.max_header_bytes == Some(0) {
+            return Err(ConfigError::Listener("Max header bytes cannot be 0".to_string()));
+        }
+
+        if self
+            .name
+            .as_ref()
+            .is_some_and(|name| name.is_empty())
+        {
+            return Err(ConfigError::Listener("Name cannot be empty".to_string()));
+        }
+
+        if self.tcp_keepalive == Some(0) {
+            return Err(ConfigError::Listener("TCP keepalive cannot be 0".to_string()));
+        }
+
+        if self.tcp_keepalive_interval == Some(0) {
+            return Err(ConfigError::Listener("TCP keepalive interval cannot be 0".to_string()));
+        }
+
+        if self.tcp_keepalive_retries == Some(0) {
+            return Err(ConfigError::Listener("TCP keepalive retries cannot be 0".to_string()));
+        }
+
+        if self.log_sample_rate == Some(0) {
+            return Err(ConfigError::Listener("Log sample rate cannot be 0".to_string()));
+        }
+
+        #[cfg(all(unix, feature = "http3"))]
+        if self.fd.is_some() && self.protocol == Protocol::Http3 {
+            return Err(ConfigError::Listener(
+                "A socket file descriptor requires a TCP protocol".to_string(),
+            ));
+        }
+
+        if self
+            .tcp_keepalive
+            .is_none()
+            && (self
+                .tcp_keepalive_interval
+                .is_some()
+                || self
+                    .tcp_keepalive_retries
+                    .is_some())
+        {
+            return Err(ConfigError::Listener(
+                "TCP keepalive interval and retries require tcp_keepalive".to_string(),
+            ));
+        }
+
+        Ok(ListenerConfig {
+            port: self.port,
+            protocol: self.protocol,
+            interface: self.interface,
+            max_concurrent_connections: self.max_concurrent_connections,
+            max_headers: self.max_headers,
+            max_header_bytes: self.max_header_bytes,
+            acl: self.acl,
+            http3: self.http3,
+            name: self.name,
+            tcp_keepalive: self.tcp_keepalive,
+            tcp_keepalive_interval: self.tcp_keepalive_interval,
+            tcp_keepalive_retries: self.tcp_keepalive_retries,
+            tcp_nodelay: self.tcp_nodelay,
+            http1_pipelining: self.http1_pipelining,
+            log_sample_rate: self.log_sample_rate,
+            ambiguous_length: self.ambiguous_length,
+            #[cfg(unix)]
+            fd: self.fd,
+            #[cfg(feature = "proxy-protocol")]
+            proxy_protocol: self.proxy_protocol,
+        })
     }
 }
 
@@ -187,10 +631,44 @@ impl ListenerConfigBuilder {
 /// println!("Listening on port {}", config.port());
 /// ```
 #[derive(Clone, Deserialize)]
+#[serde(try_from = "ListenerConfigBuilder")]
 pub struct ListenerConfig {
     port: u16,
     protocol: Protocol,
     interface: String,
+    max_concurrent_connections: Option<usize>,
+    max_headers: Option<usize>,
+    max_header_bytes: Option<usize>,
+    acl: Option<AclConfig>,
+    http3: Option<Http3Config>,
+    name: Option<String>,
+    tcp_keepalive: Option<u64>,
+    tcp_keepalive_interval: Option<u64>,
+    tcp_keepalive_retries: Option<u32>,
+    tcp_nodelay: bool,
+    http1_pipelining: bool,
+    log_sample_rate: Option<u64>,
+    ambiguous_length: AmbiguousLengthPolicy,
+    #[cfg(unix)]
+    fd: Option<Arc<OwnedFd>>,
+    #[cfg(feature = "proxy-protocol")]
+    proxy_protocol: bool,
+}
+
+impl TryFrom<ListenerConfigBuilder> for ListenerConfig {
+    type Error = ConfigError;
+
+    fn try_from(builder: ListenerConfigBuilder) -> Result<Self, Self::Error> {
+        builder.build()
+    }
+}
+
+fn default_tcp_nodelay() -> bool {
+    true
+}
+
+fn default_http1_pipelining() -> bool {
+    true
 }
 
 impl ListenerConfig {
@@ -201,6 +679,21 @@ impl ListenerConfig {
     /// - ssl: false
     /// - protocol: HTTP1 (if available)
     /// - interface: "0.0.0.0"
+    /// - max_concurrent_connections: None (unlimited)
+    /// - max_headers: None (100 on HTTP/1)
+    /// - max_header_bytes: None (unlimited)
+    /// - acl: None (all clients allowed)
+    /// - http3: None (QUIC defaults)
+    /// - name: None
+    /// - tcp_keepalive: None (disabled)
+    /// - tcp_keepalive_interval: None (system default)
+    /// - tcp_keepalive_retries: None (system default)
+    /// - tcp_nodelay: true
+    /// - http1_pipelining: true
+    /// - log_sample_rate: None (every request logged)
+    /// - ambiguous_length: Reject
+    /// - fd: None (binds interface and port)
+    /// - proxy_protocol: false
     ///
     /// # Examples
     ///
@@ -211,7 +704,59 @@ impl ListenerConfig {
     /// let config = builder.port(8080).build();
     /// ```
     pub fn builder() -> ListenerConfigBuilder {
-        ListenerConfigBuilder { port: 80, protocol: Protocol::Http1, interface: "0.0.0.0".into() }
+        ListenerConfigBuilder {
+            port: 80,
+            protocol: Protocol::Http1,
+            interface: "0.0.0.0".into(),
+            max_concurrent_connections: None,
+            max_headers: None,
+            max_header_bytes: None,
+            acl: None,
+            http3: None,
+            name: None,
+            tcp_keepalive: None,
+            tcp_keepalive_interval: None,
+            tcp_keepalive_retries: None,
+            tcp_nodelay: true,
+            http1_pipelining: true,
+            log_sample_rate: None,
+            ambiguous_length: AmbiguousLengthPolicy::Reject,
+            #[cfg(unix)]
+            fd: None,
+            #[cfg(feature = "proxy-protocol")]
+            proxy_protocol: false,
+        }
+    }
+
+    #[cfg(unix)]
+    /// Creates a `ListenerConfigBuilder` serving connections accepted on an already bound listener.
+    ///
+    /// The interface and port are taken from the address the listener is
+    /// bound to, and the listener is kept open by the configuration. See
+    /// [`ListenerConfigBuilder::fd`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::ListenerConfig;
+    ///
+    /// let listener = std::net::TcpListener::bind("127.0.0.1:8080")?;
+    /// let config = ListenerConfig::from_std(listener)?
+    ///     .build()?;
+    /// ```
+    pub fn from_std(listener: std::net::TcpListener) -> Result<ListenerConfigBuilder, ConfigError> {
+        let addr = listener
+            .local_addr()
+            .map_err(|e| ConfigError::Listener(format!("Cannot read listener address: {}", e)))?;
+
+        Ok(ListenerConfig::builder()
+            .interface(
+                &addr
+                    .ip()
+                    .to_string(),
+            )
+            .port(addr.port())
+            .fd(OwnedFd::from(listener)))
     }
 
     /// Returns the port number.
@@ -228,6 +773,87 @@ impl ListenerConfig {
     pub fn interface(&self) -> &str {
         &self.interface
     }
+
+    /// Returns the maximum number of concurrent connections, if limited.
+    pub fn max_concurrent_connections(&self) -> Option<usize> {
+        self.max_concurrent_connections
+    }
+
+    /// Returns the maximum number of request headers, if limited.
+    pub fn max_headers(&self) -> Option<usize> {
+        self.max_headers
+    }
+
+    /// Returns the maximum total size of the request headers, if limited.
+    pub fn max_header_bytes(&self) -> Option<usize> {
+        self.max_header_bytes
+    }
+
+    /// Returns the access control list, if any.
+    pub fn acl(&self) -> &Option<AclConfig> {
+        &self.acl
+    }
+
+    /// Returns the QUIC transport settings, if any.
+    pub fn http3(&self) -> &Option<Http3Config> {
+        &self.http3
+    }
+
+    /// Returns the listener name, if any.
+    pub fn name(&self) -> &Option<String> {
+        &self.name
+    }
+
+    /// Returns how long a connection stays idle before TCP keepalive probes are sent, if enabled.
+    pub fn tcp_keepalive(&self) -> Option<Duration> {
+        self.tcp_keepalive
+            .map(Duration::from_millis)
+    }
+
+    /// Returns the time between TCP keepalive probes, if set.
+    pub fn tcp_keepalive_interval(&self) -> Option<Duration> {
+        self.tcp_keepalive_interval
+            .map(Duration::from_millis)
+    }
+
+    /// Returns how many unanswered TCP keepalive probes close a connection, if set.
+    pub fn tcp_keepalive_retries(&self) -> Option<u32> {
+        self.tcp_keepalive_retries
+    }
+
+    /// Returns whether accepted connections disable Nagle's algorithm.
+    pub fn tcp_nodelay(&self) -> bool {
+        self.tcp_nodelay
+    }
+
+    /// Returns whether HTTP/1.1 connections serve more than one request.
+    pub fn http1_pipelining(&self) -> bool {
+        self.http1_pipelining
+    }
+
+    /// Returns how many requests are served per access log line, if sampled.
+    pub fn log_sample_rate(&self) -> Option<u64> {
+        self.log_sample_rate
+    }
+
+    /// Returns how requests with both `content-length` and `transfer-encoding` are answered.
+    pub fn ambiguous_length(&self) -> AmbiguousLengthPolicy {
+        self.ambiguous_length
+    }
+
+    #[cfg(unix)]
+    /// Returns the file descriptor of the socket already bound for the listener, if any.
+    pub fn fd(&self) -> Option<BorrowedFd<'_>> {
+        self.fd
+            .as_ref()
+            .map(|fd| fd.as_fd())
+    }
+
+    #[cfg(feature = "proxy-protocol")]
+    /// Returns whether TCP connections start with a PROXY protocol header.
+    pub fn proxy_protocol(&self) -> bool {
+        self.proxy_protocol
+    }
 }
 
 /// Builder for creating `ServerConfig` instances.
@@ -258,6 +884,10 @@ impl ListenerConfig {
 #[derive(Clone)]
 pub struct ServerConfigBuilder {
     listeners: Vec<ListenerConfig>,
+    max_buffered_bytes: Option<usize>,
+    empty_hosts: EmptyHostsPolicy,
+    #[cfg(feature = "admin")]
+    admin: Option<AdminConfig>,
 }
 
 impl ServerConfigBuilder {
@@ -282,6 +912,65 @@ impl ServerConfigBuilder {
         self
     }
 
+    /// Sets how many bytes of request and response bodies may be buffered across all connections.
+    ///
+    /// Bodies are buffered when they are compressed or decompressed, or when a
+    /// proxy path buffers requests or responses. Requests that would exceed
+    /// the budget are answered with `503 Service Unavailable` while the memory
+    /// is in use, on top of the per-request limits.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::{ServerConfig, ListenerConfig};
+    ///
+    /// let config = ServerConfig::builder()
+    ///     .add_listener(ListenerConfig::builder().port(8080).build()?)
+    ///     .max_buffered_bytes(64 * 1024 * 1024)
+    ///     .build();
+    /// ```
+    pub fn max_buffered_bytes(mut self, max_buffered_bytes: usize) -> Self {
+        self.max_buffered_bytes = Some(max_buffered_bytes);
+        self
+    }
+
+    /// Sets what happens to listeners once the last virtual host on their port is removed.
+    ///
+    /// By default listeners keep running and answer `404 Not Found`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::{EmptyHostsPolicy, ServerConfig, ListenerConfig};
+    ///
+    /// let config = ServerConfig::builder()
+    ///     .add_listener(ListenerConfig::builder().port(8080).build()?)
+    ///     .empty_hosts(EmptyHostsPolicy::StopListeners)
+    ///     .build();
+    /// ```
+    pub fn empty_hosts(mut self, empty_hosts: EmptyHostsPolicy) -> Self {
+        self.empty_hosts = empty_hosts;
+        self
+    }
+
+    /// Enables the admin listener.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::{server::admin::AdminConfig, ServerConfig, ListenerConfig};
+    ///
+    /// let config = ServerConfig::builder()
+    ///     .add_listener(ListenerConfig::builder().port(8080).build()?)
+    ///     .admin(AdminConfig::builder().port(9900).build()?)
+    ///     .build();
+    /// ```
+    #[cfg(feature = "admin")]
+    pub fn admin(mut self, admin: AdminConfig) -> Self {
+        self.admin = Some(admin);
+        self
+    }
+
     /// Creates the `ServerConfig` with the configured listeners.
     pub fn build(self) -> Result<ServerConfig, ConfigError> {
         if self
@@ -291,7 +980,31 @@ impl ServerConfigBuilder {
             return Err(ConfigError::Server("No listeners configured".to_string()));
         }
 
-        Ok(ServerConfig { listeners: self.listeners })
+        if self.max_buffered_bytes == Some(0) {
+            return Err(ConfigError::Server("Max buffered bytes cannot be 0".to_string()));
+        }
+
+        #[cfg(feature = "admin")]
+        if let Some(admin) = &self.admin {
+            if self
+                .listeners
+                .iter()
+                .any(|listener| listener.port() == admin.port())
+            {
+                return Err(ConfigError::Server(format!(
+                    "Admin port {} is already used by a listener",
+                    admin.port()
+                )));
+            }
+        }
+
+        Ok(ServerConfig {
+            listeners: self.listeners,
+            max_buffered_bytes: self.max_buffered_bytes,
+            empty_hosts: self.empty_hosts,
+            #[cfg(feature = "admin")]
+            admin: self.admin,
+        })
     }
 }
 
@@ -316,6 +1029,11 @@ impl ServerConfigBuilder {
 #[derive(Clone, Default, Deserialize)]
 pub struct ServerConfig {
     listeners: Vec<ListenerConfig>,
+    max_buffered_bytes: Option<usize>,
+    #[serde(default)]
+    empty_hosts: EmptyHostsPolicy,
+    #[cfg(feature = "admin")]
+    admin: Option<AdminConfig>,
 }
 
 impl ServerConfig {
@@ -331,7 +1049,13 @@ impl ServerConfig {
     ///     .build();
     /// ```
     pub fn builder() -> ServerConfigBuilder {
-        ServerConfigBuilder { listeners: vec![] }
+        ServerConfigBuilder {
+            listeners: vec![],
+            max_buffered_bytes: None,
+            empty_hosts: EmptyHostsPolicy::default(),
+            #[cfg(feature = "admin")]
+            admin: None,
+        }
     }
 
     /// Returns a reference to all configured listeners.
@@ -352,4 +1076,28 @@ impl ServerConfig {
     pub fn listeners(&self) -> &Vec<ListenerConfig> {
         &self.listeners
     }
+
+    /// Returns how many bytes of request and response bodies may be buffered across all connections.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<usize>` - The server-wide buffering budget, if limited.
+    pub fn max_buffered_bytes(&self) -> Option<usize> {
+        self.max_buffered_bytes
+    }
+
+    /// Returns what happens to listeners once the last virtual host on their port is removed.
+    ///
+    /// # Returns
+    ///
+    /// * `EmptyHostsPolicy` - The policy, `NotFound` unless configured.
+    pub fn empty_hosts(&self) -> EmptyHostsPolicy {
+        self.empty_hosts
+    }
+
+    /// Returns the admin listener configuration, if enabled.
+    #[cfg(feature = "admin")]
+    pub fn admin(&self) -> &Option<AdminConfig> {
+        &self.admin
+    }
 }
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, fs, path::Path};
+use std::{collections::HashMap, fs, path::Path, time::Duration};
 
 use log::error;
 use serde::{Deserialize, Deserializer};
@@ -35,14 +35,31 @@ pub mod path;
 ///     .security(security)
 ///     .build()?;
 /// ```
+#[derive(Deserialize)]
 pub struct VirtualHostConfigBuilder {
     hostname: String,
     port: u16,
     root_directory: String,
     default_headers: Option<Vec<(String, String)>>,
+    #[serde(default, deserialize_with = "deserialize_security_from_file")]
     security: Option<SecurityConfig>,
     status_pages: Option<HashMap<u16, String>>,
     enable_logging: bool,
+    log_format: Option<String>,
+    max_uri_length: Option<usize>,
+    max_body_size: Option<u64>,
+    request_timeout: Option<u64>,
+    max_concurrent_requests: Option<usize>,
+    allowed_methods: Option<Vec<String>>,
+    #[serde(default)]
+    allow_trace: bool,
+    #[serde(default)]
+    require_tls: bool,
+    base_path: Option<String>,
+    #[cfg(feature = "compression-brotli")]
+    max_compressed_body_size: Option<usize>,
+    #[cfg(feature = "request-decompression")]
+    max_decompressed_body_size: Option<usize>,
     #[cfg(feature = "static-files")]
     static_paths: Option<Vec<StaticPathConfig>>,
     #[cfg(feature = "reverse-proxy")]
@@ -175,7 +192,8 @@ impl VirtualHostConfigBuilder {
 
     /// Enables or disables logging for this virtual host.
     ///
-    /// When enabled, all requests to this virtual host will be logged.
+    /// When enabled, every request to this virtual host is written to the
+    /// access log once its response has been sent.
     ///
     /// # Examples
     ///
@@ -191,6 +209,231 @@ impl VirtualHostConfigBuilder {
         self
     }
 
+    /// Sets the access log format for this virtual host.
+    ///
+    /// Supported placeholders are `%remote_addr`, `%listener`, `%method`,
+    /// `%path`, `%status`, `%bytes`, `%duration_ms` and `%request_id`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::VirtualHostConfig;
+    ///
+    /// let config = VirtualHostConfig::builder()
+    ///     .log_format("%remote_addr \"%method %path\" %status %bytes %duration_ms")
+    ///     .build()?;
+    /// ```
+    pub fn log_format(mut self, log_format: &str) -> Self {
+        self.log_format = Some(log_format.to_string());
+        self
+    }
+
+    /// Sets the maximum length of the request target (path and query).
+    ///
+    /// Requests with a longer target are answered with `414 URI Too Long`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::VirtualHostConfig;
+    ///
+    /// let config = VirtualHostConfig::builder()
+    ///     .max_uri_length(8192)
+    ///     .build()?;
+    /// ```
+    pub fn max_uri_length(mut self, max_uri_length: usize) -> Self {
+        self.max_uri_length = Some(max_uri_length);
+        self
+    }
+
+    /// Sets the largest request body accepted, as declared by `content-length`.
+    ///
+    /// Requests declaring a larger body are answered with `413 Payload Too
+    /// Large` before any of it is read, and the connection is closed. Clients
+    /// sending `expect: 100-continue` are refused without uploading the body.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::VirtualHostConfig;
+    ///
+    /// let config = VirtualHostConfig::builder()
+    ///     .max_body_size(8 * 1024 * 1024)
+    ///     .build()?;
+    /// ```
+    pub fn max_body_size(mut self, max_body_size: u64) -> Self {
+        self.max_body_size = Some(max_body_size);
+        self
+    }
+
+    /// Sets how long a single request may be handled before giving up.
+    ///
+    /// Requests still running when the timeout elapses are cancelled and
+    /// answered with `503 Service Unavailable`, or `504 Gateway Timeout`
+    /// for proxy paths.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use std::time::Duration;
+    /// use vetis::config::VirtualHostConfig;
+    ///
+    /// let config = VirtualHostConfig::builder()
+    ///     .request_timeout(Duration::from_secs(30))
+    ///     .build()?;
+    /// ```
+    pub fn request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = Some(request_timeout.as_millis() as u64);
+        self
+    }
+
+    /// Sets how many requests the virtual host may handle at once.
+    ///
+    /// The limit applies to this virtual host only, so an expensive host
+    /// can't starve other hosts on the same listener. Requests over the limit
+    /// are answered with `503 Service Unavailable` and a `retry-after` header.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::VirtualHostConfig;
+    ///
+    /// let config = VirtualHostConfig::builder()
+    ///     .max_concurrent_requests(64)
+    ///     .build()?;
+    /// ```
+    pub fn max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.max_concurrent_requests = Some(max_concurrent_requests);
+        self
+    }
+
+    /// Sets the methods advertised in the `allow` header of server-wide responses.
+    ///
+    /// `OPTIONS *` requests, and `TRACE` requests when tracing is blocked,
+    /// are answered by the virtual host itself with this list. `TRACE` is
+    /// only advertised when allowed with [`allow_trace`](Self::allow_trace).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::VirtualHostConfig;
+    ///
+    /// let config = VirtualHostConfig::builder()
+    ///     .allowed_methods(&["GET", "HEAD", "OPTIONS"])
+    ///     .build()?;
+    /// ```
+    pub fn allowed_methods(mut self, allowed_methods: &[&str]) -> Self {
+        self.allowed_methods = Some(
+            allowed_methods
+                .iter()
+                .map(|method| method.to_string())
+                .collect(),
+        );
+        self
+    }
+
+    /// Allows `TRACE` requests to reach the virtual host paths.
+    ///
+    /// `TRACE` is answered with `405 Method Not Allowed` by default, since
+    /// echoing requests back can leak credentials through Cross-Site Tracing.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::VirtualHostConfig;
+    ///
+    /// let config = VirtualHostConfig::builder()
+    ///     .allow_trace(true)
+    ///     .build()?;
+    /// ```
+    pub fn allow_trace(mut self, allow_trace: bool) -> Self {
+        self.allow_trace = allow_trace;
+        self
+    }
+
+    /// Rejects requests that didn't arrive over TLS.
+    ///
+    /// Plaintext requests, such as those reaching a port that also accepts
+    /// TLS, are redirected to the same URL over `https://` with
+    /// `308 Permanent Redirect`. Requires a security configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::VirtualHostConfig;
+    ///
+    /// let config = VirtualHostConfig::builder()
+    ///     .security(security)
+    ///     .require_tls(true)
+    ///     .build()?;
+    /// ```
+    pub fn require_tls(mut self, require_tls: bool) -> Self {
+        self.require_tls = require_tls;
+        self
+    }
+
+    /// Mounts the virtual host paths under a common prefix.
+    ///
+    /// Paths are registered without the prefix, which is stripped from
+    /// request paths before routing. Requests outside the prefix are
+    /// answered with `404 Not Found`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::VirtualHostConfig;
+    ///
+    /// let config = VirtualHostConfig::builder()
+    ///     .base_path("/app")
+    ///     .build()?;
+    /// ```
+    pub fn base_path(mut self, base_path: &str) -> Self {
+        self.base_path = Some(base_path.to_string());
+        self
+    }
+
+    #[cfg(feature = "compression-brotli")]
+    /// Sets the largest response body compressed for clients sending `accept-encoding`.
+    ///
+    /// Compression buffers the whole body, so only responses with a
+    /// `content-length` up to this size are compressed, larger or streamed
+    /// ones are sent as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::VirtualHostConfig;
+    ///
+    /// let config = VirtualHostConfig::builder()
+    ///     .max_compressed_body_size(256 * 1024)
+    ///     .build()?;
+    /// ```
+    pub fn max_compressed_body_size(mut self, max_compressed_body_size: usize) -> Self {
+        self.max_compressed_body_size = Some(max_compressed_body_size);
+        self
+    }
+
+    #[cfg(feature = "request-decompression")]
+    /// Sets the largest request body accepted once its `content-encoding` is decoded.
+    ///
+    /// Request bodies encoded with `gzip`, `deflate` or `br` are decompressed
+    /// before reaching handlers. Bodies growing past this size are answered
+    /// with `413 Payload Too Large`, which guards against decompression bombs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::VirtualHostConfig;
+    ///
+    /// let config = VirtualHostConfig::builder()
+    ///     .max_decompressed_body_size(1024 * 1024)
+    ///     .build()?;
+    /// ```
+    pub fn max_decompressed_body_size(mut self, max_decompressed_body_size: usize) -> Self {
+        self.max_decompressed_body_size = Some(max_decompressed_body_size);
+        self
+    }
+
     #[cfg(feature = "static-files")]
     /// Sets the status pages for the virtual host.
     ///
@@ -292,6 +535,86 @@ impl VirtualHostConfigBuilder {
             }
         }
 
+        if self.max_uri_length == Some(0) {
+            return Err(VetisError::Config(ConfigError::VirtualHost(
+                "Max URI length cannot be 0".to_string(),
+            )));
+        }
+
+        if self.max_body_size == Some(0) {
+            return Err(VetisError::Config(ConfigError::VirtualHost(
+                "Max body size cannot be 0".to_string(),
+            )));
+        }
+
+        if self.request_timeout == Some(0) {
+            return Err(VetisError::Config(ConfigError::VirtualHost(
+                "Request timeout cannot be 0".to_string(),
+            )));
+        }
+
+        if self.max_concurrent_requests == Some(0) {
+            return Err(VetisError::Config(ConfigError::VirtualHost(
+                "Max concurrent requests cannot be 0".to_string(),
+            )));
+        }
+
+        if let Some(allowed_methods) = &self.allowed_methods {
+            if allowed_methods.is_empty() {
+                return Err(VetisError::Config(ConfigError::VirtualHost(
+                    "Allowed methods cannot be empty".to_string(),
+                )));
+            }
+
+            if let Some(method) = allowed_methods
+                .iter()
+                .find(|method| http::Method::from_bytes(method.as_bytes()).is_err())
+            {
+                return Err(VetisError::Config(ConfigError::VirtualHost(format!(
+                    "Invalid allowed method: {}",
+                    method
+                ))));
+            }
+        }
+
+        if self.require_tls
+            && self
+                .security
+                .is_none()
+        {
+            return Err(VetisError::Config(ConfigError::VirtualHost(
+                "Require TLS needs a security configuration".to_string(),
+            )));
+        }
+
+        let base_path = match self.base_path {
+            Some(base_path) if !base_path.starts_with('/') => {
+                return Err(VetisError::Config(ConfigError::VirtualHost(format!(
+                    "Base path must start with /: {}",
+                    base_path
+                ))));
+            }
+            Some(base_path) => {
+                let base_path = base_path.trim_end_matches('/');
+                (!base_path.is_empty()).then(|| base_path.to_string())
+            }
+            None => None,
+        };
+
+        #[cfg(feature = "compression-brotli")]
+        if self.max_compressed_body_size == Some(0) {
+            return Err(VetisError::Config(ConfigError::VirtualHost(
+                "Max compressed body size cannot be 0".to_string(),
+            )));
+        }
+
+        #[cfg(feature = "request-decompression")]
+        if self.max_decompressed_body_size == Some(0) {
+            return Err(VetisError::Config(ConfigError::VirtualHost(
+                "Max decompressed body size cannot be 0".to_string(),
+            )));
+        }
+
         Ok(VirtualHostConfig {
             hostname: self.hostname,
             port: self.port,
@@ -300,6 +623,19 @@ impl VirtualHostConfigBuilder {
             security: self.security,
             status_pages: self.status_pages,
             enable_logging: self.enable_logging,
+            log_format: self.log_format,
+            max_uri_length: self.max_uri_length,
+            max_body_size: self.max_body_size,
+            request_timeout: self.request_timeout,
+            max_concurrent_requests: self.max_concurrent_requests,
+            allowed_methods: self.allowed_methods,
+            allow_trace: self.allow_trace,
+            require_tls: self.require_tls,
+            base_path,
+            #[cfg(feature = "compression-brotli")]
+            max_compressed_body_size: self.max_compressed_body_size,
+            #[cfg(feature = "request-decompression")]
+            max_decompressed_body_size: self.max_decompressed_body_size,
             #[cfg(feature = "static-files")]
             static_paths: self.static_paths,
             #[cfg(feature = "reverse-proxy")]
@@ -331,15 +667,28 @@ impl VirtualHostConfigBuilder {
 /// println!("Virtual host: {}:{}", config.hostname(), config.port());
 /// ```
 #[derive(Clone, Deserialize)]
+#[serde(try_from = "VirtualHostConfigBuilder")]
 pub struct VirtualHostConfig {
     hostname: String,
     port: u16,
     root_directory: String,
     default_headers: Option<Vec<(String, String)>>,
-    #[serde(deserialize_with = "deserialize_security_from_file")]
     security: Option<SecurityConfig>,
     status_pages: Option<HashMap<u16, String>>,
     enable_logging: bool,
+    log_format: Option<String>,
+    max_uri_length: Option<usize>,
+    max_body_size: Option<u64>,
+    request_timeout: Option<u64>,
+    max_concurrent_requests: Option<usize>,
+    allowed_methods: Option<Vec<String>>,
+    allow_trace: bool,
+    require_tls: bool,
+    base_path: Option<String>,
+    #[cfg(feature = "compression-brotli")]
+    max_compressed_body_size: Option<usize>,
+    #[cfg(feature = "request-decompression")]
+    max_decompressed_body_size: Option<usize>,
     #[cfg(feature = "static-files")]
     static_paths: Option<Vec<StaticPathConfig>>,
     #[cfg(feature = "reverse-proxy")]
@@ -348,6 +697,14 @@ pub struct VirtualHostConfig {
     interface_paths: Option<Vec<InterfacePathConfig>>,
 }
 
+impl TryFrom<VirtualHostConfigBuilder> for VirtualHostConfig {
+    type Error = VetisError;
+
+    fn try_from(builder: VirtualHostConfigBuilder) -> Result<Self, Self::Error> {
+        builder.build()
+    }
+}
+
 impl VirtualHostConfig {
     /// Creates a new `VirtualHostConfigBuilder` with default settings.
     ///
@@ -355,6 +712,16 @@ impl VirtualHostConfig {
     /// - hostname: empty string (must be set)
     /// - port: 80
     /// - security: None
+    /// - max_uri_length: None
+    /// - max_body_size: None (unlimited)
+    /// - request_timeout: None
+    /// - max_concurrent_requests: None (unlimited)
+    /// - allowed_methods: None (GET, HEAD, POST, PUT, PATCH, DELETE and OPTIONS)
+    /// - allow_trace: false
+    /// - require_tls: false
+    /// - base_path: None
+    /// - max_compressed_body_size: None (1 MiB)
+    /// - max_decompressed_body_size: None (10 MiB)
     ///
     /// # Examples
     ///
@@ -375,6 +742,19 @@ impl VirtualHostConfig {
             security: None,
             status_pages: None,
             enable_logging: true,
+            log_format: None,
+            max_uri_length: None,
+            max_body_size: None,
+            request_timeout: None,
+            max_concurrent_requests: None,
+            allowed_methods: None,
+            allow_trace: false,
+            require_tls: false,
+            base_path: None,
+            #[cfg(feature = "compression-brotli")]
+            max_compressed_body_size: None,
+            #[cfg(feature = "request-decompression")]
+            max_decompressed_body_size: None,
             #[cfg(feature = "static-files")]
             static_paths: None,
             #[cfg(feature = "reverse-proxy")]
@@ -420,6 +800,29 @@ impl VirtualHostConfig {
         &self.default_headers
     }
 
+    /// Adds server-wide headers ahead of the default headers.
+    ///
+    /// The default headers are applied in order, so a header the virtual
+    /// host sets itself still takes precedence.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers shared by every virtual host of the server.
+    pub(crate) fn inherit_headers(&mut self, headers: &[(String, String)]) {
+        if headers.is_empty() {
+            return;
+        }
+
+        let mut default_headers = headers.to_vec();
+        if let Some(own_headers) = self
+            .default_headers
+            .take()
+        {
+            default_headers.extend(own_headers);
+        }
+        self.default_headers = Some(default_headers);
+    }
+
     /// Returns the security configuration if present.
     ///
     /// # Returns
@@ -447,6 +850,108 @@ impl VirtualHostConfig {
         self.enable_logging
     }
 
+    /// Returns the access log format.
+    ///
+    /// # Returns
+    ///
+    /// * `&Option<String>` - The access log format, if customized.
+    pub fn log_format(&self) -> &Option<String> {
+        &self.log_format
+    }
+
+    /// Returns the maximum request target length.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<usize>` - The maximum length of path and query, if any.
+    pub fn max_uri_length(&self) -> Option<usize> {
+        self.max_uri_length
+    }
+
+    /// Returns the largest request body accepted.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<u64>` - The maximum declared body size, in bytes, if any.
+    pub fn max_body_size(&self) -> Option<u64> {
+        self.max_body_size
+    }
+
+    /// Returns the request timeout.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Duration>` - How long a request may be handled, if limited.
+    pub fn request_timeout(&self) -> Option<Duration> {
+        self.request_timeout
+            .map(Duration::from_millis)
+    }
+
+    /// Returns how many requests the virtual host may handle at once.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<usize>` - The concurrent request limit, if any.
+    pub fn max_concurrent_requests(&self) -> Option<usize> {
+        self.max_concurrent_requests
+    }
+
+    /// Returns the methods advertised in the `allow` header, if customized.
+    ///
+    /// # Returns
+    ///
+    /// * `&Option<Vec<String>>` - The allowed methods.
+    pub fn allowed_methods(&self) -> &Option<Vec<String>> {
+        &self.allowed_methods
+    }
+
+    /// Returns whether `TRACE` requests reach the virtual host paths.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether `TRACE` is allowed.
+    pub fn allow_trace(&self) -> bool {
+        self.allow_trace
+    }
+
+    /// Returns whether plaintext requests are redirected to `https://`.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether TLS is required.
+    pub fn require_tls(&self) -> bool {
+        self.require_tls
+    }
+
+    /// Returns the prefix the virtual host paths are mounted under.
+    ///
+    /// # Returns
+    ///
+    /// * `&Option<String>` - The base path, without a trailing slash.
+    pub fn base_path(&self) -> &Option<String> {
+        &self.base_path
+    }
+
+    #[cfg(feature = "compression-brotli")]
+    /// Returns the largest response body compressed.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<usize>` - The maximum compressed body size, if set.
+    pub fn max_compressed_body_size(&self) -> Option<usize> {
+        self.max_compressed_body_size
+    }
+
+    #[cfg(feature = "request-decompression")]
+    /// Returns the largest request body accepted after decompression.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<usize>` - The maximum decompressed body size, if set.
+    pub fn max_decompressed_body_size(&self) -> Option<usize> {
+        self.max_decompressed_body_size
+    }
+
     #[cfg(feature = "static-files")]
     /// Returns the static paths.
     ///
@@ -501,6 +1006,10 @@ pub struct SecurityConfigBuilder {
     key: Vec<u8>,
     ca_cert: Option<Vec<u8>>,
     client_auth: bool,
+    allowed_client_names: Option<Vec<String>>,
+    max_early_data_size: u32,
+    session_resumption: bool,
+    session_cache_size: usize,
 }
 
 impl SecurityConfigBuilder {
@@ -644,7 +1153,8 @@ impl SecurityConfigBuilder {
 
     /// Sets whether client authentication is required.
     ///
-    /// When enabled, clients must present a valid certificate signed by the CA.
+    /// When enabled, clients must present a valid certificate signed by the CA,
+    /// otherwise their requests are answered with `403 Forbidden`.
     ///
     /// # Examples
     ///
@@ -660,6 +1170,92 @@ impl SecurityConfigBuilder {
         self
     }
 
+    /// Sets the names a client certificate must carry to be authorized.
+    ///
+    /// A certificate is authorized when its subject common name or one of its
+    /// DNS subject alternative names matches an entry. Entries starting with
+    /// `*.` match a single leading label, so `*.example.com` matches
+    /// `api.example.com` but not `example.com`. Requests presenting a valid
+    /// certificate without a matching name are answered with `403 Forbidden`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::SecurityConfig;
+    ///
+    /// let security = SecurityConfig::builder()
+    ///     .client_auth(true)
+    ///     .allowed_client_names(vec!["backend".to_string(), "*.internal.example.com".to_string()])
+    ///     .build();
+    /// ```
+    pub fn allowed_client_names(mut self, allowed_client_names: Vec<String>) -> Self {
+        self.allowed_client_names = Some(allowed_client_names);
+        self
+    }
+
+    /// Sets how much TLS 1.3 early data (0-RTT) a client may send when resuming a session.
+    ///
+    /// Early data can be replayed by an attacker, so it is disabled by default.
+    /// Since all virtual hosts of a listener share one TLS configuration, the
+    /// largest size among them applies, and early data requests for hosts
+    /// that leave it at 0 are answered with `425 Too Early`. HTTP/3 listeners
+    /// accept any amount of early data once it is enabled, as QUIC doesn't
+    /// allow other limits.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::SecurityConfig;
+    ///
+    /// let security = SecurityConfig::builder()
+    ///     .max_early_data_size(16 * 1024)
+    ///     .build();
+    /// ```
+    pub fn max_early_data_size(mut self, max_early_data_size: u32) -> Self {
+        self.max_early_data_size = max_early_data_size;
+        self
+    }
+
+    /// Sets whether clients may resume TLS sessions instead of doing a full handshake.
+    ///
+    /// Resumption is enabled by default: after each handshake the server
+    /// issues session tickets that let the client reconnect faster. Since all
+    /// virtual hosts of a listener share one TLS configuration, disabling it
+    /// on any of them disables it for the listener.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::SecurityConfig;
+    ///
+    /// let security = SecurityConfig::builder()
+    ///     .session_resumption(false)
+    ///     .build();
+    /// ```
+    pub fn session_resumption(mut self, session_resumption: bool) -> Self {
+        self.session_resumption = session_resumption;
+        self
+    }
+
+    /// Sets how many TLS sessions are kept in memory for resumption.
+    ///
+    /// Defaults to 256. Since all virtual hosts of a listener share one TLS
+    /// configuration, the largest size among them applies.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::config::SecurityConfig;
+    ///
+    /// let security = SecurityConfig::builder()
+    ///     .session_cache_size(4096)
+    ///     .build();
+    /// ```
+    pub fn session_cache_size(mut self, session_cache_size: usize) -> Self {
+        self.session_cache_size = session_cache_size;
+        self
+    }
+
     /// Creates the `SecurityConfig` with the configured settings.
     ///
     /// # Returns
@@ -676,11 +1272,41 @@ impl SecurityConfigBuilder {
             return Err(VetisError::Config(ConfigError::Security("Missing key".to_string())));
         }
 
+        if self.client_auth
+            && self
+                .ca_cert
+                .is_none()
+        {
+            return Err(VetisError::Config(ConfigError::Security(
+                "Client authentication requires a CA certificate".to_string(),
+            )));
+        }
+
+        if !self.client_auth
+            && self
+                .allowed_client_names
+                .is_some()
+        {
+            return Err(VetisError::Config(ConfigError::Security(
+                "Allowed client names require client authentication".to_string(),
+            )));
+        }
+
+        if self.session_cache_size == 0 {
+            return Err(VetisError::Config(ConfigError::Security(
+                "Session cache size cannot be 0".to_string(),
+            )));
+        }
+
         Ok(SecurityConfig {
             cert: self.cert,
             key: self.key,
             ca_cert: self.ca_cert,
             client_auth: self.client_auth,
+            allowed_client_names: self.allowed_client_names,
+            max_early_data_size: self.max_early_data_size,
+            session_resumption: self.session_resumption,
+            session_cache_size: self.session_cache_size,
         })
     }
 }
@@ -708,6 +1334,22 @@ pub struct SecurityConfig {
     key: Vec<u8>,
     ca_cert: Option<Vec<u8>>,
     client_auth: bool,
+    allowed_client_names: Option<Vec<String>>,
+    #[serde(default)]
+    max_early_data_size: u32,
+    #[serde(default = "default_session_resumption")]
+    session_resumption: bool,
+    #[serde(default = "default_session_cache_size")]
+    session_cache_size: usize,
+}
+
+fn default_session_resumption() -> bool {
+    true
+}
+
+/// Matches the session cache rustls sets up by default.
+fn default_session_cache_size() -> usize {
+    256
 }
 
 impl SecurityConfig {
@@ -718,6 +1360,10 @@ impl SecurityConfig {
     /// - key: empty (must be set)
     /// - ca_cert: None
     /// - client_auth: false
+    /// - allowed_client_names: None (any certificate signed by the CA)
+    /// - max_early_data_size: 0 (early data disabled)
+    /// - session_resumption: true
+    /// - session_cache_size: 256
     ///
     /// # Examples
     ///
@@ -735,6 +1381,10 @@ impl SecurityConfig {
             key: Vec::new(),
             ca_cert: None,
             client_auth: false,
+            allowed_client_names: None,
+            max_early_data_size: 0,
+            session_resumption: default_session_resumption(),
+            session_cache_size: default_session_cache_size(),
         }
     }
 
@@ -773,6 +1423,42 @@ impl SecurityConfig {
     pub fn client_auth(&self) -> bool {
         self.client_auth
     }
+
+    /// Returns the names a client certificate must carry, if restricted.
+    ///
+    /// # Returns
+    ///
+    /// * `&Option<Vec<String>>` - The allowed client certificate names.
+    pub fn allowed_client_names(&self) -> &Option<Vec<String>> {
+        &self.allowed_client_names
+    }
+
+    /// Returns the maximum size of early data accepted from a client.
+    ///
+    /// # Returns
+    ///
+    /// * `u32` - The maximum early data size in bytes, 0 when disabled.
+    pub fn max_early_data_size(&self) -> u32 {
+        self.max_early_data_size
+    }
+
+    /// Returns whether clients may resume TLS sessions.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` when session tickets are issued.
+    pub fn session_resumption(&self) -> bool {
+        self.session_resumption
+    }
+
+    /// Returns how many TLS sessions are kept in memory for resumption.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The size of the session cache.
+    pub fn session_cache_size(&self) -> usize {
+        self.session_cache_size
+    }
 }
 
 #[derive(Clone, Deserialize)]
@@ -781,6 +1467,10 @@ pub struct SecurityConfigFromFile {
     key_from_file: String,
     ca_cert_from_file: Option<String>,
     client_auth: Option<bool>,
+    allowed_client_names: Option<Vec<String>>,
+    max_early_data_size: Option<u32>,
+    session_resumption: Option<bool>,
+    session_cache_size: Option<usize>,
 }
 
 fn deserialize_security_from_file<'de, D>(
@@ -804,6 +1494,22 @@ where
         builder = builder.client_auth(client_auth);
     }
 
+    if let Some(allowed_client_names) = security.allowed_client_names {
+        builder = builder.allowed_client_names(allowed_client_names);
+    }
+
+    if let Some(max_early_data_size) = security.max_early_data_size {
+        builder = builder.max_early_data_size(max_early_data_size);
+    }
+
+    if let Some(session_resumption) = security.session_resumption {
+        builder = builder.session_resumption(session_resumption);
+    }
+
+    if let Some(session_cache_size) = security.session_cache_size {
+        builder = builder.session_cache_size(session_cache_size);
+    }
+
     builder
         .build()
         .map_err(serde::de::Error::custom)
@@ -1,11 +1,46 @@
+use std::time::Duration;
+
 use serde::Deserialize;
 
 use crate::errors::{ConfigError, VetisError};
 
+const DEFAULT_HEALTH_CHECK_INTERVAL_MS: u64 = 10_000;
+const DEFAULT_HEALTH_CHECK_TIMEOUT_MS: u64 = 2_000;
+
+fn default_via() -> bool {
+    true
+}
+
+fn default_retry_connection_errors() -> bool {
+    true
+}
+
 #[derive(Deserialize)]
 pub struct ProxyPathConfigBuilder {
     uri: String,
     target: String,
+    #[serde(default)]
+    buffer_request: bool,
+    #[serde(default)]
+    buffer_response: bool,
+    max_buffer_size: Option<usize>,
+    upstreams: Option<Vec<String>>,
+    #[serde(default)]
+    sticky_sessions: bool,
+    health_check_path: Option<String>,
+    health_check_interval: Option<u64>,
+    health_check_timeout: Option<u64>,
+    add_response_headers: Option<Vec<(String, String)>>,
+    remove_response_headers: Option<Vec<String>>,
+    remove_request_headers: Option<Vec<String>>,
+    #[serde(default = "default_via")]
+    via: bool,
+    max_retries: Option<u32>,
+    retry_statuses: Option<Vec<u16>>,
+    #[serde(default = "default_retry_connection_errors")]
+    retry_connection_errors: bool,
+    #[serde(default)]
+    retry_non_idempotent: bool,
 }
 
 #[cfg(feature = "reverse-proxy")]
@@ -30,6 +65,218 @@ impl ProxyPathConfigBuilder {
         self
     }
 
+    /// Allow add an upstream sharing the load with the target.
+    ///
+    /// Requests are balanced round-robin across the target and every added
+    /// upstream.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn add_upstream(mut self, upstream: &str) -> Self {
+        self.upstreams
+            .get_or_insert_with(Vec::new)
+            .push(upstream.to_string());
+        self
+    }
+
+    /// Allow pin each client to the upstream that served its first request.
+    ///
+    /// The chosen upstream is recorded in the `VETIS_UPSTREAM` cookie. When
+    /// the pinned upstream is unavailable the request falls back to
+    /// round-robin and the cookie is replaced. Disabled by default.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn sticky_sessions(mut self, sticky_sessions: bool) -> Self {
+        self.sticky_sessions = sticky_sessions;
+        self
+    }
+
+    /// Allow enable active health checks against the given path.
+    ///
+    /// Every upstream is periodically requested at this path, and upstreams
+    /// not answering with a successful status are taken out of rotation until
+    /// a later check succeeds.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn health_check(mut self, path: &str) -> Self {
+        self.health_check_path = Some(path.to_string());
+        self
+    }
+
+    /// Allow set the delay between health check rounds. Defaults to 10 seconds.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn health_check_interval(mut self, interval: Duration) -> Self {
+        self.health_check_interval = Some(interval.as_millis() as u64);
+        self
+    }
+
+    /// Allow set how long a health check may take before the upstream is
+    /// considered down. Defaults to 2 seconds.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn health_check_timeout(mut self, timeout: Duration) -> Self {
+        self.health_check_timeout = Some(timeout.as_millis() as u64);
+        self
+    }
+
+    /// Allow add a header to every response returned by the upstreams.
+    ///
+    /// The header replaces any header with the same name sent by the upstream.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn add_response_header(mut self, key: &str, value: &str) -> Self {
+        self.add_response_headers
+            .get_or_insert_with(Vec::new)
+            .push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Allow remove a header from every response returned by the upstreams.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn remove_response_header(mut self, key: &str) -> Self {
+        self.remove_response_headers
+            .get_or_insert_with(Vec::new)
+            .push(key.to_string());
+        self
+    }
+
+    /// Allow remove a header from every request before it is forwarded upstream.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn remove_request_header(mut self, key: &str) -> Self {
+        self.remove_request_headers
+            .get_or_insert_with(Vec::new)
+            .push(key.to_string());
+        self
+    }
+
+    /// Allow append a `Via: <version> vetis` header to forwarded requests and responses.
+    ///
+    /// Requests already carrying `vetis` in their `Via` header are rejected
+    /// with `508`, as they went through this proxy before. Enabled by default.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn via(mut self, via: bool) -> Self {
+        self.via = via;
+        self
+    }
+
+    /// Allow retry a failed request up to `max_retries` more times.
+    ///
+    /// Each retry goes to the next available upstream, or to the same one
+    /// when the target is the only upstream. Only idempotent methods are
+    /// retried unless `retry_non_idempotent` is enabled, and request bodies
+    /// of retried requests are buffered so they can be resent. Disabled by
+    /// default.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    /// Allow retry requests answered with the given upstream status.
+    ///
+    /// Without any retry status only failed connections are retried. When
+    /// the last attempt fails too its status is forwarded to the client.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn add_retry_status(mut self, status: u16) -> Self {
+        self.retry_statuses
+            .get_or_insert_with(Vec::new)
+            .push(status);
+        self
+    }
+
+    /// Allow retry requests whose upstream couldn't be reached or failed to answer.
+    ///
+    /// Enabled by default when `max_retries` is set.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn retry_connection_errors(mut self, retry_connection_errors: bool) -> Self {
+        self.retry_connection_errors = retry_connection_errors;
+        self
+    }
+
+    /// Allow retry non-idempotent methods such as `POST` and `PATCH`.
+    ///
+    /// An upstream may have acted on a request before failing, so retrying
+    /// them can apply it twice. Disabled by default.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn retry_non_idempotent(mut self, retry_non_idempotent: bool) -> Self {
+        self.retry_non_idempotent = retry_non_idempotent;
+        self
+    }
+
+    /// Allow collect the request body before forwarding it upstream.
+    ///
+    /// Buffering sends the upstream a request with a known `content-length`,
+    /// at the cost of holding the whole body in memory. Disabled by default.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn buffer_request(mut self, buffer_request: bool) -> Self {
+        self.buffer_request = buffer_request;
+        self
+    }
+
+    /// Allow collect the upstream response body before forwarding it to the client.
+    ///
+    /// Buffering decouples the client from upstream timing, which suits small
+    /// responses; large or long-lived streaming responses are better passed
+    /// through, since buffered bodies are held in memory. Disabled by default.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn buffer_response(mut self, buffer_response: bool) -> Self {
+        self.buffer_response = buffer_response;
+        self
+    }
+
+    /// Allow set the maximum size, in bytes, of a buffered body.
+    ///
+    /// Buffered request bodies above the limit are rejected with `413`, and
+    /// buffered upstream responses above the limit with `502`. Has no effect
+    /// on streamed bodies.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn max_buffer_size(mut self, max_buffer_size: usize) -> Self {
+        self.max_buffer_size = Some(max_buffer_size);
+        self
+    }
+
     /// Build the `ProxyPathConfig` with the configured settings.
     ///
     /// # Returns
@@ -48,7 +295,123 @@ impl ProxyPathConfigBuilder {
             )));
         }
 
-        Ok(ProxyPathConfig { uri: self.uri, target: self.target })
+        if self
+            .upstreams
+            .as_ref()
+            .is_some_and(|upstreams| {
+                upstreams
+                    .iter()
+                    .any(|upstream| upstream.is_empty())
+            })
+        {
+            return Err(VetisError::Config(ConfigError::Path(
+                "Upstream cannot be empty".to_string(),
+            )));
+        }
+
+        if self
+            .health_check_path
+            .as_ref()
+            .is_some_and(|path| !path.starts_with('/'))
+        {
+            return Err(VetisError::Config(ConfigError::Path(
+                "Health check path must start with /".to_string(),
+            )));
+        }
+
+        if self.health_check_interval == Some(0) {
+            return Err(VetisError::Config(ConfigError::Path(
+                "Health check interval cannot be 0".to_string(),
+            )));
+        }
+
+        if self.health_check_timeout == Some(0) {
+            return Err(VetisError::Config(ConfigError::Path(
+                "Health check timeout cannot be 0".to_string(),
+            )));
+        }
+
+        let header_names = self
+            .add_response_headers
+            .iter()
+            .flatten()
+            .map(|(key, _)| key)
+            .chain(
+                self.remove_response_headers
+                    .iter()
+                    .flatten(),
+            )
+            .chain(
+                self.remove_request_headers
+                    .iter()
+                    .flatten(),
+            );
+        for key in header_names {
+            if http::HeaderName::from_bytes(key.as_bytes()).is_err() {
+                return Err(VetisError::Config(ConfigError::Path(format!(
+                    "Invalid header name: {}",
+                    key
+                ))));
+            }
+        }
+
+        for (_, value) in self
+            .add_response_headers
+            .iter()
+            .flatten()
+        {
+            if http::HeaderValue::from_str(value).is_err() {
+                return Err(VetisError::Config(ConfigError::Path(format!(
+                    "Invalid header value: {}",
+                    value
+                ))));
+            }
+        }
+
+        if self.max_buffer_size == Some(0) {
+            return Err(VetisError::Config(ConfigError::Path(
+                "Max buffer size cannot be 0".to_string(),
+            )));
+        }
+
+        if self.max_retries == Some(0) {
+            return Err(VetisError::Config(ConfigError::Path(
+                "Max retries cannot be 0".to_string(),
+            )));
+        }
+
+        if let Some(status) = self
+            .retry_statuses
+            .iter()
+            .flatten()
+            .find(|status| http::StatusCode::from_u16(**status).is_err())
+        {
+            return Err(VetisError::Config(ConfigError::Path(format!(
+                "Invalid retry status: {}",
+                status
+            ))));
+        }
+
+        Ok(ProxyPathConfig {
+            uri: self.uri,
+            target: self.target,
+            buffer_request: self.buffer_request,
+            buffer_response: self.buffer_response,
+            max_buffer_size: self.max_buffer_size,
+            upstreams: self.upstreams,
+            sticky_sessions: self.sticky_sessions,
+            health_check_path: self.health_check_path,
+            health_check_interval: self.health_check_interval,
+            health_check_timeout: self.health_check_timeout,
+            add_response_headers: self.add_response_headers,
+            remove_response_headers: self.remove_response_headers,
+            remove_request_headers: self.remove_request_headers,
+            via: self.via,
+            max_retries: self.max_retries,
+            retry_statuses: self.retry_statuses,
+            retry_connection_errors: self.retry_connection_errors,
+            retry_non_idempotent: self.retry_non_idempotent,
+        })
     }
 }
 
@@ -57,9 +420,29 @@ impl ProxyPathConfigBuilder {
 pub struct ProxyPathConfig {
     uri: String,
     target: String,
+    #[serde(default)]
+    buffer_request: bool,
+    #[serde(default)]
+    buffer_response: bool,
+    max_buffer_size: Option<usize>,
+    upstreams: Option<Vec<String>>,
+    #[serde(default)]
+    sticky_sessions: bool,
+    health_check_path: Option<String>,
+    health_check_interval: Option<u64>,
+    health_check_timeout: Option<u64>,
+    add_response_headers: Option<Vec<(String, String)>>,
+    remove_response_headers: Option<Vec<String>>,
+    remove_request_headers: Option<Vec<String>>,
+    #[serde(default = "default_via")]
+    via: bool,
+    max_retries: Option<u32>,
+    retry_statuses: Option<Vec<u16>>,
+    #[serde(default = "default_retry_connection_errors")]
+    retry_connection_errors: bool,
+    #[serde(default)]
+    retry_non_idempotent: bool,
     // TODO: Add custom proxy rules
-
-    // TODO: Add support for custom headers
 }
 
 #[cfg(feature = "reverse-proxy")]
@@ -73,6 +456,22 @@ impl ProxyPathConfig {
         ProxyPathConfigBuilder {
             uri: "/test".to_string(),
             target: "http://localhost:8080".to_string(),
+            buffer_request: false,
+            buffer_response: false,
+            max_buffer_size: None,
+            upstreams: None,
+            sticky_sessions: false,
+            health_check_path: None,
+            health_check_interval: None,
+            health_check_timeout: None,
+            add_response_headers: None,
+            remove_response_headers: None,
+            remove_request_headers: None,
+            via: true,
+            max_retries: None,
+            retry_statuses: None,
+            retry_connection_errors: true,
+            retry_non_idempotent: false,
         }
     }
 
@@ -93,4 +492,154 @@ impl ProxyPathConfig {
     pub fn target(&self) -> &str {
         &self.target
     }
+
+    /// Returns whether request bodies are buffered before forwarding.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether request bodies are buffered.
+    pub fn buffer_request(&self) -> bool {
+        self.buffer_request
+    }
+
+    /// Returns whether upstream response bodies are buffered before forwarding.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether response bodies are buffered.
+    pub fn buffer_response(&self) -> bool {
+        self.buffer_response
+    }
+
+    /// Returns the maximum size of a buffered body.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<usize>` - The maximum buffered body size in bytes, if any.
+    pub fn max_buffer_size(&self) -> Option<usize> {
+        self.max_buffer_size
+    }
+
+    /// Returns the upstreams balanced together with the target.
+    ///
+    /// # Returns
+    ///
+    /// * `&Option<Vec<String>>` - The additional upstreams.
+    pub fn upstreams(&self) -> &Option<Vec<String>> {
+        &self.upstreams
+    }
+
+    /// Returns whether clients are pinned to an upstream.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether sticky sessions are enabled.
+    pub fn sticky_sessions(&self) -> bool {
+        self.sticky_sessions
+    }
+
+    /// Returns the path requested by active health checks.
+    ///
+    /// # Returns
+    ///
+    /// * `&Option<String>` - The health check path, if health checks are enabled.
+    pub fn health_check_path(&self) -> &Option<String> {
+        &self.health_check_path
+    }
+
+    /// Returns the delay between health check rounds.
+    ///
+    /// # Returns
+    ///
+    /// * `Duration` - The health check interval.
+    pub fn health_check_interval(&self) -> Duration {
+        Duration::from_millis(
+            self.health_check_interval
+                .unwrap_or(DEFAULT_HEALTH_CHECK_INTERVAL_MS),
+        )
+    }
+
+    /// Returns how long a health check may take.
+    ///
+    /// # Returns
+    ///
+    /// * `Duration` - The health check timeout.
+    pub fn health_check_timeout(&self) -> Duration {
+        Duration::from_millis(
+            self.health_check_timeout
+                .unwrap_or(DEFAULT_HEALTH_CHECK_TIMEOUT_MS),
+        )
+    }
+
+    /// Returns the headers added to upstream responses.
+    ///
+    /// # Returns
+    ///
+    /// * `&Option<Vec<(String, String)>>` - The added response headers.
+    pub fn add_response_headers(&self) -> &Option<Vec<(String, String)>> {
+        &self.add_response_headers
+    }
+
+    /// Returns the headers removed from upstream responses.
+    ///
+    /// # Returns
+    ///
+    /// * `&Option<Vec<String>>` - The removed response headers.
+    pub fn remove_response_headers(&self) -> &Option<Vec<String>> {
+        &self.remove_response_headers
+    }
+
+    /// Returns the headers removed from requests before forwarding.
+    ///
+    /// # Returns
+    ///
+    /// * `&Option<Vec<String>>` - The removed request headers.
+    pub fn remove_request_headers(&self) -> &Option<Vec<String>> {
+        &self.remove_request_headers
+    }
+
+    /// Returns whether a `Via` header is appended to forwarded messages.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether `Via` headers are appended.
+    pub fn via(&self) -> bool {
+        self.via
+    }
+
+    /// Returns how many times a failed request is retried.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<u32>` - The maximum number of retries, if retries are enabled.
+    pub fn max_retries(&self) -> Option<u32> {
+        self.max_retries
+    }
+
+    /// Returns the upstream statuses that are retried.
+    ///
+    /// # Returns
+    ///
+    /// * `&Option<Vec<u16>>` - The retried statuses.
+    pub fn retry_statuses(&self) -> &Option<Vec<u16>> {
+        &self.retry_statuses
+    }
+
+    /// Returns whether failed upstream connections are retried.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether connection errors are retried.
+    pub fn retry_connection_errors(&self) -> bool {
+        self.retry_connection_errors
+    }
+
+    /// Returns whether non-idempotent methods are retried.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether non-idempotent methods are retried.
+    pub fn retry_non_idempotent(&self) -> bool {
+        self.retry_non_idempotent
+    }
 }
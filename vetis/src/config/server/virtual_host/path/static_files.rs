@@ -1,3 +1,7 @@
+use std::collections::HashMap;
+#[cfg(feature = "static-cache")]
+use std::time::Duration;
+
 use serde::Deserialize;
 
 use crate::errors::{ConfigError, VetisError};
@@ -5,11 +9,43 @@ use crate::errors::{ConfigError, VetisError};
 #[cfg(feature = "auth")]
 use crate::server::virtual_host::path::auth::AuthType;
 
+#[cfg(feature = "static-cache")]
+const DEFAULT_CACHE_MAX_FILE_SIZE: usize = 1024 * 1024;
+
+#[cfg(feature = "static-cache")]
+const DEFAULT_CACHE_REVALIDATE_INTERVAL_MS: u64 = 1000;
+
+/// Which dotfiles, files or directories whose name starts with `.`, a static path serves.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum DotfilePolicy {
+    /// Reject every path with a dotfile segment
+    Deny,
+    /// Serve dotfiles like any other file
+    Allow,
+    /// Only serve files below a leading `.well-known` directory, as ACME challenges need
+    #[default]
+    AllowWellKnown,
+}
+
 pub struct StaticPathConfigBuilder {
     uri: String,
     extensions: String,
     directory: String,
+    directories: Option<Vec<String>>,
+    file: Option<String>,
     index_files: Option<Vec<String>>,
+    directory_template: Option<String>,
+    accept_ranges: bool,
+    mime_overrides: Option<HashMap<String, String>>,
+    attachment: bool,
+    serve_dotfiles: DotfilePolicy,
+    follow_symlinks: bool,
+    #[cfg(feature = "static-cache")]
+    cache_size: Option<usize>,
+    #[cfg(feature = "static-cache")]
+    cache_max_file_size: Option<usize>,
+    #[cfg(feature = "static-cache")]
+    cache_revalidate_interval: Option<u64>,
     #[cfg(feature = "auth")]
     auth: Option<AuthType>,
 }
@@ -45,6 +81,36 @@ impl StaticPathConfigBuilder {
         self
     }
 
+    /// Allow add a fallback directory to the static path.
+    ///
+    /// Files are looked up in the main directory first, then in each
+    /// fallback directory in the order they were added, serving the first
+    /// match. Useful to overlay user overrides on top of default assets.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn add_directory(mut self, directory: &str) -> Self {
+        self.directories
+            .get_or_insert_with(Vec::new)
+            .push(directory.to_string());
+        self
+    }
+
+    /// Allow serve a single file at the URI of the static path.
+    ///
+    /// The file is served for requests to exactly the URI, such as
+    /// `/robots.txt` or `/favicon.ico`, instead of looking requests up in
+    /// the directories. Its extension doesn't need to match `extensions`.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn file(mut self, file: &str) -> Self {
+        self.file = Some(file.to_string());
+        self
+    }
+
     /// Allow set the index files of the static path.
     ///
     /// # Returns
@@ -55,6 +121,131 @@ impl StaticPathConfigBuilder {
         self
     }
 
+    /// Allow set the HTML template rendered for directory requests.
+    ///
+    /// The template is read when the static path is created. `{{path}}` is
+    /// replaced with the request path and `{{entries}}` with a `<li>` link
+    /// for each entry of the directory. Directories are served their index
+    /// file when no template is set.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn directory_template(mut self, directory_template: &str) -> Self {
+        self.directory_template = Some(directory_template.to_string());
+        self
+    }
+
+    /// Allow enable or disable byte-range support of the static path.
+    ///
+    /// When disabled, the `accept-ranges` header is omitted and `Range`
+    /// requests are answered with the full file. Enabled by default.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn accept_ranges(mut self, accept_ranges: bool) -> Self {
+        self.accept_ranges = accept_ranges;
+        self
+    }
+
+    /// Allow set the MIME type overrides of the static path.
+    ///
+    /// Maps a file extension, without the leading dot (e.g. `mjs`), to the
+    /// content type served for it. Overrides take precedence over the
+    /// built-in MIME type lookup.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn mime_overrides(mut self, mime_overrides: HashMap<String, String>) -> Self {
+        self.mime_overrides = Some(mime_overrides);
+        self
+    }
+
+    /// Allow serve files of the static path as downloads.
+    ///
+    /// When enabled, responses carry `content-disposition: attachment` with
+    /// the name of the served file. Disabled by default.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn attachment(mut self, attachment: bool) -> Self {
+        self.attachment = attachment;
+        self
+    }
+
+    /// Allow set which dotfiles the static path serves.
+    ///
+    /// Requests for paths with a segment starting with `.` that the policy
+    /// doesn't permit are answered with `404`, so files such as `.git` or
+    /// `.env` aren't exposed. Defaults to `DotfilePolicy::AllowWellKnown`.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn serve_dotfiles(mut self, serve_dotfiles: DotfilePolicy) -> Self {
+        self.serve_dotfiles = serve_dotfiles;
+        self
+    }
+
+    /// Allow serve files reached through symlinks leading out of the static path directories.
+    ///
+    /// When disabled, requests whose path resolves, through a symlink, to a
+    /// file outside every directory of the static path are answered with
+    /// `404`. Symlinks staying inside the directories are always followed.
+    /// Disabled by default.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    #[cfg(feature = "static-cache")]
+    /// Allow enable the in-memory file cache of the static path.
+    ///
+    /// Small files are kept in memory, up to `cache_size` bytes in total,
+    /// evicting the least recently used ones first.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn cache_size(mut self, cache_size: usize) -> Self {
+        self.cache_size = Some(cache_size);
+        self
+    }
+
+    #[cfg(feature = "static-cache")]
+    /// Allow set the size of the largest file kept in the in-memory cache.
+    ///
+    /// Larger files are always served from disk. Defaults to 1 MiB.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn cache_max_file_size(mut self, cache_max_file_size: usize) -> Self {
+        self.cache_max_file_size = Some(cache_max_file_size);
+        self
+    }
+
+    #[cfg(feature = "static-cache")]
+    /// Allow set how long a cached file is served before its modification
+    /// time is checked again.
+    ///
+    /// Defaults to 1 second.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn cache_revalidate_interval(mut self, interval: Duration) -> Self {
+        self.cache_revalidate_interval = Some(interval.as_millis() as u64);
+        self
+    }
+
     #[cfg(feature = "auth")]
     /// Allow set the authentication of the static path.
     ///
@@ -91,12 +282,73 @@ impl StaticPathConfigBuilder {
                 "Directory cannot be empty".to_string(),
             )));
         }
+        if self
+            .directories
+            .as_ref()
+            .is_some_and(|directories| {
+                directories
+                    .iter()
+                    .any(|directory| directory.is_empty())
+            })
+        {
+            return Err(VetisError::Config(ConfigError::Path(
+                "Directory cannot be empty".to_string(),
+            )));
+        }
+        if self
+            .file
+            .as_ref()
+            .is_some_and(|file| file.is_empty())
+        {
+            return Err(VetisError::Config(ConfigError::Path("File cannot be empty".to_string())));
+        }
+        if self
+            .directory_template
+            .as_ref()
+            .is_some_and(|directory_template| directory_template.is_empty())
+        {
+            return Err(VetisError::Config(ConfigError::Path(
+                "Directory template cannot be empty".to_string(),
+            )));
+        }
+        #[cfg(feature = "static-cache")]
+        if self.cache_size == Some(0) {
+            return Err(VetisError::Config(ConfigError::Path(
+                "Cache size cannot be 0".to_string(),
+            )));
+        }
+        #[cfg(feature = "static-cache")]
+        if self.cache_max_file_size == Some(0) {
+            return Err(VetisError::Config(ConfigError::Path(
+                "Cache max file size cannot be 0".to_string(),
+            )));
+        }
+        #[cfg(feature = "static-cache")]
+        if self.cache_revalidate_interval == Some(0) {
+            return Err(VetisError::Config(ConfigError::Path(
+                "Cache revalidate interval cannot be 0".to_string(),
+            )));
+        }
 
         Ok(StaticPathConfig {
             uri: self.uri,
             extensions: self.extensions,
             directory: self.directory,
+            directories: self.directories,
+            file: self.file,
             index_files: self.index_files,
+            directory_template: self.directory_template,
+            accept_ranges: self.accept_ranges,
+            mime_overrides: self.mime_overrides,
+            attachment: self.attachment,
+            serve_dotfiles: self.serve_dotfiles,
+            follow_symlinks: self.follow_symlinks,
+            #[cfg(feature = "static-cache")]
+            cache_size: self.cache_size,
+            #[cfg(feature = "static-cache")]
+            cache_max_file_size: self.cache_max_file_size,
+            #[cfg(feature = "static-cache")]
+            cache_revalidate_interval: self.cache_revalidate_interval,
             #[cfg(feature = "auth")]
             auth: self.auth,
         })
@@ -109,11 +361,34 @@ pub struct StaticPathConfig {
     uri: String,
     extensions: String,
     directory: String,
+    directories: Option<Vec<String>>,
+    file: Option<String>,
     index_files: Option<Vec<String>>,
+    directory_template: Option<String>,
+    #[serde(default = "default_accept_ranges")]
+    accept_ranges: bool,
+    mime_overrides: Option<HashMap<String, String>>,
+    #[serde(default)]
+    attachment: bool,
+    #[serde(default)]
+    serve_dotfiles: DotfilePolicy,
+    #[serde(default)]
+    follow_symlinks: bool,
+    #[cfg(feature = "static-cache")]
+    cache_size: Option<usize>,
+    #[cfg(feature = "static-cache")]
+    cache_max_file_size: Option<usize>,
+    #[cfg(feature = "static-cache")]
+    cache_revalidate_interval: Option<u64>,
     #[cfg(feature = "auth")]
     auth: Option<AuthType>,
 }
 
+#[cfg(feature = "static-files")]
+fn default_accept_ranges() -> bool {
+    true
+}
+
 #[cfg(feature = "static-files")]
 impl StaticPathConfig {
     /// Allow create a new `StaticPathConfigBuilder` with default settings.
@@ -126,7 +401,21 @@ impl StaticPathConfig {
             uri: "/".to_string(),
             extensions: ".html".to_string(),
             directory: ".".to_string(),
+            directories: None,
+            file: None,
             index_files: None,
+            directory_template: None,
+            accept_ranges: true,
+            mime_overrides: None,
+            attachment: false,
+            serve_dotfiles: DotfilePolicy::AllowWellKnown,
+            follow_symlinks: false,
+            #[cfg(feature = "static-cache")]
+            cache_size: None,
+            #[cfg(feature = "static-cache")]
+            cache_max_file_size: None,
+            #[cfg(feature = "static-cache")]
+            cache_revalidate_interval: None,
             #[cfg(feature = "auth")]
             auth: None,
         }
@@ -159,6 +448,24 @@ impl StaticPathConfig {
         &self.directory
     }
 
+    /// Returns directories
+    ///
+    /// # Returns
+    ///
+    /// * `&Option<Vec<String>>` - The fallback directories, searched after the main one.
+    pub fn directories(&self) -> &Option<Vec<String>> {
+        &self.directories
+    }
+
+    /// Returns file
+    ///
+    /// # Returns
+    ///
+    /// * `&Option<String>` - The single file served at the URI, if any.
+    pub fn file(&self) -> &Option<String> {
+        &self.file
+    }
+
     /// Returns index_files
     ///
     /// # Returns
@@ -168,6 +475,94 @@ impl StaticPathConfig {
         &self.index_files
     }
 
+    /// Returns directory_template
+    ///
+    /// # Returns
+    ///
+    /// * `&Option<String>` - The template rendered for directory requests, if any.
+    pub fn directory_template(&self) -> &Option<String> {
+        &self.directory_template
+    }
+
+    /// Returns accept_ranges
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether byte-range requests are supported.
+    pub fn accept_ranges(&self) -> bool {
+        self.accept_ranges
+    }
+
+    /// Returns mime_overrides
+    ///
+    /// # Returns
+    ///
+    /// * `&Option<HashMap<String, String>>` - The mime_overrides.
+    pub fn mime_overrides(&self) -> &Option<HashMap<String, String>> {
+        &self.mime_overrides
+    }
+
+    /// Returns attachment
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether files are served as downloads.
+    pub fn attachment(&self) -> bool {
+        self.attachment
+    }
+
+    /// Returns serve_dotfiles
+    ///
+    /// # Returns
+    ///
+    /// * `DotfilePolicy` - Which dotfiles are served.
+    pub fn serve_dotfiles(&self) -> DotfilePolicy {
+        self.serve_dotfiles
+    }
+
+    /// Returns follow_symlinks
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether symlinks leading out of the directories are followed.
+    pub fn follow_symlinks(&self) -> bool {
+        self.follow_symlinks
+    }
+
+    #[cfg(feature = "static-cache")]
+    /// Returns cache_size
+    ///
+    /// # Returns
+    ///
+    /// * `Option<usize>` - The in-memory cache size in bytes, if caching is enabled.
+    pub fn cache_size(&self) -> Option<usize> {
+        self.cache_size
+    }
+
+    #[cfg(feature = "static-cache")]
+    /// Returns cache_max_file_size
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The size of the largest cached file, in bytes.
+    pub fn cache_max_file_size(&self) -> usize {
+        self.cache_max_file_size
+            .unwrap_or(DEFAULT_CACHE_MAX_FILE_SIZE)
+    }
+
+    #[cfg(feature = "static-cache")]
+    /// Returns cache_revalidate_interval
+    ///
+    /// # Returns
+    ///
+    /// * `Duration` - How long a cached file is served before being checked again.
+    pub fn cache_revalidate_interval(&self) -> Duration {
+        Duration::from_millis(
+            self.cache_revalidate_interval
+                .unwrap_or(DEFAULT_CACHE_REVALIDATE_INTERVAL_MS),
+        )
+    }
+
     #[cfg(feature = "auth")]
     /// Returns auth
     ///
@@ -18,6 +18,8 @@
 //! }
 //! ```
 
+use bytes::Bytes;
+use http::{header::IntoHeaderName, HeaderMap, HeaderValue, StatusCode};
 use thiserror::Error;
 
 /// Main error type for VeTiS operations.
@@ -75,6 +77,194 @@ pub enum VetisError {
     /// Virtual host related errors
     #[error("Virtual host error: {0}")]
     VirtualHost(#[from] VirtualHostError),
+
+    /// I/O errors
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    /// HTTP message construction errors
+    #[error("HTTP error: {0}")]
+    Http(String),
+
+    /// Request or response body errors
+    #[error("Body error: {0}")]
+    Body(String),
+
+    /// An operation did not complete in time
+    #[error("Operation timed out")]
+    Timeout,
+
+    /// Request body exceeds the configured limit
+    #[error("Payload too large")]
+    PayloadTooLarge,
+
+    /// Request body uses a content coding the server can't decode
+    #[error("Unsupported content encoding: {0}")]
+    UnsupportedEncoding(String),
+
+    /// No upstream server is available to handle the request
+    #[error("Upstream unavailable")]
+    UpstreamUnavailable,
+
+    /// Buffering a body would exceed the server-wide memory budget
+    #[error("Memory budget exceeded")]
+    MemoryBudgetExceeded,
+
+    /// An error response chosen by a handler, sent to the client as is
+    #[error("Error response: {0}")]
+    Response(Box<HttpError>),
+
+    /// A connection didn't start with a valid PROXY protocol header
+    #[error("PROXY protocol error: {0}")]
+    ProxyProtocol(String),
+}
+
+impl VetisError {
+    /// Returns the HTTP status code that best describes the error.
+    ///
+    /// Used to turn errors into responses consistently across handler,
+    /// static file and proxy paths.
+    ///
+    /// # Returns
+    ///
+    /// * `StatusCode` - The status code to answer with.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::errors::VetisError;
+    ///
+    /// assert_eq!(VetisError::PayloadTooLarge.status_code(), http::StatusCode::PAYLOAD_TOO_LARGE);
+    /// ```
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            VetisError::Config(_)
+            | VetisError::Bind(_)
+            | VetisError::Start(_)
+            | VetisError::Stop(_)
+            | VetisError::Handler(_)
+            | VetisError::Tls(_)
+            | VetisError::Io(_)
+            | VetisError::Http(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            VetisError::NoInstances
+            | VetisError::UpstreamUnavailable
+            | VetisError::MemoryBudgetExceeded => StatusCode::SERVICE_UNAVAILABLE,
+            VetisError::VirtualHost(error) => error.status_code(),
+            VetisError::Body(_) | VetisError::ProxyProtocol(_) => StatusCode::BAD_REQUEST,
+            VetisError::Timeout => StatusCode::GATEWAY_TIMEOUT,
+            VetisError::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            VetisError::UnsupportedEncoding(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            VetisError::Response(error) => error.status(),
+        }
+    }
+}
+
+/// Error response returned by a handler, with its own status, headers and body.
+///
+/// Unlike other errors, which are answered with the status page of their
+/// status code, an `HttpError` is sent to the client exactly as built.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use http::StatusCode;
+/// use vetis::errors::HttpError;
+///
+/// async fn handler(request: Request) -> Result<Response, VetisError> {
+///     if request.headers().get("x-api-key").is_none() {
+///         return Err(HttpError::new(StatusCode::FORBIDDEN).body("nope").into());
+///     }
+///     Ok(/* response */)
+/// }
+/// ```
+#[derive(Debug, Clone, Error, PartialEq)]
+#[error("{status}")]
+pub struct HttpError {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl HttpError {
+    /// Creates an error response with the given status and an empty body.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - The status code sent to the client
+    pub fn new(status: StatusCode) -> Self {
+        Self { status, headers: HeaderMap::new(), body: Bytes::new() }
+    }
+
+    /// Adds a header to the error response.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The header name
+    /// * `value` - The header value
+    pub fn header<K>(mut self, key: K, value: HeaderValue) -> Self
+    where
+        K: IntoHeaderName,
+    {
+        self.headers
+            .append(key, value);
+        self
+    }
+
+    /// Sets the body of the error response.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - The response body, anything convertible into `Bytes`
+    pub fn body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Returns the status code of the error response.
+    ///
+    /// # Returns
+    ///
+    /// * `StatusCode` - The status code sent to the client.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Splits the error response into its status, headers and body.
+    pub(crate) fn into_parts(self) -> (StatusCode, HeaderMap, Bytes) {
+        (self.status, self.headers, self.body)
+    }
+}
+
+impl From<std::io::Error> for VetisError {
+    fn from(error: std::io::Error) -> Self {
+        VetisError::Io(error.to_string())
+    }
+}
+
+impl From<HttpError> for VetisError {
+    fn from(error: HttpError) -> Self {
+        VetisError::Response(Box::new(error))
+    }
+}
+
+impl From<http::Error> for VetisError {
+    fn from(error: http::Error) -> Self {
+        VetisError::Http(error.to_string())
+    }
+}
+
+#[cfg(feature = "rustls")]
+impl From<rustls::Error> for StartError {
+    fn from(error: rustls::Error) -> Self {
+        StartError::Tls(error.to_string())
+    }
+}
+
+#[cfg(feature = "rustls")]
+impl From<rustls::Error> for VetisError {
+    fn from(error: rustls::Error) -> Self {
+        VetisError::Start(StartError::from(error))
+    }
 }
 
 /// Configuration-related errors.
@@ -113,6 +303,12 @@ pub enum ConfigError {
     /// Invalid security configuration
     #[error("Invalid security config: {0}")]
     Security(String),
+    /// Invalid access control list configuration
+    #[error("Invalid ACL config: {0}")]
+    Acl(String),
+    /// Invalid runtime configuration
+    #[error("Invalid runtime config: {0}")]
+    Runtime(String),
 }
 
 /// Server startup errors.
@@ -171,6 +367,10 @@ pub enum VirtualHostError {
     #[error("Proxy error: {0}")]
     Proxy(String),
 
+    /// The request already went through this proxy
+    #[error("Proxy loop detected")]
+    ProxyLoop,
+
     /// Interface errors
     #[error("Interface error: {0}")]
     Interface(String),
@@ -179,6 +379,30 @@ pub enum VirtualHostError {
     Auth(String),
 }
 
+impl VirtualHostError {
+    /// Returns the HTTP status code that best describes the error.
+    ///
+    /// # Returns
+    ///
+    /// * `StatusCode` - The status code to answer with.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            VirtualHostError::NoVirtualHosts => StatusCode::NOT_FOUND,
+            VirtualHostError::Handler(HandlerError::Uri(_)) => StatusCode::BAD_REQUEST,
+            VirtualHostError::Handler(HandlerError::Handler(_)) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            VirtualHostError::File(FileError::NotFound) => StatusCode::NOT_FOUND,
+            VirtualHostError::File(FileError::InvalidMetadata) => StatusCode::INTERNAL_SERVER_ERROR,
+            VirtualHostError::File(FileError::InvalidRange) => StatusCode::RANGE_NOT_SATISFIABLE,
+            VirtualHostError::Proxy(_) => StatusCode::BAD_GATEWAY,
+            VirtualHostError::ProxyLoop => StatusCode::LOOP_DETECTED,
+            VirtualHostError::Interface(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            VirtualHostError::Auth(_) => StatusCode::UNAUTHORIZED,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Error, PartialEq)]
 pub enum HandlerError {
     #[error("Uri error: {0}")]
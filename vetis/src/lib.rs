@@ -139,9 +139,14 @@ compile_error!("http2 and http3 requires tokio-rust-tls or smol-rust-tls!");
 #[cfg(all(feature = "tokio-rt", feature = "smol-rt"))]
 compile_error!("Only one runtime feature can be enabled at a time.");
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
 
-use log::{error, info};
+use log::{error, info, warn};
 
 #[cfg(feature = "smol-rt")]
 use async_signal::Signals;
@@ -168,12 +173,37 @@ pub(crate) type VetisRwLock<T> = RwLock<T>;
 
 pub(crate) type VetisVirtualHosts = Arc<VetisRwLock<HashMap<(Arc<str>, u16), VirtualHost>>>;
 
+type ShutdownFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+#[cfg(feature = "admin")]
+use crate::config::server::admin::AdminConfig;
 use crate::{
-    config::server::ServerConfig,
+    config::server::{
+        EmptyHostsPolicy, ListenerConfig, Protocol, ServerConfig, ServerConfigBuilder,
+    },
     errors::{VetisError, VirtualHostError},
-    server::{virtual_host::VirtualHost, Server},
+    server::{conn::budget::MemoryBudget, virtual_host::VirtualHost, Server},
 };
 
+/// Features reported by the startup summary, with whether they were compiled in.
+const FEATURES: &[(&str, bool)] = &[
+    ("http1", cfg!(feature = "http1")),
+    ("http2", cfg!(feature = "http2")),
+    ("http3", cfg!(feature = "http3")),
+    ("tokio-rt", cfg!(feature = "tokio-rt")),
+    ("smol-rt", cfg!(feature = "smol-rt")),
+    ("static-files", cfg!(feature = "static-files")),
+    ("static-cache", cfg!(feature = "static-cache")),
+    ("embed", cfg!(feature = "embed")),
+    ("reverse-proxy", cfg!(feature = "reverse-proxy")),
+    ("auth", cfg!(feature = "auth")),
+    ("interface", cfg!(feature = "interface")),
+    ("compression-brotli", cfg!(feature = "compression-brotli")),
+    ("request-decompression", cfg!(feature = "request-decompression")),
+    ("admin", cfg!(feature = "admin")),
+    ("config-watch", cfg!(feature = "config-watch")),
+];
+
 pub mod config;
 pub mod errors;
 mod rt;
@@ -210,7 +240,12 @@ pub static CONFIG: &str = "vetis.toml";
 pub struct Vetis {
     config: ServerConfig,
     virtual_hosts: VetisVirtualHosts,
+    memory_budget: Option<Arc<MemoryBudget>>,
     instance: Option<server::http::HttpServer>,
+    // Behind a mutex so `Vetis` stays `Sync` without asking it of the future
+    shutdown: Mutex<Option<ShutdownFuture>>,
+    #[cfg(feature = "config-watch")]
+    config_watcher: Option<server::config_watch::ConfigWatcher>,
 }
 
 impl Vetis {
@@ -229,7 +264,43 @@ impl Vetis {
     /// let server = Vetis::new(config);
     /// ```
     pub fn new(config: ServerConfig) -> Vetis {
-        Vetis { config, virtual_hosts: Arc::new(VetisRwLock::new(HashMap::new())), instance: None }
+        let memory_budget = config
+            .max_buffered_bytes()
+            .map(|limit| Arc::new(MemoryBudget::new(limit)));
+
+        Vetis {
+            config,
+            virtual_hosts: Arc::new(VetisRwLock::new(HashMap::new())),
+            memory_budget,
+            instance: None,
+            shutdown: Mutex::new(None),
+            #[cfg(feature = "config-watch")]
+            config_watcher: None,
+        }
+    }
+
+    /// Creates a new `VetisBuilder` to set up a server in one go.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Vetis;
+    ///
+    /// let mut server = Vetis::builder()
+    ///     .add_listener(ListenerConfig::builder().port(8080).build()?)
+    ///     .add_virtual_host(virtual_host)
+    ///     .header("Server", "vetis")
+    ///     .build()?;
+    ///
+    /// server.run().await?;
+    /// ```
+    pub fn builder() -> VetisBuilder {
+        VetisBuilder {
+            server: ServerConfig::builder(),
+            virtual_hosts: Vec::new(),
+            headers: Vec::new(),
+            shutdown: None,
+        }
     }
 
     /// Adds a virtual host to the server.
@@ -271,7 +342,13 @@ impl Vetis {
     ///
     /// server.add_virtual_host(vhost).await;
     /// ```
-    pub async fn add_virtual_host(&mut self, virtual_host: VirtualHost) {
+    pub async fn add_virtual_host(&mut self, mut virtual_host: VirtualHost) {
+        // Every virtual host draws from the same budget
+        virtual_host.set_memory_budget(
+            self.memory_budget
+                .clone(),
+        );
+
         let key = (Arc::from(virtual_host.hostname()), virtual_host.port());
 
         self.virtual_hosts
@@ -280,6 +357,120 @@ impl Vetis {
             .insert(key, virtual_host);
     }
 
+    /// Removes a virtual host from the server, returning it if it was registered.
+    ///
+    /// The host stops serving requests right away, requests already routed
+    /// to it complete. Once no virtual host is left on its port, the
+    /// listeners on the port either keep answering `404 Not Found` or are
+    /// stopped, as set by [`ServerConfigBuilder::empty_hosts`]. Removing the
+    /// last virtual host of the server is logged as a warning.
+    ///
+    /// # Arguments
+    ///
+    /// * `hostname` - The hostname of the virtual host
+    /// * `port` - The port of the virtual host
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the listeners on the port fail to stop.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// server.start().await?;
+    ///
+    /// if server.remove_virtual_host("example.com", 80).await?.is_some() {
+    ///     println!("example.com is no longer served");
+    /// }
+    /// ```
+    pub async fn remove_virtual_host(
+        &mut self,
+        hostname: &str,
+        port: u16,
+    ) -> Result<Option<VirtualHost>, VetisError> {
+        let (removed, port_empty) = {
+            let mut virtual_hosts = self
+                .virtual_hosts
+                .write()
+                .await;
+
+            let removed = virtual_hosts.remove(&(Arc::from(hostname), port));
+            if removed.is_none() {
+                return Ok(None);
+            }
+            info!("Removed virtual host {}:{}", hostname, port);
+
+            if virtual_hosts.is_empty() {
+                warn!("Removed the last virtual host, no requests can be served");
+            }
+
+            let port_empty = !virtual_hosts
+                .keys()
+                .any(|(_, host_port)| *host_port == port);
+
+            (removed, port_empty)
+        };
+
+        if port_empty
+            && self
+                .config
+                .empty_hosts()
+                == EmptyHostsPolicy::StopListeners
+        {
+            if let Some(instance) = &mut self.instance {
+                instance
+                    .stop_listeners(port)
+                    .await?;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Reloads virtual hosts from a config file whenever it changes.
+    ///
+    /// The `virtual_hosts` section of the file is applied right away, adding
+    /// hosts that aren't registered yet, then again on every change: hosts
+    /// are added, replaced or removed to match the file, and the changes are
+    /// logged. A file that fails to parse is logged and ignored, keeping the
+    /// current hosts. Listeners and TLS certificates are set up on start, so
+    /// a host on a new port or with a new certificate needs a restart.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The YAML config file to watch
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or parsed, or can't be watched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let mut server = Vetis::new(config);
+    /// server.watch_config("vetis.yaml").await?;
+    /// server.run().await?;
+    /// ```
+    #[cfg(feature = "config-watch")]
+    pub async fn watch_config(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<(), VetisError> {
+        let watcher = server::config_watch::watch_config(
+            path.as_ref(),
+            self.virtual_hosts
+                .clone(),
+            self.memory_budget
+                .clone(),
+        )
+        .await?;
+
+        // Replacing a previous watcher stops it
+        self.config_watcher = Some(watcher);
+
+        Ok(())
+    }
+
     /// Returns a reference to the server configuration.
     ///
     /// This provides access to the listeners and global settings
@@ -299,7 +490,8 @@ impl Vetis {
     ///
     /// This method combines `start()` and graceful shutdown handling:
     /// 1. Starts the server with all configured virtual hosts
-    /// 2. Listens for shutdown signals (Ctrl+C on Tokio, SIGQUIT on Smol)
+    /// 2. Listens for shutdown signals (Ctrl+C on Tokio, SIGQUIT on Smol), or
+    ///    for the future given to [`VetisBuilder::shutdown_on`]
     /// 3. Stops the server gracefully
     ///
     /// # Errors
@@ -326,27 +518,85 @@ impl Vetis {
     /// }
     /// ```
     pub async fn run(&mut self) -> Result<(), VetisError> {
-        self.start().await?;
-
-        for listener in self
-            .config
-            .listeners()
-        {
-            info!("Server listening on port {}:{}", listener.interface(), listener.port());
+        let shutdown = self
+            .shutdown
+            .get_mut()
+            .ok()
+            .and_then(Option::take);
+        if let Some(shutdown) = shutdown {
+            return self
+                .run_until(shutdown)
+                .await;
         }
 
-        #[cfg(feature = "tokio-rt")]
-        let _ = tokio::signal::ctrl_c().await;
+        let interrupted = async {
+            #[cfg(feature = "tokio-rt")]
+            let _ = tokio::signal::ctrl_c().await;
 
-        #[cfg(feature = "smol-rt")]
-        {
-            use async_signal::Signal;
+            #[cfg(feature = "smol-rt")]
+            {
+                use async_signal::Signal;
 
-            let mut signals = Signals::new([Signal::Quit]).unwrap();
-            while let Some(signal) = signals.next().await {
-                low_level::emulate_default_handler(signal.unwrap() as i32).unwrap();
+                let mut signals = Signals::new([Signal::Quit]).unwrap();
+                while let Some(signal) = signals.next().await {
+                    low_level::emulate_default_handler(signal.unwrap() as i32).unwrap();
+                }
             }
-        }
+        };
+
+        self.run_until(interrupted)
+            .await
+    }
+
+    /// Starts the server and runs until the given future completes.
+    ///
+    /// Works like [`run`](Self::run), but waits on a shutdown future of the
+    /// caller instead of the process signals, for applications that decide
+    /// themselves when the server stops. With the `admin` feature,
+    /// `POST /admin/shutdown` stops the server as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `shutdown` - The future whose completion stops the server
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - No virtual hosts have been added
+    /// - Server fails to start
+    /// - Server fails to stop
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::{Vetis, config::ServerConfig};
+    ///
+    /// let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    ///
+    /// let mut server = Vetis::new(config);
+    /// // Add virtual hosts, hand `stop` to the rest of the application...
+    ///
+    /// server
+    ///     .run_until(async {
+    ///         let _ = stopped.await;
+    ///     })
+    ///     .await?;
+    /// ```
+    pub async fn run_until(
+        &mut self,
+        shutdown: impl Future<Output = ()>,
+    ) -> Result<(), VetisError> {
+        self.start().await?;
+
+        #[cfg(feature = "admin")]
+        futures_util::future::select(
+            std::pin::pin!(shutdown),
+            std::pin::pin!(self.shutdown_requested()),
+        )
+        .await;
+
+        #[cfg(not(feature = "admin"))]
+        shutdown.await;
 
         info!("\nStopping server...");
 
@@ -357,8 +607,10 @@ impl Vetis {
 
     /// Starts the server without blocking.
     ///
-    /// This method starts the server and returns immediately, allowing
-    /// you to perform additional setup or handle shutdown manually.
+    /// This method starts the server and returns once every listener is bound
+    /// and accepting connections, allowing you to perform additional setup or
+    /// handle shutdown manually. If any listener fails to bind, the listeners
+    /// already bound are stopped before the error is returned.
     ///
     /// # Errors
     ///
@@ -410,9 +662,111 @@ impl Vetis {
             .await?;
         self.instance = Some(server);
 
+        for listener in self
+            .config
+            .listeners()
+        {
+            match listener.name() {
+                Some(name) => info!(
+                    "Server listening on port {}:{} ({})",
+                    listener.interface(),
+                    listener.port(),
+                    name
+                ),
+                None => {
+                    info!("Server listening on port {}:{}", listener.interface(), listener.port())
+                }
+            }
+        }
+
+        self.log_summary()
+            .await;
+
         Ok(())
     }
 
+    /// Logs the enabled features, listeners and virtual hosts, one `key=value` line each.
+    async fn log_summary(&self) {
+        let features: Vec<&str> = FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| *name)
+            .collect();
+        info!("Startup summary: features={}", features.join(","));
+
+        let virtual_hosts = self
+            .virtual_hosts
+            .read()
+            .await;
+
+        for listener in self
+            .config
+            .listeners()
+        {
+            // TCP listeners speak TLS to clients once a virtual host on their port is secure
+            let tls = listener.protocol() == &Protocol::Http3
+                || virtual_hosts
+                    .values()
+                    .any(|virtual_host| {
+                        virtual_host.port() == listener.port() && virtual_host.is_secure()
+                    });
+
+            info!(
+                "Startup summary: listener name={} interface={} port={} protocol={:?} tls={}",
+                listener
+                    .name()
+                    .as_deref()
+                    .unwrap_or("-"),
+                listener.interface(),
+                listener.port(),
+                listener.protocol(),
+                if tls { "yes" } else { "no" }
+            );
+        }
+
+        let mut hosts: Vec<&VirtualHost> = virtual_hosts
+            .values()
+            .collect();
+        hosts.sort_by(|a, b| (a.port(), a.hostname()).cmp(&(b.port(), b.hostname())));
+
+        for virtual_host in hosts {
+            info!(
+                "Startup summary: virtual_host hostname={} port={} paths={} secure={}",
+                virtual_host.hostname(),
+                virtual_host.port(),
+                virtual_host
+                    .paths()
+                    .count(),
+                if virtual_host.is_secure() { "yes" } else { "no" }
+            );
+        }
+    }
+
+    /// Waits until a shutdown is requested with `POST /admin/shutdown`.
+    ///
+    /// [`Vetis::run`] already stops the server once this resolves; use it to
+    /// drain a server started with [`Vetis::start`]. Never resolves when the
+    /// server isn't running or has no admin listener.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// server.start().await?;
+    /// server.shutdown_requested().await;
+    /// server.stop().await?;
+    /// ```
+    #[cfg(feature = "admin")]
+    pub async fn shutdown_requested(&self) {
+        match &self.instance {
+            Some(instance) => {
+                instance
+                    .shutdown_requested()
+                    .await
+            }
+            None => std::future::pending().await,
+        }
+    }
+
     /// Stops the server gracefully.
     ///
     /// This method shuts down all listeners and waits for ongoing
@@ -451,3 +805,167 @@ impl Vetis {
         Ok(())
     }
 }
+
+/// Builder for a [`Vetis`] server, its listeners and virtual hosts.
+///
+/// Gathers what `ServerConfig::builder`, `Vetis::new` and
+/// `Vetis::add_virtual_host` otherwise set up separately.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use vetis::{config::ListenerConfig, Vetis};
+///
+/// let mut server = Vetis::builder()
+///     .add_listener(ListenerConfig::builder().port(8080).build()?)
+///     .add_virtual_host(virtual_host)
+///     .shutdown_on(async {
+///         let _ = stopped.await;
+///     })
+///     .build()?;
+///
+/// server.run().await?;
+/// ```
+pub struct VetisBuilder {
+    server: ServerConfigBuilder,
+    virtual_hosts: Vec<VirtualHost>,
+    headers: Vec<(String, String)>,
+    shutdown: Option<ShutdownFuture>,
+}
+
+impl VetisBuilder {
+    /// Adds a listener to the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener configuration
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn add_listener(mut self, listener: ListenerConfig) -> Self {
+        self.server = self
+            .server
+            .add_listener(listener);
+        self
+    }
+
+    /// Sets how many bytes of request bodies may be buffered across all connections.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_buffered_bytes` - The server-wide buffering budget
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn max_buffered_bytes(mut self, max_buffered_bytes: usize) -> Self {
+        self.server = self
+            .server
+            .max_buffered_bytes(max_buffered_bytes);
+        self
+    }
+
+    /// Enables the admin listener.
+    ///
+    /// # Arguments
+    ///
+    /// * `admin` - The admin listener configuration
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    #[cfg(feature = "admin")]
+    pub fn admin(mut self, admin: AdminConfig) -> Self {
+        self.server = self
+            .server
+            .admin(admin);
+        self
+    }
+
+    /// Adds a virtual host to the server.
+    ///
+    /// A virtual host added later with the same hostname and port replaces
+    /// the earlier one, as with [`Vetis::add_virtual_host`].
+    ///
+    /// # Arguments
+    ///
+    /// * `virtual_host` - The virtual host
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn add_virtual_host(mut self, virtual_host: VirtualHost) -> Self {
+        self.virtual_hosts
+            .push(virtual_host);
+        self
+    }
+
+    /// Adds a header to the responses of every virtual host.
+    ///
+    /// Default headers a virtual host sets itself take precedence.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The header name
+    /// * `value` - The header value
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn header(mut self, key: &str, value: &str) -> Self {
+        self.headers
+            .push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Stops a server started with [`Vetis::run`] once the future completes.
+    ///
+    /// Replaces the process signals `run` waits on by default, like
+    /// [`Vetis::run_until`] does.
+    ///
+    /// # Arguments
+    ///
+    /// * `shutdown` - The future whose completion stops the server
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder.
+    pub fn shutdown_on(mut self, shutdown: impl Future<Output = ()> + Send + 'static) -> Self {
+        self.shutdown = Some(Box::pin(shutdown));
+        self
+    }
+
+    /// Creates the server, ready to be started.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vetis, VetisError>` - The server, or an error if the server configuration is invalid.
+    pub fn build(self) -> Result<Vetis, VetisError> {
+        let config = self
+            .server
+            .build()?;
+
+        let mut vetis = Vetis::new(config);
+        vetis.shutdown = Mutex::new(self.shutdown);
+
+        // Nothing else holds the lock yet, so hosts are added without awaiting
+        let mut virtual_hosts = HashMap::with_capacity(
+            self.virtual_hosts
+                .len(),
+        );
+        for mut virtual_host in self.virtual_hosts {
+            virtual_host.inherit_headers(&self.headers);
+            virtual_host.set_memory_budget(
+                vetis
+                    .memory_budget
+                    .clone(),
+            );
+            let key = (Arc::from(virtual_host.hostname()), virtual_host.port());
+            virtual_hosts.insert(key, virtual_host);
+        }
+        vetis.virtual_hosts = Arc::new(VetisRwLock::new(virtual_hosts));
+
+        Ok(vetis)
+    }
+}
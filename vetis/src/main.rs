@@ -17,7 +17,10 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
 use std::{error::Error, fs::read_to_string, path::Path};
 use vetis::{
-    config::server::{virtual_host::VirtualHostConfig, ServerConfig},
+    config::{
+        runtime::RuntimeConfig,
+        server::{virtual_host::VirtualHostConfig, ServerConfig},
+    },
     server::virtual_host::VirtualHost,
     Vetis,
 };
@@ -25,8 +28,8 @@ use vetis::{
 #[derive(Deserialize)]
 pub struct VetisServerConfig {
     log_level: String,
-    worker_threads: usize,
-    max_blocking_threads: usize,
+    #[serde(flatten)]
+    runtime: RuntimeConfig,
     server: ServerConfig,
     virtual_hosts: Vec<VirtualHostConfig>,
 }
@@ -54,6 +57,7 @@ struct Args {
 }
 
 async fn run(
+    config_path: String,
     server_config: ServerConfig,
     virtual_hosts_config: Vec<VirtualHostConfig>,
 ) -> Result<(), Box<dyn Error>> {
@@ -67,6 +71,13 @@ async fn run(
             .await;
     }
 
+    #[cfg(feature = "config-watch")]
+    server
+        .watch_config(&config_path)
+        .await?;
+    #[cfg(not(feature = "config-watch"))]
+    let _ = config_path;
+
     if let Err(e) = server.run().await {
         error!("Failed to start server: {}", e);
     }
@@ -78,6 +89,7 @@ fn init_runtime() -> Result<(), Box<dyn Error>> {
     let args = Args::parse();
     if let Some(config) = args.config {
         if Path::exists(Path::new(&config)) {
+            let config_path = config.clone();
             let file = read_to_string(&config);
             if let Err(e) = file {
                 return Err(e.into());
@@ -96,20 +108,9 @@ fn init_runtime() -> Result<(), Box<dyn Error>> {
                     .format_module_path(false)
                     .init();
 
-                    #[cfg(feature = "tokio-rt")]
-                    {
-                        let rt = tokio::runtime::Builder::new_multi_thread()
-                            .enable_all()
-                            .worker_threads(config.worker_threads)
-                            .max_blocking_threads(config.max_blocking_threads)
-                            .build()?;
-                        rt.block_on(async { run(config.server, config.virtual_hosts).await })?;
-                    }
-
-                    #[cfg(feature = "smol-rt")]
-                    {
-                        smol::block_on(async { run(config.server, config.virtual_hosts).await })?;
-                    }
+                    config
+                        .runtime
+                        .block_on(run(config_path, config.server, config.virtual_hosts))??;
                 } else {
                     eprintln!(
                         "Failed to parse config file: {}",
@@ -0,0 +1,195 @@
+//! Internal admin listener serving JSON introspection endpoints.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use http::{header, HeaderValue, Method, StatusCode};
+use serde::Serialize;
+
+use crate::{
+    config::server::{admin::AdminConfig, virtual_host::VirtualHostConfig, ListenerConfig},
+    errors::VetisError,
+    server::{
+        conn::{metrics::ListenerMetrics, shutdown::ShutdownTrigger},
+        http::{Request, Response},
+        virtual_host::{
+            handler_fn,
+            path::{HandlerPath, Path},
+            VirtualHost,
+        },
+    },
+    VetisRwLock, VetisVirtualHosts,
+};
+
+#[derive(Serialize)]
+struct PathInfo<'a> {
+    uri: &'a str,
+    kind: &'static str,
+}
+
+#[derive(Serialize)]
+struct VirtualHostInfo<'a> {
+    hostname: &'a str,
+    port: u16,
+    secure: bool,
+    paths: Vec<PathInfo<'a>>,
+}
+
+#[derive(Serialize)]
+struct ListenerInfo<'a> {
+    name: &'a Option<String>,
+    interface: &'a str,
+    port: u16,
+    protocol: String,
+    active_connections: usize,
+    in_flight_requests: usize,
+    total_streams: u64,
+    bytes_sent: u64,
+}
+
+/// State shared by the admin endpoints.
+struct AdminState {
+    virtual_hosts: VetisVirtualHosts,
+    listeners: Vec<(ListenerConfig, Arc<ListenerMetrics>)>,
+    shutdown: Mutex<Option<ShutdownTrigger>>,
+}
+
+fn json_response<T: Serialize>(status: StatusCode, value: &T) -> Result<Response, VetisError> {
+    let body = serde_json::to_vec(value).map_err(|e| VetisError::Body(e.to_string()))?;
+
+    Ok(Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, HeaderValue::from_static("application/json"))
+        .bytes(body))
+}
+
+fn method_not_allowed(allowed: &'static str) -> Response {
+    Response::builder()
+        .status(StatusCode::METHOD_NOT_ALLOWED)
+        .header(header::ALLOW, HeaderValue::from_static(allowed))
+        .text("Method not allowed")
+}
+
+async fn serve_vhosts(state: &AdminState) -> Result<Response, VetisError> {
+    let virtual_hosts = state
+        .virtual_hosts
+        .read()
+        .await;
+
+    let mut hosts: Vec<VirtualHostInfo> = virtual_hosts
+        .values()
+        .map(|virtual_host| VirtualHostInfo {
+            hostname: virtual_host.hostname(),
+            port: virtual_host.port(),
+            secure: virtual_host.is_secure(),
+            paths: virtual_host
+                .paths()
+                .map(|path| PathInfo { uri: path.uri(), kind: path.kind() })
+                .collect(),
+        })
+        .collect();
+    hosts.sort_by(|a, b| (a.port, a.hostname).cmp(&(b.port, b.hostname)));
+
+    json_response(StatusCode::OK, &hosts)
+}
+
+fn serve_metrics(state: &AdminState) -> Result<Response, VetisError> {
+    let listeners: Vec<ListenerInfo> = state
+        .listeners
+        .iter()
+        .map(|(config, metrics)| ListenerInfo {
+            name: config.name(),
+            interface: config.interface(),
+            port: config.port(),
+            protocol: format!("{:?}", config.protocol()),
+            active_connections: metrics.active_connections(),
+            in_flight_requests: metrics.in_flight_requests(),
+            total_streams: metrics.total_streams(),
+            bytes_sent: metrics.bytes_sent(),
+        })
+        .collect();
+
+    json_response(StatusCode::OK, &listeners)
+}
+
+fn serve_shutdown(state: &AdminState) -> Result<Response, VetisError> {
+    let trigger = state
+        .shutdown
+        .lock()
+        .ok()
+        .and_then(|mut shutdown| shutdown.take());
+
+    if let Some(trigger) = trigger {
+        trigger.trigger();
+    }
+
+    json_response(StatusCode::ACCEPTED, &HashMap::from([("status", "shutting down")]))
+}
+
+async fn serve(state: Arc<AdminState>, request: Request) -> Result<Response, VetisError> {
+    match request.uri().path() {
+        "/admin/vhosts" if request.method() == Method::GET => serve_vhosts(&state).await,
+        "/admin/metrics" if request.method() == Method::GET => serve_metrics(&state),
+        "/admin/shutdown" if request.method() == Method::POST => serve_shutdown(&state),
+        "/admin/vhosts" | "/admin/metrics" => Ok(method_not_allowed("GET")),
+        "/admin/shutdown" => Ok(method_not_allowed("POST")),
+        _ => Ok(Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .text("Not found")),
+    }
+}
+
+/// Creates the virtual hosts served by the admin listener.
+///
+/// The endpoints answer for `localhost` and for the admin interface address,
+/// so they can be reached by name or by IP.
+///
+/// # Arguments
+///
+/// * `config` - The admin listener configuration
+/// * `virtual_hosts` - The virtual hosts of the server, listed by `/admin/vhosts`
+/// * `listeners` - The listeners of the server and their metrics, listed by `/admin/metrics`
+/// * `shutdown` - The trigger fired by `/admin/shutdown`
+///
+/// # Returns
+///
+/// * `Result<VetisVirtualHosts, VetisError>` - The admin virtual host registry.
+pub(crate) fn admin_virtual_hosts(
+    config: &AdminConfig,
+    virtual_hosts: VetisVirtualHosts,
+    listeners: Vec<(ListenerConfig, Arc<ListenerMetrics>)>,
+    shutdown: ShutdownTrigger,
+) -> Result<VetisVirtualHosts, VetisError> {
+    let state =
+        Arc::new(AdminState { virtual_hosts, listeners, shutdown: Mutex::new(Some(shutdown)) });
+
+    let mut hostnames = vec!["localhost"];
+    if !matches!(config.interface(), "localhost" | "0.0.0.0" | "::") {
+        hostnames.push(config.interface());
+    }
+
+    let mut admin_hosts = HashMap::new();
+    for hostname in hostnames {
+        // The admin endpoints serve no files, any existing directory will do
+        let host_config = VirtualHostConfig::builder()
+            .hostname(hostname)
+            .port(config.port())
+            .root_directory(".")
+            .build()?;
+
+        let state = state.clone();
+        let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/admin")
+                .handler(handler_fn(move |request| serve(state.clone(), request)))
+                .build()?,
+        );
+
+        admin_hosts.insert((Arc::from(hostname), config.port()), virtual_host);
+    }
+
+    Ok(Arc::new(VetisRwLock::new(admin_hosts)))
+}
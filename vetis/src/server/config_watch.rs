@@ -0,0 +1,234 @@
+//! Reloading of virtual hosts from a watched config file.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use crossfire::{mpsc, AsyncRx};
+use log::{error, info, warn};
+use notify::{EventKind, RecursiveMode, Watcher};
+use rt_gate::spawn_worker;
+use serde_yaml_ng::Value;
+
+use crate::{
+    config::server::virtual_host::VirtualHostConfig,
+    errors::{ConfigError, VetisError},
+    server::{conn::budget::MemoryBudget, virtual_host::VirtualHost},
+    utils::time::sleep,
+    VetisVirtualHosts,
+};
+
+/// How long to wait for an editor to finish writing before reloading.
+const SETTLE_DELAY: Duration = Duration::from_millis(100);
+
+type HostKey = (Arc<str>, u16);
+
+/// Keeps a config file watched, the background reload stops once dropped.
+pub(crate) struct ConfigWatcher {
+    _watcher: notify::RecommendedWatcher,
+}
+
+/// Applies the virtual host section of a config file to the shared map.
+struct Reloader {
+    path: PathBuf,
+    virtual_hosts: VetisVirtualHosts,
+    memory_budget: Option<Arc<MemoryBudget>>,
+    /// Entries of the file the last time it was applied, by host
+    loaded: HashMap<HostKey, Value>,
+}
+
+/// Starts watching a config file, reloading its virtual hosts on change.
+///
+/// The file is applied once right away, adding the virtual hosts it lists
+/// that aren't registered yet. On every later change, hosts that are new
+/// are added, hosts whose entry changed are replaced and hosts whose entry
+/// was removed are dropped. Hosts registered by other means are left alone.
+/// A file that can't be parsed is logged and the current hosts are kept.
+///
+/// # Arguments
+///
+/// * `path` - The YAML config file, with a `virtual_hosts` section
+/// * `virtual_hosts` - The virtual hosts of the server
+/// * `memory_budget` - The server memory budget handed to added hosts
+///
+/// # Returns
+///
+/// * `Result<ConfigWatcher, VetisError>` - The watcher, or an error if the file is invalid or can't be watched.
+pub(crate) async fn watch_config(
+    path: &Path,
+    virtual_hosts: VetisVirtualHosts,
+    memory_budget: Option<Arc<MemoryBudget>>,
+) -> Result<ConfigWatcher, VetisError> {
+    let mut reloader =
+        Reloader { path: path.to_path_buf(), virtual_hosts, memory_budget, loaded: HashMap::new() };
+
+    let entries = reloader
+        .parse()
+        .map_err(|e| VetisError::Config(ConfigError::VirtualHost(e)))?;
+    reloader
+        .apply(entries, true)
+        .await;
+
+    // Editors often replace the file instead of writing it, so the
+    // directory is watched and events are matched by file name
+    let directory = match path.parent() {
+        Some(parent)
+            if !parent
+                .as_os_str()
+                .is_empty() =>
+        {
+            parent.to_path_buf()
+        }
+        _ => PathBuf::from("."),
+    };
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_os_string());
+
+    let (sender, receiver) = mpsc::unbounded_async::<()>();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        if event
+            .paths
+            .iter()
+            .any(|changed| changed.file_name() == file_name.as_deref())
+        {
+            let _ = sender.send(());
+        }
+    })
+    .map_err(|e| VetisError::Io(e.to_string()))?;
+
+    watcher
+        .watch(&directory, RecursiveMode::NonRecursive)
+        .map_err(|e| VetisError::Io(e.to_string()))?;
+
+    spawn_worker(reload_on_change(reloader, receiver));
+
+    Ok(ConfigWatcher { _watcher: watcher })
+}
+
+/// Reloads the file after each burst of changes, until the watcher is dropped.
+async fn reload_on_change(mut reloader: Reloader, receiver: AsyncRx<mpsc::List<()>>) {
+    while receiver
+        .recv()
+        .await
+        .is_ok()
+    {
+        sleep(SETTLE_DELAY).await;
+        while receiver
+            .try_recv()
+            .is_ok()
+        {}
+
+        match reloader.parse() {
+            Ok(entries) => {
+                reloader
+                    .apply(entries, false)
+                    .await
+            }
+            Err(e) => error!(
+                "Ignoring invalid config {}, keeping the current virtual hosts: {}",
+                reloader
+                    .path
+                    .display(),
+                e
+            ),
+        }
+    }
+}
+
+impl Reloader {
+    /// Reads the virtual host entries of the file.
+    fn parse(&self) -> Result<Vec<(HostKey, Value, VirtualHostConfig)>, String> {
+        let file = std::fs::read_to_string(&self.path).map_err(|e| e.to_string())?;
+        let document: Value = serde_yaml_ng::from_str(&file).map_err(|e| e.to_string())?;
+
+        let Some(Value::Sequence(hosts)) = document.get("virtual_hosts") else {
+            return Err("Missing virtual_hosts section".to_string());
+        };
+
+        let mut keys = HashSet::new();
+        let mut entries = Vec::with_capacity(hosts.len());
+        for entry in hosts {
+            let config: VirtualHostConfig =
+                serde_yaml_ng::from_value(entry.clone()).map_err(|e| e.to_string())?;
+            let key: HostKey = (Arc::from(config.hostname()), config.port());
+            if !keys.insert(key.clone()) {
+                return Err(format!("Duplicate virtual host {}:{}", key.0, key.1));
+            }
+            entries.push((key, entry.clone(), config));
+        }
+
+        Ok(entries)
+    }
+
+    /// Updates the shared map to match the entries, logging what changed.
+    async fn apply(&mut self, entries: Vec<(HostKey, Value, VirtualHostConfig)>, initial: bool) {
+        let mut loaded = HashMap::with_capacity(entries.len());
+        let mut changed = Vec::new();
+        for (key, value, config) in entries {
+            if self
+                .loaded
+                .get(&key)
+                != Some(&value)
+            {
+                changed.push((key.clone(), config));
+            }
+            loaded.insert(key, value);
+        }
+
+        // Hosts are built before taking the lock, so requests aren't held up
+        let changed: Vec<(HostKey, VirtualHost)> = changed
+            .into_iter()
+            .map(|(key, config)| {
+                let mut virtual_host = VirtualHost::new(config);
+                virtual_host.set_memory_budget(
+                    self.memory_budget
+                        .clone(),
+                );
+                (key, virtual_host)
+            })
+            .collect();
+
+        let mut virtual_hosts = self
+            .virtual_hosts
+            .write()
+            .await;
+
+        for key in self
+            .loaded
+            .keys()
+            .filter(|key| !loaded.contains_key(*key))
+        {
+            virtual_hosts.remove(key);
+            info!("Removed virtual host {}:{}", key.0, key.1);
+        }
+
+        for (key, virtual_host) in changed {
+            // Hosts the file lists when watching starts are usually registered already
+            if initial && virtual_hosts.contains_key(&key) {
+                continue;
+            }
+            let action = if virtual_hosts.contains_key(&key) { "Updated" } else { "Added" };
+            info!("{} virtual host {}:{}", action, key.0, key.1);
+            virtual_hosts.insert(key, virtual_host);
+        }
+
+        if virtual_hosts.is_empty() {
+            warn!(
+                "No virtual hosts left after reloading {}, no requests can be served",
+                self.path.display()
+            );
+        }
+
+        self.loaded = loaded;
+    }
+}
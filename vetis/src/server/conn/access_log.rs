@@ -0,0 +1,259 @@
+//! Access log lines for served requests.
+
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use http::{Method, Response, StatusCode, Uri};
+use hyper_body_utils::HttpBody;
+use log::info;
+
+use crate::{
+    config::server::virtual_host::VirtualHostConfig,
+    server::conn::{counting::count_bytes, listener::ConnectionInfo, request_id::RequestId},
+};
+
+/// Format used when a virtual host enables logging without a `log_format`.
+pub(crate) const DEFAULT_LOG_FORMAT: &str =
+    "%remote_addr %method %path %status %bytes %duration_ms %request_id %tags";
+
+const PLACEHOLDERS: [&str; 9] = [
+    "%remote_addr",
+    "%listener",
+    "%method",
+    "%path",
+    "%status",
+    "%bytes",
+    "%duration_ms",
+    "%request_id",
+    "%tags",
+];
+
+/// Response extension holding the tags a handler attached for the access log.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LogTags(pub(crate) Vec<(String, String)>);
+
+impl LogTags {
+    /// Renders the tags as `key=value` pairs separated by spaces, or `-` without tags.
+    fn format(&self) -> String {
+        if self.0.is_empty() {
+            return "-".to_string();
+        }
+
+        self.0
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// A served request, as recorded in the access log.
+pub(crate) struct AccessLogEntry {
+    pub(crate) remote_addr: SocketAddr,
+    pub(crate) listener: Option<Arc<str>>,
+    pub(crate) method: Method,
+    pub(crate) uri: Uri,
+    pub(crate) status: StatusCode,
+    pub(crate) bytes: u64,
+    pub(crate) duration: Duration,
+    pub(crate) request_id: RequestId,
+    pub(crate) tags: LogTags,
+}
+
+impl AccessLogEntry {
+    /// Renders the entry with the given format.
+    ///
+    /// Supported placeholders are `%remote_addr`, `%listener`, `%method`, `%path`,
+    /// `%status`, `%bytes`, `%duration_ms`, `%request_id` and `%tags`; anything
+    /// else is copied as is.
+    /// `%listener` is replaced by `-` for listeners without a name, and `%tags`
+    /// for responses without tags.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The log format.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The rendered log line.
+    pub(crate) fn format(&self, format: &str) -> String {
+        let mut line = String::with_capacity(format.len() + 32);
+        let mut rest = format;
+
+        while let Some(index) = rest.find('%') {
+            line.push_str(&rest[..index]);
+            rest = &rest[index..];
+
+            let placeholder = PLACEHOLDERS
+                .iter()
+                .find(|placeholder| rest.starts_with(*placeholder));
+
+            let Some(placeholder) = placeholder else {
+                line.push('%');
+                rest = &rest[1..];
+                continue;
+            };
+
+            match *placeholder {
+                "%remote_addr" => line.push_str(
+                    &self
+                        .remote_addr
+                        .to_string(),
+                ),
+                "%listener" => line.push_str(
+                    self.listener
+                        .as_deref()
+                        .unwrap_or("-"),
+                ),
+                "%method" => line.push_str(self.method.as_str()),
+                "%path" => line.push_str(
+                    self.uri
+                        .path_and_query()
+                        .map(|path_and_query| path_and_query.as_str())
+                        .unwrap_or("/"),
+                ),
+                "%status" => line.push_str(self.status.as_str()),
+                "%bytes" => line.push_str(
+                    &self
+                        .bytes
+                        .to_string(),
+                ),
+                "%request_id" => line.push_str(
+                    self.request_id
+                        .as_str(),
+                ),
+                "%tags" => line.push_str(&self.tags.format()),
+                _ => line.push_str(
+                    &self
+                        .duration
+                        .as_millis()
+                        .to_string(),
+                ),
+            }
+
+            rest = &rest[placeholder.len()..];
+        }
+
+        line.push_str(rest);
+        line
+    }
+}
+
+/// Picks one in every `rate` requests of a listener for the access log.
+#[derive(Debug)]
+pub(crate) struct LogSampler {
+    rate: u64,
+    requests: AtomicU64,
+}
+
+impl LogSampler {
+    /// Creates a sampler logging one in every `rate` requests.
+    pub(crate) fn new(rate: u64) -> Self {
+        Self { rate, requests: AtomicU64::new(0) }
+    }
+
+    /// Counts a request, returning whether it should be logged.
+    pub(crate) fn sample(&self) -> bool {
+        self.requests
+            .fetch_add(1, Ordering::Relaxed)
+            % self.rate
+            == 0
+    }
+}
+
+/// Returns the access log format of a virtual host.
+///
+/// # Arguments
+///
+/// * `config` - The virtual host configuration.
+///
+/// # Returns
+///
+/// * `Option<&str>` - The log format, or `None` if logging is disabled for the host.
+pub(crate) fn access_log_format(config: &VirtualHostConfig) -> Option<&str> {
+    if !config.enable_logging() {
+        return None;
+    }
+
+    Some(
+        config
+            .log_format()
+            .as_deref()
+            .unwrap_or(DEFAULT_LOG_FORMAT),
+    )
+}
+
+/// Logs the response once its body has been fully sent, if the virtual host
+/// has logging enabled.
+///
+/// Listeners with a log sample rate only log the requests picked by their
+/// sampler, plus every server error.
+///
+/// # Arguments
+///
+/// * `config` - The virtual host configuration.
+/// * `response` - The response being sent.
+/// * `connection_info` - The connection the request was received on.
+/// * `method` - The request method.
+/// * `uri` - The request URI.
+/// * `request_id` - The request identifier.
+/// * `started` - When the request started being processed.
+///
+/// # Returns
+///
+/// * `Response<HttpBody>` - The response, with its body wrapped for logging.
+pub(crate) fn with_access_log(
+    config: &VirtualHostConfig,
+    response: Response<HttpBody>,
+    connection_info: &ConnectionInfo,
+    method: Method,
+    uri: Uri,
+    request_id: &RequestId,
+    started: Instant,
+) -> Response<HttpBody> {
+    let Some(format) = access_log_format(config) else {
+        return response;
+    };
+
+    let status = response.status();
+    if !status.is_server_error()
+        && connection_info
+            .log_sampler
+            .as_ref()
+            .is_some_and(|sampler| !sampler.sample())
+    {
+        return response;
+    }
+
+    let format = format.to_string();
+    let remote_addr = connection_info.client_addr;
+    let listener = connection_info
+        .listener_name
+        .clone();
+    let request_id = request_id.clone();
+    let tags = response
+        .extensions()
+        .get::<LogTags>()
+        .cloned()
+        .unwrap_or_default();
+    count_bytes(response, move |bytes| {
+        let entry = AccessLogEntry {
+            remote_addr,
+            listener,
+            method,
+            uri,
+            status,
+            bytes,
+            duration: started.elapsed(),
+            request_id,
+            tags,
+        };
+        info!("{}", entry.format(&format));
+    })
+}
@@ -0,0 +1,85 @@
+//! Server-wide accounting of buffered request and response bodies.
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use crate::errors::VetisError;
+
+/// Byte budget shared by every connection of a server.
+///
+/// Paths that buffer bodies in memory reserve their bytes here, so many
+/// requests within their own limits can't exhaust memory together.
+#[derive(Debug)]
+pub(crate) struct MemoryBudget {
+    limit: usize,
+    used: AtomicUsize,
+}
+
+impl MemoryBudget {
+    /// Creates a budget allowing up to `limit` buffered bytes.
+    pub(crate) fn new(limit: usize) -> Self {
+        Self { limit, used: AtomicUsize::new(0) }
+    }
+
+    /// Returns the number of bytes currently reserved.
+    #[cfg(all(test, feature = "request-decompression"))]
+    pub(crate) fn used(&self) -> usize {
+        self.used
+            .load(Ordering::Relaxed)
+    }
+}
+
+/// Bytes reserved from a [`MemoryBudget`], released when dropped.
+///
+/// A reservation without a budget accepts any size, so buffering code
+/// doesn't need to care whether the server limits memory.
+#[derive(Debug, Default)]
+pub(crate) struct BudgetReservation {
+    budget: Option<Arc<MemoryBudget>>,
+    bytes: usize,
+}
+
+impl BudgetReservation {
+    /// Creates an empty reservation against `budget`.
+    pub(crate) fn new(budget: Option<Arc<MemoryBudget>>) -> Self {
+        Self { budget, bytes: 0 }
+    }
+
+    /// Reserves `bytes` more from the budget.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The number of bytes about to be buffered
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), VetisError>` - `VetisError::MemoryBudgetExceeded` when the budget can't fit them.
+    pub(crate) fn grow(&mut self, bytes: usize) -> Result<(), VetisError> {
+        let Some(budget) = &self.budget else {
+            return Ok(());
+        };
+
+        budget
+            .used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                used.checked_add(bytes)
+                    .filter(|used| *used <= budget.limit)
+            })
+            .map_err(|_| VetisError::MemoryBudgetExceeded)?;
+
+        self.bytes += bytes;
+        Ok(())
+    }
+}
+
+impl Drop for BudgetReservation {
+    fn drop(&mut self) {
+        if let Some(budget) = &self.budget {
+            budget
+                .used
+                .fetch_sub(self.bytes, Ordering::Relaxed);
+        }
+    }
+}
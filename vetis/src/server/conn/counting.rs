@@ -0,0 +1,85 @@
+//! Response bodies that count the bytes they send.
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use http::Response;
+use http_body_util::BodyExt;
+use hyper::body::{Body, Frame};
+use hyper_body_utils::HttpBody;
+
+/// Wraps a response body so `on_complete` receives the number of bytes sent.
+///
+/// `on_complete` runs once, when the body ends or, if the client goes away
+/// first, when it is dropped with the bytes sent so far.
+///
+/// # Arguments
+///
+/// * `response` - The response being sent.
+/// * `on_complete` - Called with the total number of body bytes.
+///
+/// # Returns
+///
+/// * `Response<HttpBody>` - The response, with its body wrapped for counting.
+pub(crate) fn count_bytes<F>(response: Response<HttpBody>, on_complete: F) -> Response<HttpBody>
+where
+    F: FnOnce(u64) + Send + Sync + Unpin + 'static,
+{
+    response.map(|body| {
+        HttpBody::Stream(
+            CountingBody { inner: body, bytes: 0, on_complete: Some(on_complete) }.boxed(),
+        )
+    })
+}
+
+/// Counts the data frames of a body and reports the total when done.
+pub(crate) struct CountingBody<F: FnOnce(u64)> {
+    inner: HttpBody,
+    bytes: u64,
+    on_complete: Option<F>,
+}
+
+impl<F: FnOnce(u64)> CountingBody<F> {
+    fn complete(&mut self) {
+        if let Some(on_complete) = self
+            .on_complete
+            .take()
+        {
+            on_complete(self.bytes);
+        }
+    }
+}
+
+impl<F: FnOnce(u64) + Unpin> Body for CountingBody<F> {
+    type Data = Bytes;
+
+    type Error = std::io::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let result = Pin::new(&mut self.inner).poll_frame(cx);
+
+        match &result {
+            Poll::Ready(Some(Ok(frame))) => {
+                if let Some(data) = frame.data_ref() {
+                    self.bytes += data.len() as u64;
+                }
+            }
+            Poll::Ready(_) => self.complete(),
+            Poll::Pending => {}
+        }
+
+        result
+    }
+}
+
+impl<F: FnOnce(u64)> Drop for CountingBody<F> {
+    fn drop(&mut self) {
+        self.complete();
+    }
+}
@@ -1,4 +1,6 @@
-use std::{future::Future, pin::Pin};
+use std::{future::Future, net::SocketAddr, pin::Pin, sync::Arc};
+
+use http::HeaderMap;
 
 #[cfg(any(feature = "http1", feature = "http2"))]
 use crate::server::conn::listener::tcp::TcpListener;
@@ -6,8 +8,12 @@ use crate::server::conn::listener::tcp::TcpListener;
 use crate::server::conn::listener::udp::UdpListener;
 
 use crate::{
-    config::server::{ListenerConfig, Protocol},
+    config::server::{AmbiguousLengthPolicy, ListenerConfig, Protocol},
     errors::VetisError,
+    server::{
+        conn::{access_log::LogSampler, metrics::ListenerMetrics},
+        http::TlsInfo,
+    },
     VetisVirtualHosts,
 };
 
@@ -17,6 +23,63 @@ pub(crate) mod tcp;
 #[cfg(feature = "http3")]
 pub(crate) mod udp;
 
+/// Details of an accepted connection, shared by the requests it carries.
+#[derive(Clone)]
+pub(crate) struct ConnectionInfo {
+    pub(crate) client_addr: SocketAddr,
+    pub(crate) listener_name: Option<Arc<str>>,
+    pub(crate) tls_info: Option<Arc<TlsInfo>>,
+    pub(crate) header_limits: HeaderLimits,
+    /// Whether an HTTP/1 connection serves more than one request.
+    #[cfg_attr(not(feature = "http1"), allow(dead_code))]
+    pub(crate) keep_alive: bool,
+    /// How requests with both `content-length` and `transfer-encoding` are answered.
+    #[cfg_attr(not(feature = "http1"), allow(dead_code))]
+    pub(crate) ambiguous_length: AmbiguousLengthPolicy,
+    pub(crate) log_sampler: Option<Arc<LogSampler>>,
+}
+
+/// Limits on the request headers accepted by a listener.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct HeaderLimits {
+    pub(crate) max_headers: Option<usize>,
+    pub(crate) max_header_bytes: Option<usize>,
+}
+
+impl HeaderLimits {
+    /// Returns the header limits configured on a listener.
+    pub(crate) fn from_config(config: &ListenerConfig) -> Self {
+        Self { max_headers: config.max_headers(), max_header_bytes: config.max_header_bytes() }
+    }
+
+    /// Returns whether the headers exceed the listener limits.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The request headers
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether the request must be answered with `431 Request Header Fields Too Large`.
+    pub(crate) fn exceeded(&self, headers: &HeaderMap) -> bool {
+        if self
+            .max_headers
+            .is_some_and(|max_headers| headers.len() > max_headers)
+        {
+            return true;
+        }
+
+        self.max_header_bytes
+            .is_some_and(|max_header_bytes| {
+                let header_bytes: usize = headers
+                    .iter()
+                    .map(|(name, value)| name.as_str().len() + value.len())
+                    .sum();
+                header_bytes > max_header_bytes
+            })
+    }
+}
+
 pub type ListenerResult<'a, T> = Pin<Box<dyn Future<Output = Result<T, VetisError>> + Send + 'a>>;
 
 pub trait Listener {
@@ -26,6 +89,8 @@ pub trait Listener {
 
     fn set_virtual_hosts(&mut self, virtual_hosts: VetisVirtualHosts);
 
+    fn metrics(&self) -> Arc<ListenerMetrics>;
+
     fn listen(&mut self) -> ListenerResult<'_, ()>;
 
     fn stop(&mut self) -> ListenerResult<'_, ()>;
@@ -67,6 +132,15 @@ impl Listener for ServerListener {
         }
     }
 
+    fn metrics(&self) -> Arc<ListenerMetrics> {
+        match self {
+            #[cfg(any(feature = "http1", feature = "http2"))]
+            ServerListener::Tcp(tcp_listener) => tcp_listener.metrics(),
+            #[cfg(feature = "http3")]
+            ServerListener::Udp(udp_listener) => udp_listener.metrics(),
+        }
+    }
+
     fn listen(&mut self) -> ListenerResult<'_, ()> {
         Box::pin(async move {
             match self {
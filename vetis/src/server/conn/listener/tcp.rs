@@ -1,16 +1,22 @@
 use std::{
     collections::HashMap,
     net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::pin,
     sync::Arc,
+    time::{Duration, Instant},
 };
 
-use http::header;
+use futures_util::future::{select, Either};
+
+use http::{header, HeaderValue};
+use http_body_util::BodyExt;
 use hyper::{body::Incoming, service::service_fn};
 
 use hyper_body_utils::HttpBody;
-use log::{debug, error, info};
+use log::{debug, error, trace, warn};
 
 use rt_gate::{spawn_server, spawn_worker, GateTask};
+use socket2::{SockRef, TcpKeepalive};
 
 #[cfg(feature = "smol-rt")]
 use peekable::future::AsyncPeekable;
@@ -29,9 +35,9 @@ use crate::rt::smol::SmolExecutor;
 use hyper_util::rt::TokioExecutor;
 
 #[cfg(feature = "smol-rt")]
-use smol::io::{AsyncRead, AsyncWrite};
+use smol::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 #[cfg(feature = "tokio-rt")]
-use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 
 #[cfg(all(feature = "tokio-rt", any(feature = "http1", feature = "http2")))]
 use hyper_util::rt::TokioIo;
@@ -43,14 +49,23 @@ use futures_rustls::TlsAcceptor;
 #[cfg(all(feature = "smol-rt", any(feature = "http1", feature = "http2")))]
 use smol_hyper::rt::FuturesIo;
 
+#[cfg(feature = "proxy-protocol")]
+use crate::server::conn::proxy_protocol::read_proxy_header;
 use crate::{
-    config::server::{ListenerConfig, Protocol},
+    config::server::{AmbiguousLengthPolicy, ListenerConfig, Protocol},
     errors::VetisError,
     server::{
-        conn::listener::{Listener, ListenerResult},
-        http::{static_response, Request},
+        conn::{
+            access_log::{with_access_log, LogSampler},
+            listener::{ConnectionInfo, HeaderLimits, Listener, ListenerResult},
+            metrics::{ConnectionGuard, ListenerMetrics},
+            request_id::RequestId,
+            shutdown::{shutdown_channel, ShutdownSignal, ShutdownTrigger},
+        },
+        http::{static_response, Request, TlsInfo},
         tls::TlsFactory,
     },
+    utils::time::timeout,
     VetisRwLock, VetisVirtualHosts,
 };
 
@@ -72,11 +87,53 @@ type VetisIo<T> = FuturesIo<T>;
 #[cfg(all(feature = "smol-rt", feature = "http2"))]
 type VetisExecutor = SmolExecutor;
 
+/// Time a connection gets to send its PROXY protocol header before it is dropped.
+#[cfg(feature = "proxy-protocol")]
+const PROXY_HEADER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time a connection refused over the cap gets to show whether it speaks TLS.
+const REFUSAL_PEEK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Response sent to plaintext HTTP/1 connections refused over the cap.
+const SERVICE_UNAVAILABLE: &[u8] =
+    b"HTTP/1.1 503 Service Unavailable\r\nconnection: close\r\ncontent-length: 0\r\n\r\n";
+
+/// Collects the details negotiated on a TLS connection.
+///
+/// # Arguments
+///
+/// * `connection` - The TLS connection
+///
+/// # Returns
+///
+/// * `TlsInfo` - The TLS details of the connection.
+fn tls_info(connection: &rustls::ServerConnection) -> TlsInfo {
+    TlsInfo {
+        sni: connection
+            .server_name()
+            .map(str::to_string),
+        alpn: connection
+            .alpn_protocol()
+            .map(<[u8]>::to_vec),
+        peer_certificates: connection
+            .peer_certificates()
+            .map(|certificates| {
+                certificates
+                    .iter()
+                    .map(|certificate| certificate.to_vec())
+                    .collect()
+            }),
+    }
+}
+
 /// TCP listener
 pub struct TcpListener {
     task: Option<GateTask>,
     config: ListenerConfig,
     virtual_hosts: VetisVirtualHosts,
+    metrics: Arc<ListenerMetrics>,
+    shutdown: Option<ShutdownTrigger>,
+    released: Option<ShutdownSignal>,
 }
 
 impl Listener for TcpListener {
@@ -90,7 +147,14 @@ impl Listener for TcpListener {
     ///
     /// * `Self` - A new `TcpListener` instance.
     fn new(config: ListenerConfig) -> Self {
-        Self { task: None, config, virtual_hosts: Arc::new(VetisRwLock::new(HashMap::new())) }
+        Self {
+            task: None,
+            config,
+            virtual_hosts: Arc::new(VetisRwLock::new(HashMap::new())),
+            metrics: Arc::new(ListenerMetrics::default()),
+            shutdown: None,
+            released: None,
+        }
     }
 
     /// Set the virtual hosts
@@ -102,6 +166,15 @@ impl Listener for TcpListener {
         self.virtual_hosts = virtual_hosts;
     }
 
+    /// Returns the connection and request metrics of this listener
+    ///
+    /// # Returns
+    ///
+    /// * `Arc<ListenerMetrics>` - The listener metrics.
+    fn metrics(&self) -> Arc<ListenerMetrics> {
+        self.metrics.clone()
+    }
+
     /// Listen for incoming connections
     ///
     /// # Returns
@@ -127,6 +200,14 @@ impl Listener for TcpListener {
                 }
             };
 
+            #[cfg(unix)]
+            let listener = match self.config.fd() {
+                Some(fd) => inherited_listener(fd)?,
+                None => VetisTcpListener::bind(addr)
+                    .await
+                    .map_err(|e| VetisError::Bind(e.to_string()))?,
+            };
+            #[cfg(not(unix))]
             let listener = VetisTcpListener::bind(addr)
                 .await
                 .map_err(|e| VetisError::Bind(e.to_string()))?;
@@ -152,14 +233,26 @@ impl Listener for TcpListener {
 
     /// Stop the listener
     ///
+    /// Open connections are asked to shut down gracefully, letting in-flight
+    /// requests complete before the connection is closed.
+    ///
     /// # Returns
     ///
     /// * `ListenerResult<'_, ()>` - A `ListenerResult` instance containing the result of the listener.
     fn stop(&mut self) -> ListenerResult<'_, ()> {
         let future = async move {
+            if let Some(shutdown) = self.shutdown.take() {
+                shutdown.trigger();
+            }
             if let Some(mut task) = self.task.take() {
                 task.cancel().await;
             }
+            // Cancelling may drop the accept loop later, wait until the socket is closed
+            if let Some(released) = self.released.take() {
+                released
+                    .wait()
+                    .await;
+            }
             Ok(())
         };
 
@@ -167,6 +260,88 @@ impl Listener for TcpListener {
     }
 }
 
+/// Returns a listener accepting on a socket already bound by a supervisor.
+///
+/// The descriptor is duplicated, the configured one stays open so the
+/// listener can be started again on it after a stop.
+///
+/// # Arguments
+///
+/// * `fd` - The file descriptor of the bound socket
+///
+/// # Returns
+///
+/// * `Result<VetisTcpListener, VetisError>` - The listener, or `VetisError::Bind` if the descriptor isn't usable.
+#[cfg(unix)]
+fn inherited_listener(fd: std::os::fd::BorrowedFd<'_>) -> Result<VetisTcpListener, VetisError> {
+    use std::os::fd::AsRawFd;
+
+    let fd = fd
+        .try_clone_to_owned()
+        .map_err(|e| {
+            VetisError::Bind(format!("Cannot use socket descriptor {}: {}", fd.as_raw_fd(), e))
+        })?;
+
+    let listener = std::net::TcpListener::from(fd);
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| VetisError::Bind(e.to_string()))?;
+
+    #[cfg(feature = "tokio-rt")]
+    let listener = VetisTcpListener::from_std(listener);
+    #[cfg(feature = "smol-rt")]
+    let listener = VetisTcpListener::try_from(listener);
+
+    listener.map_err(|e| VetisError::Bind(e.to_string()))
+}
+
+/// Returns the TCP keepalive parameters of accepted connections, or `None` when disabled.
+pub(crate) fn tcp_keepalive(config: &ListenerConfig) -> Option<TcpKeepalive> {
+    let keepalive = TcpKeepalive::new().with_time(config.tcp_keepalive()?);
+
+    #[cfg(not(any(target_os = "openbsd", target_os = "haiku", target_os = "solaris")))]
+    let keepalive = match config.tcp_keepalive_interval() {
+        Some(interval) => keepalive.with_interval(interval),
+        None => keepalive,
+    };
+
+    #[cfg(not(any(target_os = "openbsd", target_os = "haiku", target_os = "solaris")))]
+    let keepalive = match config.tcp_keepalive_retries() {
+        Some(retries) => keepalive.with_retries(retries),
+        None => keepalive,
+    };
+
+    Some(keepalive)
+}
+
+/// Applies the socket options of a listener to an accepted connection.
+///
+/// Options that can't be set are logged and left at the system default,
+/// the connection is still served.
+///
+/// # Arguments
+///
+/// * `socket` - The accepted connection
+/// * `nodelay` - Whether to disable Nagle's algorithm
+/// * `keepalive` - The TCP keepalive parameters, if enabled
+/// * `client_addr` - The client address, for logging
+pub(crate) fn apply_socket_options(
+    socket: SockRef<'_>,
+    nodelay: bool,
+    keepalive: Option<&TcpKeepalive>,
+    client_addr: SocketAddr,
+) {
+    if let Err(e) = socket.set_tcp_nodelay(nodelay) {
+        warn!("Cannot set TCP_NODELAY={} for {}: {:?}", nodelay, client_addr, e);
+    }
+
+    if let Some(keepalive) = keepalive {
+        if let Err(e) = socket.set_tcp_keepalive(keepalive) {
+            error!("Cannot enable TCP keepalive for {}: {:?}", client_addr, e);
+        }
+    }
+}
+
 /// Decompose the TCP listener into smaller, more manageable structs
 impl TcpListener {
     async fn handle_connections(
@@ -193,13 +368,54 @@ impl TcpListener {
             }
         };
         let tls_acceptor = VetisTlsAcceptor::from(Arc::new(tls_config));
+        let max_connections = self
+            .config
+            .max_concurrent_connections();
+        let metrics = self.metrics.clone();
+        let listener_name: Option<Arc<str>> = self
+            .config
+            .name()
+            .as_deref()
+            .map(Arc::from);
+        let header_limits = HeaderLimits::from_config(&self.config);
+        let log_sampler = self
+            .config
+            .log_sample_rate()
+            .map(|rate| Arc::new(LogSampler::new(rate)));
+        let keep_alive = self
+            .config
+            .http1_pipelining();
+        let ambiguous_length = self
+            .config
+            .ambiguous_length();
+        let keepalive = tcp_keepalive(&self.config);
+        let nodelay = self
+            .config
+            .tcp_nodelay();
+        #[cfg(feature = "proxy-protocol")]
+        let proxy_protocol = self
+            .config
+            .proxy_protocol();
+        let acl = self
+            .config
+            .acl()
+            .clone()
+            .map(Arc::new);
+        let (shutdown_trigger, shutdown) = shutdown_channel();
+        self.shutdown = Some(shutdown_trigger);
+        let (released_trigger, released) = shutdown_channel();
+        self.released = Some(released);
         let future = async move {
+            // Locals drop in reverse order, so the socket is closed before release is signaled
+            let _released = released_trigger;
+            let listener = listener;
             loop {
                 let result = listener
                     .accept()
                     .await;
 
-                let (stream, client_addr) = match result {
+                #[cfg_attr(not(feature = "proxy-protocol"), allow(unused_mut))]
+                let (mut stream, mut client_addr) = match result {
                     Ok(conn_info) => conn_info,
                     Err(e) => {
                         error!("Cannot accept connection: {:?}", e);
@@ -207,93 +423,137 @@ impl TcpListener {
                     }
                 };
 
-                // TODO: Check ACL before proceeding
+                let protocol = protocol.clone();
+                let port = port.clone();
+                let virtual_hosts = virtual_hosts.clone();
+                let tls_acceptor = tls_acceptor.clone();
+                let metrics = metrics.clone();
+                let listener_name = listener_name.clone();
+                let log_sampler = log_sampler.clone();
+                let keepalive = keepalive.clone();
+                let acl = acl.clone();
+                let shutdown = shutdown.clone();
 
-                let mut peekable = AsyncPeekable::from(stream);
-
-                let mut peeked = [0; 2];
-                let result = peekable
-                    .peek_exact(&mut peeked)
-                    .await;
+                // Everything the client can stall runs in the connection task, so a slow
+                // client never holds up the accept loop
+                spawn_worker(async move {
+                    // The header comes from the load balancer, ahead of anything the client sends
+                    #[cfg(feature = "proxy-protocol")]
+                    if proxy_protocol {
+                        match timeout(PROXY_HEADER_TIMEOUT, read_proxy_header(&mut stream)).await {
+                            Some(Ok(Some(source))) => client_addr = source,
+                            Some(Ok(None)) => {}
+                            Some(Err(e)) => {
+                                error!("Invalid PROXY protocol header from {}: {}", client_addr, e);
+                                return;
+                            }
+                            None => {
+                                warn!(
+                                    "Timed out reading PROXY protocol header from {}",
+                                    client_addr
+                                );
+                                return;
+                            }
+                        }
+                    }
 
-                if let Err(e) = result {
-                    error!("Cannot peek connection: {:?}", e);
-                    continue;
-                }
+                    if let Some(acl) = &acl {
+                        if !acl.is_allowed(client_addr.ip()) {
+                            error!("Connection from {} denied by ACL", client_addr);
+                            return;
+                        }
+                    }
 
-                let is_tls = peeked.starts_with(&[0x16, 0x03]);
+                    apply_socket_options(
+                        SockRef::from(&stream),
+                        nodelay,
+                        keepalive.as_ref(),
+                        client_addr,
+                    );
 
-                if is_tls {
-                    let tls_stream = tls_acceptor
-                        .accept(peekable)
-                        .await;
-
-                    let tls_stream = match tls_stream {
-                        Ok(tls_stream) => tls_stream,
-                        Err(e) => {
-                            error!("Cannot accept connection: {:?}", e);
-                            continue;
+                    let Some(connection_guard) = metrics.track_connection(max_connections) else {
+                        if let Some(refused) = metrics.refuse_connection() {
+                            warn!(
+                                "Too many connections, refused {} connection(s), latest from {}",
+                                refused, client_addr
+                            );
                         }
+                        refuse_connection(stream, &protocol).await;
+                        return;
                     };
 
-                    let io = VetisIo::new(tls_stream);
-                    match protocol {
-                        #[cfg(feature = "http1")]
-                        Protocol::Http1 => {
-                            let _ = handle_http1_request(
-                                port.clone(),
-                                io,
-                                virtual_hosts.clone(),
-                                client_addr,
-                            );
-                        }
-                        #[cfg(feature = "http2")]
-                        Protocol::Http2 => {
-                            let _ = handle_http2_request(
-                                port.clone(),
-                                io,
-                                virtual_hosts.clone(),
-                                client_addr,
-                            );
-                        }
-                        #[cfg(feature = "http3")]
-                        Protocol::Http3 => {
-                            // HTTP/3 is handled by UDP listener
-                        }
-                        _ => {
-                            panic!("Unsupported protocol");
-                        }
+                    let mut peekable = AsyncPeekable::from(stream);
+
+                    let mut peeked = [0; 2];
+                    let result = peekable
+                        .peek_exact(&mut peeked)
+                        .await;
+
+                    if let Err(e) = result {
+                        error!("Cannot peek connection: {:?}", e);
+                        return;
                     }
-                } else {
-                    let io = VetisIo::new(peekable);
-                    match protocol {
-                        #[cfg(feature = "http1")]
-                        Protocol::Http1 => {
-                            let _ = handle_http1_request(
-                                port.clone(),
-                                io,
-                                virtual_hosts.clone(),
-                                client_addr,
-                            );
-                        }
-                        #[cfg(feature = "http2")]
-                        Protocol::Http2 => {
-                            let _ = handle_http2_request(
-                                port.clone(),
-                                io,
-                                virtual_hosts.clone(),
-                                client_addr,
-                            );
-                        }
-                        #[cfg(feature = "http3")]
-                        Protocol::Http3 => {
-                            // HTTP/3 is handled by UDP listener
-                        }
-                        _ => {
-                            panic!("Unsupported protocol");
-                        }
+
+                    let is_tls = peeked.starts_with(&[0x16, 0x03]);
+
+                    if is_tls {
+                        let tls_stream = tls_acceptor
+                            .accept(peekable)
+                            .await;
+
+                        let tls_stream = match tls_stream {
+                            Ok(tls_stream) => tls_stream,
+                            Err(e) => {
+                                error!("Cannot accept connection: {:?}", e);
+                                return;
+                            }
+                        };
+
+                        let (_, connection) = tls_stream.get_ref();
+                        let connection_info = ConnectionInfo {
+                            client_addr,
+                            listener_name,
+                            tls_info: Some(Arc::new(tls_info(connection))),
+                            header_limits,
+                            keep_alive,
+                            ambiguous_length,
+                            log_sampler,
+                        };
+
+                        let io = VetisIo::new(tls_stream);
+                        serve_connection(
+                            protocol,
+                            port,
+                            io,
+                            virtual_hosts,
+                            connection_info,
+                            metrics,
+                            connection_guard,
+                            shutdown,
+                        );
+                    } else {
+                        let io = VetisIo::new(peekable);
+                        let connection_info = ConnectionInfo {
+                            client_addr,
+                            listener_name,
+                            tls_info: None,
+                            header_limits,
+                            keep_alive,
+                            ambiguous_length,
+                            log_sampler,
+                        };
+                        serve_connection(
+                            protocol,
+                            port,
+                            io,
+                            virtual_hosts,
+                            connection_info,
+                            metrics,
+                            connection_guard,
+                            shutdown,
+                        );
                     }
-                }
+                });
             }
         };
 
@@ -303,36 +563,295 @@ impl TcpListener {
     }
 }
 
+/// Answers a connection refused over the cap, so clients can tell overload from a network failure.
+///
+/// Plaintext HTTP/1 connections get a `503 Service Unavailable` closing the
+/// connection. TLS connections are closed right away, as the handshake would
+/// spend the resources the cap protects.
+async fn refuse_connection<S>(stream: S, protocol: &Protocol)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    if !matches!(protocol, Protocol::Http1) {
+        return;
+    }
+
+    let mut peekable = AsyncPeekable::from(stream);
+    let mut peeked = [0; 2];
+    let plaintext = timeout(REFUSAL_PEEK_TIMEOUT, peekable.peek_exact(&mut peeked))
+        .await
+        .is_some_and(|result| result.is_ok())
+        && !peeked.starts_with(&[0x16, 0x03]);
+    if !plaintext {
+        return;
+    }
+
+    if peekable
+        .write_all(SERVICE_UNAVAILABLE)
+        .await
+        .is_ok()
+    {
+        #[cfg(feature = "tokio-rt")]
+        let _ = peekable
+            .shutdown()
+            .await;
+        #[cfg(feature = "smol-rt")]
+        let _ = peekable
+            .close()
+            .await;
+    }
+}
+
+/// Serves HTTP requests on an accepted connection with the listener protocol.
+#[allow(clippy::too_many_arguments)]
+fn serve_connection<T>(
+    protocol: Protocol,
+    port: Arc<u16>,
+    io: VetisIo<T>,
+    virtual_hosts: VetisVirtualHosts,
+    connection_info: ConnectionInfo,
+    metrics: Arc<ListenerMetrics>,
+    connection_guard: ConnectionGuard,
+    shutdown: ShutdownSignal,
+) where
+    T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    match protocol {
+        #[cfg(feature = "http1")]
+        Protocol::Http1 => {
+            let _ = handle_http1_request(
+                port,
+                io,
+                virtual_hosts,
+                connection_info,
+                metrics,
+                connection_guard,
+                shutdown,
+            );
+        }
+        #[cfg(feature = "http2")]
+        Protocol::Http2 => {
+            let _ = handle_http2_request(
+                port,
+                io,
+                virtual_hosts,
+                connection_info,
+                metrics,
+                connection_guard,
+                shutdown,
+            );
+        }
+        #[cfg(feature = "http3")]
+        Protocol::Http3 => {
+            // HTTP/3 is handled by UDP listener
+        }
+        _ => {
+            panic!("Unsupported protocol");
+        }
+    }
+}
+
+/// Largest request body read and discarded to keep a connection alive after an early response.
+const MAX_DRAINED_BODY_SIZE: usize = 64 * 1024;
+
+/// Answers a request without handling it, reading its body first.
+///
+/// An HTTP/1 connection can only carry the next request once the body of
+/// the current one has been read. Bodies larger than `MAX_DRAINED_BODY_SIZE`,
+/// or that the client waits to send until `100 Continue`, are left unread and
+/// the connection is closed after the response instead.
+async fn reject_request(
+    req: http::Request<Incoming>,
+    mut response: http::Response<HttpBody>,
+) -> http::Response<HttpBody> {
+    let version = req.version();
+    let expects_continue = req
+        .headers()
+        .contains_key(header::EXPECT);
+
+    let mut body = req.into_body();
+    let mut drained = 0;
+    let fully_drained = !expects_continue
+        && loop {
+            match body.frame().await {
+                None => break true,
+                Some(Ok(frame)) => {
+                    if let Ok(data) = frame.into_data() {
+                        drained += data.len();
+                        if drained > MAX_DRAINED_BODY_SIZE {
+                            break false;
+                        }
+                    }
+                }
+                Some(Err(_)) => break false,
+            }
+        };
+
+    if !fully_drained && version <= http::Version::HTTP_11 {
+        response
+            .headers_mut()
+            .insert(header::CONNECTION, HeaderValue::from_static("close"));
+    }
+
+    response
+}
+
+/// Answers a request with the HTTP version it was received with.
 async fn process_request(
     req: http::Request<Incoming>,
     virtual_hosts: VetisVirtualHosts,
     port: Arc<u16>,
-    client_addr: SocketAddr,
+    connection_info: ConnectionInfo,
 ) -> Result<http::Response<HttpBody>, VetisError> {
-    let host = req
+    let version = req.version();
+
+    let mut response = route_request(req, virtual_hosts, port, connection_info).await?;
+    *response.version_mut() = version;
+
+    Ok(response)
+}
+
+/// Returns the lowercased hostname a request is addressed to.
+///
+/// Absolute-form requests (`GET http://example.com/ HTTP/1.1`) are addressed
+/// to their URI authority, which takes precedence over the `host` header as
+/// RFC 9112 requires. Other requests use the `host` header, or `localhost`
+/// without one. A `host` header that is empty, not UTF-8 or lacks a hostname
+/// returns `None`, rather than routing the request to a default host.
+fn request_host<B>(req: &http::Request<B>) -> Option<String> {
+    if let Some(authority) = req
+        .uri()
+        .authority()
+    {
+        return Some(
+            authority
+                .host()
+                .to_ascii_lowercase(),
+        );
+    }
+
+    let Some(host) = req
         .headers()
-        .get(header::HOST);
-
-    let host = if let Some(host) = host {
-        let host_port = host.to_str();
-        match host_port {
-            Ok(host_port) => Some(
-                host_port
-                    .split_once(':')
-                    .map(|(host, _)| host)
-                    .unwrap_or(host_port),
-            ),
-            Err(_) => Some("localhost"),
+        .get(header::HOST)
+    else {
+        return Some("localhost".to_string());
+    };
+
+    let authority = host
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<http::uri::Authority>()
+        .ok()?;
+
+    let host = authority.host();
+    if host.is_empty() {
+        return None;
+    }
+
+    Some(host.to_ascii_lowercase())
+}
+
+/// Removes leading and trailing ASCII whitespace from a header value.
+fn trim_whitespace(value: &[u8]) -> &[u8] {
+    let Some(start) = value
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+    else {
+        return &[];
+    };
+    let end = value
+        .iter()
+        .rposition(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(start);
+    &value[start..=end]
+}
+
+/// Returns whether the request framing cannot be trusted.
+///
+/// Several `content-length` values that differ are always ambiguous, as is a
+/// request carrying both `content-length` and `transfer-encoding` unless the
+/// listener prefers the latter, in which case `content-length` is dropped.
+///
+/// # Arguments
+///
+/// * `headers` - The request headers
+/// * `policy` - How the listener answers requests with both framing headers
+///
+/// # Returns
+///
+/// * `bool` - Whether the request must be answered with `400 Bad Request`.
+fn ambiguous_length(headers: &mut http::HeaderMap, policy: AmbiguousLengthPolicy) -> bool {
+    let mut lengths = headers
+        .get_all(header::CONTENT_LENGTH)
+        .iter()
+        .flat_map(|value| {
+            value
+                .as_bytes()
+                .split(|byte| *byte == b',')
+        })
+        .map(trim_whitespace);
+    let Some(length) = lengths.next() else {
+        return false;
+    };
+    if lengths.any(|other| other != length) {
+        return true;
+    }
+
+    if !headers.contains_key(header::TRANSFER_ENCODING) {
+        return false;
+    }
+
+    match policy {
+        AmbiguousLengthPolicy::Reject => true,
+        AmbiguousLengthPolicy::PreferTransferEncoding => {
+            headers.remove(header::CONTENT_LENGTH);
+            false
         }
-    } else {
-        match req
-            .uri()
-            .authority()
-        {
-            Some(auth) => Some(auth.host()),
-            None => Some("localhost"),
+    }
+}
+
+async fn route_request(
+    mut req: http::Request<Incoming>,
+    virtual_hosts: VetisVirtualHosts,
+    port: Arc<u16>,
+    connection_info: ConnectionInfo,
+) -> Result<http::Response<HttpBody>, VetisError> {
+    let started = Instant::now();
+
+    if connection_info
+        .header_limits
+        .exceeded(req.headers())
+    {
+        error!("Request headers from {} exceed the listener limits", connection_info.client_addr);
+        let response = static_response(
+            http::StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+            None,
+            "Request header fields too large".to_string(),
+        );
+        return Ok(reject_request(req, response).await);
+    }
+
+    if ambiguous_length(req.headers_mut(), connection_info.ambiguous_length) {
+        error!(
+            "Request from {} has ambiguous content-length and transfer-encoding",
+            connection_info.client_addr
+        );
+        let mut response = static_response(
+            http::StatusCode::BAD_REQUEST,
+            None,
+            "Ambiguous request length".to_string(),
+        );
+        // Where the body ends is unknown, so it is left unread and the connection closed
+        if req.version() <= http::Version::HTTP_11 {
+            response
+                .headers_mut()
+                .insert(header::CONNECTION, HeaderValue::from_static("close"));
         }
-    };
+        return Ok(response);
+    }
+
+    let host = request_host(&req);
 
     if let Some(host) = host {
         debug!("Serving request for host: {}", host);
@@ -340,11 +859,22 @@ async fn process_request(
             .read()
             .await;
 
-        let virtual_host = virtual_hosts.get(&(host.into(), *port.clone()));
+        let virtual_host = virtual_hosts.get(&(host.as_str().into(), *port.clone()));
 
         if let Some(virtual_host) = virtual_host {
+            trace!("Host {}:{} matched a virtual host", host, port);
+
             // TODO: Save client_addr in request, grab url from request for logging
-            let (parts, body) = req.into_parts();
+            let (mut parts, body) = req.into_parts();
+            if let Some(tls_info) = &connection_info.tls_info {
+                parts
+                    .extensions
+                    .insert(tls_info.clone());
+            }
+            let request_id = RequestId::assign(&mut parts.headers);
+            parts
+                .extensions
+                .insert(request_id.clone());
             let request = Request::from_parts(parts, HttpBody::from_incoming(body));
 
             let method = request
@@ -355,58 +885,47 @@ async fn process_request(
                 .uri()
                 .clone();
 
-            let vetis_response = virtual_host
-                .route(request)
-                .await?;
-
-            let mut response = vetis_response.into_inner();
-
-            let default_headers = virtual_host
-                .config()
-                .default_headers();
-            if let Some(default_headers) = default_headers {
-                for (key, value) in default_headers {
-                    let header_name = header::HeaderName::from_bytes(key.as_bytes());
-                    if header_name.is_err() {
-                        error!("Invalid header name: {}", key);
-                        continue;
-                    }
-                    let header_name = header_name.unwrap();
-
-                    let header_value = header::HeaderValue::from_str(value);
-                    if header_value.is_err() {
-                        error!("Invalid header value: {}", value);
-                        continue;
-                    }
-                    let header_value = header_value.unwrap();
-
-                    response
-                        .headers_mut()
-                        .insert(header_name, header_value);
+            let mut response = match virtual_host
+                .dispatch(request)
+                .await
+            {
+                Ok(response) => response.into_inner(),
+                Err(err) => {
+                    error!("[{}] Error executing request: {:?}", request_id, err);
+                    return Err(err);
                 }
-            }
+            };
+            request_id.tag_response(&mut response);
 
-            // TODO: Log request and its response status code (move it to oneshot channel?)
-            info!("{} {} {} {}", client_addr, method, uri, response.status());
+            let response = with_access_log(
+                virtual_host.config(),
+                response,
+                &connection_info,
+                method,
+                uri,
+                &request_id,
+                started,
+            );
 
             Ok::<http::Response<HttpBody>, VetisError>(response)
         } else {
+            trace!("Host {}:{} matched no virtual host", host, port);
             error!("Virtual host not found: {}", host);
             let response = static_response(
                 http::StatusCode::NOT_FOUND,
                 None,
                 "Virtual host not found".to_string(),
             );
-            Ok(response)
+            Ok(reject_request(req, response).await)
         }
     } else {
-        error!("Host not found in request");
+        error!("Malformed host header from {}", connection_info.client_addr);
         let response = static_response(
             http::StatusCode::BAD_REQUEST,
             None,
-            "Host not found in request".to_string(),
+            "Malformed host header".to_string(),
         );
-        Ok(response)
+        Ok(reject_request(req, response).await)
     }
 }
 
@@ -415,22 +934,55 @@ fn handle_http1_request<T>(
     port: Arc<u16>,
     io: VetisIo<T>,
     virtual_hosts: VetisVirtualHosts,
-    client_addr: SocketAddr,
+    connection_info: ConnectionInfo,
+    metrics: Arc<ListenerMetrics>,
+    connection_guard: ConnectionGuard,
+    shutdown: ShutdownSignal,
 ) -> Result<(), VetisError>
 where
     T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
 {
+    let max_headers = connection_info
+        .header_limits
+        .max_headers;
+    let keep_alive = connection_info.keep_alive;
+
     let service_fn = service_fn(move |req| {
         let value = virtual_hosts.clone();
         let port = port.clone();
-        async move { process_request(req, value, port, client_addr).await }
+        let connection_info = connection_info.clone();
+        let metrics = metrics.clone();
+        let request_guard = metrics.track_request();
+        async move {
+            let _request_guard = request_guard;
+            let response = process_request(req, value, port, connection_info).await?;
+            Ok::<_, VetisError>(metrics.count_response(response))
+        }
     });
 
     let future = async move {
-        if let Err(err) = http1::Builder::new()
-            .serve_connection(io, service_fn)
-            .await
-        {
+        let _connection_guard = connection_guard;
+        let mut builder = http1::Builder::new();
+        // hyper answers pipelined requests one at a time, in order; without
+        // keep-alive it closes the connection after the first response
+        builder.keep_alive(keep_alive);
+        // hyper answers requests with too many headers with a 431 itself
+        if let Some(max_headers) = max_headers {
+            builder.max_headers(max_headers);
+        }
+        let mut connection = pin!(builder.serve_connection(io, service_fn));
+
+        let result = match select(connection.as_mut(), pin!(shutdown.wait())).await {
+            Either::Left((result, _)) => result,
+            Either::Right(_) => {
+                connection
+                    .as_mut()
+                    .graceful_shutdown();
+                connection.await
+            }
+        };
+
+        if let Err(err) = result {
             error!("Error serving connection: {:?}", err);
         }
     };
@@ -441,11 +993,14 @@ where
 }
 
 #[cfg(feature = "http2")]
-pub fn handle_http2_request<T>(
+fn handle_http2_request<T>(
     port: Arc<u16>,
     io: VetisIo<T>,
     virtual_hosts: VetisVirtualHosts,
-    client_addr: SocketAddr,
+    connection_info: ConnectionInfo,
+    metrics: Arc<ListenerMetrics>,
+    connection_guard: ConnectionGuard,
+    shutdown: ShutdownSignal,
 ) -> Result<(), VetisError>
 where
     T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
@@ -453,14 +1008,32 @@ where
     let service_fn = service_fn(move |req| {
         let value = virtual_hosts.clone();
         let port = port.clone();
-        async move { process_request(req, value, port, client_addr).await }
+        let connection_info = connection_info.clone();
+        let metrics = metrics.clone();
+        let request_guard = metrics.track_request();
+        async move {
+            let _request_guard = request_guard;
+            let response = process_request(req, value, port, connection_info).await?;
+            Ok::<_, VetisError>(metrics.count_response(response))
+        }
     });
 
     let future = async move {
-        if let Err(err) = http2::Builder::new(VetisExecutor::new())
-            .serve_connection(io, service_fn)
-            .await
-        {
+        let _connection_guard = connection_guard;
+        let mut connection =
+            pin!(http2::Builder::new(VetisExecutor::new()).serve_connection(io, service_fn));
+
+        let result = match select(connection.as_mut(), pin!(shutdown.wait())).await {
+            Either::Left((result, _)) => result,
+            Either::Right(_) => {
+                connection
+                    .as_mut()
+                    .graceful_shutdown();
+                connection.await
+            }
+        };
+
+        if let Err(err) = result {
             error!("Error serving connection: {:?}", err);
         }
     };
@@ -1,37 +1,188 @@
 use std::{
     collections::HashMap,
+    fmt,
+    io::{self, IoSliceMut},
     net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::{pin, Pin},
     sync::Arc,
+    task::{Context, Poll},
+    time::Instant,
 };
 
 use bytes::Bytes;
-use futures_util::StreamExt;
+use futures_util::{
+    future::{select, Either},
+    FutureExt, StreamExt,
+};
 use h3::server::{Connection, RequestResolver};
 use h3_quinn::{
-    quinn::{self, crypto::rustls::QuicServerConfig},
+    quinn::{
+        self,
+        crypto::rustls::{HandshakeData, QuicServerConfig},
+        udp::{RecvMeta, Transmit},
+        AsyncUdpSocket, UdpPoller,
+    },
     Connection as QuinnConnection,
 };
 
 use hyper_body_utils::HttpBody;
-use log::{debug, error, info};
+use log::{debug, error, trace, warn};
 use rt_gate::{spawn_server, spawn_worker, GateTask};
+use rustls::pki_types::CertificateDer;
 
 use crate::{
-    config::server::ListenerConfig,
-    errors::{StartError::Tls, VetisError},
+    config::server::{http3::Http3Config, ListenerConfig},
+    errors::{ConfigError, StartError::Tls, VetisError},
     server::{
-        conn::listener::{Listener, ListenerResult},
-        http::{static_response, Request},
+        conn::{
+            access_log::{with_access_log, LogSampler},
+            listener::{ConnectionInfo, HeaderLimits, Listener, ListenerResult},
+            metrics::ListenerMetrics,
+            request_id::RequestId,
+            shutdown::{shutdown_channel, ShutdownSignal, ShutdownTrigger},
+        },
+        http::{static_response, EarlyData, Request, TlsInfo},
         tls::TlsFactory,
     },
     VetisRwLock, VetisVirtualHosts,
 };
 
+/// Largest HTTP/3 header section accepted when the listener sets no header size limit.
+const DEFAULT_MAX_FIELD_SECTION_SIZE: u64 = 64 * 1024;
+
+/// Builds the QUIC transport parameters from the listener HTTP/3 settings.
+///
+/// Unreliable datagrams are always refused, HTTP/3 requests never use them.
+///
+/// # Arguments
+///
+/// * `http3` - The HTTP/3 settings of the listener, if any
+///
+/// # Returns
+///
+/// * `Result<quinn::TransportConfig, VetisError>` - The transport configuration.
+fn transport_config(http3: Option<&Http3Config>) -> Result<quinn::TransportConfig, VetisError> {
+    let mut transport = quinn::TransportConfig::default();
+    transport.datagram_receive_buffer_size(None);
+
+    let Some(http3) = http3 else {
+        return Ok(transport);
+    };
+
+    if let Some(max_idle_timeout) = http3.max_idle_timeout() {
+        let max_idle_timeout = quinn::IdleTimeout::try_from(max_idle_timeout).map_err(|_| {
+            VetisError::Config(ConfigError::Listener("Max idle timeout is too large".to_string()))
+        })?;
+        transport.max_idle_timeout(Some(max_idle_timeout));
+    }
+
+    if let Some(max_concurrent_bidi_streams) = http3.max_concurrent_bidi_streams() {
+        transport.max_concurrent_bidi_streams(max_concurrent_bidi_streams.into());
+    }
+
+    if let Some(receive_window) = http3.receive_window() {
+        transport.receive_window(receive_window.into());
+    }
+
+    Ok(transport)
+}
+
+/// A UDP socket that signals once it is closed.
+///
+/// Open connections keep the socket of a QUIC endpoint bound after the
+/// accept loop stops, until the last of them is closed.
+struct ReleasingSocket {
+    // Fields drop in order, so the socket is closed before release is signaled
+    socket: Arc<dyn AsyncUdpSocket>,
+    _released: ShutdownTrigger,
+}
+
+impl fmt::Debug for ReleasingSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReleasingSocket")
+            .field("socket", &self.socket)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AsyncUdpSocket for ReleasingSocket {
+    fn create_io_poller(self: Arc<Self>) -> Pin<Box<dyn UdpPoller>> {
+        self.socket
+            .clone()
+            .create_io_poller()
+    }
+
+    fn try_send(&self, transmit: &Transmit) -> io::Result<()> {
+        self.socket
+            .try_send(transmit)
+    }
+
+    fn poll_recv(
+        &self,
+        cx: &mut Context,
+        bufs: &mut [IoSliceMut<'_>],
+        meta: &mut [RecvMeta],
+    ) -> Poll<io::Result<usize>> {
+        self.socket
+            .poll_recv(cx, bufs, meta)
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket
+            .local_addr()
+    }
+
+    fn max_transmit_segments(&self) -> usize {
+        self.socket
+            .max_transmit_segments()
+    }
+
+    fn max_receive_segments(&self) -> usize {
+        self.socket
+            .max_receive_segments()
+    }
+
+    fn may_fragment(&self) -> bool {
+        self.socket
+            .may_fragment()
+    }
+}
+
+/// Binds a QUIC endpoint whose socket fires `released` once it is closed.
+///
+/// # Arguments
+///
+/// * `server_config` - The QUIC server configuration
+/// * `addr` - The address to bind
+/// * `released` - The trigger fired when the socket is closed
+///
+/// # Returns
+///
+/// * `io::Result<quinn::Endpoint>` - The bound endpoint.
+fn bind_endpoint(
+    server_config: quinn::ServerConfig,
+    addr: SocketAddr,
+    released: ShutdownTrigger,
+) -> io::Result<quinn::Endpoint> {
+    let runtime =
+        quinn::default_runtime().ok_or_else(|| io::Error::other("no async runtime found"))?;
+    let socket = runtime.wrap_udp_socket(std::net::UdpSocket::bind(addr)?)?;
+    quinn::Endpoint::new_with_abstract_socket(
+        quinn::EndpointConfig::default(),
+        Some(server_config),
+        Arc::new(ReleasingSocket { socket, _released: released }),
+        runtime,
+    )
+}
+
 /// UDP listener
 pub struct UdpListener {
     config: ListenerConfig,
     task: Option<GateTask>,
     virtual_hosts: VetisVirtualHosts,
+    metrics: Arc<ListenerMetrics>,
+    shutdown: Option<ShutdownTrigger>,
+    released: Option<ShutdownSignal>,
 }
 
 impl Listener for UdpListener {
@@ -45,7 +196,14 @@ impl Listener for UdpListener {
     ///
     /// * `Self` - A new `UdpListener` instance.
     fn new(config: ListenerConfig) -> Self {
-        Self { config, task: None, virtual_hosts: Arc::new(VetisRwLock::new(HashMap::new())) }
+        Self {
+            config,
+            task: None,
+            virtual_hosts: Arc::new(VetisRwLock::new(HashMap::new())),
+            metrics: Arc::new(ListenerMetrics::default()),
+            shutdown: None,
+            released: None,
+        }
     }
 
     /// Allow set virtual hosts
@@ -57,6 +215,15 @@ impl Listener for UdpListener {
         self.virtual_hosts = virtual_hosts;
     }
 
+    /// Returns the connection and request metrics of this listener
+    ///
+    /// # Returns
+    ///
+    /// * `Arc<ListenerMetrics>` - The listener metrics.
+    fn metrics(&self) -> Arc<ListenerMetrics> {
+        self.metrics.clone()
+    }
+
     /// Listen for incoming connections
     ///
     /// # Returns
@@ -82,6 +249,24 @@ impl Listener for UdpListener {
                 }
             };
 
+            // QUIC has no plaintext mode, a listener without certificates would serve nothing
+            let port = self.config.port();
+            let secure = self
+                .virtual_hosts
+                .read()
+                .await
+                .iter()
+                .any(|((_, host_port), virtual_host)| {
+                    *host_port == port
+                        && virtual_host
+                            .config()
+                            .security()
+                            .is_some()
+                });
+            if !secure {
+                return Err(VetisError::Start(Tls("HTTP/3 requires TLS".to_string())));
+            }
+
             let tls_config = TlsFactory::create_tls_config(
                 self.virtual_hosts
                     .clone(),
@@ -89,25 +274,41 @@ impl Listener for UdpListener {
             )
             .await?;
 
-            if let Some(tls_config) = tls_config {
-                let quic_config = QuicServerConfig::try_from(tls_config)
-                    .map_err(|e| VetisError::Start(Tls(e.to_string())))?;
+            let Some(mut tls_config) = tls_config else {
+                return Err(VetisError::Start(Tls("HTTP/3 requires TLS".to_string())));
+            };
 
-                let server_config = quinn::ServerConfig::with_crypto(Arc::new(quic_config));
+            // QUIC only allows early data to be disabled or unbounded
+            let early_data = tls_config.max_early_data_size > 0;
+            if early_data {
+                tls_config.max_early_data_size = u32::MAX;
+            }
 
-                let endpoint = quinn::Endpoint::server(server_config, addr)
-                    .map_err(|e| VetisError::Bind(e.to_string()))?;
+            let quic_config = QuicServerConfig::try_from(tls_config)
+                .map_err(|e| VetisError::Start(Tls(e.to_string())))?;
 
-                let server_task = self
-                    .handle_connections(
-                        endpoint,
-                        self.virtual_hosts
-                            .clone(),
-                    )
-                    .await?;
+            let mut server_config = quinn::ServerConfig::with_crypto(Arc::new(quic_config));
+            server_config.transport_config(Arc::new(transport_config(
+                self.config
+                    .http3()
+                    .as_ref(),
+            )?));
 
-                self.task = Some(server_task);
-            }
+            let (released_trigger, released) = shutdown_channel();
+            let endpoint = bind_endpoint(server_config, addr, released_trigger)
+                .map_err(|e| VetisError::Bind(e.to_string()))?;
+            self.released = Some(released);
+
+            let server_task = self
+                .handle_connections(
+                    endpoint,
+                    self.virtual_hosts
+                        .clone(),
+                    early_data,
+                )
+                .await?;
+
+            self.task = Some(server_task);
 
             Ok(())
         };
@@ -116,14 +317,34 @@ impl Listener for UdpListener {
 
     /// Stop the listener
     ///
+    /// Open connections receive a `GOAWAY`, letting in-flight requests
+    /// complete before the connection is closed. Returns once the socket is
+    /// closed, unless requests are still in flight.
+    ///
     /// # Returns
     ///
     /// * `ListenerResult<'_, ()>` - A `ListenerResult` instance containing the result of the listener.
     fn stop(&mut self) -> ListenerResult<'_, ()> {
         Box::pin(async move {
+            if let Some(shutdown) = self.shutdown.take() {
+                shutdown.trigger();
+            }
             if let Some(mut task) = self.task.take() {
                 task.cancel().await;
             }
+            // The socket stays bound until the last connection is closed, which
+            // requests in flight hold open until they complete
+            if let Some(released) = self.released.take() {
+                if self
+                    .metrics
+                    .in_flight_requests()
+                    == 0
+                {
+                    released
+                        .wait()
+                        .await;
+                }
+            }
             Ok(())
         })
     }
@@ -134,8 +355,36 @@ impl UdpListener {
         &mut self,
         endpoint: quinn::Endpoint,
         virtual_hosts: VetisVirtualHosts,
+        early_data: bool,
     ) -> Result<GateTask, VetisError> {
         let port = self.config.port();
+        let max_connections = self
+            .config
+            .max_concurrent_connections();
+        let metrics = self.metrics.clone();
+        let listener_name: Option<Arc<str>> = self
+            .config
+            .name()
+            .as_deref()
+            .map(Arc::from);
+        let header_limits = HeaderLimits::from_config(&self.config);
+        // Header sections are bounded while decoded, before the listener limits are checked
+        let max_field_section_size = header_limits
+            .max_header_bytes
+            .map_or(DEFAULT_MAX_FIELD_SECTION_SIZE, |max_header_bytes| max_header_bytes as u64);
+        let ambiguous_length = self
+            .config
+            .ambiguous_length();
+        let log_sampler = self
+            .config
+            .log_sample_rate()
+            .map(|rate| Arc::new(LogSampler::new(rate)));
+        let acl = self
+            .config
+            .acl()
+            .clone();
+        let (shutdown_trigger, shutdown) = shutdown_channel();
+        self.shutdown = Some(shutdown_trigger);
         let task = spawn_server(async move {
             while let Some(new_conn) = endpoint
                 .accept()
@@ -143,11 +392,37 @@ impl UdpListener {
             {
                 let virtual_hosts = virtual_hosts.clone();
                 let addr = new_conn.remote_address();
+                if let Some(acl) = &acl {
+                    if !acl.is_allowed(addr.ip()) {
+                        error!("Connection from {} denied by ACL", addr);
+                        new_conn.refuse();
+                        continue;
+                    }
+                }
+                let Some(mut connection_guard) = metrics.track_connection(max_connections) else {
+                    if let Some(refused) = metrics.refuse_connection() {
+                        warn!(
+                            "Too many connections, refused {} connection(s), latest from {}",
+                            refused, addr
+                        );
+                    }
+                    new_conn.refuse();
+                    continue;
+                };
+                let metrics = metrics.clone();
+                let listener_name = listener_name.clone();
+                let log_sampler = log_sampler.clone();
+                let shutdown = shutdown.clone();
                 spawn_worker(async move {
-                    match new_conn.await {
-                        Ok(conn) => {
+                    match accept_connection(new_conn, early_data).await {
+                        Ok((conn, mut handshake)) => {
+                            let quic_conn = conn.clone();
                             let mut h3_conn: Connection<QuinnConnection, Bytes> =
-                                match Connection::new(QuinnConnection::new(conn)).await {
+                                match h3::server::builder()
+                                    .max_field_section_size(max_field_section_size)
+                                    .build(QuinnConnection::new(conn))
+                                    .await
+                                {
                                     Ok(conn) => conn,
                                     Err(err) => {
                                         error!("Cannot create connection: {:?}", err);
@@ -155,17 +430,76 @@ impl UdpListener {
                                     }
                                 };
 
+                            // Every request holds a clone, the signal fires once they all completed
+                            let (in_flight, requests_done) = shutdown_channel();
+                            let mut in_flight = Some(in_flight);
+                            let mut shutting_down = false;
                             loop {
-                                match h3_conn
-                                    .accept()
-                                    .await
-                                {
+                                let accepted = if shutting_down {
+                                    // The connection is closed once the in-flight requests completed
+                                    match select(pin!(h3_conn.accept()), pin!(requests_done.wait()))
+                                        .await
+                                    {
+                                        Either::Left((accepted, _)) => accepted,
+                                        Either::Right(_) => break,
+                                    }
+                                } else {
+                                    let accepted =
+                                        match select(pin!(h3_conn.accept()), pin!(shutdown.wait()))
+                                            .await
+                                        {
+                                            Either::Left((accepted, _)) => Some(accepted),
+                                            Either::Right(_) => None,
+                                        };
+
+                                    let Some(accepted) = accepted else {
+                                        shutting_down = true;
+                                        in_flight = None;
+                                        if let Err(err) = h3_conn
+                                            .shutdown(0)
+                                            .await
+                                        {
+                                            error!("Cannot shutdown connection: {:?}", err);
+                                            break;
+                                        }
+                                        continue;
+                                    };
+
+                                    accepted
+                                };
+
+                                match accepted {
                                     Ok(Some(resolver)) => {
+                                        connection_guard.add_stream();
+                                        let early_data = match handshake.as_mut() {
+                                            Some(pending) => {
+                                                let early_data = pending
+                                                    .now_or_never()
+                                                    .is_none();
+                                                if !early_data {
+                                                    handshake = None;
+                                                }
+                                                early_data
+                                            }
+                                            None => false,
+                                        };
+                                        let connection_info = ConnectionInfo {
+                                            client_addr: addr,
+                                            listener_name: listener_name.clone(),
+                                            tls_info: Some(Arc::new(tls_info(&quic_conn))),
+                                            header_limits,
+                                            keep_alive: true,
+                                            ambiguous_length,
+                                            log_sampler: log_sampler.clone(),
+                                        };
                                         let result = handle_http_request(
                                             port,
                                             resolver,
                                             virtual_hosts.clone(),
-                                            addr,
+                                            connection_info,
+                                            metrics.clone(),
+                                            early_data,
+                                            in_flight.clone(),
                                         );
 
                                         if let Err(err) = result {
@@ -181,6 +515,12 @@ impl UdpListener {
                                     }
                                 }
                             }
+
+                            debug!(
+                                "Connection from {} closed after {} streams",
+                                addr,
+                                connection_guard.streams()
+                            );
                         }
                         Err(err) => {
                             error!("Accepting connection failed: {:?}", err);
@@ -198,155 +538,249 @@ impl UdpListener {
     }
 }
 
+/// Accepts an incoming connection.
+///
+/// With early data enabled the connection is returned before its handshake
+/// completes, along with a future resolving once it does, so that requests
+/// sent as 0-RTT data are served and can be told apart.
+///
+/// # Arguments
+///
+/// * `incoming` - The incoming connection
+/// * `early_data` - Whether early data is accepted
+///
+/// # Returns
+///
+/// * `Result<(quinn::Connection, Option<quinn::ZeroRttAccepted>), quinn::ConnectionError>` - The connection and its pending handshake, if any.
+async fn accept_connection(
+    incoming: quinn::Incoming,
+    early_data: bool,
+) -> Result<(quinn::Connection, Option<quinn::ZeroRttAccepted>), quinn::ConnectionError> {
+    let connecting = incoming.accept()?;
+    if !early_data {
+        return Ok((connecting.await?, None));
+    }
+
+    match connecting.into_0rtt() {
+        Ok((connection, handshake)) => Ok((connection, Some(handshake))),
+        Err(connecting) => Ok((connecting.await?, None)),
+    }
+}
+
+/// Collects the TLS details negotiated on a QUIC connection.
+///
+/// The client certificates are only known once the handshake completes, so
+/// requests sent as early data never carry them.
+///
+/// # Arguments
+///
+/// * `connection` - The QUIC connection
+///
+/// # Returns
+///
+/// * `TlsInfo` - The TLS details of the connection.
+fn tls_info(connection: &quinn::Connection) -> TlsInfo {
+    let (sni, alpn) = match connection
+        .handshake_data()
+        .and_then(|data| {
+            data.downcast::<HandshakeData>()
+                .ok()
+        }) {
+        Some(handshake) => (handshake.server_name, handshake.protocol),
+        None => (None, None),
+    };
+
+    let peer_certificates = connection
+        .peer_identity()
+        .and_then(|identity| {
+            identity
+                .downcast::<Vec<CertificateDer<'static>>>()
+                .ok()
+        })
+        .map(|certificates| {
+            certificates
+                .iter()
+                .map(|certificate| certificate.to_vec())
+                .collect()
+        });
+
+    TlsInfo { sni, alpn, peer_certificates }
+}
+
 fn handle_http_request(
     port: u16,
     resolver: RequestResolver<QuinnConnection, Bytes>,
     virtual_hosts: VetisVirtualHosts,
-    client_addr: SocketAddr,
+    connection_info: ConnectionInfo,
+    metrics: Arc<ListenerMetrics>,
+    early_data: bool,
+    in_flight: Option<ShutdownTrigger>,
 ) -> Result<(), VetisError> {
     let virtual_hosts = virtual_hosts.clone();
     spawn_worker(async move {
-        let result = resolver
+        let _in_flight = in_flight;
+        let _request_guard = metrics.track_request();
+        let started = Instant::now();
+        // Malformed or oversized header frames fail here, h3 then resets the
+        // stream or closes the connection with the matching error code
+        let (req, stream) = match resolver
             .resolve_request()
-            .await;
-        if let Ok((req, stream)) = result {
-            let (mut send_stream, recv_stream) = stream.split();
-            let (parts, _) = req.into_parts();
-            let method = parts.method.clone();
-            let uri = parts.uri.clone();
-            let body = HttpBody::from_quic_server(recv_stream);
-            let request = http::Request::from_parts(parts, body);
-
-            let host = request
-                .uri()
-                .authority();
-
-            let virtual_hosts = virtual_hosts.clone();
-            let response = if let Some(host) = host {
-                debug!("Serving request for host: {}", host);
-                let virtual_host = virtual_hosts
-                    .read()
-                    .await;
-
-                let virtual_host = virtual_host.get(&(host.host().into(), port));
-
-                let response = if let Some(virtual_host) = virtual_host {
-                    let (parts, body) = request.into_parts();
-                    let request = Request::from_parts(parts, body);
-
-                    let vetis_response = virtual_host
-                        .route(request)
-                        .await;
+            .await
+        {
+            Ok(resolved) => resolved,
+            Err(err) => {
+                error!("Cannot read request from {}: {:?}", connection_info.client_addr, err);
+                return;
+            }
+        };
+        let (mut send_stream, recv_stream) = stream.split();
+        let (mut parts, _) = req.into_parts();
+        if early_data {
+            parts
+                .extensions
+                .insert(EarlyData);
+        }
+        if let Some(tls_info) = &connection_info.tls_info {
+            parts
+                .extensions
+                .insert(tls_info.clone());
+        }
+        let request_id = RequestId::assign(&mut parts.headers);
+        parts
+            .extensions
+            .insert(request_id.clone());
+        let method = parts.method.clone();
+        let uri = parts.uri.clone();
+        let body = HttpBody::from_quic_server(recv_stream);
+        let request = http::Request::from_parts(parts, body);
+
+        let host = request
+            .uri()
+            .authority();
+
+        let virtual_hosts = virtual_hosts.clone();
+        let response = if connection_info
+            .header_limits
+            .exceeded(request.headers())
+        {
+            error!(
+                "Request headers from {} exceed the listener limits",
+                connection_info.client_addr
+            );
+            Ok(static_response(
+                http::StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+                None,
+                "Request header fields too large".to_string(),
+            ))
+        } else if let Some(host) = host {
+            debug!("Serving request for host: {}", host);
+            let virtual_host = virtual_hosts
+                .read()
+                .await;
+
+            let virtual_host = virtual_host.get(&(host.host().into(), port));
 
-                    let response = if let Err(err) = vetis_response {
-                        error!("Error executing request: {:?}", err);
+            let response = if let Some(virtual_host) = virtual_host {
+                trace!("Host {}:{} matched a virtual host", host.host(), port);
+
+                let (parts, body) = request.into_parts();
+                let request = Request::from_parts(parts, body);
+
+                let mut response = match virtual_host
+                    .dispatch(request)
+                    .await
+                {
+                    Err(err) => {
+                        error!("[{}] Error executing request: {:?}", request_id, err);
                         static_response(
                             http::StatusCode::INTERNAL_SERVER_ERROR,
                             None,
                             "Internal server error".to_string(),
                         )
-                    } else {
-                        let mut response = vetis_response
-                            .unwrap()
-                            .into_inner();
-
-                        let default_headers = virtual_host
-                            .config()
-                            .default_headers();
-
-                        if let Some(default_headers) = default_headers {
-                            for (key, value) in default_headers {
-                                let header_name =
-                                    http::header::HeaderName::from_bytes(key.as_bytes());
-                                if header_name.is_err() {
-                                    error!("Invalid header name: {}", key);
-                                    continue;
-                                }
-                                let header_name = header_name.unwrap();
-
-                                let header_value =
-                                    http::header::HeaderValue::from_str(value.as_str());
-                                if header_value.is_err() {
-                                    error!("Invalid header value: {}", value);
-                                    continue;
-                                }
-                                let header_value = header_value.unwrap();
-
-                                response
-                                    .headers_mut()
-                                    .insert(header_name, header_value);
-                            }
-                        }
-
-                        response
-                    };
-
-                    // TODO: Log request and its response status code (move it to oneshot channel?)
-                    info!("{} {} {} {}", client_addr, method, uri, response.status());
-
-                    Ok::<_, VetisError>(response)
-                } else {
-                    error!("Virtual host not found: {}", host);
-                    let response = static_response(
-                        http::StatusCode::NOT_FOUND,
-                        None,
-                        "Virtual host not found".to_string(),
-                    );
-                    Ok(response)
+                    }
+                    Ok(vetis_response) => vetis_response.into_inner(),
                 };
+                request_id.tag_response(&mut response);
+
+                let response = with_access_log(
+                    virtual_host.config(),
+                    response,
+                    &connection_info,
+                    method,
+                    uri,
+                    &request_id,
+                    started,
+                );
 
-                response
+                Ok::<_, VetisError>(response)
             } else {
-                error!("Host not found in request");
+                trace!("Host {}:{} matched no virtual host", host.host(), port);
+                error!("Virtual host not found: {}", host);
                 let response = static_response(
-                    http::StatusCode::BAD_REQUEST,
+                    http::StatusCode::NOT_FOUND,
                     None,
-                    "Host not found in request".to_string(),
+                    "Virtual host not found".to_string(),
                 );
                 Ok(response)
             };
 
-            if let Ok(response) = response {
-                let (parts, mut body) = response.into_parts();
+            response
+        } else {
+            error!("Host not found in request");
+            let response = static_response(
+                http::StatusCode::BAD_REQUEST,
+                None,
+                "Host not found in request".to_string(),
+            );
+            Ok(response)
+        };
 
-                let mut resp = http::Response::builder()
-                    .status(parts.status)
-                    .version(parts.version)
-                    .extension(parts.extensions)
-                    .body(())
-                    .unwrap();
+        if let Ok(response) = response {
+            let response = metrics.count_response(response);
+            let (mut parts, mut body) = response.into_parts();
+            parts.version = http::Version::HTTP_3;
 
-                resp.headers_mut()
-                    .extend(parts.headers);
+            let resp = http::Response::from_parts(parts, ());
 
-                match send_stream
-                    .send_response(resp)
-                    .await
-                {
-                    Ok(_) => {
-                        debug!("Successfully respond to connection");
-                    }
-                    Err(err) => {
-                        error!("Unable to send response to connection: {:?}", err);
-                    }
+            match send_stream
+                .send_response(resp)
+                .await
+            {
+                Ok(_) => {
+                    debug!("Successfully respond to connection");
+                }
+                Err(err) => {
+                    error!("Unable to send response to connection: {:?}", err);
+                    return;
                 }
+            }
 
-                while let Some(buf) = body.next().await {
-                    if let Ok(buf) = buf {
-                        if let Ok(bytes) = buf.into_data() {
-                            let _ = send_stream
-                                .send_data(bytes)
-                                .await;
-                        }
+            while let Some(buf) = body.next().await {
+                let buf = match buf {
+                    Ok(buf) => buf,
+                    Err(err) => {
+                        // A truncated body must not look complete to the client
+                        error!("[{}] Error reading response body: {:?}", request_id, err);
+                        send_stream.stop_stream(h3::error::Code::H3_INTERNAL_ERROR);
+                        return;
+                    }
+                };
+                if let Ok(bytes) = buf.into_data() {
+                    if let Err(err) = send_stream
+                        .send_data(bytes)
+                        .await
+                    {
+                        error!("Unable to send response body to connection: {:?}", err);
+                        return;
                     }
                 }
-
-                let _ = send_stream
-                    .finish()
-                    .await;
-            } else {
-                error!("HttpServer - Error serving connection: {:?}", response.err());
             }
+
+            let _ = send_stream
+                .finish()
+                .await;
+        } else {
+            error!("HttpServer - Error serving connection: {:?}", response.err());
         }
     });
 
@@ -0,0 +1,234 @@
+//! Connection and request metrics shared by listeners.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use http::Response;
+use hyper_body_utils::HttpBody;
+
+use crate::server::conn::counting::count_bytes;
+
+/// Shortest time between two warnings about connections refused over the cap, in seconds.
+const REFUSAL_WARNING_INTERVAL: u64 = 10;
+
+/// Connection and request gauges for a listener.
+///
+/// Counters are plain atomics so they can be updated from the accept loop
+/// and from request workers without locking.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let metrics = ListenerMetrics::default();
+/// println!("In-flight requests: {}", metrics.in_flight_requests());
+/// ```
+#[derive(Debug, Default)]
+pub struct ListenerMetrics {
+    active_connections: AtomicUsize,
+    in_flight_requests: AtomicUsize,
+    total_streams: AtomicU64,
+    bytes_sent: AtomicU64,
+    refused_connections: AtomicU64,
+    /// Connections refused since the last warning was logged
+    unreported_refusals: AtomicU64,
+    /// When the last refusal warning was logged, in seconds since the Unix epoch
+    last_refusal_warning: AtomicU64,
+}
+
+impl ListenerMetrics {
+    /// Returns the number of connections currently open.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The number of open connections.
+    pub fn active_connections(&self) -> usize {
+        self.active_connections
+            .load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of requests currently being processed.
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The number of in-flight requests.
+    pub fn in_flight_requests(&self) -> usize {
+        self.in_flight_requests
+            .load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of streams accepted since the listener started.
+    ///
+    /// # Returns
+    ///
+    /// * `u64` - The number of accepted streams.
+    pub fn total_streams(&self) -> u64 {
+        self.total_streams
+            .load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of response body bytes sent since the listener started.
+    ///
+    /// Responses are counted once their body ends or the client goes away,
+    /// so a response still being streamed isn't included yet.
+    ///
+    /// # Returns
+    ///
+    /// * `u64` - The number of body bytes sent.
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+            .load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of connections refused over the cap since the listener started.
+    ///
+    /// # Returns
+    ///
+    /// * `u64` - The number of refused connections.
+    pub fn refused_connections(&self) -> u64 {
+        self.refused_connections
+            .load(Ordering::Relaxed)
+    }
+
+    /// Counts a connection refused over the cap.
+    ///
+    /// Refusals are expected under load, so they are reported in batches:
+    /// at most one warning is due every `REFUSAL_WARNING_INTERVAL` seconds.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<u64>` - The number of connections refused since the last warning, if one is due now.
+    pub(crate) fn refuse_connection(&self) -> Option<u64> {
+        self.refused_connections
+            .fetch_add(1, Ordering::Relaxed);
+        self.unreported_refusals
+            .fetch_add(1, Ordering::Relaxed);
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let last = self
+            .last_refusal_warning
+            .load(Ordering::Relaxed);
+        if now < last + REFUSAL_WARNING_INTERVAL {
+            return None;
+        }
+
+        // Only the worker winning the exchange reports the batch
+        self.last_refusal_warning
+            .compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed)
+            .ok()?;
+        Some(
+            self.unreported_refusals
+                .swap(0, Ordering::Relaxed),
+        )
+    }
+
+    /// Registers a new connection, unless `max_connections` would be exceeded.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_connections` - Optional cap on concurrent connections.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<ConnectionGuard>` - A guard that releases the connection when dropped, or `None` if the cap was reached.
+    pub(crate) fn track_connection(
+        self: &Arc<Self>,
+        max_connections: Option<usize>,
+    ) -> Option<ConnectionGuard> {
+        let previous = self
+            .active_connections
+            .fetch_add(1, Ordering::Relaxed);
+
+        if let Some(max_connections) = max_connections {
+            if previous >= max_connections {
+                self.active_connections
+                    .fetch_sub(1, Ordering::Relaxed);
+                return None;
+            }
+        }
+
+        Some(ConnectionGuard { metrics: self.clone(), streams: 0 })
+    }
+
+    /// Registers a new in-flight request.
+    ///
+    /// # Returns
+    ///
+    /// * `RequestGuard` - A guard that releases the request when dropped.
+    pub(crate) fn track_request(self: &Arc<Self>) -> RequestGuard {
+        self.in_flight_requests
+            .fetch_add(1, Ordering::Relaxed);
+        RequestGuard { metrics: self.clone() }
+    }
+
+    /// Adds the body bytes of a response to [`ListenerMetrics::bytes_sent`] once sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - The response being sent.
+    ///
+    /// # Returns
+    ///
+    /// * `Response<HttpBody>` - The response, with its body wrapped for counting.
+    pub(crate) fn count_response(
+        self: &Arc<Self>,
+        response: Response<HttpBody>,
+    ) -> Response<HttpBody> {
+        let metrics = self.clone();
+        count_bytes(response, move |bytes| {
+            metrics
+                .bytes_sent
+                .fetch_add(bytes, Ordering::Relaxed);
+        })
+    }
+}
+
+/// Keeps a connection accounted for in [`ListenerMetrics`] while alive.
+pub(crate) struct ConnectionGuard {
+    metrics: Arc<ListenerMetrics>,
+    #[cfg_attr(not(feature = "http3"), allow(dead_code))]
+    streams: u64,
+}
+
+#[cfg(feature = "http3")]
+impl ConnectionGuard {
+    /// Records a new stream accepted on this connection.
+    pub(crate) fn add_stream(&mut self) {
+        self.streams += 1;
+        self.metrics
+            .total_streams
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of streams accepted on this connection.
+    pub(crate) fn streams(&self) -> u64 {
+        self.streams
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.metrics
+            .active_connections
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Keeps a request accounted for in [`ListenerMetrics`] while alive.
+pub(crate) struct RequestGuard {
+    metrics: Arc<ListenerMetrics>,
+}
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        self.metrics
+            .in_flight_requests
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
@@ -1 +1,9 @@
+pub(crate) mod access_log;
+pub(crate) mod budget;
+pub(crate) mod counting;
 pub(crate) mod listener;
+pub mod metrics;
+#[cfg(feature = "proxy-protocol")]
+pub(crate) mod proxy_protocol;
+pub(crate) mod request_id;
+pub(crate) mod shutdown;
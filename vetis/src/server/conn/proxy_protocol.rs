@@ -0,0 +1,177 @@
+//! PROXY protocol headers sent by L4 load balancers ahead of each connection.
+//!
+//! Both the text (v1) and binary (v2) formats are read, as described in
+//! <https://www.haproxy.org/download/2.9/doc/proxy-protocol.txt>.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+#[cfg(feature = "smol-rt")]
+use futures_lite::{AsyncRead, AsyncReadExt};
+#[cfg(feature = "tokio-rt")]
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::errors::VetisError;
+
+/// Signature starting every v2 header.
+const V2_SIGNATURE: [u8; 12] = *b"\r\n\r\n\0\r\nQUIT\n";
+
+/// Longest v1 header, including the trailing CRLF.
+const V1_MAX_LENGTH: usize = 107;
+
+fn invalid(reason: &str) -> VetisError {
+    VetisError::ProxyProtocol(reason.to_string())
+}
+
+/// Reads the PROXY protocol header at the start of a connection.
+///
+/// Only the header is consumed, so the stream is left at the first byte
+/// sent by the client itself, ready for the TLS sniff.
+///
+/// # Arguments
+///
+/// * `stream` - The accepted connection
+///
+/// # Returns
+///
+/// * `Result<Option<SocketAddr>, VetisError>` - The client address carried by the header,
+///   `None` for health checks and unknown address families, which keep the peer address,
+///   or `VetisError::ProxyProtocol` if the connection doesn't start with a valid header.
+pub(crate) async fn read_proxy_header<S>(stream: &mut S) -> Result<Option<SocketAddr>, VetisError>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut prefix = [0; 6];
+    read(stream, &mut prefix).await?;
+
+    if prefix == *b"PROXY " {
+        return read_v1(stream).await;
+    }
+
+    if prefix == V2_SIGNATURE[..6] {
+        return read_v2(stream).await;
+    }
+
+    Err(invalid("Missing PROXY protocol header"))
+}
+
+async fn read<S>(stream: &mut S, buf: &mut [u8]) -> Result<(), VetisError>
+where
+    S: AsyncRead + Unpin,
+{
+    stream
+        .read_exact(buf)
+        .await
+        .map(|_| ())
+        .map_err(|e| VetisError::Io(e.to_string()))
+}
+
+/// Reads the rest of a v1 header, `PROXY TCP4 <src> <dst> <sport> <dport>\r\n`.
+async fn read_v1<S>(stream: &mut S) -> Result<Option<SocketAddr>, VetisError>
+where
+    S: AsyncRead + Unpin,
+{
+    // Read byte by byte, so nothing past the CRLF is consumed
+    let mut line = Vec::with_capacity(V1_MAX_LENGTH);
+    let mut byte = [0; 1];
+    while !line.ends_with(b"\r\n") {
+        if line.len() + "PROXY ".len() >= V1_MAX_LENGTH {
+            return Err(invalid("PROXY protocol v1 header too long"));
+        }
+        read(stream, &mut byte).await?;
+        line.push(byte[0]);
+    }
+
+    let line = std::str::from_utf8(&line[..line.len() - 2])
+        .map_err(|_| invalid("PROXY protocol v1 header is not ASCII"))?;
+
+    parse_v1(line)
+}
+
+/// Parses the fields of a v1 header following `PROXY `.
+fn parse_v1(line: &str) -> Result<Option<SocketAddr>, VetisError> {
+    let fields: Vec<&str> = line
+        .split(' ')
+        .collect();
+
+    match fields.first() {
+        Some(&"UNKNOWN") => return Ok(None),
+        Some(&"TCP4") | Some(&"TCP6") if fields.len() == 5 => {}
+        _ => return Err(invalid("Malformed PROXY protocol v1 header")),
+    }
+
+    let ip: IpAddr = fields[1]
+        .parse()
+        .map_err(|_| invalid("Invalid PROXY protocol v1 source address"))?;
+    let port: u16 = fields[3]
+        .parse()
+        .map_err(|_| invalid("Invalid PROXY protocol v1 source port"))?;
+
+    if ip.is_ipv4() != (fields[0] == "TCP4") {
+        return Err(invalid("PROXY protocol v1 address doesn't match its family"));
+    }
+
+    Ok(Some(SocketAddr::new(ip, port)))
+}
+
+/// Reads the rest of a v2 header: signature, version and command, family,
+/// length and the addresses.
+async fn read_v2<S>(stream: &mut S) -> Result<Option<SocketAddr>, VetisError>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut header = [0; 10];
+    read(stream, &mut header).await?;
+
+    if header[..6] != V2_SIGNATURE[6..] {
+        return Err(invalid("Invalid PROXY protocol v2 signature"));
+    }
+
+    let version_command = header[6];
+    let family = header[7];
+    let length = u16::from_be_bytes([header[8], header[9]]) as usize;
+
+    if version_command >> 4 != 2 {
+        return Err(invalid("Unsupported PROXY protocol version"));
+    }
+
+    let mut addresses = vec![0; length];
+    read(stream, &mut addresses).await?;
+
+    match version_command & 0x0f {
+        // LOCAL, sent by the load balancer itself, such as for health checks
+        0x0 => return Ok(None),
+        0x1 => {}
+        _ => return Err(invalid("Unsupported PROXY protocol v2 command")),
+    }
+
+    parse_v2_addresses(family, &addresses)
+}
+
+/// Returns the source address of a v2 PROXY command.
+fn parse_v2_addresses(family: u8, addresses: &[u8]) -> Result<Option<SocketAddr>, VetisError> {
+    let too_short = || invalid("PROXY protocol v2 addresses are truncated");
+
+    match family >> 4 {
+        // AF_INET: source, destination, source port, destination port
+        0x1 => {
+            if addresses.len() < 12 {
+                return Err(too_short());
+            }
+            let ip = Ipv4Addr::new(addresses[0], addresses[1], addresses[2], addresses[3]);
+            let port = u16::from_be_bytes([addresses[8], addresses[9]]);
+            Ok(Some(SocketAddr::new(IpAddr::V4(ip), port)))
+        }
+        // AF_INET6
+        0x2 => {
+            if addresses.len() < 36 {
+                return Err(too_short());
+            }
+            let mut ip = [0; 16];
+            ip.copy_from_slice(&addresses[..16]);
+            let port = u16::from_be_bytes([addresses[32], addresses[33]]);
+            Ok(Some(SocketAddr::new(IpAddr::V6(Ipv6Addr::from(ip)), port)))
+        }
+        // AF_UNSPEC and AF_UNIX carry no address the server can use
+        _ => Ok(None),
+    }
+}
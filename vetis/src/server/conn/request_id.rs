@@ -0,0 +1,76 @@
+//! Identifiers tying the log lines of a request together.
+
+use std::{fmt, sync::Arc};
+
+use http::{HeaderMap, HeaderName, HeaderValue};
+
+/// Header carrying the request identifier, to and from clients and upstreams.
+pub(crate) const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest forwarded identifier that is kept, longer ones are replaced.
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// Identifier of a request, shared by its access log and error log lines.
+#[derive(Clone, Debug)]
+pub(crate) struct RequestId(Arc<str>);
+
+impl RequestId {
+    /// Returns the identifier of a request, setting its `x-request-id` header.
+    ///
+    /// An identifier forwarded by the client or a proxy in front is kept, so
+    /// logs of every hop share it, as long as it is printable ASCII without
+    /// spaces. Otherwise a random one is generated.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The request headers
+    ///
+    /// # Returns
+    ///
+    /// * `RequestId` - The identifier of the request.
+    pub(crate) fn assign(headers: &mut HeaderMap) -> Self {
+        let forwarded = headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .filter(|value| is_valid(value));
+
+        if let Some(forwarded) = forwarded {
+            return Self(Arc::from(forwarded));
+        }
+
+        let generated = format!("{:032x}", rand::random::<u128>());
+        if let Ok(value) = HeaderValue::from_str(&generated) {
+            headers.insert(REQUEST_ID_HEADER, value);
+        }
+        Self(Arc::from(generated))
+    }
+
+    /// Returns the identifier.
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Echoes the identifier to the client in the `x-request-id` response header.
+    pub(crate) fn tag_response<B>(&self, response: &mut http::Response<B>) {
+        if let Ok(value) = HeaderValue::from_str(&self.0) {
+            response
+                .headers_mut()
+                .insert(REQUEST_ID_HEADER, value);
+        }
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Returns whether a forwarded identifier can be logged as is.
+fn is_valid(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LENGTH
+        && value
+            .bytes()
+            .all(|byte| byte.is_ascii_graphic())
+}
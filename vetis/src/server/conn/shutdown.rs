@@ -0,0 +1,42 @@
+//! Shutdown notification shared by listeners and their connections.
+
+use crossfire::{mpmc, null::CloseHandle, MAsyncRx};
+
+/// Creates a linked shutdown trigger and signal.
+///
+/// # Returns
+///
+/// * `(ShutdownTrigger, ShutdownSignal)` - The trigger kept by the listener and the signal handed to connections.
+pub(crate) fn shutdown_channel() -> (ShutdownTrigger, ShutdownSignal) {
+    let (handle, receiver) = mpmc::Null::new().new_async();
+    (ShutdownTrigger { _handle: handle }, ShutdownSignal { receiver })
+}
+
+/// Notifies every linked [`ShutdownSignal`] once it and all its clones are triggered or dropped.
+#[derive(Clone)]
+pub(crate) struct ShutdownTrigger {
+    _handle: CloseHandle<mpmc::Null>,
+}
+
+impl ShutdownTrigger {
+    /// Notifies every linked signal that a shutdown was requested.
+    pub(crate) fn trigger(self) {
+        drop(self);
+    }
+}
+
+/// Resolves once the linked [`ShutdownTrigger`] fires.
+#[derive(Clone)]
+pub(crate) struct ShutdownSignal {
+    receiver: MAsyncRx<mpmc::Null>,
+}
+
+impl ShutdownSignal {
+    /// Waits until a shutdown is requested.
+    pub(crate) async fn wait(&self) {
+        let _ = self
+            .receiver
+            .recv()
+            .await;
+    }
+}
@@ -1,14 +1,24 @@
 use std::{collections::HashMap, sync::Arc};
 
 use http::HeaderMap;
+use log::info;
 
 use hyper_body_utils::HttpBody;
 
+#[cfg(feature = "admin")]
+use crate::server::{
+    admin::admin_virtual_hosts,
+    conn::shutdown::{shutdown_channel, ShutdownSignal},
+};
 use crate::{
     config::server::{Protocol, ServerConfig},
-    errors::VetisError,
+    errors::{ConfigError, VetisError},
     server::{
-        conn::listener::{Listener, ServerListener},
+        conn::{
+            listener::{Listener, ServerListener},
+            metrics::ListenerMetrics,
+        },
+        tls::TlsFactory,
         Server,
     },
     VetisRwLock, VetisVirtualHosts,
@@ -17,12 +27,17 @@ use crate::{
 mod request;
 mod response;
 
+#[cfg(feature = "http3")]
+pub(crate) use crate::server::http::request::EarlyData;
+pub(crate) use crate::server::http::request::TlsInfo;
 pub use crate::server::http::{request::Request, response::Response};
 
 pub struct HttpServer {
     config: ServerConfig,
     listeners: Vec<ServerListener>,
     virtual_hosts: VetisVirtualHosts,
+    #[cfg(feature = "admin")]
+    admin: Option<(ServerListener, ShutdownSignal)>,
 }
 
 impl Server for HttpServer {
@@ -40,6 +55,8 @@ impl Server for HttpServer {
             config,
             listeners: Vec::new(),
             virtual_hosts: Arc::new(VetisRwLock::new(HashMap::new())),
+            #[cfg(feature = "admin")]
+            admin: None,
         }
     }
 
@@ -54,56 +71,60 @@ impl Server for HttpServer {
 
     /// Start the server.
     ///
+    /// Every listener is bound before this returns, so connections can be made
+    /// right away. If any listener fails to bind, the ones already bound are
+    /// stopped and the error is returned.
+    ///
     /// # Returns
     ///
     /// * `Result<(), VetisError>` - A result containing `()` if the server started successfully, or a `VetisError` if the server failed to start.
     async fn start(&mut self) -> Result<(), VetisError> {
+        self.validate_listeners()
+            .await?;
+
+        self.validate_tls()
+            .await?;
+
         let mut listeners: Vec<ServerListener> = self
             .config
             .listeners()
             .iter()
-            .map(|listener_config| match listener_config.protocol() {
-                #[cfg(feature = "http1")]
-                Protocol::Http1 => {
-                    let mut listener = ServerListener::new(listener_config.clone());
-                    listener.set_virtual_hosts(
-                        self.virtual_hosts
-                            .clone(),
-                    );
-                    listener
-                }
-                #[cfg(feature = "http2")]
-                Protocol::Http2 => {
-                    let mut listener = ServerListener::new(listener_config.clone());
-                    listener.set_virtual_hosts(
-                        self.virtual_hosts
-                            .clone(),
-                    );
-                    listener
-                }
-                #[cfg(feature = "http3")]
-                Protocol::Http3 => {
-                    let mut listener = ServerListener::new(listener_config.clone());
-                    listener.set_virtual_hosts(
-                        self.virtual_hosts
-                            .clone(),
-                    );
-                    listener
-                }
-                _ => {
-                    panic!("Unsupported protocol");
-                }
+            .map(|listener_config| {
+                let mut listener = ServerListener::new(listener_config.clone());
+                listener.set_virtual_hosts(
+                    self.virtual_hosts
+                        .clone(),
+                );
+                listener
             })
             .collect();
 
-        for listener in listeners.iter_mut() {
-            listener
+        for index in 0..listeners.len() {
+            if let Err(e) = listeners[index]
                 .listen()
-                .await?;
+                .await
+            {
+                // Release the listeners already bound, so a failed start leaves nothing behind
+                for listener in listeners[..index].iter_mut() {
+                    let _ = listener
+                        .stop()
+                        .await;
+                }
+                return Err(e);
+            }
         }
 
         self.listeners = listeners;
 
+        #[cfg(feature = "admin")]
+        if let Err(e) = self
+            .start_admin()
+            .await
+        {
+            let _ = self.stop().await;
+            return Err(e);
+        }
+
         Ok(())
     }
 
@@ -121,10 +142,192 @@ impl Server for HttpServer {
                 .stop()
                 .await?;
         }
+
+        #[cfg(feature = "admin")]
+        if let Some((mut admin, _)) = self.admin.take() {
+            admin.stop().await?;
+        }
+
         Ok(())
     }
 }
 
+impl HttpServer {
+    /// Checks that every listener protocol is enabled in this build and usable by the
+    /// virtual hosts on its port.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), VetisError>` - `VetisError::Config` describing the first mismatch found.
+    async fn validate_listeners(&self) -> Result<(), VetisError> {
+        let virtual_hosts = self
+            .virtual_hosts
+            .read()
+            .await;
+
+        for listener_config in self
+            .config
+            .listeners()
+        {
+            let port = listener_config.port();
+            let protocol = listener_config.protocol();
+            let (feature, enabled) = match protocol {
+                Protocol::Http1 => ("http1", cfg!(feature = "http1")),
+                Protocol::Http2 => ("http2", cfg!(feature = "http2")),
+                Protocol::Http3 => ("http3", cfg!(feature = "http3")),
+            };
+
+            if !enabled {
+                return Err(VetisError::Config(ConfigError::Listener(format!(
+                    "Listener on port {} uses {:?}, which requires the `{}` feature",
+                    port, protocol, feature
+                ))));
+            }
+
+            // QUIC has no plaintext mode, so every host served over HTTP/3 needs a certificate
+            if *protocol == Protocol::Http3 {
+                let plaintext_host = virtual_hosts
+                    .iter()
+                    .find(|((_, host_port), virtual_host)| {
+                        *host_port == port
+                            && virtual_host
+                                .config()
+                                .security()
+                                .is_none()
+                    });
+
+                if let Some(((hostname, _), _)) = plaintext_host {
+                    return Err(VetisError::Config(ConfigError::Listener(format!(
+                        "Virtual host {} on port {} has no security configuration, which HTTP/3 requires",
+                        hostname, port
+                    ))));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds the TLS configuration of the virtual hosts once, before any listener starts.
+    ///
+    /// Every listener builds the same configuration from the shared virtual
+    /// hosts, so a bad certificate or key is reported here once, instead of
+    /// by whichever listener happens to start first.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), VetisError>` - `VetisError::Start(StartError::Tls)` describing the invalid configuration.
+    async fn validate_tls(&self) -> Result<(), VetisError> {
+        TlsFactory::create_tls_config(
+            self.virtual_hosts
+                .clone(),
+            Vec::new(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Binds the admin listener, if configured, once every listener is started.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), VetisError>` - A `VetisError` if the admin listener failed to start.
+    #[cfg(feature = "admin")]
+    async fn start_admin(&mut self) -> Result<(), VetisError> {
+        let Some(admin_config) = self
+            .config
+            .admin()
+            .clone()
+        else {
+            return Ok(());
+        };
+
+        let listeners = self
+            .config
+            .listeners()
+            .iter()
+            .cloned()
+            .zip(self.metrics())
+            .collect();
+
+        let (shutdown_trigger, shutdown) = shutdown_channel();
+        let virtual_hosts = admin_virtual_hosts(
+            &admin_config,
+            self.virtual_hosts
+                .clone(),
+            listeners,
+            shutdown_trigger,
+        )?;
+
+        let mut listener = ServerListener::new(admin_config.listener()?);
+        listener.set_virtual_hosts(virtual_hosts);
+        listener
+            .listen()
+            .await?;
+
+        self.admin = Some((listener, shutdown));
+
+        Ok(())
+    }
+
+    /// Waits until a shutdown is requested through the admin listener.
+    ///
+    /// Never resolves when the admin listener isn't running.
+    #[cfg(feature = "admin")]
+    pub async fn shutdown_requested(&self) {
+        match &self.admin {
+            Some((_, shutdown)) => {
+                shutdown
+                    .wait()
+                    .await
+            }
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Stops the listeners on a port, leaving the others running.
+    ///
+    /// # Arguments
+    ///
+    /// * `port` - The port of the listeners to stop
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), VetisError>` - A `VetisError` if a listener failed to stop.
+    pub async fn stop_listeners(&mut self, port: u16) -> Result<(), VetisError> {
+        // Listeners are started in configuration order
+        for (listener, listener_config) in self
+            .listeners
+            .iter_mut()
+            .zip(
+                self.config
+                    .listeners(),
+            )
+            .filter(|(_, listener_config)| listener_config.port() == port)
+        {
+            listener
+                .stop()
+                .await?;
+            info!("Stopped listener on port {}:{}", listener_config.interface(), port);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the metrics of every started listener.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Arc<ListenerMetrics>>` - The metrics, in listener configuration order.
+    pub fn metrics(&self) -> Vec<Arc<ListenerMetrics>> {
+        self.listeners
+            .iter()
+            .map(|listener| listener.metrics())
+            .collect()
+    }
+}
+
 // TODO: Move to utils, try make it more flexible
 pub fn static_response(
     status: http::StatusCode,
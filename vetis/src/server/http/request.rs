@@ -1,5 +1,9 @@
+use std::sync::Arc;
+
 use hyper_body_utils::HttpBody;
 
+use crate::server::conn::request_id::RequestId;
+
 /// HTTP request wrapper supporting multiple protocols.
 ///
 /// The `Request` struct provides a unified interface for handling HTTP requests
@@ -28,6 +32,18 @@ pub struct Request {
     pub(crate) inner: Option<http::Request<HttpBody>>,
 }
 
+/// Request extension marking a request received as TLS early data (0-RTT).
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct EarlyData;
+
+/// Request extension holding the TLS details of the connection a request arrived on.
+#[derive(Debug)]
+pub(crate) struct TlsInfo {
+    pub(crate) sni: Option<String>,
+    pub(crate) alpn: Option<Vec<u8>>,
+    pub(crate) peer_certificates: Option<Vec<Vec<u8>>>,
+}
+
 impl Request {
     /// Creates a `Request` from an HTTP/1 or HTTP/2 request.
     ///
@@ -96,6 +112,32 @@ impl Request {
         }
     }
 
+    /// Returns the HTTP version the request was received with.
+    ///
+    /// The server answers with the same version, so handlers only need this
+    /// to adapt their responses to the protocol.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Request;
+    ///
+    /// async fn handler(request: Request) -> Result<vetis::Response, vetis::VetisError> {
+    ///     let protocol = match request.version() {
+    ///         http::Version::HTTP_3 => "h3",
+    ///         http::Version::HTTP_2 => "h2",
+    ///         _ => "http/1.1",
+    ///     };
+    ///     Ok(vetis::Response::builder().text(protocol))
+    /// }
+    /// ```
+    pub fn version(&self) -> http::Version {
+        match &self.inner {
+            Some(req) => req.version(),
+            None => panic!("No request"),
+        }
+    }
+
     /// Returns the HTTP method.
     ///
     /// # Examples
@@ -119,6 +161,465 @@ impl Request {
         }
     }
 
+    /// Returns the value of a header.
+    ///
+    /// Returns `None` when the header is absent or isn't valid visible ASCII.
+    /// When the header is repeated the first value is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The header name, matched case-insensitively
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Request;
+    ///
+    /// async fn handler(request: Request) -> Result<vetis::Response, vetis::VetisError> {
+    ///     let user_agent = request.header("user-agent").unwrap_or("unknown");
+    ///     Ok(vetis::Response::builder().text(user_agent))
+    /// }
+    /// ```
+    pub fn header(&self, name: impl AsRef<str>) -> Option<&str> {
+        self.headers()
+            .get(name.as_ref())
+            .and_then(|value| value.to_str().ok())
+    }
+
+    /// Returns the value of the `content-type` header.
+    ///
+    /// Returns `None` when the header is absent or isn't valid visible ASCII.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Request;
+    ///
+    /// async fn handler(request: Request) -> Result<vetis::Response, vetis::VetisError> {
+    ///     if request.content_type() == Some("application/json") {
+    ///         /* parse JSON */
+    ///     }
+    ///     Ok(/* response */)
+    /// }
+    /// ```
+    pub fn content_type(&self) -> Option<&str> {
+        self.header(http::header::CONTENT_TYPE)
+    }
+
+    /// Returns the value of the `content-length` header.
+    ///
+    /// Returns `None` when the header is absent or isn't a valid length.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Request;
+    ///
+    /// async fn handler(request: Request) -> Result<vetis::Response, vetis::VetisError> {
+    ///     if request.content_length().is_some_and(|length| length > 1024 * 1024) {
+    ///         /* reject large uploads */
+    ///     }
+    ///     Ok(/* response */)
+    /// }
+    /// ```
+    pub fn content_length(&self) -> Option<u64> {
+        self.header(http::header::CONTENT_LENGTH)
+            .and_then(|value| {
+                value
+                    .trim()
+                    .parse::<u64>()
+                    .ok()
+            })
+    }
+
+    /// Returns whether the `accept` header allows the given media type.
+    ///
+    /// Media ranges such as `text/*` and `*/*` are honored, and types with
+    /// a `q` value of `0` are rejected. A request without an `accept`
+    /// header accepts anything.
+    ///
+    /// # Arguments
+    ///
+    /// * `mime` - The media type to check, e.g. `application/json`
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Request;
+    ///
+    /// async fn handler(request: Request) -> Result<vetis::Response, vetis::VetisError> {
+    ///     if !request.accepts("application/json") {
+    ///         /* answer with 406 Not Acceptable */
+    ///     }
+    ///     Ok(/* response */)
+    /// }
+    /// ```
+    pub fn accepts(&self, mime: &str) -> bool {
+        self.accept_quality(mime) > 0.0
+    }
+
+    /// Returns the option the `accept` header prefers the most.
+    ///
+    /// Options are ranked by the `q` value of their best matching media
+    /// range; ties go to the option listed first. Returns `None` when no
+    /// option is acceptable.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The media types the handler can produce
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Request;
+    ///
+    /// async fn handler(request: Request) -> Result<vetis::Response, vetis::VetisError> {
+    ///     match request.preferred(&["text/html", "application/json"]) {
+    ///         Some("application/json") => { /* serve JSON */ }
+    ///         Some(_) => { /* serve HTML */ }
+    ///         None => { /* answer with 406 Not Acceptable */ }
+    ///     }
+    ///     Ok(/* response */)
+    /// }
+    /// ```
+    pub fn preferred<'a>(&self, options: &[&'a str]) -> Option<&'a str> {
+        options
+            .iter()
+            .map(|option| (*option, self.accept_quality(option)))
+            .filter(|(_, quality)| *quality > 0.0)
+            .fold(None, |best: Option<(&'a str, f32)>, (option, quality)| match best {
+                Some((_, best_quality)) if best_quality >= quality => best,
+                _ => Some((option, quality)),
+            })
+            .map(|(option, _)| option)
+    }
+
+    /// Returns whether the request arrived as TLS early data (0-RTT).
+    ///
+    /// Early data can be replayed by an attacker, so handlers should only
+    /// perform idempotent operations for such requests, or answer them with
+    /// `425 Too Early` so the client retries after the handshake. Early data
+    /// is only detected on HTTP/3 listeners, and only when enabled with
+    /// `SecurityConfig::max_early_data_size`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Request;
+    ///
+    /// async fn handler(request: Request) -> Result<vetis::Response, vetis::VetisError> {
+    ///     if request.is_early_data() && request.method() != http::Method::GET {
+    ///         return Ok(vetis::Response::builder()
+    ///             .status(http::StatusCode::TOO_EARLY)
+    ///             .text("Too early"));
+    ///     }
+    ///     Ok(/* response */)
+    /// }
+    /// ```
+    pub fn is_early_data(&self) -> bool {
+        match &self.inner {
+            Some(req) => req
+                .extensions()
+                .get::<EarlyData>()
+                .is_some(),
+            None => panic!("No request"),
+        }
+    }
+
+    /// Returns the identifier of the request.
+    ///
+    /// The identifier is taken from the `x-request-id` header sent by the
+    /// client or a proxy in front, or generated by the listener, and is
+    /// shared by the access log and error log lines of the request. Returns
+    /// `None` for requests that didn't go through a listener.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Request;
+    ///
+    /// async fn handler(request: Request) -> Result<vetis::Response, vetis::VetisError> {
+    ///     log::info!("[{}] Creating order", request.request_id().unwrap_or("-"));
+    ///     Ok(/* response */)
+    /// }
+    /// ```
+    pub fn request_id(&self) -> Option<&str> {
+        match &self.inner {
+            Some(req) => req
+                .extensions()
+                .get::<RequestId>()
+                .map(RequestId::as_str),
+            None => panic!("No request"),
+        }
+    }
+
+    /// Returns the server name the client asked for during the TLS handshake (SNI).
+    ///
+    /// Returns `None` for plain HTTP requests or when the client sent no server name.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Request;
+    ///
+    /// async fn handler(request: Request) -> Result<vetis::Response, vetis::VetisError> {
+    ///     if let Some(sni) = request.sni() {
+    ///         println!("TLS server name: {}", sni);
+    ///     }
+    ///     Ok(/* response */)
+    /// }
+    /// ```
+    pub fn sni(&self) -> Option<&str> {
+        self.tls_info()
+            .and_then(|tls_info| {
+                tls_info
+                    .sni
+                    .as_deref()
+            })
+    }
+
+    /// Returns the application protocol negotiated during the TLS handshake (ALPN).
+    ///
+    /// Returns `None` for plain HTTP requests or when no protocol was negotiated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Request;
+    ///
+    /// async fn handler(request: Request) -> Result<vetis::Response, vetis::VetisError> {
+    ///     let http2 = request.alpn() == Some(b"h2".as_slice());
+    ///     Ok(/* response */)
+    /// }
+    /// ```
+    pub fn alpn(&self) -> Option<&[u8]> {
+        self.tls_info()
+            .and_then(|tls_info| {
+                tls_info
+                    .alpn
+                    .as_deref()
+            })
+    }
+
+    /// Returns the certificate chain presented by the client, in DER format.
+    ///
+    /// The client certificate comes first, followed by its intermediates. A
+    /// chain is only present when the virtual host enables client
+    /// authentication, in which case it has been verified against the CA
+    /// certificate.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Request;
+    ///
+    /// async fn handler(request: Request) -> Result<vetis::Response, vetis::VetisError> {
+    ///     if let Some(chain) = request.peer_certificates() {
+    ///         /* authorize the client from chain[0] */
+    ///     }
+    ///     Ok(/* response */)
+    /// }
+    /// ```
+    pub fn peer_certificates(&self) -> Option<&[Vec<u8>]> {
+        self.tls_info()
+            .and_then(|tls_info| {
+                tls_info
+                    .peer_certificates
+                    .as_deref()
+            })
+    }
+
+    /// Returns whether the request arrived over TLS.
+    ///
+    /// Always `true` for HTTP/3, which runs over QUIC. Useful to decide on
+    /// `Secure` cookies, HSTS or redirects to `https://`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Request;
+    ///
+    /// async fn handler(request: Request) -> Result<vetis::Response, vetis::VetisError> {
+    ///     let cookie = if request.is_secure() { "id=1; Secure" } else { "id=1" };
+    ///     Ok(/* response */)
+    /// }
+    /// ```
+    pub fn is_secure(&self) -> bool {
+        self.tls_info()
+            .is_some()
+    }
+
+    /// Returns the scheme the request arrived with, `https` or `http`.
+    ///
+    /// Derived from [`is_secure`](Self::is_secure), as HTTP/1 requests don't
+    /// carry their scheme in the request line.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Request;
+    ///
+    /// async fn handler(request: Request) -> Result<vetis::Response, vetis::VetisError> {
+    ///     log::info!("Served over {}", request.scheme());
+    ///     Ok(/* response */)
+    /// }
+    /// ```
+    pub fn scheme(&self) -> &str {
+        if self.is_secure() {
+            "https"
+        } else {
+            "http"
+        }
+    }
+
+    /// Returns the host and optional port the request is addressed to.
+    ///
+    /// The URI authority is used when present, as for HTTP/2, HTTP/3 and
+    /// absolute-form HTTP/1 requests, otherwise the `host` header. Returns
+    /// `None` when neither is present or the header isn't valid visible ASCII.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Request;
+    ///
+    /// async fn handler(request: Request) -> Result<vetis::Response, vetis::VetisError> {
+    ///     let host = request.host().unwrap_or("localhost");
+    ///     Ok(vetis::Response::builder().text(host))
+    /// }
+    /// ```
+    pub fn host(&self) -> Option<&str> {
+        if let Some(authority) = self
+            .uri()
+            .authority()
+        {
+            return Some(authority.as_str());
+        }
+
+        self.header(http::header::HOST)
+            .map(str::trim)
+            .filter(|host| !host.is_empty())
+    }
+
+    /// Returns the absolute URL of the request, as `scheme://host/path?query`.
+    ///
+    /// Returns `None` when the host the request is addressed to is unknown,
+    /// see [`host`](Self::host).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Request;
+    ///
+    /// async fn handler(request: Request) -> Result<vetis::Response, vetis::VetisError> {
+    ///     let Some(url) = request.full_url() else {
+    ///         return Ok(vetis::Response::builder()
+    ///             .status(http::StatusCode::BAD_REQUEST)
+    ///             .text("Missing host"));
+    ///     };
+    ///     Ok(vetis::Response::builder().text(&url))
+    /// }
+    /// ```
+    pub fn full_url(&self) -> Option<String> {
+        let path_and_query = self
+            .uri()
+            .path_and_query()
+            .map_or("/", |path_and_query| path_and_query.as_str());
+
+        Some(format!("{}://{}{}", self.scheme(), self.host()?, path_and_query))
+    }
+
+    fn tls_info(&self) -> Option<&TlsInfo> {
+        match &self.inner {
+            Some(req) => req
+                .extensions()
+                .get::<Arc<TlsInfo>>()
+                .map(Arc::as_ref),
+            None => panic!("No request"),
+        }
+    }
+
+    /// Returns the `q` value the `accept` header gives to a media type.
+    ///
+    /// The most specific matching media range wins, so `text/html;q=0`
+    /// rejects HTML even when `*/*` is accepted.
+    fn accept_quality(&self, mime: &str) -> f32 {
+        let Some(accept) = self
+            .headers()
+            .get(http::header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+        else {
+            return 1.0;
+        };
+
+        let Some((mime_type, mime_subtype)) = mime.split_once('/') else {
+            return 0.0;
+        };
+
+        accept
+            .split(',')
+            .filter_map(|range| {
+                let mut params = range.split(';');
+                let (range_type, range_subtype) = params
+                    .next()?
+                    .trim()
+                    .split_once('/')?;
+
+                let specificity = match (range_type, range_subtype) {
+                    ("*", "*") => 0,
+                    (range_type, "*") if range_type.eq_ignore_ascii_case(mime_type) => 1,
+                    (range_type, range_subtype)
+                        if range_type.eq_ignore_ascii_case(mime_type)
+                            && range_subtype.eq_ignore_ascii_case(mime_subtype) =>
+                    {
+                        2
+                    }
+                    _ => return None,
+                };
+
+                let quality = params
+                    .filter_map(|param| {
+                        param
+                            .trim()
+                            .strip_prefix("q=")
+                    })
+                    .find_map(|quality| {
+                        quality
+                            .trim()
+                            .parse::<f32>()
+                            .ok()
+                    })
+                    .unwrap_or(1.0);
+
+                Some((specificity, quality))
+            })
+            .max_by_key(|(specificity, _)| *specificity)
+            .map_or(0.0, |(_, quality)| quality)
+    }
+
+    /// Returns a copy of the request with an empty body.
+    ///
+    /// Keeps the method, URI, version, headers and extensions, so the request
+    /// can still be described once its body has been handed to a path.
+    pub(crate) fn head(&self) -> Request {
+        let Some(req) = &self.inner else {
+            return Request { inner: None };
+        };
+
+        let mut head = http::Request::new(HttpBody::from_bytes(&[]));
+        *head.method_mut() = req.method().clone();
+        *head.uri_mut() = req.uri().clone();
+        *head.version_mut() = req.version();
+        *head.headers_mut() = req
+            .headers()
+            .clone();
+        *head.extensions_mut() = req
+            .extensions()
+            .clone();
+
+        Request { inner: Some(head) }
+    }
+
     pub fn into_parts(self) -> (http::request::Parts, HttpBody) {
         match self.inner {
             Some(req) => {
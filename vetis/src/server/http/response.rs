@@ -1,5 +1,13 @@
+use bytes::Bytes;
+use http::HeaderValue;
+use http_body_util::{BodyExt, Full};
 use hyper_body_utils::HttpBody;
 
+use crate::{
+    errors::{HttpError, VetisError},
+    server::conn::access_log::LogTags,
+};
+
 /// Builder for creating HTTP responses.
 ///
 /// `ResponseBuilder` provides a fluent interface for constructing HTTP responses
@@ -50,9 +58,35 @@ impl ResponseBuilder {
         self
     }
 
+    /// Sets the HTTP status code for the response from its numeric value.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The status code, such as one read from configuration or an upstream
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `code` isn't between 100 and 999.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Response;
+    ///
+    /// let response = Response::builder()
+    ///     .status_code(404)?
+    ///     .text("Not found");
+    /// ```
+    pub fn status_code(self, code: u16) -> Result<Self, VetisError> {
+        let status = http::StatusCode::from_u16(code).map_err(http::Error::from)?;
+        Ok(self.status(status))
+    }
+
     /// Sets the HTTP version for the response.
     ///
-    /// By default, responses use HTTP/1.1.
+    /// By default, responses use HTTP/1.1. Responses sent by the server are
+    /// always given the version of the request they answer, so this only
+    /// matters for responses used outside of it.
     ///
     /// # Examples
     ///
@@ -97,6 +131,32 @@ impl ResponseBuilder {
         self
     }
 
+    /// Marks the response as a download, suggesting a filename.
+    ///
+    /// Sets `content-disposition: attachment; filename="..."`. Names that
+    /// aren't plain ASCII are also sent RFC 5987 encoded as
+    /// `filename*=UTF-8''...`.
+    ///
+    /// # Arguments
+    ///
+    /// * `filename` - The filename suggested to the client
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Response;
+    ///
+    /// let response = Response::builder()
+    ///     .attachment("report.csv")
+    ///     .text("id,name\n1,vetis\n");
+    /// ```
+    pub fn attachment(self, filename: &str) -> Self {
+        self.header(
+            http::header::CONTENT_DISPOSITION,
+            crate::utils::disposition::attachment(filename),
+        )
+    }
+
     /// Sets the headers for the response.
     ///
     /// This replaces all existing headers.
@@ -120,6 +180,9 @@ impl ResponseBuilder {
 
     /// Sets the body from a text string and creates the final `Response`.
     ///
+    /// The length of the text is sent as `content-length`, unless the
+    /// header was already set, so the response isn't sent chunked.
+    ///
     /// # Arguments
     ///
     /// * `text` - The response body as a text slice
@@ -133,25 +196,133 @@ impl ResponseBuilder {
     ///     .text("Hello, World!");
     /// ```    
     pub fn text(self, text: &str) -> Response {
-        self.body(HttpBody::from_text(text))
+        self.content_length(text.len())
+            .body(HttpBody::from_text(text))
     }
 
     /// Sets the body with bytes and creates the final `Response`.
     ///
+    /// Owned buffers such as `Vec<u8>` and `Bytes` are sent without copying,
+    /// with their length as `content-length` unless the header was already set.
+    ///
     /// # Arguments
     ///
-    /// * `bytes` - The response body as a `Bytes`
+    /// * `bytes` - The response body, anything convertible into `Bytes`
     ///
     /// # Examples
     ///
     /// ```rust,ignore
     /// use vetis::Response;
     ///
+    /// let png: Vec<u8> = render_chart();
     /// let response = Response::builder()
-    ///     .bytes(b"Hello, World!");
+    ///     .header(http::header::CONTENT_TYPE, "image/png".parse().unwrap())
+    ///     .bytes(png);
     /// ```
-    pub fn bytes(self, bytes: &[u8]) -> Response {
-        self.body(HttpBody::from_bytes(bytes))
+    pub fn bytes(self, bytes: impl Into<Bytes>) -> Response {
+        let bytes = bytes.into();
+        self.content_length(bytes.len())
+            .body(HttpBody::Stream(
+                Full::new(bytes)
+                    .map_err(|never| match never {})
+                    .boxed(),
+            ))
+    }
+
+    /// Creates an empty `204 No Content` response.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Response;
+    ///
+    /// let response = Response::builder()
+    ///     .no_content();
+    /// ```
+    pub fn no_content(self) -> Response {
+        self.status(http::StatusCode::NO_CONTENT)
+            .text("")
+    }
+
+    /// Creates an empty `304 Not Modified` response.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Response;
+    ///
+    /// let response = Response::builder()
+    ///     .header(http::header::ETAG, "\"v1\"".parse().unwrap())
+    ///     .not_modified();
+    /// ```
+    pub fn not_modified(self) -> Response {
+        self.status(http::StatusCode::NOT_MODIFIED)
+            .text("")
+    }
+
+    /// Creates an empty `202 Accepted` response.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Response;
+    ///
+    /// let response = Response::builder()
+    ///     .accepted();
+    /// ```
+    pub fn accepted(self) -> Response {
+        self.status(http::StatusCode::ACCEPTED)
+            .text("")
+    }
+
+    /// Creates an empty `201 Created` response pointing at the new resource.
+    ///
+    /// # Arguments
+    ///
+    /// * `location` - The URI of the created resource, sent as the `location` header
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `location` isn't a valid header value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Response;
+    ///
+    /// let response = Response::builder()
+    ///     .created("/users/42")?;
+    /// ```
+    pub fn created(self, location: &str) -> Result<Response, VetisError> {
+        let location = HeaderValue::from_str(location).map_err(http::Error::from)?;
+
+        Ok(self
+            .status(http::StatusCode::CREATED)
+            .header(http::header::LOCATION, location)
+            .text(""))
+    }
+
+    /// Sets `content-length` for a body of known length, unless already set.
+    ///
+    /// Statuses that can't carry a body are left alone: `304 Not Modified`
+    /// describes the length of the representation it stands for, not its own.
+    fn content_length(mut self, length: usize) -> Self {
+        if self
+            .status
+            .is_informational()
+            || self.status == http::StatusCode::NO_CONTENT
+            || self.status == http::StatusCode::NOT_MODIFIED
+        {
+            return self;
+        }
+
+        let headers = self
+            .headers
+            .get_or_insert_with(http::HeaderMap::new);
+        if !headers.contains_key(http::header::CONTENT_LENGTH) {
+            headers.insert(http::header::CONTENT_LENGTH, HeaderValue::from(length));
+        }
+        self
     }
 
     /// Sets the body and creates the final `Response`.
@@ -250,4 +421,123 @@ impl Response {
     pub fn into_inner(self) -> http::Response<HttpBody> {
         self.inner
     }
+
+    /// Returns the response status.
+    ///
+    /// # Returns
+    ///
+    /// * `http::StatusCode` - The status code of the response.
+    pub fn status(&self) -> http::StatusCode {
+        self.inner.status()
+    }
+
+    /// Returns the response headers.
+    ///
+    /// # Returns
+    ///
+    /// * `&http::HeaderMap` - The headers of the response.
+    pub fn headers(&self) -> &http::HeaderMap {
+        self.inner.headers()
+    }
+
+    /// Returns the response headers for modification.
+    ///
+    /// # Returns
+    ///
+    /// * `&mut http::HeaderMap` - The headers of the response.
+    pub fn headers_mut(&mut self) -> &mut http::HeaderMap {
+        self.inner
+            .headers_mut()
+    }
+
+    /// Attaches a tag to the response, logged by the `%tags` access log placeholder.
+    ///
+    /// Tags are logged as `key=value` pairs in the order they were added,
+    /// and aren't sent to the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The tag name, such as `user` or `route`
+    /// * `value` - The tag value
+    ///
+    /// # Returns
+    ///
+    /// * `Response` - The response with the tag attached.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::Response;
+    ///
+    /// let response = Response::builder()
+    ///     .text("Hello")
+    ///     .log_tag("user", "42");
+    /// ```
+    pub fn log_tag(mut self, key: &str, value: &str) -> Response {
+        self.add_log_tag(key, value);
+        self
+    }
+
+    /// Attaches a tag to the response in place, as [`log_tag`](Self::log_tag) does.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The tag name
+    /// * `value` - The tag value
+    pub fn add_log_tag(&mut self, key: &str, value: &str) {
+        self.inner
+            .extensions_mut()
+            .get_or_insert_default::<LogTags>()
+            .0
+            .push((key.to_string(), value.to_string()));
+    }
+
+    /// Transforms the response body, keeping the status, version and headers.
+    ///
+    /// This lets middleware wrap or replace the body, for instance to compress
+    /// it or count the bytes sent. Headers describing the body, such as
+    /// `content-length`, are left untouched and must be updated by the caller
+    /// when the transformation changes them.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The function transforming the body
+    ///
+    /// # Returns
+    ///
+    /// * `Response` - The response with the transformed body.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use http_body_util::BodyExt;
+    /// use hyper_body_utils::HttpBody;
+    /// use vetis::Response;
+    ///
+    /// let response = Response::builder()
+    ///     .text("hello")
+    ///     .map_body(|body| {
+    ///         HttpBody::Stream(
+    ///             body.map_frame(|frame| frame.map_data(|data| data.to_ascii_uppercase().into()))
+    ///                 .boxed(),
+    ///         )
+    ///     });
+    /// ```
+    pub fn map_body(self, f: impl FnOnce(HttpBody) -> HttpBody) -> Response {
+        let (parts, body) = self
+            .inner
+            .into_parts();
+
+        Response { inner: http::Response::from_parts(parts, f(body)) }
+    }
+}
+
+impl From<HttpError> for Response {
+    fn from(error: HttpError) -> Self {
+        let (status, headers, body) = error.into_parts();
+        Response::builder()
+            .status(status)
+            .headers(headers)
+            .bytes(body)
+    }
 }
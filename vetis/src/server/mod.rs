@@ -37,6 +37,10 @@ use std::future::Future;
 
 use crate::{config::server::ServerConfig, errors::VetisError, VetisVirtualHosts};
 
+#[cfg(feature = "admin")]
+pub(crate) mod admin;
+#[cfg(feature = "config-watch")]
+pub(crate) mod config_watch;
 pub mod conn;
 pub mod http;
 pub mod tls;
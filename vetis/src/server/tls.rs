@@ -7,9 +7,12 @@ use crate::{
 
 use rustls::{
     pki_types::{CertificateDer, PrivateKeyDer},
-    server::ResolvesServerCertUsingSni,
+    server::{
+        NoServerSessionStorage, ResolvesServerCertUsingSni, ServerSessionMemoryCache,
+        WebPkiClientVerifier,
+    },
     sign::CertifiedKey,
-    ServerConfig,
+    RootCertStore, ServerConfig,
 };
 
 pub struct TlsFactory {}
@@ -27,6 +30,10 @@ impl TlsFactory {
         #[cfg(feature = "__rustls_rustcrypto")]
         let provider = rustls_rustcrypto::provider();
         let mut resolver = ResolvesServerCertUsingSni::new();
+        let mut max_early_data_size = 0;
+        let mut session_resumption = true;
+        let mut session_cache_size = 0;
+        let mut client_roots = RootCertStore::empty();
         let virtual_hosts = virtual_hosts
             .read()
             .await;
@@ -35,6 +42,14 @@ impl TlsFactory {
                 .config()
                 .security()
             {
+                // Early data is accepted for the listener if any host allows it, virtual
+                // hosts answer early requests with 425 unless they allow it themselves
+                max_early_data_size = max_early_data_size.max(security.max_early_data_size());
+                // Virtual hosts share one TLS configuration, so resumption stays on only if
+                // every host allows it, and the cache is sized for the host asking the most
+                session_resumption &= security.session_resumption();
+                session_cache_size = session_cache_size.max(security.session_cache_size());
+
                 let cert = security.cert();
                 let key = security.key();
 
@@ -42,6 +57,13 @@ impl TlsFactory {
                 let mut chain = vec![cert];
                 if let Some(ca_cert) = security.ca_cert() {
                     let ca_cert = CertificateDer::from(ca_cert.to_vec());
+                    if security.client_auth() {
+                        client_roots
+                            .add(ca_cert.clone())
+                            .map_err(|e| {
+                                Tls(format!("Failed to add client CA certificate: {}", e))
+                            })?;
+                    }
                     chain.push(ca_cert);
                 }
 
@@ -52,23 +74,136 @@ impl TlsFactory {
 
                 let hostname = hostname.0.clone();
 
-                resolver
-                    .add(&hostname, certified_key)
-                    .map_err(|e| Tls(e.to_string()))?;
+                resolver.add(&hostname, certified_key)?;
             }
         }
 
-        let builder = rustls::ServerConfig::builder_with_provider(Arc::new(provider))
-            .with_protocol_versions(&[&rustls::version::TLS13])
-            .map_err(|e| VetisError::Start(Tls(e.to_string())))?;
+        let provider = Arc::new(provider);
+        let builder = rustls::ServerConfig::builder_with_provider(provider.clone())
+            .with_protocol_versions(&[&rustls::version::TLS13])?;
+
+        // Virtual hosts share the TLS configuration, so certificates are requested
+        // from every client and each virtual host enforces its own policy
+        let builder = if client_roots.is_empty() {
+            builder.with_no_client_auth()
+        } else {
+            let verifier =
+                WebPkiClientVerifier::builder_with_provider(Arc::new(client_roots), provider)
+                    .allow_unauthenticated()
+                    .build()
+                    .map_err(|e| Tls(format!("Failed to create client verifier: {}", e)))?;
+            builder.with_client_cert_verifier(verifier)
+        };
 
-        let mut tls_config = builder
-            .with_no_client_auth()
-            .with_cert_resolver(Arc::new(resolver));
+        let mut tls_config = builder.with_cert_resolver(Arc::new(resolver));
 
-        tls_config.max_early_data_size = u32::MAX;
+        tls_config.max_early_data_size = max_early_data_size;
+        if !session_resumption {
+            // Without tickets or stored sessions every connection does a full handshake
+            tls_config.send_tls13_tickets = 0;
+            tls_config.session_storage = Arc::new(NoServerSessionStorage {});
+        } else if session_cache_size > 0 {
+            tls_config.session_storage = ServerSessionMemoryCache::new(session_cache_size);
+        }
         tls_config.alpn_protocols = alpn_protocols;
 
         Ok(Some(tls_config))
     }
 }
+
+/// Returns the subject common names and DNS subject alternative names of a certificate.
+///
+/// # Arguments
+///
+/// * `cert` - The DER encoded certificate
+///
+/// # Returns
+///
+/// * `Vec<String>` - The names, empty if the certificate can't be parsed.
+pub(crate) fn certificate_names(cert: &[u8]) -> Vec<String> {
+    let cert = CertificateDer::from(cert);
+    let Ok(cert) = webpki::EndEntityCert::try_from(&cert) else {
+        return Vec::new();
+    };
+
+    let mut names = common_names(cert.subject());
+    names.extend(
+        cert.valid_dns_names()
+            .map(str::to_string),
+    );
+    names
+}
+
+/// Returns whether a certificate name matches an allowed name, where `*.` matches one label.
+///
+/// # Arguments
+///
+/// * `allowed` - The allowed name, possibly a wildcard
+/// * `name` - The name found in the certificate
+///
+/// # Returns
+///
+/// * `bool` - Whether the name is allowed.
+pub(crate) fn name_matches(allowed: &str, name: &str) -> bool {
+    match allowed.strip_prefix("*.") {
+        Some(suffix) => name
+            .split_once('.')
+            .is_some_and(|(label, rest)| !label.is_empty() && rest.eq_ignore_ascii_case(suffix)),
+        None => allowed.eq_ignore_ascii_case(name),
+    }
+}
+
+const COMMON_NAME_OID: &[u8] = &[0x55, 0x04, 0x03];
+
+/// Extracts the common names from the contents of a DER encoded X.509 `Name`.
+fn common_names(mut subject: &[u8]) -> Vec<String> {
+    let mut names = Vec::new();
+    // Name ::= SEQUENCE OF SET OF SEQUENCE { type OID, value ANY }
+    while let Some((0x31, mut set, rest)) = der_next(subject) {
+        subject = rest;
+        while let Some((0x30, attribute, rest)) = der_next(set) {
+            set = rest;
+            let Some((0x06, oid, value)) = der_next(attribute) else {
+                continue;
+            };
+
+            if oid != COMMON_NAME_OID {
+                continue;
+            }
+
+            // UTF8String, PrintableString or IA5String
+            if let Some((0x0c | 0x13 | 0x16, value, _)) = der_next(value) {
+                if let Ok(value) = std::str::from_utf8(value) {
+                    names.push(value.to_string());
+                }
+            }
+        }
+    }
+    names
+}
+
+/// Splits the next DER element off `input`, returning its tag, contents and the remaining input.
+fn der_next(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, input) = input.split_first()?;
+    let (&first, mut input) = input.split_first()?;
+
+    let len = if first < 0x80 {
+        first as usize
+    } else {
+        let octets = (first & 0x7f) as usize;
+        if octets == 0 || octets > 4 || input.len() < octets {
+            return None;
+        }
+        let (len, rest) = input.split_at(octets);
+        input = rest;
+        len.iter()
+            .fold(0, |len, &octet| (len << 8) | octet as usize)
+    };
+
+    if input.len() < len {
+        return None;
+    }
+
+    let (contents, rest) = input.split_at(len);
+    Some((tag, contents, rest))
+}
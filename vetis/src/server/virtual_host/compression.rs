@@ -0,0 +1,301 @@
+//! Response compression negotiated from the request `accept-encoding` header.
+
+use std::{io::Write, sync::Arc};
+
+use bytes::Bytes;
+use http::{header, HeaderMap, HeaderValue, StatusCode};
+use http_body_util::BodyExt;
+use hyper_body_utils::HttpBody;
+
+use crate::{
+    errors::VetisError,
+    server::{
+        conn::budget::{BudgetReservation, MemoryBudget},
+        http::Response,
+    },
+};
+
+/// Content codings supported by the server, in order of preference.
+const SUPPORTED_ENCODINGS: &[ContentEncoding] = &[ContentEncoding::Brotli];
+
+/// Largest response body compressed when the virtual host sets no limit.
+pub(crate) const DEFAULT_MAX_COMPRESSED_BODY_SIZE: usize = 1024 * 1024;
+
+/// A content coding the server can apply to response bodies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContentEncoding {
+    /// Brotli (`br`)
+    Brotli,
+}
+
+impl ContentEncoding {
+    /// Returns the token used in `accept-encoding` and `content-encoding`
+    ///
+    /// # Returns
+    ///
+    /// * `&'static str` - The content coding token.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentEncoding::Brotli => "br",
+        }
+    }
+
+    fn encode(&self, body: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            ContentEncoding::Brotli => {
+                let mut writer = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+                writer.write_all(body)?;
+                Ok(writer.into_inner())
+            }
+        }
+    }
+}
+
+/// Picks the preferred supported coding allowed by an `accept-encoding` header.
+///
+/// Codings with a `q` value of `0` are rejected, and `*` matches any coding
+/// not listed explicitly. Returns `None` when the response should be sent
+/// with the identity coding.
+///
+/// # Arguments
+///
+/// * `accept_encoding` - The value of the request `accept-encoding` header
+///
+/// # Returns
+///
+/// * `Option<ContentEncoding>` - The coding to apply, if any.
+pub fn negotiate(accept_encoding: &str) -> Option<ContentEncoding> {
+    let accepted: Vec<(&str, f32)> = accept_encoding
+        .split(',')
+        .filter_map(|coding| {
+            let mut params = coding.split(';');
+            let name = params
+                .next()?
+                .trim();
+            if name.is_empty() {
+                return None;
+            }
+
+            let quality = params
+                .filter_map(|param| {
+                    param
+                        .trim()
+                        .strip_prefix("q=")
+                })
+                .find_map(|quality| {
+                    quality
+                        .trim()
+                        .parse::<f32>()
+                        .ok()
+                })
+                .unwrap_or(1.0);
+
+            Some((name, quality))
+        })
+        .collect();
+
+    let quality_of = |token: &str| {
+        accepted
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(token))
+            .or_else(|| {
+                accepted
+                    .iter()
+                    .find(|(name, _)| *name == "*")
+            })
+            .map(|(_, quality)| *quality)
+    };
+
+    SUPPORTED_ENCODINGS
+        .iter()
+        .copied()
+        .filter_map(|encoding| {
+            quality_of(encoding.as_str())
+                .filter(|quality| *quality > 0.0)
+                .map(|quality| (encoding, quality))
+        })
+        .fold(None, |best: Option<(ContentEncoding, f32)>, (encoding, quality)| match best {
+            Some((_, best_quality)) if best_quality >= quality => best,
+            _ => Some((encoding, quality)),
+        })
+        .map(|(encoding, _)| encoding)
+}
+
+/// Returns the body length of a response worth compressing, if it is one.
+fn compressible_length(status: StatusCode, headers: &HeaderMap, max_size: usize) -> Option<usize> {
+    if status != StatusCode::OK
+        || headers.contains_key(header::CONTENT_ENCODING)
+        || headers.contains_key(header::CONTENT_RANGE)
+    {
+        return None;
+    }
+
+    // Streamed bodies have no length and are left alone, as are empty ones
+    let length = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            value
+                .parse::<usize>()
+                .ok()
+        })
+        .filter(|length| *length > 0 && *length <= max_size)?;
+
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            value
+                .split(';')
+                .next()
+        })
+        .is_some_and(|mime| {
+            let mime = mime
+                .trim()
+                .to_ascii_lowercase();
+            mime.starts_with("text/")
+                || mime.ends_with("+json")
+                || mime.ends_with("+xml")
+                || matches!(
+                    mime.as_str(),
+                    "application/json"
+                        | "application/javascript"
+                        | "application/xml"
+                        | "application/wasm"
+                        | "image/svg+xml"
+                )
+        })
+        .then_some(length)
+}
+
+/// Compresses a response with the coding negotiated from `accept-encoding`.
+///
+/// Only successful, textual responses that are not already encoded and
+/// declare a `content-length` of at most `max_size` are compressed. The
+/// body is buffered, compressed off the async runtime, `content-length`
+/// recomputed, and `vary: accept-encoding` added.
+///
+/// When the server has a [`MemoryBudget`], the buffered bytes are reserved
+/// from it while compressing, and responses it can't fit are sent as they are.
+///
+/// # Arguments
+///
+/// * `accept_encoding` - The value of the request `accept-encoding` header, if any
+/// * `max_size` - The largest body compressed
+/// * `memory_budget` - The server memory budget, if any
+/// * `response` - The response to compress
+///
+/// # Returns
+///
+/// * `Result<Response, VetisError>` - The compressed response, or the original one.
+pub(crate) async fn compress_response(
+    accept_encoding: Option<&str>,
+    max_size: usize,
+    memory_budget: Option<Arc<MemoryBudget>>,
+    response: Response,
+) -> Result<Response, VetisError> {
+    let Some(encoding) = accept_encoding.and_then(negotiate) else {
+        return Ok(response);
+    };
+
+    let Some(length) = compressible_length(
+        response
+            .inner
+            .status(),
+        response
+            .inner
+            .headers(),
+        max_size,
+    ) else {
+        return Ok(response);
+    };
+
+    // Room for the body and its compressed copy, which brotli keeps about as large at worst
+    let mut reservation = BudgetReservation::new(memory_budget);
+    if reservation
+        .grow(length.saturating_mul(2))
+        .is_err()
+    {
+        return Ok(response);
+    }
+
+    let (mut parts, body) = response
+        .into_inner()
+        .into_parts();
+
+    let body: Bytes = body
+        .collect()
+        .await?
+        .to_bytes();
+
+    #[cfg(feature = "tokio-rt")]
+    let body = match tokio::task::spawn_blocking(move || encoding.encode(&body)).await {
+        Ok(result) => result,
+        Err(e) => Err(e.into()),
+    }?;
+
+    #[cfg(feature = "smol-rt")]
+    let body = smol::unblock(move || encoding.encode(&body)).await?;
+
+    set_encoding_headers(&mut parts.headers, encoding);
+    parts
+        .headers
+        .insert(header::CONTENT_LENGTH, HeaderValue::from(body.len()));
+
+    Ok(Response { inner: http::Response::from_parts(parts, HttpBody::from_bytes(&body)) })
+}
+
+/// Sets the headers of a compressed response on a response to a `HEAD` request without a body.
+///
+/// The coding is negotiated as for `GET`, so `content-encoding` and `vary`
+/// match its response. As the body isn't there to encode, the compressed
+/// length is unknown and `content-length` is dropped.
+///
+/// # Arguments
+///
+/// * `accept_encoding` - The value of the request `accept-encoding` header, if any
+/// * `max_size` - The largest body compressed
+/// * `response` - The response to the `HEAD` request
+///
+/// # Returns
+///
+/// * `Response` - The response with the headers of its compressed form, or the original one.
+pub(crate) fn compress_head_response(
+    accept_encoding: Option<&str>,
+    max_size: usize,
+    mut response: Response,
+) -> Response {
+    let Some(encoding) = accept_encoding.and_then(negotiate) else {
+        return response;
+    };
+
+    if compressible_length(
+        response
+            .inner
+            .status(),
+        response
+            .inner
+            .headers(),
+        max_size,
+    )
+    .is_none()
+    {
+        return response;
+    }
+
+    let headers = response
+        .inner
+        .headers_mut();
+    set_encoding_headers(headers, encoding);
+    headers.remove(header::CONTENT_LENGTH);
+
+    response
+}
+
+/// Marks the headers of a response as those of its body encoded with `encoding`.
+fn set_encoding_headers(headers: &mut HeaderMap, encoding: ContentEncoding) {
+    headers.remove(header::TRANSFER_ENCODING);
+    headers.remove(header::ACCEPT_RANGES);
+    headers.insert(header::CONTENT_ENCODING, HeaderValue::from_static(encoding.as_str()));
+    headers.append(header::VARY, HeaderValue::from_static("accept-encoding"));
+}
@@ -0,0 +1,150 @@
+//! Request body decompression driven by the request `content-encoding` header.
+
+use std::{io::Read, sync::Arc};
+
+use bytes::{Bytes, BytesMut};
+use http::{header, HeaderValue};
+use http_body_util::BodyExt;
+use hyper_body_utils::HttpBody;
+
+use crate::{
+    errors::VetisError,
+    server::{
+        conn::budget::{BudgetReservation, MemoryBudget},
+        http::Request,
+    },
+};
+
+/// Content codings the server can decode, including the legacy `x-gzip` alias.
+const SUPPORTED_CODINGS: &[&str] = &["gzip", "x-gzip", "deflate", "br"];
+
+/// Largest decompressed request body accepted when the virtual host sets no limit.
+pub(crate) const DEFAULT_MAX_DECOMPRESSED_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// Decodes `body` with a single content coding, failing once it grows past `max_size`.
+fn decode(coding: &str, body: &[u8], max_size: usize) -> Result<Vec<u8>, VetisError> {
+    let reader: Box<dyn Read + '_> = match coding {
+        "gzip" | "x-gzip" => Box::new(flate2::read::MultiGzDecoder::new(body)),
+        "deflate" => Box::new(flate2::read::ZlibDecoder::new(body)),
+        "br" => Box::new(brotli::Decompressor::new(body, 4096)),
+        coding => return Err(VetisError::UnsupportedEncoding(coding.to_string())),
+    };
+
+    // Reading one byte past the limit tells a body of exactly `max_size` bytes from a larger one
+    let mut decoded = Vec::new();
+    reader
+        .take(max_size as u64 + 1)
+        .read_to_end(&mut decoded)
+        .map_err(|e| VetisError::Body(format!("Failed to decode {} body: {}", coding, e)))?;
+
+    if decoded.len() > max_size {
+        return Err(VetisError::PayloadTooLarge);
+    }
+
+    Ok(decoded)
+}
+
+/// Collects a body, failing once it grows past `max_size` or `reservation` can't fit it.
+async fn collect_body(
+    mut body: HttpBody,
+    max_size: usize,
+    reservation: &mut BudgetReservation,
+) -> Result<Bytes, VetisError> {
+    let mut buffer = BytesMut::new();
+    while let Some(frame) = body.frame().await {
+        let frame = frame.map_err(|e| VetisError::Body(e.to_string()))?;
+        if let Ok(data) = frame.into_data() {
+            if buffer.len() + data.len() > max_size {
+                return Err(VetisError::PayloadTooLarge);
+            }
+            reservation.grow(data.len())?;
+            buffer.extend_from_slice(&data);
+        }
+    }
+    Ok(buffer.freeze())
+}
+
+/// Decompresses a request body encoded with `gzip`, `deflate` or `br`.
+///
+/// Codings listed in `content-encoding` are undone in reverse order. The
+/// body is buffered, `content-encoding` removed and `content-length`
+/// recomputed, so handlers see the decoded body. Requests without a
+/// `content-encoding`, or using `identity`, are returned untouched.
+///
+/// When the request carries a server [`MemoryBudget`], the buffered bytes
+/// are reserved from it until the request is dropped.
+///
+/// # Arguments
+///
+/// * `request` - The request to decompress
+/// * `max_size` - The largest body accepted, both before and after decoding
+///
+/// # Returns
+///
+/// * `Result<Request, VetisError>` - The decompressed request, `VetisError::PayloadTooLarge`
+///   when the body exceeds `max_size`, `VetisError::MemoryBudgetExceeded` when the server budget
+///   can't fit it, or `VetisError::UnsupportedEncoding` for unknown codings.
+pub(crate) async fn decompress_request(
+    request: Request,
+    max_size: usize,
+) -> Result<Request, VetisError> {
+    let codings: Vec<String> = match request
+        .headers()
+        .get(header::CONTENT_ENCODING)
+    {
+        Some(value) => value
+            .to_str()
+            .map_err(|_| VetisError::UnsupportedEncoding("invalid header".to_string()))?
+            .split(',')
+            .map(|coding| {
+                coding
+                    .trim()
+                    .to_ascii_lowercase()
+            })
+            .filter(|coding| !coding.is_empty() && coding != "identity")
+            .collect(),
+        None => return Ok(request),
+    };
+
+    if let Some(coding) = codings
+        .iter()
+        .find(|coding| !SUPPORTED_CODINGS.contains(&coding.as_str()))
+    {
+        return Err(VetisError::UnsupportedEncoding(coding.clone()));
+    }
+
+    let (mut parts, body) = request.into_parts();
+    if codings.is_empty() {
+        return Ok(Request::from_parts(parts, body));
+    }
+
+    let mut reservation = BudgetReservation::new(
+        parts
+            .extensions
+            .get::<Arc<MemoryBudget>>()
+            .cloned(),
+    );
+
+    let mut body = collect_body(body, max_size, &mut reservation)
+        .await?
+        .to_vec();
+    for coding in codings.iter().rev() {
+        body = decode(coding, &body, max_size)?;
+        reservation.grow(body.len())?;
+    }
+
+    parts
+        .headers
+        .remove(header::CONTENT_ENCODING);
+    parts
+        .headers
+        .remove(header::TRANSFER_ENCODING);
+    parts
+        .headers
+        .insert(header::CONTENT_LENGTH, HeaderValue::from(body.len()));
+    parts
+        .extensions
+        .insert(Arc::new(reservation));
+
+    Ok(Request::from_parts(parts, HttpBody::from_bytes(&body)))
+}
@@ -0,0 +1,132 @@
+//! Hooks run on responses once virtual hosts have built them.
+
+use std::time::{Duration, Instant};
+
+use http::{HeaderName, HeaderValue};
+
+use crate::server::http::Response;
+
+/// Timing of a request, as measured by the virtual host serving it.
+#[derive(Debug, Clone, Copy)]
+pub struct ResponseTiming {
+    started: Instant,
+    elapsed: Duration,
+}
+
+impl ResponseTiming {
+    /// Create the timing of a request started at `started` and answered now
+    pub(crate) fn since(started: Instant) -> Self {
+        Self { started, elapsed: started.elapsed() }
+    }
+
+    /// Returns when the virtual host started serving the request
+    ///
+    /// # Returns
+    ///
+    /// * `Instant` - The instant the request was dispatched to the virtual host.
+    pub fn started(&self) -> Instant {
+        self.started
+    }
+
+    /// Returns how long the virtual host took to build the response
+    ///
+    /// # Returns
+    ///
+    /// * `Duration` - The time spent routing, handling and transforming the request.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+/// Trait for mutating responses right before they are sent.
+///
+/// Hooks are registered on a virtual host with
+/// [`VirtualHost::add_response_hook`](crate::server::virtual_host::VirtualHost::add_response_hook)
+/// and run in registration order, after paths, transforms and default
+/// headers, so they see the response as the client will. They suit headers
+/// only known once the response is built, such as `server-timing`.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use vetis::server::virtual_host::hook::{ResponseHook, ResponseTiming};
+///
+/// struct SlowRequests;
+///
+/// impl ResponseHook for SlowRequests {
+///     fn after_response(&self, response: &mut vetis::Response, timing: &ResponseTiming) {
+///         if timing.elapsed().as_secs() > 1 {
+///             response
+///                 .headers_mut()
+///                 .insert("x-slow", http::HeaderValue::from_static("1"));
+///         }
+///     }
+/// }
+/// ```
+pub trait ResponseHook: Send + Sync {
+    /// Mutates a response before it is sent
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - The response built by the virtual host
+    /// * `timing` - The timing of the request
+    fn after_response(&self, response: &mut Response, timing: &ResponseTiming);
+}
+
+/// Header carrying server-side metrics, as defined by the Server Timing specification.
+const SERVER_TIMING: HeaderName = HeaderName::from_static("server-timing");
+
+/// Adds a `server-timing` entry with the time taken to build each response.
+///
+/// Entries already set by handlers are kept, the new one is appended as
+/// `<metric>;dur=<milliseconds>`.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use vetis::server::virtual_host::hook::ServerTiming;
+///
+/// virtual_host.add_response_hook(ServerTiming::new("app"));
+/// ```
+pub struct ServerTiming {
+    metric: String,
+}
+
+impl ServerTiming {
+    /// Create a new hook reporting the response duration under the given metric name
+    ///
+    /// # Arguments
+    ///
+    /// * `metric` - The metric name, such as `app` or `total`
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new `ServerTiming` instance.
+    pub fn new(metric: &str) -> Self {
+        Self { metric: metric.to_string() }
+    }
+}
+
+impl Default for ServerTiming {
+    fn default() -> Self {
+        Self::new("total")
+    }
+}
+
+impl ResponseHook for ServerTiming {
+    fn after_response(&self, response: &mut Response, timing: &ResponseTiming) {
+        let duration = timing
+            .elapsed()
+            .as_secs_f64()
+            * 1000.0;
+        let Ok(value) = HeaderValue::from_str(&format!("{};dur={:.3}", self.metric, duration))
+        else {
+            log::error!("Invalid server timing metric: {}", self.metric);
+            return;
+        };
+
+        response
+            .headers_mut()
+            .append(SERVER_TIMING, value);
+    }
+}
@@ -21,22 +21,37 @@
 ///     Ok(response)
 /// }));
 /// ```
-use std::{future::Future, path::PathBuf, pin::Pin};
+use std::{
+    collections::HashMap,
+    future::Future,
+    path::PathBuf,
+    pin::Pin,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
 
 use http::StatusCode;
+use http_body_util::BodyExt;
 use hyper_body_utils::HttpBody;
 #[cfg(feature = "python")]
 use pyo3::Python;
-use radix_trie::Trie;
+use radix_trie::{Trie, TrieCommon};
 use std::sync::Arc;
 
 use crate::{
-    config::server::virtual_host::VirtualHostConfig,
-    errors::{FileError, VetisError, VirtualHostError},
+    config::server::virtual_host::{SecurityConfig, VirtualHostConfig},
+    errors::{VetisError, VirtualHostError},
     server::{
+        conn::budget::MemoryBudget,
         http::{Request, Response},
-        virtual_host::path::{HostPath, Path},
+        tls::{certificate_names, name_matches},
+        virtual_host::{
+            hook::{ResponseHook, ResponseTiming},
+            path::{HandlerPath, HostPath, Path},
+            transform::{apply_transforms, BodyTransform},
+        },
     },
+    utils::{headers::parse_header, time::timeout},
 };
 
 #[cfg(feature = "smol-rt")]
@@ -44,6 +59,15 @@ use smol::fs::File;
 #[cfg(feature = "tokio-rt")]
 use tokio::fs::File;
 
+#[cfg(feature = "compression-brotli")]
+use crate::server::virtual_host::compression::{
+    compress_head_response, compress_response, DEFAULT_MAX_COMPRESSED_BODY_SIZE,
+};
+#[cfg(feature = "request-decompression")]
+use crate::server::virtual_host::decompression::{
+    decompress_request, DEFAULT_MAX_DECOMPRESSED_BODY_SIZE,
+};
+
 #[cfg(feature = "static-files")]
 use crate::server::virtual_host::path::static_files::StaticPath;
 
@@ -53,7 +77,13 @@ use crate::server::virtual_host::path::proxy::ProxyPath;
 #[cfg(feature = "interface")]
 use crate::server::virtual_host::path::interface::InterfacePath;
 
+#[cfg(feature = "compression-brotli")]
+pub mod compression;
+#[cfg(feature = "request-decompression")]
+pub mod decompression;
+pub mod hook;
 pub mod path;
+pub mod transform;
 
 /// Type alias for boxed handler closures.
 ///
@@ -82,6 +112,12 @@ pub type BoxedHandlerClosure = Box<
         + Sync,
 >;
 
+/// Type alias for the error handler of a virtual host.
+///
+/// It turns the error returned by a path into the response sent to the
+/// client, given the request the path failed to serve, without its body.
+pub type ErrorHandler = Box<dyn Fn(VetisError, &Request) -> Response + Send + Sync>;
+
 /// Creates a handler closure from a function.
 ///
 /// This utility function converts any compatible async function into a
@@ -122,10 +158,248 @@ where
     Box::new(move |req| Box::pin(f(req)))
 }
 
+/// Trait for request handlers implemented as types.
+///
+/// Closures created with [`handler_fn`] implement it, so handler paths accept
+/// either. Implementing it on a struct lets a handler own state, such as a
+/// database pool or counters, and be tested on its own.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use std::{future::Future, pin::Pin, sync::atomic::{AtomicUsize, Ordering}};
+///
+/// use vetis::{
+///     server::virtual_host::{path::HandlerPath, Handler},
+///     Request, Response, VetisError,
+/// };
+///
+/// struct Counter {
+///     hits: AtomicUsize,
+/// }
+///
+/// impl Handler for Counter {
+///     fn handle(
+///         &self,
+///         _request: Request,
+///     ) -> Pin<Box<dyn Future<Output = Result<Response, VetisError>> + Send + '_>> {
+///         Box::pin(async move {
+///             let hits = self.hits.fetch_add(1, Ordering::Relaxed) + 1;
+///             Ok(Response::builder()
+///                 .status(http::StatusCode::OK)
+///                 .text(&hits.to_string()))
+///         })
+///     }
+/// }
+///
+/// let path = HandlerPath::builder()
+///     .uri("/hits")
+///     .handler(Counter { hits: AtomicUsize::new(0) })
+///     .build()?;
+/// ```
+pub trait Handler: Send + Sync {
+    /// Handles a request
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request to handle
+    ///
+    /// # Returns
+    ///
+    /// * `Pin<Box<dyn Future<Output = Result<Response, VetisError>> + Send + '_>>` - The future that will handle the request
+    fn handle(
+        &self,
+        request: Request,
+    ) -> Pin<Box<dyn Future<Output = Result<Response, VetisError>> + Send + '_>>;
+}
+
+impl<F> Handler for F
+where
+    F: Fn(Request) -> Pin<Box<dyn Future<Output = Result<Response, VetisError>> + Send>>
+        + Send
+        + Sync,
+{
+    fn handle(
+        &self,
+        request: Request,
+    ) -> Pin<Box<dyn Future<Output = Result<Response, VetisError>> + Send + '_>> {
+        self(request)
+    }
+}
+
+impl Handler for Box<dyn Handler> {
+    fn handle(
+        &self,
+        request: Request,
+    ) -> Pin<Box<dyn Future<Output = Result<Response, VetisError>> + Send + '_>> {
+        self.as_ref()
+            .handle(request)
+    }
+}
+
+/// Methods advertised in the `allow` header when the virtual host doesn't customize them.
+const DEFAULT_ALLOWED_METHODS: &[&str] =
+    &["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"];
+
+/// Builds the `allow` header value advertised by the virtual host.
+fn allow_header(config: &VirtualHostConfig) -> http::HeaderValue {
+    let mut methods: Vec<&str> = match config.allowed_methods() {
+        Some(methods) => methods
+            .iter()
+            .map(String::as_str)
+            .collect(),
+        None => DEFAULT_ALLOWED_METHODS.to_vec(),
+    };
+
+    methods.retain(|method| *method != "TRACE");
+    if config.allow_trace() {
+        methods.push("TRACE");
+    }
+
+    // Methods are validated when the configuration is built
+    http::HeaderValue::from_str(&methods.join(", "))
+        .unwrap_or_else(|_| http::HeaderValue::from_static("GET, HEAD"))
+}
+
+/// Drops the body of a response to a `HEAD` request, keeping its status and headers.
+///
+/// Bodies without a `content-length` are read to measure it, as a `GET` would have received.
+async fn without_body(response: Response) -> Result<Response, VetisError> {
+    let (mut parts, body) = response
+        .into_inner()
+        .into_parts();
+
+    if !parts
+        .headers
+        .contains_key(http::header::CONTENT_LENGTH)
+    {
+        let length = body
+            .collect()
+            .await?
+            .to_bytes()
+            .len();
+        parts
+            .headers
+            .insert(http::header::CONTENT_LENGTH, http::HeaderValue::from(length));
+    }
+
+    Ok(Response { inner: http::Response::from_parts(parts, HttpBody::from_bytes(&[])) })
+}
+
+/// Asks an HTTP/1 client to close the connection after a request whose body was rejected.
+///
+/// The rest of the body is left unread, so the connection can't carry another request.
+fn close_connection(version: http::Version, mut response: Response) -> Response {
+    if version <= http::Version::HTTP_11 {
+        response
+            .inner
+            .headers_mut()
+            .insert(http::header::CONNECTION, http::HeaderValue::from_static("close"));
+    }
+    response
+}
+
+/// Returns the `https://` URL of a request, keeping the host and port it was sent to.
+fn https_location(request: &Request, hostname: &str, port: u16) -> String {
+    // Absolute-form requests are addressed to their URI authority rather than the host header
+    let host = request
+        .uri()
+        .authority()
+        .map(|authority| authority.to_string())
+        .or_else(|| {
+            request
+                .headers()
+                .get(http::header::HOST)
+                .and_then(|host| host.to_str().ok())
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| format!("{}:{}", hostname, port));
+
+    let path_and_query = request
+        .uri()
+        .path_and_query()
+        .map(|path_and_query| path_and_query.as_str())
+        .unwrap_or("/");
+
+    format!("https://{}{}", host, path_and_query)
+}
+
+/// Seconds clients are asked to wait when a virtual host is at its concurrent request limit.
+const BUSY_RETRY_AFTER_SECS: u64 = 1;
+
+/// A request counted against `max_concurrent_requests`, released when dropped.
+struct RequestSlot<'a>(&'a AtomicUsize);
+
+impl Drop for RequestSlot<'_> {
+    fn drop(&mut self) {
+        self.0
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Instant by which a request must be answered, set when the virtual host has a request timeout.
+///
+/// Paths waiting on other services bound their calls to the time left, so they can answer
+/// on their own before the request is cancelled.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RequestDeadline(Instant);
+
+impl RequestDeadline {
+    /// Creates a deadline `timeout` from now.
+    pub(crate) fn after(timeout: Duration) -> Self {
+        Self(Instant::now() + timeout)
+    }
+
+    /// Returns the time left before the deadline, zero once it has passed.
+    pub(crate) fn remaining(&self) -> Duration {
+        self.0
+            .saturating_duration_since(Instant::now())
+    }
+}
+
+/// Removes the base path from a request path, or returns `None` when the path lies outside it.
+fn strip_base_path(uri_path: &str, base_path: &str) -> Option<String> {
+    let base_path = base_path.trim_end_matches('/');
+    match uri_path.strip_prefix(base_path)? {
+        "" => Some("/".to_string()),
+        rest if rest.starts_with('/') => Some(rest.to_string()),
+        _ => None,
+    }
+}
+
+/// Returns whether the request carries a client certificate accepted by the security policy.
+fn client_authorized(security: &SecurityConfig, request: &Request) -> bool {
+    let Some(leaf) = request
+        .peer_certificates()
+        .and_then(|chain| chain.first())
+    else {
+        return false;
+    };
+
+    let Some(allowed_names) = security.allowed_client_names() else {
+        return true;
+    };
+
+    certificate_names(leaf)
+        .iter()
+        .any(|name| {
+            allowed_names
+                .iter()
+                .any(|allowed| name_matches(allowed, name))
+        })
+}
+
 // All of them should have a handler to process requests
 pub struct VirtualHost {
     config: VirtualHostConfig,
     paths: Trie<String, HostPath>,
+    transforms: Vec<Box<dyn BodyTransform>>,
+    response_hooks: Vec<Box<dyn ResponseHook>>,
+    error_handler: Option<ErrorHandler>,
+    request_count: AtomicU64,
+    in_flight_requests: AtomicUsize,
+    path_request_counts: HashMap<String, AtomicU64>,
+    memory_budget: Option<Arc<MemoryBudget>>,
 }
 
 impl VirtualHost {
@@ -139,7 +413,17 @@ impl VirtualHost {
     ///
     /// * `Self` - A new `VirtualHost` instance.
     pub fn new(host_config: VirtualHostConfig) -> Self {
-        let mut host = Self { config: host_config.clone(), paths: Trie::new() };
+        let mut host = Self {
+            config: host_config.clone(),
+            paths: Trie::new(),
+            transforms: Vec::new(),
+            response_hooks: Vec::new(),
+            error_handler: None,
+            request_count: AtomicU64::new(0),
+            in_flight_requests: AtomicUsize::new(0),
+            path_request_counts: HashMap::new(),
+            memory_budget: None,
+        };
 
         #[cfg(feature = "python")]
         Python::initialize();
@@ -168,6 +452,48 @@ impl VirtualHost {
         host
     }
 
+    /// Create a new virtual host with a single handler serving every path
+    ///
+    /// # Arguments
+    ///
+    /// * `host_config` - A `VirtualHostConfig` instance containing the virtual host configuration.
+    /// * `handler` - The handler invoked for every request.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new `VirtualHost` instance.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::server::virtual_host::{handler_fn, VirtualHost};
+    ///
+    /// let vhost = VirtualHost::with_handler(
+    ///     config,
+    ///     handler_fn(|_request| async move {
+    ///         Ok(vetis::Response::builder()
+    ///             .status(http::StatusCode::OK)
+    ///             .text("Hello, World!"))
+    ///     }),
+    /// );
+    /// ```
+    pub fn with_handler(host_config: VirtualHostConfig, handler: BoxedHandlerClosure) -> Self {
+        let mut host = Self::new(host_config);
+        host.set_handler(handler);
+        host
+    }
+
+    /// Set the handler serving every request not matched by a more specific path
+    ///
+    /// The handler is registered at `/`, replacing any path already registered there.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler invoked for every request.
+    pub fn set_handler(&mut self, handler: BoxedHandlerClosure) {
+        self.add_path(HostPath::Handler(HandlerPath::root(handler)));
+    }
+
     /// Add a path to the virtual host
     ///
     /// # Arguments
@@ -178,6 +504,12 @@ impl VirtualHost {
         P: Into<HostPath>,
     {
         let path = path.into();
+        self.path_request_counts
+            .insert(
+                path.uri()
+                    .to_string(),
+                AtomicU64::new(0),
+            );
         self.paths.insert(
             path.uri()
                 .to_string(),
@@ -185,6 +517,65 @@ impl VirtualHost {
         );
     }
 
+    /// Add a response body transform to the virtual host
+    ///
+    /// Transforms run in the order they were added, on every response
+    /// returned by the virtual host paths.
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - A `BodyTransform` implementation.
+    pub fn add_transform<T>(&mut self, transform: T)
+    where
+        T: BodyTransform + 'static,
+    {
+        self.transforms
+            .push(Box::new(transform));
+    }
+
+    /// Add a hook run on every response right before it is sent
+    ///
+    /// Hooks run in the order they were added, once the response has been
+    /// transformed and the default headers added.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - A `ResponseHook` implementation.
+    pub fn add_response_hook<H>(&mut self, hook: H)
+    where
+        H: ResponseHook + 'static,
+    {
+        self.response_hooks
+            .push(Box::new(hook));
+    }
+
+    /// Set the handler rendering the errors returned by paths
+    ///
+    /// It replaces the default mapping of errors to status pages, for every
+    /// error a path returns except those built with `HttpError`, which are
+    /// already responses and are sent as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The function turning an error and the failed request into a response.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// virtual_host.set_error_handler(|error, request| {
+    ///     Response::builder()
+    ///         .status(error.status_code())
+    ///         .header(http::header::CONTENT_TYPE, http::HeaderValue::from_static("application/json"))
+    ///         .text(&format!(r#"{{"error":"{}","path":"{}"}}"#, error, request.uri().path()))
+    /// });
+    /// ```
+    pub fn set_error_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(VetisError, &Request) -> Response + Send + Sync + 'static,
+    {
+        self.error_handler = Some(Box::new(handler));
+    }
+
     /// Returns virtual host configuration
     ///
     /// # Returns
@@ -224,6 +615,78 @@ impl VirtualHost {
             .is_some()
     }
 
+    /// Returns the number of requests routed to the virtual host
+    ///
+    /// Every request is counted, including those rejected before reaching a path.
+    ///
+    /// # Returns
+    ///
+    /// * `u64` - The number of requests routed since the virtual host was created.
+    pub fn request_count(&self) -> u64 {
+        self.request_count
+            .load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of requests handled by a path
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The URI the path was registered with.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<u64>` - The number of requests matched by the path, or `None` if no path is registered at `uri`.
+    pub fn path_request_count(&self, uri: &str) -> Option<u64> {
+        self.path_request_counts
+            .get(uri)
+            .map(|count| count.load(Ordering::Relaxed))
+    }
+
+    /// Sets the server-wide budget that buffered bodies are accounted against
+    ///
+    /// # Arguments
+    ///
+    /// * `memory_budget` - The budget shared by the virtual hosts of the server, or `None` for no limit.
+    pub(crate) fn set_memory_budget(&mut self, memory_budget: Option<Arc<MemoryBudget>>) {
+        self.memory_budget = memory_budget;
+    }
+
+    /// Adds server-wide headers to every response, overridden by the virtual host's own default headers
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers shared by every virtual host of the server.
+    pub(crate) fn inherit_headers(&mut self, headers: &[(String, String)]) {
+        self.config
+            .inherit_headers(headers);
+    }
+
+    /// Returns the paths registered on the virtual host
+    ///
+    /// # Returns
+    ///
+    /// * `impl Iterator<Item = &HostPath>` - The paths, ordered by URI.
+    pub fn paths(&self) -> impl Iterator<Item = &HostPath> {
+        self.paths.values()
+    }
+
+    /// Counts a request as in flight, unless the virtual host is at its concurrent request limit.
+    fn track_request(&self) -> Option<RequestSlot<'_>> {
+        let previous = self
+            .in_flight_requests
+            .fetch_add(1, Ordering::Relaxed);
+        let slot = RequestSlot(&self.in_flight_requests);
+
+        match self
+            .config
+            .max_concurrent_requests()
+        {
+            // Dropping the slot gives the count back
+            Some(max_concurrent_requests) if previous >= max_concurrent_requests => None,
+            _ => Some(slot),
+        }
+    }
+
     async fn serve_status_page(&self, status: u16) -> Result<Response, VetisError> {
         let status_code = match StatusCode::from_u16(status) {
             Ok(code) => code,
@@ -265,6 +728,73 @@ impl VirtualHost {
         Ok(static_status_response)
     }
 
+    /// Runs a request through the virtual host as a listener would, without any network I/O.
+    ///
+    /// The request is routed to its path, transformed and compressed like
+    /// in [`VirtualHost::route`], then the configured default headers are
+    /// added and the response hooks run. This makes handlers, static paths and status pages testable
+    /// in-process, without binding ports.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request to dispatch
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Response, VetisError>` - The response listeners would send.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use vetis::{server::virtual_host::VirtualHost, Request};
+    ///
+    /// let request = http::Request::builder()
+    ///     .uri("/hello")
+    ///     .body(hyper_body_utils::HttpBody::from_text(""))?;
+    /// let (parts, body) = request.into_parts();
+    ///
+    /// let response = virtual_host
+    ///     .dispatch(Request::from_parts(parts, body))
+    ///     .await?;
+    /// assert_eq!(response.into_inner().status(), http::StatusCode::OK);
+    /// ```
+    pub async fn dispatch(&self, request: Request) -> Result<Response, VetisError> {
+        let started = Instant::now();
+
+        let mut response = self
+            .route(request)
+            .await?;
+
+        if let Some(default_headers) = self
+            .config
+            .default_headers()
+        {
+            for (key, value) in default_headers {
+                let Some((header_name, header_value)) = parse_header(key, value) else {
+                    log::error!("Invalid default header: {}: {:?}", key, value);
+                    continue;
+                };
+
+                response
+                    .inner
+                    .headers_mut()
+                    .insert(header_name, header_value);
+            }
+        }
+
+        if !self
+            .response_hooks
+            .is_empty()
+        {
+            let timing = ResponseTiming::since(started);
+            for hook in &self.response_hooks {
+                hook.after_response(&mut response, &timing);
+            }
+        }
+
+        Ok(response)
+    }
+
     /// Route request to the appropriate handler
     ///
     /// # Arguments
@@ -278,6 +808,130 @@ impl VirtualHost {
         &self,
         request: Request,
     ) -> Pin<Box<dyn Future<Output = Result<Response, VetisError>> + Send + '_>> {
+        self.request_count
+            .fetch_add(1, Ordering::Relaxed);
+
+        if let Some(max_uri_length) = self
+            .config
+            .max_uri_length()
+        {
+            let uri_length = request
+                .uri()
+                .path_and_query()
+                .map(|path_and_query| {
+                    path_and_query
+                        .as_str()
+                        .len()
+                })
+                .unwrap_or(0);
+
+            if uri_length > max_uri_length {
+                return Box::pin(async move {
+                    self.serve_status_page(http::StatusCode::URI_TOO_LONG.as_u16())
+                        .await
+                });
+            }
+        }
+
+        if let Some(max_body_size) = self
+            .config
+            .max_body_size()
+        {
+            if request
+                .content_length()
+                .is_some_and(|content_length| content_length > max_body_size)
+            {
+                let version = request.version();
+                return Box::pin(async move {
+                    log::error!(
+                        "[{}] Request body exceeds {} bytes",
+                        request
+                            .request_id()
+                            .unwrap_or("-"),
+                        max_body_size
+                    );
+                    let response = self
+                        .serve_status_page(http::StatusCode::PAYLOAD_TOO_LARGE.as_u16())
+                        .await?;
+
+                    // The body is never read, clients waiting for `100 Continue` don't send it
+                    Ok(close_connection(version, response))
+                });
+            }
+        }
+
+        if self
+            .config
+            .require_tls()
+            && !request.is_secure()
+        {
+            let version = request.version();
+            let location = https_location(&request, self.hostname(), self.port());
+            return Box::pin(async move {
+                let location = http::HeaderValue::from_str(&location).map_err(http::Error::from)?;
+                let response = Response::builder()
+                    .status(http::StatusCode::PERMANENT_REDIRECT)
+                    .header(http::header::LOCATION, location)
+                    .text("");
+
+                // The client has to reconnect with TLS, any body left is never read
+                Ok(close_connection(version, response))
+            });
+        }
+
+        if let Some(security) = self
+            .config
+            .security()
+        {
+            if security.client_auth() && !client_authorized(security, &request) {
+                return Box::pin(async move {
+                    self.serve_status_page(http::StatusCode::FORBIDDEN.as_u16())
+                        .await
+                });
+            }
+        }
+
+        // Early data is enabled for a whole listener once one of its hosts opts in,
+        // the others ask the client to retry after the handshake
+        if request.is_early_data()
+            && self
+                .config
+                .security()
+                .as_ref()
+                .map_or(true, |security| security.max_early_data_size() == 0)
+        {
+            return Box::pin(async move {
+                self.serve_status_page(http::StatusCode::TOO_EARLY.as_u16())
+                    .await
+            });
+        }
+
+        if request.method() == http::Method::TRACE
+            && !self
+                .config
+                .allow_trace()
+        {
+            return Box::pin(async move {
+                let mut response = self
+                    .serve_status_page(http::StatusCode::METHOD_NOT_ALLOWED.as_u16())
+                    .await?;
+                response
+                    .inner
+                    .headers_mut()
+                    .insert(http::header::ALLOW, allow_header(&self.config));
+                Ok(response)
+            });
+        }
+
+        // `OPTIONS *` asks about the server as a whole, rather than any of its paths
+        if request.method() == http::Method::OPTIONS && request.uri() == "*" {
+            return Box::pin(async move {
+                Ok(Response::builder()
+                    .header(http::header::ALLOW, allow_header(&self.config))
+                    .no_content())
+            });
+        }
+
         let uri_path: String = request
             .uri()
             .path()
@@ -290,51 +944,255 @@ impl VirtualHost {
             });
         }
 
+        let uri_path = match self
+            .config
+            .base_path()
+        {
+            Some(base_path) => match strip_base_path(&uri_path, base_path) {
+                Some(path) => path,
+                None => {
+                    return Box::pin(async move {
+                        self.serve_status_page(http::StatusCode::NOT_FOUND.as_u16())
+                            .await
+                    });
+                }
+            },
+            None => uri_path,
+        };
+
         let matches = self
             .paths
             .get_ancestor_value(&uri_path);
 
         let Some(path) = matches else {
+            log::trace!("Path {} on {}:{} matched no path", uri_path, self.hostname(), self.port());
             return Box::pin(async move {
                 self.serve_status_page(http::StatusCode::NOT_FOUND.as_u16())
                     .await
             });
         };
 
+        log::trace!(
+            "Path {} on {}:{} matched {} path {}",
+            uri_path,
+            self.hostname(),
+            self.port(),
+            path.kind(),
+            path.uri()
+        );
+
+        if let Some(count) = self
+            .path_request_counts
+            .get(path.uri())
+        {
+            count.fetch_add(1, Ordering::Relaxed);
+        }
+
         let target_path: String = uri_path
             .strip_prefix(path.uri())
             .unwrap_or(&uri_path)
             .into();
 
-        let result = path.handle(request, Arc::from(target_path));
+        #[cfg(feature = "compression-brotli")]
+        let accept_encoding = request
+            .headers()
+            .get(http::header::ACCEPT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        #[cfg(feature = "reverse-proxy")]
+        let is_proxy = matches!(path, HostPath::Proxy(_));
+        #[cfg(not(feature = "reverse-proxy"))]
+        let is_proxy = false;
+
+        let timeout_status = if is_proxy {
+            http::StatusCode::GATEWAY_TIMEOUT
+        } else {
+            http::StatusCode::SERVICE_UNAVAILABLE
+        };
+
+        let deadline = self
+            .config
+            .request_timeout()
+            .map(RequestDeadline::after);
+
+        let version = request.version();
+
+        // Error lines carry the request id, matching them with the access log line
+        let request_id = request
+            .request_id()
+            .unwrap_or("-")
+            .to_string();
+
+        // Handlers answer HEAD like GET, the body is dropped once the response is built
+        let is_head = request.method() == http::Method::HEAD;
+        let head_as_get = is_head && matches!(path, HostPath::Handler(_));
+        let mut request = request;
+        if let Some(inner) = request
+            .inner
+            .as_mut()
+        {
+            if head_as_get {
+                *inner.method_mut() = http::Method::GET;
+            }
+
+            // Paths buffering the body reserve its bytes from the budget found here
+            if let Some(memory_budget) = &self.memory_budget {
+                inner
+                    .extensions_mut()
+                    .insert(memory_budget.clone());
+            }
+
+            if let Some(deadline) = deadline {
+                inner
+                    .extensions_mut()
+                    .insert(deadline);
+            }
+        }
+
+        let Some(slot) = self.track_request() else {
+            return Box::pin(async move {
+                log::error!(
+                    "[{}] Too many concurrent requests for {}",
+                    request_id,
+                    self.hostname()
+                );
+                let mut response = self
+                    .serve_status_page(http::StatusCode::SERVICE_UNAVAILABLE.as_u16())
+                    .await?;
+                response
+                    .inner
+                    .headers_mut()
+                    .insert(
+                        http::header::RETRY_AFTER,
+                        http::HeaderValue::from(BUSY_RETRY_AFTER_SECS),
+                    );
+                Ok(response)
+            });
+        };
 
         Box::pin(async move {
-            match result.await {
-                Ok(response) => Ok(response),
-                Err(error) => {
-                    match error {
-                        VetisError::VirtualHost(VirtualHostError::File(FileError::NotFound)) => {
-                            log::error!("Invalid path: {}", error);
-                            return self
-                                .serve_status_page(http::StatusCode::NOT_FOUND.as_u16())
-                                .await;
-                        }
-                        VetisError::VirtualHost(VirtualHostError::Proxy(ref error)) => {
-                            log::error!("Proxy error: {}", error);
-                            return self
-                                .serve_status_page(http::StatusCode::BAD_GATEWAY.as_u16())
-                                .await;
-                        }
-                        VetisError::VirtualHost(VirtualHostError::Auth(e)) => {
-                            log::error!("Auth error: {}", e);
-                            return self
-                                .serve_status_page(http::StatusCode::UNAUTHORIZED.as_u16())
-                                .await;
+            let _slot = slot;
+
+            // Proxied bodies are forwarded as sent, leaving decoding to the upstream
+            #[cfg(feature = "request-decompression")]
+            let request = if is_proxy {
+                request
+            } else {
+                let max_size = self
+                    .config
+                    .max_decompressed_body_size()
+                    .unwrap_or(DEFAULT_MAX_DECOMPRESSED_BODY_SIZE);
+                match decompress_request(request, max_size).await {
+                    Ok(request) => request,
+                    Err(error) => {
+                        log::error!("[{}] Request body rejected: {}", request_id, error);
+                        let response = self
+                            .serve_status_page(
+                                error
+                                    .status_code()
+                                    .as_u16(),
+                            )
+                            .await?;
+                        return Ok(close_connection(version, response));
+                    }
+                }
+            };
+
+            // The request is handed to the path, a copy is kept to describe it to the error handler
+            let request_head = self
+                .error_handler
+                .as_ref()
+                .map(|_| request.head());
+
+            let result = path.handle(request, Arc::from(target_path));
+
+            let result = match deadline {
+                // Dropping the handler future on expiry cancels it
+                Some(deadline) => match timeout(deadline.remaining(), result).await {
+                    Some(result) => result,
+                    None => {
+                        log::error!(
+                            "[{}] Request timed out after {:?}",
+                            request_id,
+                            self.config
+                                .request_timeout()
+                        );
+                        return self
+                            .serve_status_page(timeout_status.as_u16())
+                            .await;
+                    }
+                },
+                None => result.await,
+            };
+
+            match result {
+                Ok(response) => {
+                    let response = apply_transforms(&self.transforms, response).await?;
+
+                    #[cfg(feature = "compression-brotli")]
+                    let response = {
+                        let max_size = self
+                            .config
+                            .max_compressed_body_size()
+                            .unwrap_or(DEFAULT_MAX_COMPRESSED_BODY_SIZE);
+                        // Paths other than handlers answer HEAD without a body to compress
+                        if is_head && !head_as_get {
+                            compress_head_response(accept_encoding.as_deref(), max_size, response)
+                        } else {
+                            compress_response(
+                                accept_encoding.as_deref(),
+                                max_size,
+                                self.memory_budget
+                                    .clone(),
+                                response,
+                            )
+                            .await?
                         }
-                        _ => {}
+                    };
+
+                    if head_as_get {
+                        return without_body(response).await;
                     }
 
-                    Err(error)
+                    Ok(response)
+                }
+                Err(VetisError::Response(error)) => Ok(Response::from(*error)),
+                Err(error) => {
+                    if let (Some(error_handler), Some(request)) =
+                        (&self.error_handler, &request_head)
+                    {
+                        log::error!("[{}] Request failed: {}", request_id, error);
+                        let close = matches!(
+                            error,
+                            VetisError::Body(_)
+                                | VetisError::PayloadTooLarge
+                                | VetisError::MemoryBudgetExceeded
+                        );
+                        let response = error_handler(error, request);
+                        return Ok(if close {
+                            close_connection(version, response)
+                        } else {
+                            response
+                        });
+                    }
+
+                    log::error!("[{}] Request failed: {}", request_id, error);
+                    let response = self
+                        .serve_status_page(
+                            error
+                                .status_code()
+                                .as_u16(),
+                        )
+                        .await?;
+
+                    // Body errors can stop reading the request body half way
+                    Ok(match error {
+                        VetisError::Body(_)
+                        | VetisError::PayloadTooLarge
+                        | VetisError::MemoryBudgetExceeded => close_connection(version, response),
+                        _ => response,
+                    })
                 }
             }
         })
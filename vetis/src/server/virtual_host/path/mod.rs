@@ -8,6 +8,9 @@ use std::sync::Arc;
 use crate::server::virtual_host::path::interface::InterfacePath;
 #[cfg(feature = "reverse-proxy")]
 use crate::server::virtual_host::path::proxy::ProxyPath;
+use crate::server::virtual_host::path::redirect::RedirectPath;
+#[cfg(feature = "embed")]
+use crate::server::virtual_host::path::static_files::embedded::EmbeddedStaticPath;
 #[cfg(feature = "static-files")]
 use crate::server::virtual_host::path::static_files::StaticPath;
 
@@ -15,7 +18,7 @@ use crate::{
     errors::{HandlerError, VetisError, VirtualHostError},
     server::{
         http::{Request, Response},
-        virtual_host::BoxedHandlerClosure,
+        virtual_host::{BoxedHandlerClosure, Handler},
     },
 };
 
@@ -25,6 +28,7 @@ pub mod auth;
 pub mod interface;
 #[cfg(feature = "reverse-proxy")]
 pub mod proxy;
+pub mod redirect;
 #[cfg(feature = "static-files")]
 pub mod static_files;
 
@@ -58,17 +62,44 @@ pub trait Path {
 pub enum HostPath {
     /// Handler path
     Handler(HandlerPath),
+    /// Redirect path
+    Redirect(RedirectPath),
     #[cfg(feature = "reverse-proxy")]
     /// Proxy path
     Proxy(ProxyPath),
     #[cfg(feature = "static-files")]
     /// Static path
     Static(StaticPath),
+    #[cfg(feature = "embed")]
+    /// Embedded static path
+    Embedded(EmbeddedStaticPath),
     #[cfg(feature = "interface")]
     /// Interface path
     Interface(InterfacePath),
 }
 
+impl HostPath {
+    /// Returns the name of the kind of path, as reported by `/admin/vhosts` and routing traces
+    ///
+    /// # Returns
+    ///
+    /// * `&'static str` - The kind of path
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            HostPath::Handler(_) => "handler",
+            HostPath::Redirect(_) => "redirect",
+            #[cfg(feature = "reverse-proxy")]
+            HostPath::Proxy(_) => "proxy",
+            #[cfg(feature = "static-files")]
+            HostPath::Static(_) => "static",
+            #[cfg(feature = "embed")]
+            HostPath::Embedded(_) => "embedded",
+            #[cfg(feature = "interface")]
+            HostPath::Interface(_) => "interface",
+        }
+    }
+}
+
 impl Path for HostPath {
     /// Returns the URI of the path
     ///
@@ -78,10 +109,13 @@ impl Path for HostPath {
     fn uri(&self) -> &str {
         match self {
             HostPath::Handler(handler) => handler.uri(),
+            HostPath::Redirect(redirect) => redirect.uri(),
             #[cfg(feature = "reverse-proxy")]
             HostPath::Proxy(proxy) => proxy.uri(),
             #[cfg(feature = "static-files")]
             HostPath::Static(static_path) => static_path.uri(),
+            #[cfg(feature = "embed")]
+            HostPath::Embedded(embedded_path) => embedded_path.uri(),
             #[cfg(feature = "interface")]
             HostPath::Interface(interface_path) => interface_path.uri(),
         }
@@ -104,10 +138,13 @@ impl Path for HostPath {
     ) -> Pin<Box<dyn Future<Output = Result<Response, VetisError>> + Send + '_>> {
         match self {
             HostPath::Handler(handler) => handler.handle(request, uri),
+            HostPath::Redirect(redirect) => redirect.handle(request, uri),
             #[cfg(feature = "reverse-proxy")]
             HostPath::Proxy(proxy) => proxy.handle(request, uri),
             #[cfg(feature = "static-files")]
             HostPath::Static(static_path) => static_path.handle(request, uri),
+            #[cfg(feature = "embed")]
+            HostPath::Embedded(embedded_path) => embedded_path.handle(request, uri),
             #[cfg(feature = "interface")]
             HostPath::Interface(interface_path) => interface_path.handle(request, uri),
         }
@@ -117,7 +154,8 @@ impl Path for HostPath {
 /// Builder for handler path
 pub struct HandlerPathBuilder {
     uri: Arc<String>,
-    handler: Option<BoxedHandlerClosure>,
+    handler: Option<Box<dyn Handler>>,
+    method_handlers: Vec<(http::Method, Box<dyn Handler>)>,
 }
 
 impl HandlerPathBuilder {
@@ -135,17 +173,55 @@ impl HandlerPathBuilder {
         self
     }
 
-    /// Allow set handler function
+    /// Allow set the request handler
+    ///
+    /// Accepts a closure created with `handler_fn`, a type implementing
+    /// `Handler`, or a `Box<dyn Handler>`. When handlers are also set for
+    /// specific methods, this one serves the remaining methods.
     ///
     /// # Arguments
     ///
-    /// * `handler` - The handler function
+    /// * `handler` - The handler
     ///
     /// # Returns
     ///
     /// * `Self` - The builder
-    pub fn handler(mut self, handler: BoxedHandlerClosure) -> Self {
-        self.handler = Some(handler);
+    pub fn handler(mut self, handler: impl Handler + 'static) -> Self {
+        self.handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Allow set the handler of requests with the given method
+    ///
+    /// Without a handler serving the remaining methods, requests with any
+    /// other method are answered with `405 Method Not Allowed`, and
+    /// `OPTIONS` requests with `204 No Content`, both listing the methods
+    /// set in the `allow` header. `HEAD` requests are served by the `GET`
+    /// handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The request method
+    /// * `handler` - The handler
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let path = HandlerPath::builder()
+    ///     .uri("/items")
+    ///     .method(http::Method::GET, handler_fn(list_items))
+    ///     .method(http::Method::POST, handler_fn(create_item))
+    ///     .build()?;
+    /// ```
+    pub fn method(mut self, method: http::Method, handler: impl Handler + 'static) -> Self {
+        self.method_handlers
+            .retain(|(registered, _)| *registered != method);
+        self.method_handlers
+            .push((method, Box::new(handler)));
         self
     }
 
@@ -161,23 +237,31 @@ impl HandlerPathBuilder {
             ))));
         }
 
-        let handler = match self.handler {
-            Some(handler) => handler,
-            None => {
-                return Err(VetisError::VirtualHost(VirtualHostError::Handler(
-                    HandlerError::Handler("Handler must be set".to_string()),
-                )))
-            }
-        };
+        if self
+            .handler
+            .is_none()
+            && self
+                .method_handlers
+                .is_empty()
+        {
+            return Err(VetisError::VirtualHost(VirtualHostError::Handler(HandlerError::Handler(
+                "Handler must be set".to_string(),
+            ))));
+        }
 
-        Ok(HostPath::Handler(HandlerPath { uri: self.uri, handler }))
+        Ok(HostPath::Handler(HandlerPath {
+            uri: self.uri,
+            handler: self.handler,
+            method_handlers: self.method_handlers,
+        }))
     }
 }
 
 /// Handler path
 pub struct HandlerPath {
     uri: Arc<String>,
-    handler: BoxedHandlerClosure,
+    handler: Option<Box<dyn Handler>>,
+    method_handlers: Vec<(http::Method, Box<dyn Handler>)>,
 }
 
 impl HandlerPath {
@@ -187,7 +271,44 @@ impl HandlerPath {
     ///
     /// * `HandlerPathBuilder` - The builder
     pub fn builder() -> HandlerPathBuilder {
-        HandlerPathBuilder { uri: Arc::from("/".to_string()), handler: None }
+        HandlerPathBuilder {
+            uri: Arc::from("/".to_string()),
+            handler: None,
+            method_handlers: Vec::new(),
+        }
+    }
+
+    /// Create a handler path serving every request from the root `/`
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler function
+    ///
+    /// # Returns
+    ///
+    /// * `HandlerPath` - The handler path
+    pub(crate) fn root(handler: BoxedHandlerClosure) -> Self {
+        HandlerPath {
+            uri: Arc::from("/".to_string()),
+            handler: Some(Box::new(handler)),
+            method_handlers: Vec::new(),
+        }
+    }
+
+    /// Returns the `allow` header value listing the methods with a handler, and `OPTIONS`
+    fn allow_header(&self) -> http::HeaderValue {
+        let mut methods: Vec<&str> = self
+            .method_handlers
+            .iter()
+            .map(|(method, _)| method.as_str())
+            .collect();
+        if !methods.contains(&"OPTIONS") {
+            methods.push("OPTIONS");
+        }
+
+        // Method names are valid header value tokens
+        http::HeaderValue::from_str(&methods.join(", "))
+            .unwrap_or_else(|_| http::HeaderValue::from_static("OPTIONS"))
     }
 }
 
@@ -216,6 +337,28 @@ impl Path for HandlerPath {
         request: Request,
         _uri: Arc<String>,
     ) -> Pin<Box<dyn Future<Output = Result<Response, VetisError>> + Send + '_>> {
-        (self.handler)(request)
+        let method_handler = self
+            .method_handlers
+            .iter()
+            .find(|(method, _)| method == request.method())
+            .map(|(_, handler)| handler);
+
+        match (method_handler, &self.handler) {
+            (Some(handler), _) | (None, Some(handler)) => handler.handle(request),
+            (None, None) => {
+                let allow = self.allow_header();
+                let options = request.method() == http::Method::OPTIONS;
+                Box::pin(async move {
+                    let response = Response::builder().header(http::header::ALLOW, allow);
+                    Ok(if options {
+                        response.no_content()
+                    } else {
+                        response
+                            .status(http::StatusCode::METHOD_NOT_ALLOWED)
+                            .text("")
+                    })
+                })
+            }
+        }
     }
 }
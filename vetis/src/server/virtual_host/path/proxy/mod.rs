@@ -2,27 +2,202 @@ use crate::{
     config::server::virtual_host::path::proxy::ProxyPathConfig,
     errors::{VetisError, VirtualHostError},
     server::{
+        conn::budget::{BudgetReservation, MemoryBudget},
         http::{Request, Response},
-        virtual_host::path::{HostPath, Path},
+        virtual_host::{
+            path::{
+                proxy::upstream::{spawn_health_checks, sticky_upstream, Upstreams, STICKY_COOKIE},
+                HostPath, Path,
+            },
+            RequestDeadline,
+        },
     },
+    utils::{
+        headers::{parse_header, strip_unsafe_values},
+        time::timeout,
+    },
+};
+use bytes::{Bytes, BytesMut};
+use deboa::{
+    client::conn::pool::HttpConnectionPool,
+    errors::{DeboaError, ResponseError},
+    request::DeboaRequest,
+    Client,
 };
-use deboa::{client::conn::pool::HttpConnectionPool, request::DeboaRequest, Client};
+use http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use http_body_util::BodyExt;
+use hyper_body_utils::HttpBody;
 use std::{
     future::Future,
     pin::Pin,
     sync::{Arc, OnceLock},
 };
 
+mod upstream;
+
 static CLIENT: OnceLock<Client> = OnceLock::new();
 
+/// Collects a body into memory, giving up once it grows past `max_size`.
+///
+/// # Arguments
+///
+/// * `body` - The body to collect
+/// * `max_size` - Optional limit on the collected size, in bytes
+/// * `reservation` - The server memory reservation the collected bytes are added to
+///
+/// # Returns
+///
+/// * `Result<Option<Bytes>, VetisError>` - The collected body, `None` if it exceeded `max_size`,
+///   `VetisError::Body` if reading failed, or `VetisError::MemoryBudgetExceeded`.
+async fn buffer_body(
+    mut body: HttpBody,
+    max_size: Option<usize>,
+    reservation: &mut BudgetReservation,
+) -> Result<Option<Bytes>, VetisError> {
+    let mut buffer = BytesMut::new();
+    while let Some(frame) = body.frame().await {
+        let frame = frame.map_err(|e| VetisError::Body(e.to_string()))?;
+        if let Ok(data) = frame.into_data() {
+            if let Some(max_size) = max_size {
+                if buffer.len() + data.len() > max_size {
+                    return Ok(None);
+                }
+            }
+            reservation.grow(data.len())?;
+            buffer.extend_from_slice(&data);
+        }
+    }
+    Ok(Some(buffer.freeze()))
+}
+
+/// Replaces framing headers to match a buffered body.
+fn set_buffered_length(headers: &mut HeaderMap, body: &Bytes) {
+    headers.remove(header::TRANSFER_ENCODING);
+    headers.insert(header::CONTENT_LENGTH, HeaderValue::from(body.len()));
+}
+
+/// Rebuilds an upstream answer the client reported as an error status.
+///
+/// The client keeps the status and the start of the body, after a prefix of
+/// its own, but not the headers.
+fn status_response(
+    status: StatusCode,
+    message: &str,
+) -> Result<(http::response::Parts, HttpBody), VetisError> {
+    let prefix = format!("Could not process request ({}): ", status);
+    let body = message
+        .strip_prefix(prefix.as_str())
+        .unwrap_or(message);
+    let response = http::Response::builder()
+        .status(status)
+        .header(header::CONTENT_LENGTH, body.len())
+        .body(HttpBody::from_text(body))?;
+    Ok(response.into_parts())
+}
+
+/// Headers describing a single connection, which a proxy must not forward (RFC 7230, section 6.1).
+const HOP_BY_HOP_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// Name this proxy identifies itself with in `Via` headers.
+const VIA_PSEUDONYM: &str = "vetis";
+
+/// Returns whether a `Via` header lists this proxy as a recipient.
+fn via_contains_self(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::VIA)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|entry| {
+            entry
+                .split_whitespace()
+                .nth(1)
+                == Some(VIA_PSEUDONYM)
+        })
+}
+
+/// Appends this proxy to the `Via` header, after the entries already present.
+fn append_via(headers: &mut HeaderMap, version: http::Version) -> Result<(), VetisError> {
+    let received = match version {
+        http::Version::HTTP_09 => "0.9",
+        http::Version::HTTP_10 => "1.0",
+        http::Version::HTTP_2 => "2",
+        http::Version::HTTP_3 => "3",
+        _ => "1.1",
+    };
+
+    let mut entries: Vec<&str> = headers
+        .get_all(header::VIA)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect();
+    let own = format!("{} {}", received, VIA_PSEUDONYM);
+    entries.push(&own);
+
+    let value = HeaderValue::from_str(&entries.join(", "))
+        .map_err(|e| VetisError::VirtualHost(VirtualHostError::Proxy(e.to_string())))?;
+    headers.insert(header::VIA, value);
+    Ok(())
+}
+
+/// Removes hop-by-hop headers, including `proxy-*` headers and those listed in `connection`.
+fn strip_hop_by_hop_headers(headers: &mut HeaderMap) {
+    let listed: Vec<header::HeaderName> = headers
+        .get_all(header::CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|name| {
+            header::HeaderName::from_bytes(
+                name.trim()
+                    .as_bytes(),
+            )
+            .ok()
+        })
+        .collect();
+
+    let proxy_headers: Vec<header::HeaderName> = headers
+        .keys()
+        .filter(|name| {
+            name.as_str()
+                .starts_with("proxy-")
+        })
+        .cloned()
+        .collect();
+
+    for name in HOP_BY_HOP_HEADERS {
+        headers.remove(*name);
+    }
+
+    for name in listed
+        .iter()
+        .chain(&proxy_headers)
+    {
+        headers.remove(name);
+    }
+}
+
 /// Proxy path
 pub struct ProxyPath {
     config: ProxyPathConfig,
+    upstreams: Arc<Upstreams>,
 }
 
 impl ProxyPath {
     /// Create a new proxy path with provided configuration
     ///
+    /// When health checks are configured they are spawned on the current
+    /// runtime, so the path must be created from within it.
+    ///
     /// # Arguments
     ///
     /// * `config` - The proxy path configuration
@@ -31,7 +206,76 @@ impl ProxyPath {
     ///
     /// * `ProxyPath` - The proxy path
     pub fn new(config: ProxyPathConfig) -> ProxyPath {
-        ProxyPath { config }
+        let upstreams = Upstreams::new(
+            std::iter::once(config.target()).chain(
+                config
+                    .upstreams()
+                    .iter()
+                    .flatten()
+                    .map(String::as_str),
+            ),
+        );
+        let upstreams = Arc::new(upstreams);
+
+        if let Some(path) = config.health_check_path() {
+            spawn_health_checks(
+                &upstreams,
+                path.clone(),
+                config.health_check_interval(),
+                config.health_check_timeout(),
+            );
+        }
+
+        ProxyPath { config, upstreams }
+    }
+
+    /// Returns how many times a request with `method` may be retried.
+    fn retries(&self, method: &Method) -> u32 {
+        match self
+            .config
+            .max_retries()
+        {
+            Some(max_retries)
+                if method.is_idempotent()
+                    || self
+                        .config
+                        .retry_non_idempotent() =>
+            {
+                max_retries
+            }
+            _ => 0,
+        }
+    }
+
+    /// Returns whether an upstream answer with `status` is retried.
+    fn is_retry_status(&self, status: StatusCode) -> bool {
+        self.config
+            .retry_statuses()
+            .iter()
+            .flatten()
+            .any(|retry_status| *retry_status == status.as_u16())
+    }
+
+    fn rewrite_response_headers(&self, headers: &mut HeaderMap) {
+        for key in self
+            .config
+            .remove_response_headers()
+            .iter()
+            .flatten()
+        {
+            headers.remove(key.as_str());
+        }
+
+        for (key, value) in self
+            .config
+            .add_response_headers()
+            .iter()
+            .flatten()
+        {
+            if let Some((key, value)) = parse_header(key, value) {
+                headers.insert(key, value);
+            }
+        }
     }
 }
 
@@ -75,49 +319,249 @@ impl Path for ProxyPath {
         request: Request,
         uri: Arc<String>,
     ) -> Pin<Box<dyn Future<Output = Result<Response, VetisError>> + Send + '_>> {
-        let (request_parts, request_body) = request.into_parts();
+        let (mut request_parts, request_body) = request.into_parts();
+
+        let sticky_sessions = self
+            .config
+            .sticky_sessions();
+        let pinned = if sticky_sessions { sticky_upstream(&request_parts.headers) } else { None };
+        let upstream = self
+            .upstreams
+            .select(pinned.as_deref());
 
-        let target = self.config.target();
+        let max_buffer_size = self
+            .config
+            .max_buffer_size();
+
+        // Buffered bodies stay accounted for until the proxied exchange completes
+        let mut reservation = BudgetReservation::new(
+            request_parts
+                .extensions
+                .get::<Arc<MemoryBudget>>()
+                .cloned(),
+        );
+
+        let deadline = request_parts
+            .extensions
+            .get::<RequestDeadline>()
+            .copied();
 
         Box::pin(async move {
-            let target_url = format!("{}{}", target, uri);
-            let deboa_request = match DeboaRequest::at(target_url, request_parts.method) {
-                Ok(request) => request,
-                Err(e) => {
-                    return Err(VetisError::VirtualHost(VirtualHostError::Proxy(e.to_string())))
+            strip_hop_by_hop_headers(&mut request_parts.headers);
+
+            if self.config.via() {
+                if via_contains_self(&request_parts.headers) {
+                    log::error!("Request to {} already went through this proxy", uri);
+                    return Err(VetisError::VirtualHost(VirtualHostError::ProxyLoop));
                 }
-            };
+                append_via(&mut request_parts.headers, request_parts.version)?;
+            }
 
-            let deboa_request = match deboa_request
-                .headers(request_parts.headers)
-                .body(request_body)
-                .build()
+            let retries = self.retries(&request_parts.method);
+
+            // Retried requests keep their body around, so it can be sent again
+            let mut replay = None;
+            let request_body = if self
+                .config
+                .buffer_request()
+                || retries > 0
             {
-                Ok(request) => request,
-                Err(e) => {
-                    return Err(VetisError::VirtualHost(VirtualHostError::Proxy(e.to_string())))
+                let body = buffer_body(request_body, max_buffer_size, &mut reservation).await?;
+                let Some(body) = body else {
+                    return Err(VetisError::PayloadTooLarge);
+                };
+                if self
+                    .config
+                    .buffer_request()
+                {
+                    set_buffered_length(&mut request_parts.headers, &body);
+                }
+                let request_body = HttpBody::from_bytes(&body);
+                if retries > 0 {
+                    replay = Some(body);
                 }
+                request_body
+            } else {
+                request_body
             };
 
+            for key in self
+                .config
+                .remove_request_headers()
+                .iter()
+                .flatten()
+            {
+                request_parts
+                    .headers
+                    .remove(key.as_str());
+            }
+
             let client = CLIENT.get_or_init(|| {
                 Client::builder()
                     .pool(HttpConnectionPool::default())
                     .build()
             });
 
-            // TODO: Check errors and handle them properly by returning a proper response 500, 503 or 504
-            let response = client
-                .execute(deboa_request)
-                .await;
+            let mut upstream = upstream;
+            let mut request_body = Some(request_body);
+            let mut attempt = 0;
+            let response = loop {
+                let Some(request_body) = request_body
+                    .take()
+                    .or_else(|| {
+                        replay
+                            .as_ref()
+                            .map(|body| HttpBody::from_bytes(body))
+                    })
+                else {
+                    return Err(VetisError::VirtualHost(VirtualHostError::Proxy(
+                        "Request body cannot be resent".to_string(),
+                    )));
+                };
 
-            let response = match response {
-                Ok(response) => response,
-                Err(e) => {
-                    return Err(VetisError::VirtualHost(VirtualHostError::Proxy(e.to_string())))
+                let target_url = format!("{}{}", upstream.target(), uri);
+                let deboa_request = match DeboaRequest::at(
+                    target_url,
+                    request_parts
+                        .method
+                        .clone(),
+                ) {
+                    Ok(request) => request,
+                    Err(e) => {
+                        return Err(VetisError::VirtualHost(VirtualHostError::Proxy(e.to_string())))
+                    }
+                };
+
+                let deboa_request = match deboa_request
+                    .headers(
+                        request_parts
+                            .headers
+                            .clone(),
+                    )
+                    .body(request_body)
+                    .build()
+                {
+                    Ok(request) => request,
+                    Err(e) => {
+                        return Err(VetisError::VirtualHost(VirtualHostError::Proxy(e.to_string())))
+                    }
+                };
+
+                // TODO: Check errors and handle them properly by returning a proper response 500, 503 or 504
+                let response = client.execute(deboa_request);
+
+                // A slow upstream uses up what is left of the request timeout, it isn't marked down
+                let response = match deadline {
+                    Some(deadline) => match timeout(deadline.remaining(), response).await {
+                        Some(response) => response,
+                        None => {
+                            log::error!(
+                                "Upstream {} did not answer before the deadline",
+                                upstream.id()
+                            );
+                            return Err(VetisError::Timeout);
+                        }
+                    },
+                    None => response.await,
+                };
+
+                let response = match response {
+                    Ok(response) => {
+                        upstream.mark_up();
+                        break response.into_parts();
+                    }
+                    Err(e) => e,
+                };
+
+                // Upstream error statuses come back as errors too, only failing to
+                // reach the upstream or a server error takes it out of rotation
+                let retryable = match &response {
+                    DeboaError::Response(ResponseError::Receive { status_code, message }) => {
+                        if status_code.is_server_error() {
+                            upstream.mark_down();
+                        } else {
+                            upstream.mark_up();
+                        }
+                        if attempt >= retries || !self.is_retry_status(*status_code) {
+                            break status_response(*status_code, message)?;
+                        }
+                        true
+                    }
+                    DeboaError::Connection(_) | DeboaError::Io(_) => {
+                        upstream.mark_down();
+                        self.config
+                            .retry_connection_errors()
+                    }
+                    _ => false,
+                };
+                if attempt >= retries || !retryable {
+                    return Err(VetisError::VirtualHost(VirtualHostError::Proxy(
+                        response.to_string(),
+                    )));
                 }
+
+                log::warn!(
+                    "Upstream {} failed: {}, retrying request to {}",
+                    upstream.id(),
+                    response,
+                    uri
+                );
+
+                attempt += 1;
+                upstream = self
+                    .upstreams
+                    .select(None);
             };
+            let set_cookie = sticky_sessions && pinned.as_deref() != Some(upstream.id());
+
+            let (mut response_parts, response_body) = response;
+
+            strip_hop_by_hop_headers(&mut response_parts.headers);
+
+            for name in strip_unsafe_values(&mut response_parts.headers) {
+                log::warn!("Dropped upstream response header {} with control characters", name);
+            }
+
+            if self.config.via() {
+                append_via(&mut response_parts.headers, response_parts.version)?;
+            }
+
+            let response_body = if self
+                .config
+                .buffer_response()
+            {
+                let body = buffer_body(response_body, max_buffer_size, &mut reservation)
+                    .await
+                    .map_err(|e| match e {
+                        VetisError::Body(e) => VetisError::VirtualHost(VirtualHostError::Proxy(e)),
+                        e => e,
+                    })?;
+                let Some(body) = body else {
+                    return Err(VetisError::VirtualHost(VirtualHostError::Proxy(
+                        "Upstream response exceeds max buffer size".to_string(),
+                    )));
+                };
+                set_buffered_length(&mut response_parts.headers, &body);
+                HttpBody::from_bytes(&body)
+            } else {
+                response_body
+            };
+
+            self.rewrite_response_headers(&mut response_parts.headers);
 
-            let (response_parts, response_body) = response.into_parts();
+            if set_cookie {
+                let cookie = format!(
+                    "{}={}; Path={}; HttpOnly",
+                    STICKY_COOKIE,
+                    upstream.id(),
+                    self.config.uri()
+                );
+                let cookie = HeaderValue::from_str(&cookie)
+                    .map_err(|e| VetisError::VirtualHost(VirtualHostError::Proxy(e.to_string())))?;
+                response_parts
+                    .headers
+                    .append(header::SET_COOKIE, cookie);
+            }
 
             let vetis_response = Response::builder()
                 .status(response_parts.status)
@@ -0,0 +1,234 @@
+//! Upstream selection for proxy paths.
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use deboa::{request, Client};
+use http::{header, HeaderMap};
+use log::{debug, warn};
+use rt_gate::spawn_worker;
+
+use crate::utils::time::{sleep, timeout};
+
+/// Cookie pinning a client to an upstream when sticky sessions are enabled.
+pub(crate) const STICKY_COOKIE: &str = "VETIS_UPSTREAM";
+
+/// How long a failed upstream is kept out of rotation.
+const RETRY_AFTER: Duration = Duration::from_secs(10);
+
+/// A proxy upstream and its availability.
+pub(crate) struct Upstream {
+    target: String,
+    id: String,
+    healthy: AtomicBool,
+    failed_at: Mutex<Option<Instant>>,
+}
+
+impl Upstream {
+    fn new(target: &str) -> Self {
+        let mut hasher = DefaultHasher::new();
+        target.hash(&mut hasher);
+
+        Self {
+            target: target.to_string(),
+            id: format!("{:016x}", hasher.finish()),
+            healthy: AtomicBool::new(true),
+            failed_at: Mutex::new(None),
+        }
+    }
+
+    /// Returns the upstream base URL.
+    pub(crate) fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Returns the opaque identifier stored in the sticky session cookie.
+    pub(crate) fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns whether the upstream is in rotation.
+    pub(crate) fn is_available(&self) -> bool {
+        if !self
+            .healthy
+            .load(Ordering::Relaxed)
+        {
+            return false;
+        }
+
+        match self
+            .failed_at
+            .lock()
+        {
+            Ok(failed_at) => failed_at.map_or(true, |failed_at| failed_at.elapsed() >= RETRY_AFTER),
+            Err(_) => true,
+        }
+    }
+
+    /// Takes the upstream out of rotation after a failed request.
+    pub(crate) fn mark_down(&self) {
+        if let Ok(mut failed_at) = self
+            .failed_at
+            .lock()
+        {
+            *failed_at = Some(Instant::now());
+        }
+    }
+
+    /// Takes the upstream out of rotation after a failed health check.
+    fn mark_unhealthy(&self) {
+        self.healthy
+            .store(false, Ordering::Relaxed);
+    }
+
+    /// Puts the upstream back in rotation after a successful request or health check.
+    pub(crate) fn mark_up(&self) {
+        self.healthy
+            .store(true, Ordering::Relaxed);
+        if let Ok(mut failed_at) = self
+            .failed_at
+            .lock()
+        {
+            *failed_at = None;
+        }
+    }
+}
+
+/// Round-robin set of upstreams.
+pub(crate) struct Upstreams {
+    upstreams: Vec<Upstream>,
+    next: AtomicUsize,
+}
+
+impl Upstreams {
+    /// Creates the set from upstream base URLs.
+    ///
+    /// # Arguments
+    ///
+    /// * `targets` - The upstream base URLs, at least one
+    pub(crate) fn new<'a>(targets: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            upstreams: targets
+                .into_iter()
+                .map(Upstream::new)
+                .collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Picks the upstream for a request.
+    ///
+    /// The pinned upstream is used while it is available; otherwise the next
+    /// available upstream in round-robin order is picked. When every upstream
+    /// is down the next one in order is returned anyway.
+    ///
+    /// # Arguments
+    ///
+    /// * `pinned` - The identifier of the upstream the client is pinned to, if any
+    ///
+    /// # Returns
+    ///
+    /// * `&Upstream` - The selected upstream.
+    pub(crate) fn select(&self, pinned: Option<&str>) -> &Upstream {
+        if let Some(upstream) = pinned.and_then(|pinned| {
+            self.upstreams
+                .iter()
+                .find(|upstream| upstream.id() == pinned)
+        }) {
+            if upstream.is_available() {
+                return upstream;
+            }
+        }
+
+        let start = self
+            .next
+            .fetch_add(1, Ordering::Relaxed);
+        let count = self.upstreams.len();
+
+        (0..count)
+            .map(|offset| &self.upstreams[(start + offset) % count])
+            .find(|upstream| upstream.is_available())
+            .unwrap_or(&self.upstreams[start % count])
+    }
+}
+
+/// Periodically checks every upstream, updating its availability.
+///
+/// The checks stop once the upstreams are dropped along with their proxy path.
+///
+/// # Arguments
+///
+/// * `upstreams` - The upstreams to check
+/// * `path` - The path requested on each upstream
+/// * `interval` - The delay between check rounds
+/// * `check_timeout` - How long a single check may take
+pub(crate) fn spawn_health_checks(
+    upstreams: &Arc<Upstreams>,
+    path: String,
+    interval: Duration,
+    check_timeout: Duration,
+) {
+    let weak_upstreams = Arc::downgrade(upstreams);
+    spawn_worker(async move {
+        let client = Client::builder().build();
+        while let Some(upstreams) = weak_upstreams.upgrade() {
+            for upstream in &upstreams.upstreams {
+                let url = format!("{}{}", upstream.target(), path);
+                let check = async {
+                    request::get(url.as_str())?
+                        .send_with(&client)
+                        .await
+                };
+
+                match timeout(check_timeout, check).await {
+                    Some(Ok(_)) => {
+                        debug!("Health check passed for {}", url);
+                        upstream.mark_up();
+                    }
+                    Some(Err(e)) => {
+                        warn!("Health check failed for {}: {}", url, e);
+                        upstream.mark_unhealthy();
+                    }
+                    None => {
+                        warn!("Health check timed out for {}", url);
+                        upstream.mark_unhealthy();
+                    }
+                }
+            }
+
+            drop(upstreams);
+            sleep(interval).await;
+        }
+    });
+}
+
+/// Extracts the sticky session upstream identifier from request cookies.
+///
+/// # Arguments
+///
+/// * `headers` - The request headers
+///
+/// # Returns
+///
+/// * `Option<String>` - The pinned upstream identifier, if present.
+pub(crate) fn sticky_upstream(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|cookie| {
+            cookie
+                .trim()
+                .split_once('=')
+        })
+        .find(|(name, _)| *name == STICKY_COOKIE)
+        .map(|(_, value)| value.to_string())
+}
@@ -0,0 +1,248 @@
+//! Paths redirecting requests to another location.
+
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use http::{HeaderValue, StatusCode};
+
+use crate::{
+    errors::{HandlerError, VetisError, VirtualHostError},
+    server::{
+        http::{Request, Response},
+        virtual_host::path::{HostPath, Path},
+    },
+};
+
+/// Builder for redirect path
+pub struct RedirectPathBuilder {
+    uri: String,
+    target: String,
+    status: StatusCode,
+}
+
+impl RedirectPathBuilder {
+    /// Allow set the uri of the path to redirect
+    ///
+    /// A trailing `/*` is accepted for symmetry with the target, the path
+    /// matches every request below the uri either way.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The uri of the redirect path, such as `/old` or `/old/*`
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder
+    pub fn uri(mut self, uri: &str) -> Self {
+        self.uri = uri.to_string();
+        self
+    }
+
+    /// Allow set the location requests are redirected to
+    ///
+    /// The target can be a path on the same host or an absolute URL. A `*`
+    /// in the target is replaced with the part of the request path following
+    /// the uri, and the request query is then kept, so `/old/*` redirected to
+    /// `/new/*` sends `/old/a?b=c` to `/new/a?b=c`.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The redirect location
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder
+    pub fn target(mut self, target: &str) -> Self {
+        self.target = target.to_string();
+        self
+    }
+
+    /// Allow set the status of redirect responses, `301 Moved Permanently` by default
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - One of `301`, `302`, `307` or `308`
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The builder
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Build the redirect path
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HostPath, VetisError>` - The redirect path or error
+    pub fn build(self) -> Result<HostPath, VetisError> {
+        let uri = self
+            .uri
+            .strip_suffix("/*")
+            .unwrap_or(&self.uri);
+        let uri = if uri.is_empty() && !self.uri.is_empty() { "/" } else { uri };
+        if uri.is_empty() {
+            return Err(VetisError::VirtualHost(VirtualHostError::Handler(HandlerError::Uri(
+                "URI cannot be empty".to_string(),
+            ))));
+        }
+
+        if self
+            .target
+            .is_empty()
+        {
+            return Err(VetisError::VirtualHost(VirtualHostError::Handler(HandlerError::Uri(
+                "Redirect target cannot be empty".to_string(),
+            ))));
+        }
+
+        if ![
+            StatusCode::MOVED_PERMANENTLY,
+            StatusCode::FOUND,
+            StatusCode::TEMPORARY_REDIRECT,
+            StatusCode::PERMANENT_REDIRECT,
+        ]
+        .contains(&self.status)
+        {
+            return Err(VetisError::VirtualHost(VirtualHostError::Handler(HandlerError::Handler(
+                "Redirect status must be 301, 302, 307 or 308".to_string(),
+            ))));
+        }
+
+        Ok(HostPath::Redirect(RedirectPath {
+            uri: Arc::from(uri.to_string()),
+            target: self.target,
+            status: self.status,
+        }))
+    }
+}
+
+/// Redirect path
+///
+/// Answers every request below its uri with a redirect to the target.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// virtual_host.add_path(
+///     RedirectPath::builder()
+///         .uri("/old/*")
+///         .target("/new/*")
+///         .status(http::StatusCode::PERMANENT_REDIRECT)
+///         .build()?,
+/// );
+/// ```
+pub struct RedirectPath {
+    uri: Arc<String>,
+    target: String,
+    status: StatusCode,
+}
+
+impl RedirectPath {
+    /// Allow create a new redirect path builder
+    ///
+    /// # Returns
+    ///
+    /// * `RedirectPathBuilder` - The builder
+    pub fn builder() -> RedirectPathBuilder {
+        RedirectPathBuilder {
+            uri: "/".to_string(),
+            target: String::new(),
+            status: StatusCode::MOVED_PERMANENTLY,
+        }
+    }
+
+    /// Returns the location a request is redirected to
+    ///
+    /// # Arguments
+    ///
+    /// * `suffix` - The request path following the uri of the redirect path
+    /// * `query` - The request query, if any
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The redirect location
+    fn location(&self, suffix: &str, query: Option<&str>) -> String {
+        if !self
+            .target
+            .contains('*')
+        {
+            return self.target.clone();
+        }
+
+        // The target wildcard usually follows a slash, as the suffix starts with one
+        let suffix = if self
+            .target
+            .contains("/*")
+        {
+            suffix.trim_start_matches('/')
+        } else {
+            suffix
+        };
+
+        let mut location = self
+            .target
+            .replacen('*', suffix, 1);
+        if let Some(query) = query {
+            location.push('?');
+            location.push_str(query);
+        }
+        location
+    }
+}
+
+impl From<RedirectPath> for HostPath {
+    /// Convert redirect path to host path
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The redirect path to convert
+    ///
+    /// # Returns
+    ///
+    /// * `HostPath` - The host path
+    fn from(value: RedirectPath) -> Self {
+        HostPath::Redirect(value)
+    }
+}
+
+impl Path for RedirectPath {
+    /// Returns the uri of the redirect path
+    ///
+    /// # Returns
+    ///
+    /// * `&str` - The uri of the redirect path
+    fn uri(&self) -> &str {
+        self.uri.as_ref()
+    }
+
+    /// Handles the request for the redirect path
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request to handle
+    /// * `uri` - The request path following the uri of the redirect path
+    ///
+    /// # Returns
+    ///
+    /// * `Pin<Box<dyn Future<Output = Result<Response, VetisError>> + Send + '_>>` - The response to the request
+    fn handle(
+        &self,
+        request: Request,
+        uri: Arc<String>,
+    ) -> Pin<Box<dyn Future<Output = Result<Response, VetisError>> + Send + '_>> {
+        let location = self.location(
+            &uri,
+            request
+                .uri()
+                .query(),
+        );
+        Box::pin(async move {
+            let location = HeaderValue::from_str(&location).map_err(http::Error::from)?;
+            Ok(Response::builder()
+                .status(self.status)
+                .header(http::header::LOCATION, location)
+                .text(""))
+        })
+    }
+}
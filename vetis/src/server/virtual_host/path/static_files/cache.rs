@@ -0,0 +1,248 @@
+//! In-memory cache of small static files.
+
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper_body_utils::HttpBody;
+use log::error;
+use lru::LruCache;
+
+use crate::errors::{FileError, VetisError, VirtualHostError};
+
+/// A file held in memory.
+pub(crate) struct CachedFile {
+    contents: Bytes,
+    modified: SystemTime,
+    checked_at: Instant,
+}
+
+impl CachedFile {
+    /// Returns the file contents.
+    pub(crate) fn contents(&self) -> &Bytes {
+        &self.contents
+    }
+
+    /// Returns a body streaming the given range of the contents, without copying them.
+    pub(crate) fn body(&self, range: Range<usize>) -> HttpBody {
+        let contents = self
+            .contents
+            .slice(range);
+        HttpBody::Stream(
+            Full::new(contents)
+                .map_err(|never| match never {})
+                .boxed(),
+        )
+    }
+
+    /// Returns the file modification time when it was cached.
+    pub(crate) fn modified(&self) -> SystemTime {
+        self.modified
+    }
+}
+
+struct Entries {
+    files: LruCache<PathBuf, Arc<CachedFile>>,
+    size: usize,
+}
+
+/// Size-bounded LRU cache of file contents, invalidated by modification time.
+pub(crate) struct FileCache {
+    entries: Mutex<Entries>,
+    max_size: usize,
+    max_file_size: usize,
+    revalidate_interval: Duration,
+    disk_accesses: AtomicUsize,
+}
+
+impl FileCache {
+    /// Creates an empty cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_size` - The total size of cached files, in bytes
+    /// * `max_file_size` - The size of the largest file cached, in bytes
+    /// * `revalidate_interval` - How long an entry is served before its modification time is checked
+    pub(crate) fn new(
+        max_size: usize,
+        max_file_size: usize,
+        revalidate_interval: Duration,
+    ) -> Self {
+        Self {
+            entries: Mutex::new(Entries { files: LruCache::unbounded(), size: 0 }),
+            max_size,
+            max_file_size: max_file_size.min(max_size),
+            revalidate_interval,
+            disk_accesses: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns how many times the cache went to disk to check or load a file.
+    #[cfg(test)]
+    pub(crate) fn disk_accesses(&self) -> usize {
+        self.disk_accesses
+            .load(Ordering::Relaxed)
+    }
+
+    /// Returns whether the file is cached and doesn't need revalidating yet.
+    pub(crate) fn is_fresh(&self, path: &Path) -> bool {
+        self.entries
+            .lock()
+            .ok()
+            .and_then(|entries| {
+                entries
+                    .files
+                    .peek(path)
+                    .map(|cached| {
+                        cached
+                            .checked_at
+                            .elapsed()
+                            < self.revalidate_interval
+                    })
+            })
+            .unwrap_or(false)
+    }
+
+    /// Returns the cached file, loading it from disk when missing or stale.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to look up
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Arc<CachedFile>>, VetisError>` - The cached file, or `None` if it is too large to cache.
+    pub(crate) async fn get(&self, path: &Path) -> Result<Option<Arc<CachedFile>>, VetisError> {
+        let cached = self
+            .entries
+            .lock()
+            .ok()
+            .and_then(|mut entries| {
+                entries
+                    .files
+                    .get(path)
+                    .cloned()
+            });
+
+        if let Some(cached) = &cached {
+            if cached
+                .checked_at
+                .elapsed()
+                < self.revalidate_interval
+            {
+                return Ok(Some(cached.clone()));
+            }
+        }
+
+        self.disk_accesses
+            .fetch_add(1, Ordering::Relaxed);
+
+        let file_path = path.to_path_buf();
+        let max_file_size = self.max_file_size;
+        let cached_modified = cached
+            .as_ref()
+            .map(|cached| cached.modified);
+        let load = move || -> std::io::Result<Option<(Option<Bytes>, SystemTime)>> {
+            let metadata = std::fs::metadata(&file_path)?;
+            if !metadata.is_file() || metadata.len() > max_file_size as u64 {
+                return Ok(None);
+            }
+
+            let modified = metadata.modified()?;
+            if cached_modified == Some(modified) {
+                return Ok(Some((None, modified)));
+            }
+
+            let contents = std::fs::read(&file_path)?;
+            Ok(Some((Some(Bytes::from(contents)), modified)))
+        };
+
+        #[cfg(feature = "tokio-rt")]
+        let loaded = match tokio::task::spawn_blocking(load).await {
+            Ok(result) => result,
+            Err(e) => Err(e.into()),
+        };
+
+        #[cfg(feature = "smol-rt")]
+        let loaded = smol::unblock(load).await;
+
+        let loaded = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                error!("Error loading file {} into cache: {}", path.display(), e);
+                self.remove(path);
+                return Err(VetisError::VirtualHost(VirtualHostError::File(FileError::NotFound)));
+            }
+        };
+
+        let Some((contents, modified)) = loaded else {
+            self.remove(path);
+            return Ok(None);
+        };
+
+        let contents = match (contents, cached) {
+            (Some(contents), _) => contents,
+            (None, Some(cached)) => cached
+                .contents
+                .clone(),
+            (None, None) => return Ok(None),
+        };
+
+        let file = Arc::new(CachedFile { contents, modified, checked_at: Instant::now() });
+        self.insert(path, file.clone());
+
+        Ok(Some(file))
+    }
+
+    fn insert(&self, path: &Path, file: Arc<CachedFile>) {
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+
+        let size = file.contents.len();
+        if let Some(previous) = entries
+            .files
+            .put(path.to_path_buf(), file)
+        {
+            entries.size -= previous
+                .contents
+                .len();
+        }
+        entries.size += size;
+
+        while entries.size > self.max_size {
+            match entries
+                .files
+                .pop_lru()
+            {
+                Some((_, evicted)) => {
+                    entries.size -= evicted
+                        .contents
+                        .len()
+                }
+                None => break,
+            }
+        }
+    }
+
+    fn remove(&self, path: &Path) {
+        if let Ok(mut entries) = self.entries.lock() {
+            if let Some(removed) = entries
+                .files
+                .pop(path)
+            {
+                entries.size -= removed
+                    .contents
+                    .len();
+            }
+        }
+    }
+}
@@ -0,0 +1,287 @@
+//! Static files embedded in the binary at compile time.
+
+use std::{collections::HashMap, future::Future, pin::Pin, sync::Arc};
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper_body_utils::HttpBody;
+use include_dir::Dir;
+
+#[cfg(feature = "auth")]
+use crate::server::virtual_host::path::auth::Auth;
+use crate::{
+    config::server::virtual_host::path::static_files::StaticPathConfig,
+    errors::{FileError, VetisError, VirtualHostError},
+    server::{
+        http::{static_response, Request, Response},
+        virtual_host::path::{
+            static_files::{
+                dotfiles_permitted, file_headers, if_range_matches, parse_range, partial_headers,
+                unsatisfiable_headers, ByteRange,
+            },
+            HostPath, Path,
+        },
+    },
+};
+
+/// Static path serving files from memory instead of the filesystem.
+///
+/// The configuration is shared with [`StaticPath`](super::StaticPath): the
+/// URI, index files, MIME overrides, ranges, attachments, dotfiles and
+/// auth apply the same way, while the directories, file, directory
+/// template and cache settings are ignored.
+pub struct EmbeddedStaticPath {
+    config: Box<StaticPathConfig>,
+    files: HashMap<String, &'static [u8]>,
+}
+
+impl EmbeddedStaticPath {
+    /// Create a static path serving the files of an embedded directory
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the static path
+    /// * `dir` - The directory, as embedded with `include_dir!`
+    ///
+    /// # Returns
+    ///
+    /// * `EmbeddedStaticPath` - The static path
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// static ASSETS: include_dir::Dir = include_dir::include_dir!("$CARGO_MANIFEST_DIR/assets");
+    ///
+    /// virtual_host.add_path(EmbeddedStaticPath::from_dir(
+    ///     StaticPathConfig::builder()
+    ///         .uri("/")
+    ///         .build()?,
+    ///     &ASSETS,
+    /// ));
+    /// ```
+    pub fn from_dir(config: StaticPathConfig, dir: &'static Dir<'static>) -> EmbeddedStaticPath {
+        let mut files = HashMap::new();
+        let mut pending = vec![dir];
+        while let Some(dir) = pending.pop() {
+            for file in dir.files() {
+                // Entries are keyed by their path relative to the embedded root
+                let path = file
+                    .path()
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                files.insert(path, file.contents());
+            }
+            pending.extend(dir.dirs());
+        }
+
+        EmbeddedStaticPath::from_files(config, files)
+    }
+
+    /// Create a static path serving the given files
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration for the static path
+    /// * `files` - The file contents, by path relative to the static path URI
+    ///
+    /// # Returns
+    ///
+    /// * `EmbeddedStaticPath` - The static path
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let files = HashMap::from([("index.html".to_string(), include_bytes!("index.html").as_slice())]);
+    ///
+    /// virtual_host.add_path(EmbeddedStaticPath::from_files(
+    ///     StaticPathConfig::builder()
+    ///         .uri("/")
+    ///         .build()?,
+    ///     files,
+    /// ));
+    /// ```
+    pub fn from_files(
+        config: StaticPathConfig,
+        files: HashMap<String, &'static [u8]>,
+    ) -> EmbeddedStaticPath {
+        let files = files
+            .into_iter()
+            .map(|(path, contents)| {
+                (
+                    path.trim_start_matches('/')
+                        .to_string(),
+                    contents,
+                )
+            })
+            .collect();
+
+        EmbeddedStaticPath { config: Box::new(config), files }
+    }
+
+    /// Finds the file for a path, falling back to the index files for directories.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The requested path, relative to the static path URI
+    ///
+    /// # Returns
+    ///
+    /// * `Option<(String, &'static [u8])>` - The path of the file found and its contents.
+    fn lookup(&self, path: &str) -> Option<(String, &'static [u8])> {
+        if let Some(contents) = self.files.get(path) {
+            return Some((path.to_string(), contents));
+        }
+
+        let directory = path.trim_end_matches('/');
+        self.config
+            .index_files()
+            .iter()
+            .flatten()
+            .map(|index_file| {
+                if directory.is_empty() {
+                    index_file.clone()
+                } else {
+                    format!("{}/{}", directory, index_file)
+                }
+            })
+            .find_map(|index_path| {
+                self.files
+                    .get(&index_path)
+                    .map(|contents| (index_path, *contents))
+            })
+    }
+
+    /// Serves an embedded file, or the requested range of it.
+    fn serve_file(
+        &self,
+        file_path: &std::path::Path,
+        contents: &'static [u8],
+        range: Option<&str>,
+    ) -> Result<Response, VetisError> {
+        let filesize = contents.len() as u64;
+
+        if let Some(range) = range {
+            match parse_range(range, filesize)? {
+                ByteRange::Unsatisfiable => {
+                    return Ok(Response::builder()
+                        .status(http::StatusCode::RANGE_NOT_SATISFIABLE)
+                        .headers(unsatisfiable_headers(filesize)?)
+                        .body(HttpBody::from_text("")));
+                }
+                ByteRange::Range(start, end) => {
+                    return Ok(Response::builder()
+                        .status(http::StatusCode::PARTIAL_CONTENT)
+                        .headers(partial_headers(&self.config, file_path, start, end, filesize)?)
+                        .body(body(&contents[start as usize..=end as usize])));
+                }
+            }
+        }
+
+        Ok(Response::builder()
+            .status(http::StatusCode::OK)
+            .headers(file_headers(&self.config, file_path, filesize, None)?)
+            .body(body(contents)))
+    }
+}
+
+/// Returns a body streaming the contents, without copying them.
+fn body(contents: &'static [u8]) -> HttpBody {
+    HttpBody::Stream(
+        Full::new(Bytes::from_static(contents))
+            .map_err(|never| match never {})
+            .boxed(),
+    )
+}
+
+impl From<EmbeddedStaticPath> for HostPath {
+    /// Convert embedded static path to host path
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The embedded static path to convert
+    ///
+    /// # Returns
+    ///
+    /// * `HostPath` - The host path
+    fn from(value: EmbeddedStaticPath) -> Self {
+        HostPath::Embedded(value)
+    }
+}
+
+impl Path for EmbeddedStaticPath {
+    /// Returns the uri of the embedded static path
+    ///
+    /// # Returns
+    ///
+    /// * `&str` - The uri of the embedded static path
+    fn uri(&self) -> &str {
+        self.config.uri()
+    }
+
+    /// Handles the request for the embedded static path
+    ///
+    /// # Returns
+    ///
+    /// * `Pin<Box<dyn Future<Output = Result<Response, VetisError>> + Send + '_>>` - The response to the request
+    fn handle(
+        &self,
+        request: Request,
+        uri: Arc<String>,
+    ) -> Pin<Box<dyn Future<Output = Result<Response, VetisError>> + Send + '_>> {
+        Box::pin(async move {
+            #[cfg(feature = "auth")]
+            if let Some(auth) = self.config.auth() {
+                if !auth
+                    .authenticate(request.headers())
+                    .await
+                    .unwrap_or(false)
+                {
+                    return Err(VetisError::VirtualHost(VirtualHostError::Auth(
+                        "Unauthorized".to_string(),
+                    )));
+                }
+            }
+
+            let uri = uri
+                .strip_prefix("/")
+                .unwrap_or(&uri);
+            if !dotfiles_permitted(
+                self.config
+                    .serve_dotfiles(),
+                uri,
+            ) {
+                return Err(VetisError::VirtualHost(VirtualHostError::File(FileError::NotFound)));
+            }
+
+            let Some((file_path, contents)) = self.lookup(uri) else {
+                return Err(VetisError::VirtualHost(VirtualHostError::File(FileError::NotFound)));
+            };
+            let file_path = std::path::Path::new(&file_path);
+
+            if request.method() == http::Method::HEAD {
+                let headers = file_headers(&self.config, file_path, contents.len() as u64, None)?;
+
+                return Ok(Response {
+                    inner: static_response(http::StatusCode::OK, Some(headers), String::new()),
+                });
+            }
+
+            // Without a modification time no `If-Range` validator matches, the whole file is sent
+            let if_range = request
+                .headers()
+                .get(http::header::IF_RANGE)
+                .and_then(|value| value.to_str().ok());
+            let range = request
+                .headers()
+                .get(http::header::RANGE)
+                .filter(|_| {
+                    self.config
+                        .accept_ranges()
+                        && if_range_matches(if_range, None)
+                })
+                .and_then(|value| value.to_str().ok());
+
+            self.serve_file(file_path, contents, range)
+        })
+    }
+}
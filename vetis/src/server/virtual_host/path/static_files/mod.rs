@@ -1,35 +1,294 @@
-use filedescriptor::{AsRawFileDescriptor, FileDescriptor, RawFileDescriptor};
 use hyper_body_utils::HttpBody;
-use log::error;
+use log::{error, warn};
 
 #[cfg(feature = "smol-rt")]
-use futures_lite::AsyncSeekExt;
-use lru::LruCache;
+use futures_lite::{AsyncReadExt, AsyncSeekExt};
 #[cfg(feature = "tokio-rt")]
-use tokio::io::AsyncSeekExt;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
 
+#[cfg(feature = "static-cache")]
+use crate::server::virtual_host::path::static_files::cache::FileCache;
 use crate::{
-    config::server::virtual_host::path::static_files::StaticPathConfig,
+    config::server::virtual_host::path::static_files::{DotfilePolicy, StaticPathConfig},
     errors::{FileError, VetisError, VirtualHostError},
     server::{
         http::{static_response, Request, Response},
         virtual_host::path::{HostPath, Path},
     },
-    VetisFile, VetisRwLock,
+    VetisFile,
 };
+use bytes::Bytes;
 use http::{HeaderMap, HeaderValue};
-use std::{future::Future, num::NonZeroUsize, path::PathBuf, pin::Pin, sync::Arc};
+use http_body_util::{BodyExt, StreamBody};
+use hyper::body::Frame;
+use std::{future::Future, path::PathBuf, pin::Pin, sync::Arc, time::SystemTime};
 
 #[cfg(feature = "auth")]
 use crate::server::virtual_host::path::auth::Auth;
 
-pub(crate) type VetisFileCache = Arc<VetisRwLock<LruCache<String, RawFileDescriptor>>>;
+#[cfg(feature = "static-cache")]
+mod cache;
+#[cfg(feature = "embed")]
+pub mod embedded;
+
+/// Content type of files whose type can't be determined from their name.
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// Size of the chunks files are read in when streamed.
+const FILE_CHUNK_SIZE: usize = 64 * 1024;
 
 /// Static path
 pub struct StaticPath {
-    config: StaticPathConfig,
+    config: Box<StaticPathConfig>,
+    directories: Vec<PathBuf>,
+    /// The directories with symlinks resolved, that files must stay in unless symlinks are followed
+    roots: Vec<PathBuf>,
     index_file: Option<String>,
-    file_cache: VetisFileCache,
+    directory_template: Option<String>,
+    #[cfg(feature = "static-cache")]
+    content_cache: Option<FileCache>,
+}
+
+/// Outcome of a `Range` request header checked against a file size.
+enum ByteRange {
+    /// The range can't be served
+    Unsatisfiable,
+    /// Serve the bytes from the first offset to the second, inclusive
+    Range(u64, u64),
+}
+
+/// Returns whether a dotfile policy permits serving a path.
+///
+/// # Arguments
+///
+/// * `policy` - The dotfile policy of the static path
+/// * `path` - The requested path, relative to the static path URI
+///
+/// # Returns
+///
+/// * `bool` - `false` if a segment of the path is a dotfile the policy doesn't permit.
+fn dotfiles_permitted(policy: DotfilePolicy, path: &str) -> bool {
+    if policy == DotfilePolicy::Allow {
+        return true;
+    }
+
+    let mut segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .peekable();
+
+    if policy == DotfilePolicy::AllowWellKnown {
+        segments.next_if_eq(&".well-known");
+    }
+
+    !segments.any(|segment| segment.starts_with('.'))
+}
+
+/// Escapes text for use in HTML content and attribute values.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Parses a `Range` request header against a file size.
+///
+/// # Arguments
+///
+/// * `range` - The value of the `Range` header
+/// * `filesize` - The size of the requested file
+///
+/// # Returns
+///
+/// * `Result<ByteRange, VetisError>` - The part of the file to serve.
+fn parse_range(range: &str, filesize: u64) -> Result<ByteRange, VetisError> {
+    let (unit, range) = range
+        .split_once("=")
+        .ok_or(VetisError::VirtualHost(VirtualHostError::File(FileError::InvalidRange)))?;
+    if unit != "bytes" {
+        return Err(VetisError::VirtualHost(VirtualHostError::File(FileError::InvalidRange)));
+    }
+
+    let (start, end) = range
+        .split_once("-")
+        .ok_or(VetisError::VirtualHost(VirtualHostError::File(FileError::InvalidRange)))?;
+    let start = start
+        .parse::<u64>()
+        .map_err(|_| VetisError::VirtualHost(VirtualHostError::File(FileError::InvalidRange)))?;
+    let end = end
+        .parse::<u64>()
+        .map_err(|_| VetisError::VirtualHost(VirtualHostError::File(FileError::InvalidRange)))?;
+
+    if start > end || start >= filesize {
+        Ok(ByteRange::Unsatisfiable)
+    } else {
+        // Ranges running past the end of the file are served up to its last byte
+        Ok(ByteRange::Range(start, end.min(filesize - 1)))
+    }
+}
+
+/// Returns whether an `If-Range` validator matches the file, so its range can be served.
+///
+/// Requests without `If-Range` always match. Entity tags are not generated
+/// for static files, so a request validated by one never matches.
+///
+/// # Arguments
+///
+/// * `if_range` - The value of the `If-Range` header
+/// * `modified` - The modification time of the file, if known
+///
+/// # Returns
+///
+/// * `bool` - Whether the range applies to the current file.
+fn if_range_matches(if_range: Option<&str>, modified: Option<SystemTime>) -> bool {
+    match if_range {
+        None => true,
+        Some(validator) if validator.starts_with('"') || validator.starts_with("W/") => false,
+        Some(date) => {
+            modified.is_some_and(|modified| crate::utils::date::format_date(modified) == date)
+        }
+    }
+}
+
+/// Returns the headers of a `206 Partial Content` response for the bytes `start` to `end`.
+fn partial_headers(
+    config: &StaticPathConfig,
+    file_path: &std::path::Path,
+    start: u64,
+    end: u64,
+    filesize: u64,
+) -> Result<HeaderMap, VetisError> {
+    let mut headers = HeaderMap::new();
+    headers.insert(http::header::CONTENT_TYPE, content_type(config, file_path)?);
+    headers.insert(http::header::CONTENT_LENGTH, HeaderValue::from(end - start + 1));
+    headers.insert(
+        http::header::CONTENT_RANGE,
+        HeaderValue::from_str(&format!("bytes {}-{}/{}", start, end, filesize)).map_err(|_| {
+            VetisError::VirtualHost(VirtualHostError::File(FileError::InvalidRange))
+        })?,
+    );
+    Ok(headers)
+}
+
+/// Returns a body streaming `length` bytes of a file from its current position.
+///
+/// The file is read in chunks of [`FILE_CHUNK_SIZE`] bytes, and never past
+/// `length`, so a range ends where it was requested rather than at the end
+/// of the file.
+fn file_body(file: VetisFile, length: u64) -> HttpBody {
+    let chunks = futures_util::stream::unfold(Some(file.take(length)), |reader| async move {
+        let mut reader = reader?;
+        let mut chunk = vec![0; FILE_CHUNK_SIZE];
+        match reader
+            .read(&mut chunk)
+            .await
+        {
+            Ok(0) => None,
+            Ok(read) => {
+                chunk.truncate(read);
+                Some((Ok(Frame::data(Bytes::from(chunk))), Some(reader)))
+            }
+            // The stream ends after reporting the error
+            Err(e) => Some((Err(e), None)),
+        }
+    });
+
+    HttpBody::Stream(StreamBody::new(chunks).boxed())
+}
+
+/// Returns the headers of a `416 Range Not Satisfiable` response, giving the file size.
+fn unsatisfiable_headers(filesize: u64) -> Result<HeaderMap, VetisError> {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        http::header::CONTENT_RANGE,
+        HeaderValue::from_str(&format!("bytes */{}", filesize)).map_err(|_| {
+            VetisError::VirtualHost(VirtualHostError::File(FileError::InvalidRange))
+        })?,
+    );
+    Ok(headers)
+}
+
+/// Returns the headers of a full response for a file.
+fn file_headers(
+    config: &StaticPathConfig,
+    file_path: &std::path::Path,
+    filesize: u64,
+    modified: Option<SystemTime>,
+) -> Result<HeaderMap, VetisError> {
+    let mut headers = HeaderMap::new();
+    if config.accept_ranges() {
+        headers.insert(http::header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    }
+
+    headers.insert(http::header::CONTENT_TYPE, content_type(config, file_path)?);
+
+    headers.insert(http::header::CONTENT_LENGTH, HeaderValue::from(filesize));
+
+    // Lets clients validate resumed downloads with `If-Range`
+    if let Some(modified) = modified {
+        let date = crate::utils::date::format_date(modified);
+        headers.insert(
+            http::header::LAST_MODIFIED,
+            date.parse()
+                .map_err(|_| {
+                    VetisError::VirtualHost(VirtualHostError::File(FileError::InvalidMetadata))
+                })?,
+        );
+    }
+
+    if config.attachment() {
+        if let Some(filename) = file_path
+            .file_name()
+            .and_then(|filename| filename.to_str())
+        {
+            headers.insert(
+                http::header::CONTENT_DISPOSITION,
+                crate::utils::disposition::attachment(filename),
+            );
+        }
+    }
+
+    Ok(headers)
+}
+
+/// Returns the content type of a file, `application/octet-stream` when it is unknown.
+fn content_type(
+    config: &StaticPathConfig,
+    file_path: &std::path::Path,
+) -> Result<HeaderValue, VetisError> {
+    let content_type = config
+        .mime_overrides()
+        .as_ref()
+        .and_then(|mime_overrides| {
+            file_path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .and_then(|extension| mime_overrides.get(extension))
+                .cloned()
+        })
+        .or_else(|| {
+            file_path
+                .file_name()
+                .and_then(|filename| filename.to_str())
+                .and_then(minimime::lookup_by_filename)
+                .map(|mime_type| mime_type.content_type)
+        });
+
+    match content_type {
+        Some(content_type) => HeaderValue::from_str(&content_type).map_err(|_| {
+            VetisError::VirtualHost(VirtualHostError::File(FileError::InvalidMetadata))
+        }),
+        None => Ok(HeaderValue::from_static(DEFAULT_CONTENT_TYPE)),
+    }
 }
 
 impl StaticPath {
@@ -43,112 +302,203 @@ impl StaticPath {
     ///
     /// * `StaticPath` - The static path
     pub fn new(config: StaticPathConfig) -> StaticPath {
-        let file_cache = Arc::new(VetisRwLock::new(LruCache::new(NonZeroUsize::new(100).unwrap())));
-        if let Some(index_files) = config.index_files() {
-            let directory = PathBuf::from(config.directory());
-            if let Some(index_file) = index_files
-                .iter()
-                .find(|index_file| {
-                    directory
-                        .join(index_file)
-                        .exists()
-                })
-            {
-                return StaticPath {
-                    config: config.clone(),
-                    index_file: Some(index_file.to_string()),
-                    file_cache,
-                };
-            }
+        #[cfg(feature = "static-cache")]
+        let content_cache = config
+            .cache_size()
+            .map(|cache_size| {
+                FileCache::new(
+                    cache_size,
+                    config.cache_max_file_size(),
+                    config.cache_revalidate_interval(),
+                )
+            });
+        let directories: Vec<PathBuf> = std::iter::once(config.directory())
+            .chain(
+                config
+                    .directories()
+                    .iter()
+                    .flatten()
+                    .map(String::as_str),
+            )
+            .map(PathBuf::from)
+            .collect();
+        let roots = directories
+            .iter()
+            .filter_map(|directory| {
+                directory
+                    .canonicalize()
+                    .ok()
+            })
+            .collect();
+
+        let index_file = config
+            .index_files()
+            .iter()
+            .flatten()
+            .find(|index_file| {
+                directories
+                    .iter()
+                    .any(|directory| {
+                        directory
+                            .join(index_file)
+                            .exists()
+                    })
+            })
+            .cloned();
+
+        let directory_template = config
+            .directory_template()
+            .as_ref()
+            .and_then(|template| match std::fs::read_to_string(template) {
+                Ok(template) => Some(template),
+                Err(e) => {
+                    error!("Failed to read directory template {}: {}", template, e);
+                    None
+                }
+            });
+
+        StaticPath {
+            config: Box::new(config),
+            directories,
+            roots,
+            index_file,
+            directory_template,
+            #[cfg(feature = "static-cache")]
+            content_cache,
         }
-        StaticPath { config, index_file: None, file_cache }
     }
 
-    async fn cache_file(&self, file_path: &std::path::Path) -> Result<VetisFile, VetisError> {
-        let path = file_path
-            .display()
-            .to_string();
-        let lock = self
-            .file_cache
-            .clone();
-
-        let open_file = move || {
-            #[cfg(feature = "tokio-rt")]
-            let mut lock = lock.blocking_write();
-
-            #[cfg(feature = "smol-rt")]
-            let mut lock = lock.write_blocking();
+    /// Finds a file in the directories of the static path, in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `relative` - The file path, relative to the directories
+    ///
+    /// # Returns
+    ///
+    /// * `PathBuf` - The first existing match, or the path under the main directory if none exists.
+    fn resolve(&self, relative: &std::path::Path) -> PathBuf {
+        self.directories
+            .iter()
+            .map(|directory| directory.join(relative))
+            .find(|file| file.exists())
+            .unwrap_or_else(|| self.directories[0].join(relative))
+    }
 
-            if let Some(raw_fd) = lock.get(&path) {
-                FileDescriptor::dup(raw_fd)
-            } else {
-                let file = std::fs::File::open(path.clone());
-                match file {
-                    Ok(file) => {
-                        let raw_fd =
-                            lock.get_or_insert(path.clone(), || file.as_raw_file_descriptor());
-                        FileDescriptor::dup(raw_fd)
-                    }
-                    Err(e) => {
-                        error!("Error opening file {}: {}", path, e);
-                        Err(filedescriptor::Error::Io(e))
-                    }
-                }
-            }
-        };
+    /// Returns whether a file is reached through a symlink leading out of the directories.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The file path, as resolved in the directories
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if symlinks aren't followed and the file lies outside every directory.
+    fn escapes_roots(&self, file: &std::path::Path) -> bool {
+        if self
+            .config
+            .follow_symlinks()
+        {
+            return false;
+        }
 
-        #[cfg(feature = "tokio-rt")]
-        let task = {
-            let task = tokio::task::spawn_blocking(open_file);
-            match task.await {
-                Ok(result) => result,
-                Err(e) => Err(filedescriptor::Error::Io(e.into())),
-            }
+        // Missing files are left to the not found handling
+        let Ok(file) = file.canonicalize() else {
+            return false;
         };
 
-        #[cfg(feature = "smol-rt")]
-        let task = smol::unblock(open_file).await;
-
-        match task {
-            Ok(raw_fd) => {
-                let file = raw_fd.as_file();
-                match file {
-                    Ok(file) => {
-                        #[cfg(feature = "tokio-rt")]
-                        let file = VetisFile::from_std(file);
+        !self
+            .roots
+            .iter()
+            .any(|root| file.starts_with(root))
+    }
 
-                        #[cfg(feature = "smol-rt")]
-                        let file = VetisFile::from(file);
+    #[cfg(all(test, feature = "static-cache"))]
+    /// Returns the in-memory file cache, if enabled.
+    pub(crate) fn content_cache(&self) -> Option<&FileCache> {
+        self.content_cache
+            .as_ref()
+    }
 
-                        Ok(file)
-                    }
-                    Err(e) => {
-                        error!("Error opening file {}", e);
-                        Err(VetisError::VirtualHost(VirtualHostError::File(FileError::NotFound)))
-                    }
-                }
-            }
-            Err(e) => {
-                error!("Error opening file: {}", e);
-                Err(VetisError::VirtualHost(VirtualHostError::File(FileError::NotFound)))
-            }
-        }
+    /// Opens a file of the static path.
+    ///
+    /// Every response gets a handle of its own: handles sharing an open file
+    /// share its offset too, so concurrent reads and range seeks would move
+    /// each other.
+    async fn open_file(&self, file_path: &std::path::Path) -> Result<VetisFile, VetisError> {
+        VetisFile::open(file_path)
+            .await
+            .map_err(|e| {
+                error!("Error opening file {}: {}", file_path.display(), e);
+                VetisError::VirtualHost(VirtualHostError::File(FileError::NotFound))
+            })
     }
 
     async fn serve_file(
         &self,
         file_path: &std::path::Path,
         range: Option<&str>,
+        if_range: Option<&str>,
     ) -> Result<Response, VetisError> {
+        #[cfg(feature = "static-cache")]
+        if let Some(content_cache) = &self.content_cache {
+            if let Some(cached) = content_cache
+                .get(file_path)
+                .await?
+            {
+                let filesize = cached
+                    .contents()
+                    .len() as u64;
+                let modified = Some(cached.modified());
+
+                // A range of a file that changed since the validator was issued would be stitched
+                // from different versions, so the whole file is sent instead
+                let range = range.filter(|_| if_range_matches(if_range, modified));
+
+                if let Some(range) = range {
+                    match parse_range(range, filesize)? {
+                        ByteRange::Unsatisfiable => {
+                            return Ok(Response::builder()
+                                .status(http::StatusCode::RANGE_NOT_SATISFIABLE)
+                                .headers(unsatisfiable_headers(filesize)?)
+                                .body(HttpBody::from_text("")));
+                        }
+                        ByteRange::Range(start, end) => {
+                            return Ok(Response::builder()
+                                .status(http::StatusCode::PARTIAL_CONTENT)
+                                .headers(partial_headers(
+                                    &self.config,
+                                    file_path,
+                                    start,
+                                    end,
+                                    filesize,
+                                )?)
+                                .body(cached.body(start as usize..end as usize + 1)));
+                        }
+                    }
+                }
+
+                return Ok(Response::builder()
+                    .status(http::StatusCode::OK)
+                    .headers(file_headers(&self.config, file_path, filesize, modified)?)
+                    .body(cached.body(0..filesize as usize)));
+            }
+        }
+
         let mut file = self
-            .cache_file(file_path)
+            .open_file(file_path)
             .await?;
 
-        let filesize = match file
+        let (filesize, modified) = match file
             .metadata()
             .await
         {
-            Ok(metadata) => metadata.len(),
+            Ok(metadata) => (
+                metadata.len(),
+                metadata
+                    .modified()
+                    .ok(),
+            ),
             Err(e) => {
                 error!("Error getting metadata for file {}: {}", file_path.display(), e);
                 return Err(VetisError::VirtualHost(VirtualHostError::File(
@@ -157,67 +507,61 @@ impl StaticPath {
             }
         };
 
-        if let Some(range) = range {
-            let range_info = match range
-                .split_once("=")
-                .ok_or(VetisError::VirtualHost(VirtualHostError::File(FileError::InvalidRange)))
-            {
-                Ok(info) => info,
-                Err(e) => return Err(e),
-            };
-
-            let (unit, range) = range_info;
-            if unit != "bytes" {
-                return Err(VetisError::VirtualHost(VirtualHostError::File(
-                    FileError::InvalidRange,
-                )));
-            }
+        let range = range.filter(|_| if_range_matches(if_range, modified));
 
-            let (start, end) = range
-                .split_once("-")
-                .ok_or(VetisError::VirtualHost(VirtualHostError::File(FileError::InvalidRange)))?;
-            let start = start
-                .parse::<u64>()
-                .map_err(|_| {
-                    VetisError::VirtualHost(VirtualHostError::File(FileError::InvalidRange))
-                })?;
-            let end = end
-                .parse::<u64>()
-                .map_err(|_| {
-                    VetisError::VirtualHost(VirtualHostError::File(FileError::InvalidRange))
-                })?;
-            if start > end || start >= filesize {
-                return Ok(Response::builder()
-                    .status(http::StatusCode::RANGE_NOT_SATISFIABLE)
-                    .body(HttpBody::from_text("")));
-            } else if start < end
-                && end < filesize
-                && file
-                    .seek(std::io::SeekFrom::Start(start))
-                    .await
-                    .is_ok()
-            {
-                return Ok(Response::builder()
-                    .status(http::StatusCode::PARTIAL_CONTENT)
-                    .body(HttpBody::from_file(file)));
+        if let Some(range) = range {
+            match parse_range(range, filesize)? {
+                ByteRange::Unsatisfiable => {
+                    return Ok(Response::builder()
+                        .status(http::StatusCode::RANGE_NOT_SATISFIABLE)
+                        .headers(unsatisfiable_headers(filesize)?)
+                        .body(HttpBody::from_text("")));
+                }
+                ByteRange::Range(start, end)
+                    if file
+                        .seek(std::io::SeekFrom::Start(start))
+                        .await
+                        .is_ok() =>
+                {
+                    return Ok(Response::builder()
+                        .status(http::StatusCode::PARTIAL_CONTENT)
+                        .headers(partial_headers(&self.config, file_path, start, end, filesize)?)
+                        .body(file_body(file, end - start + 1)));
+                }
+                _ => {}
             }
         }
 
         Ok(Response::builder()
             .status(http::StatusCode::OK)
-            .header(
-                http::header::ACCEPT_RANGES,
-                "bytes"
-                    .parse()
-                    .unwrap(),
-            )
-            .header(http::header::CONTENT_LENGTH, HeaderValue::from(filesize))
-            .body(HttpBody::from_file(file)))
+            .headers(file_headers(&self.config, file_path, filesize, modified)?)
+            .body(file_body(file, filesize)))
     }
 
     async fn serve_metadata(&self, file_path: PathBuf) -> Result<Response, VetisError> {
+        #[cfg(feature = "static-cache")]
+        if let Some(content_cache) = &self.content_cache {
+            if let Some(cached) = content_cache
+                .get(&file_path)
+                .await?
+            {
+                let headers = file_headers(
+                    &self.config,
+                    &file_path,
+                    cached
+                        .contents()
+                        .len() as u64,
+                    Some(cached.modified()),
+                )?;
+
+                return Ok(Response {
+                    inner: static_response(http::StatusCode::OK, Some(headers), String::new()),
+                });
+            }
+        }
+
         let file = self
-            .cache_file(&file_path)
+            .open_file(&file_path)
             .await?;
 
         let metadata = match file
@@ -231,74 +575,105 @@ impl StaticPath {
             }
         };
 
-        let len = metadata.len();
-        let mut headers = HeaderMap::new();
-        match len
-            .to_string()
-            .parse()
-        {
-            Ok(len) => {
-                headers.insert(http::header::CONTENT_LENGTH, len);
+        // Some filesystems don't record modification times, last-modified is then omitted
+        let modified = match metadata.modified() {
+            Ok(modified) => Some(modified),
+            Err(e) => {
+                warn!("Modification time unavailable for file {:?}: {}", file_path, e);
+                None
             }
-            Err(_) => todo!(),
-        }
-        let last_modified = metadata.modified();
-        match last_modified {
-            Ok(date) => {
-                let date = crate::utils::date::format_date(date);
-                headers.insert(
-                    http::header::LAST_MODIFIED,
-                    date.parse()
-                        .map_err(|_| {
-                            VetisError::VirtualHost(VirtualHostError::File(
-                                FileError::InvalidMetadata,
-                            ))
-                        })?,
-                );
+        };
+
+        let headers = file_headers(&self.config, &file_path, metadata.len(), modified)?;
+
+        Ok(Response { inner: static_response(http::StatusCode::OK, Some(headers), String::new()) })
+    }
+
+    /// Renders the directory template for a directory of the static path.
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - The directory template
+    /// * `directory` - The directory on disk
+    /// * `relative` - The directory path, relative to the static path URI
+    /// * `path` - The request path, the entry links are built from
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Response, VetisError>` - The rendered listing, `FileError::NotFound` if the directory can't be read.
+    fn serve_directory_template(
+        &self,
+        template: &str,
+        directory: &std::path::Path,
+        relative: &str,
+        path: &str,
+    ) -> Result<Response, VetisError> {
+        let entries = match std::fs::read_dir(directory) {
+            Ok(entries) => entries,
+            Err(e) => {
+                error!("Error reading directory {}: {}", directory.display(), e);
+                return Err(VetisError::VirtualHost(VirtualHostError::File(FileError::NotFound)));
             }
-            Err(_) => todo!(),
-        }
+        };
 
-        match file_path.file_name() {
-            Some(filename) => {
-                let mime_type = minimime::lookup_by_filename(
-                    filename
-                        .to_str()
-                        .ok_or(VetisError::VirtualHost(VirtualHostError::File(
-                            FileError::InvalidMetadata,
-                        )))?,
-                );
-                if let Some(mime_type) = mime_type {
-                    headers.insert(
-                        http::header::CONTENT_TYPE,
-                        HeaderValue::from_str(
-                            mime_type
-                                .content_type
-                                .as_str(),
-                        )
-                        .map_err(|_| {
-                            VetisError::VirtualHost(VirtualHostError::File(
-                                FileError::InvalidMetadata,
-                            ))
-                        })?,
-                    );
+        let mut names: Vec<String> = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let mut name = entry
+                    .file_name()
+                    .into_string()
+                    .ok()?;
+                if !dotfiles_permitted(
+                    self.config
+                        .serve_dotfiles(),
+                    &format!("{}/{}", relative, name),
+                ) {
+                    return None;
                 }
-            }
-            None => {
-                return Err(VetisError::VirtualHost(VirtualHostError::File(
-                    FileError::InvalidMetadata,
-                )));
-            }
-        }
+                if entry
+                    .file_type()
+                    .is_ok_and(|file_type| file_type.is_dir())
+                {
+                    name.push('/');
+                }
+                Some(name)
+            })
+            .collect();
+        names.sort();
+
+        let base = escape_html(path.trim_end_matches('/'));
+        let entries: String = names
+            .iter()
+            .map(|name| {
+                let name = escape_html(name);
+                format!("<li><a href=\"{}/{}\">{}</a></li>\n", base, name, name)
+            })
+            .collect();
+
+        let body = template
+            .replace("{{path}}", &escape_html(path))
+            .replace("{{entries}}", &entries);
 
-        Ok(Response { inner: static_response(http::StatusCode::OK, Some(headers), String::new()) })
+        Ok(Response::builder()
+            .status(http::StatusCode::OK)
+            .header(
+                http::header::CONTENT_TYPE,
+                HeaderValue::from_static("text/html; charset=utf-8"),
+            )
+            .text(&body))
     }
 
     async fn serve_index_file(&self, directory: &std::path::Path) -> Result<Response, VetisError> {
         match &self.index_file {
             Some(index_file) => {
-                let full_path = directory.join(index_file);
-                self.serve_file(&full_path, None)
+                let full_path = self.resolve(&directory.join(index_file));
+                if self.escapes_roots(&full_path) {
+                    warn!("Index file {} leads out of the static path", full_path.display());
+                    return Err(VetisError::VirtualHost(VirtualHostError::File(
+                        FileError::NotFound,
+                    )));
+                }
+                self.serve_file(&full_path, None, None)
                     .await
             }
             None => {
@@ -350,11 +725,6 @@ impl Path for StaticPath {
                     .extensions(),
             );
 
-            let directory = PathBuf::from(
-                self.config
-                    .directory(),
-            );
-
             #[cfg(feature = "auth")]
             if let Some(auth) = self.config.auth() {
                 if !auth
@@ -371,29 +741,85 @@ impl Path for StaticPath {
             let uri = uri
                 .strip_prefix("/")
                 .unwrap_or(&uri);
-            let file = directory.join(uri);
-
-            if self
-                .config
-                .index_files()
-                .is_some()
-            {
-                if !file.exists() {
-                    if let Ok(ext_regex) = ext_regex {
-                        if !ext_regex.is_match(uri.as_ref()) {
-                            return self
-                                .serve_index_file(&directory)
-                                .await;
+            if !dotfiles_permitted(
+                self.config
+                    .serve_dotfiles(),
+                uri,
+            ) {
+                return Err(VetisError::VirtualHost(VirtualHostError::File(FileError::NotFound)));
+            }
+            let file = match self.config.file() {
+                // Only the exact URI is served, not paths below it
+                Some(file) if uri.is_empty() && std::path::Path::new(file).is_file() => {
+                    PathBuf::from(file)
+                }
+                Some(_) => {
+                    return Err(VetisError::VirtualHost(VirtualHostError::File(
+                        FileError::NotFound,
+                    )));
+                }
+                None => {
+                    #[cfg(feature = "static-cache")]
+                    let cached = self
+                        .content_cache
+                        .as_ref()
+                        .and_then(|content_cache| {
+                            self.directories
+                                .iter()
+                                .map(|directory| directory.join(uri))
+                                .find(|file| content_cache.is_fresh(file))
+                        });
+                    #[cfg(not(feature = "static-cache"))]
+                    let cached: Option<PathBuf> = None;
+
+                    if let Some(file) = cached {
+                        // Served from memory, without touching the filesystem.
+                        file
+                    } else {
+                        let file = self.resolve(std::path::Path::new(uri));
+                        if self.escapes_roots(&file) {
+                            warn!("File {} leads out of the static path", file.display());
+                            return Err(VetisError::VirtualHost(VirtualHostError::File(
+                                FileError::NotFound,
+                            )));
+                        }
+                        if let Some(template) = &self.directory_template {
+                            if file.is_dir() {
+                                return self.serve_directory_template(
+                                    template,
+                                    &file,
+                                    uri,
+                                    request.uri().path(),
+                                );
+                            }
+                        }
+                        if self
+                            .config
+                            .index_files()
+                            .is_some()
+                        {
+                            if !file.exists() {
+                                if let Ok(ext_regex) = ext_regex {
+                                    if !ext_regex.is_match(uri.as_ref()) {
+                                        return self
+                                            .serve_index_file(std::path::Path::new(""))
+                                            .await;
+                                    }
+                                }
+                            } else if file.is_dir() {
+                                return self
+                                    .serve_index_file(std::path::Path::new(uri))
+                                    .await;
+                            }
+                        } else if !file.exists() {
+                            return Err(VetisError::VirtualHost(VirtualHostError::File(
+                                FileError::NotFound,
+                            )));
                         }
+                        file
                     }
-                } else if file.is_dir() {
-                    return self
-                        .serve_index_file(&file)
-                        .await;
                 }
-            } else if !file.exists() {
-                return Err(VetisError::VirtualHost(VirtualHostError::File(FileError::NotFound)));
-            }
+            };
 
             if request.method() == http::Method::HEAD {
                 return self
@@ -401,9 +827,12 @@ impl Path for StaticPath {
                     .await;
             }
 
-            let range = if request
-                .headers()
-                .contains_key(http::header::RANGE)
+            let range = if self
+                .config
+                .accept_ranges()
+                && request
+                    .headers()
+                    .contains_key(http::header::RANGE)
             {
                 let value = request
                     .headers()
@@ -418,7 +847,12 @@ impl Path for StaticPath {
                 None
             };
 
-            self.serve_file(&file, range)
+            let if_range = request
+                .headers()
+                .get(http::header::IF_RANGE)
+                .and_then(|value| value.to_str().ok());
+
+            self.serve_file(&file, range, if_range)
                 .await
         })
     }
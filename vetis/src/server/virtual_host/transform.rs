@@ -0,0 +1,181 @@
+//! Response body transformations applied by virtual hosts.
+
+use bytes::{Bytes, BytesMut};
+use http::{header, HeaderMap, HeaderValue};
+use http_body_util::BodyExt;
+use hyper_body_utils::HttpBody;
+
+use crate::{errors::VetisError, server::http::Response};
+
+/// Trait for transforming outgoing response bodies.
+///
+/// Transforms are registered on a virtual host with
+/// [`VirtualHost::add_transform`](crate::server::virtual_host::VirtualHost::add_transform)
+/// and run in registration order. The body is only buffered when at least one
+/// transform applies to the response.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use bytes::Bytes;
+/// use http::HeaderMap;
+/// use vetis::server::virtual_host::transform::BodyTransform;
+///
+/// struct Uppercase;
+///
+/// impl BodyTransform for Uppercase {
+///     fn applies_to(&self, headers: &HeaderMap) -> bool {
+///         headers.get(http::header::CONTENT_TYPE).is_some_and(|value| value == "text/plain")
+///     }
+///
+///     fn transform(&self, body: Bytes) -> Bytes {
+///         Bytes::from(body.to_ascii_uppercase())
+///     }
+/// }
+/// ```
+pub trait BodyTransform: Send + Sync {
+    /// Checks whether the transform should run for a response
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The response headers
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - `true` if the body should be transformed
+    fn applies_to(&self, headers: &HeaderMap) -> bool;
+
+    /// Transforms the buffered response body
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - The complete response body
+    ///
+    /// # Returns
+    ///
+    /// * `Bytes` - The transformed body
+    fn transform(&self, body: Bytes) -> Bytes;
+}
+
+/// Inserts a snippet right before the closing `</body>` tag of HTML responses.
+///
+/// Responses that aren't `text/html`, or that are already content-encoded,
+/// pass through untouched. When there is no `</body>` tag the snippet is
+/// appended to the end of the document.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use vetis::server::virtual_host::transform::InjectBeforeClosingBody;
+///
+/// virtual_host.add_transform(InjectBeforeClosingBody::new(
+///     "<script src=\"/livereload.js\"></script>",
+/// ));
+/// ```
+pub struct InjectBeforeClosingBody {
+    snippet: Bytes,
+}
+
+impl InjectBeforeClosingBody {
+    /// Create a new transform injecting the given snippet
+    ///
+    /// # Arguments
+    ///
+    /// * `snippet` - The snippet to inject
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new `InjectBeforeClosingBody` instance.
+    pub fn new(snippet: &str) -> Self {
+        Self { snippet: Bytes::copy_from_slice(snippet.as_bytes()) }
+    }
+}
+
+impl BodyTransform for InjectBeforeClosingBody {
+    fn applies_to(&self, headers: &HeaderMap) -> bool {
+        if headers.contains_key(header::CONTENT_ENCODING) {
+            return false;
+        }
+
+        headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| {
+                value
+                    .split(';')
+                    .next()
+            })
+            .is_some_and(|mime| {
+                mime.trim()
+                    .eq_ignore_ascii_case("text/html")
+            })
+    }
+
+    fn transform(&self, body: Bytes) -> Bytes {
+        const CLOSING_BODY: &[u8] = b"</body>";
+
+        let position = body
+            .windows(CLOSING_BODY.len())
+            .rposition(|window| window.eq_ignore_ascii_case(CLOSING_BODY))
+            .unwrap_or(body.len());
+
+        let mut transformed = BytesMut::with_capacity(body.len() + self.snippet.len());
+        transformed.extend_from_slice(&body[..position]);
+        transformed.extend_from_slice(&self.snippet);
+        transformed.extend_from_slice(&body[position..]);
+        transformed.freeze()
+    }
+}
+
+/// Runs the applicable transforms over a response body.
+///
+/// # Arguments
+///
+/// * `transforms` - The registered transforms
+/// * `response` - The response to transform
+///
+/// # Returns
+///
+/// * `Result<Response, VetisError>` - The transformed response, with `content-length` recomputed.
+pub(crate) async fn apply_transforms(
+    transforms: &[Box<dyn BodyTransform>],
+    response: Response,
+) -> Result<Response, VetisError> {
+    let applicable: Vec<&dyn BodyTransform> = transforms
+        .iter()
+        .map(|transform| transform.as_ref())
+        .filter(|transform| {
+            transform.applies_to(
+                response
+                    .inner
+                    .headers(),
+            )
+        })
+        .collect();
+
+    if applicable.is_empty() {
+        return Ok(response);
+    }
+
+    let (mut parts, body) = response
+        .into_inner()
+        .into_parts();
+
+    let mut body = body
+        .collect()
+        .await?
+        .to_bytes();
+
+    for transform in applicable {
+        body = transform.transform(body);
+    }
+
+    parts
+        .headers
+        .remove(header::TRANSFER_ENCODING);
+    parts
+        .headers
+        .insert(header::CONTENT_LENGTH, HeaderValue::from(body.len()));
+
+    Ok(Response { inner: http::Response::from_parts(parts, HttpBody::from_bytes(&body)) })
+}
@@ -0,0 +1,279 @@
+mod access_log_tests {
+    use std::{
+        sync::Arc,
+        time::{Duration, Instant},
+    };
+
+    use http::{HeaderMap, HeaderValue, Method, StatusCode};
+    use http_body_util::BodyExt;
+    use hyper_body_utils::HttpBody;
+    #[cfg(feature = "smol-rt")]
+    use macro_rules_attribute::apply;
+    #[cfg(feature = "smol-rt")]
+    use smol_macros::test;
+
+    use crate::{
+        config::server::{virtual_host::VirtualHostConfig, AmbiguousLengthPolicy},
+        server::conn::{
+            access_log::{access_log_format, with_access_log, AccessLogEntry, LogSampler, LogTags},
+            listener::{ConnectionInfo, HeaderLimits},
+            request_id::RequestId,
+        },
+        tests::{capture_logs, captured_logs},
+    };
+
+    fn request_id(id: &str) -> RequestId {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-request-id", HeaderValue::from_str(id).unwrap());
+        RequestId::assign(&mut headers)
+    }
+
+    #[test]
+    fn test_request_id_assign() {
+        assert_eq!(request_id("trace-42").as_str(), "trace-42");
+
+        // Identifiers that would break the log line are replaced
+        for invalid in ["", "with space", &"x".repeat(129)] {
+            let mut headers = HeaderMap::new();
+            headers.insert("x-request-id", HeaderValue::from_str(invalid).unwrap());
+            let request_id = RequestId::assign(&mut headers);
+            assert_ne!(request_id.as_str(), invalid);
+            assert_eq!(
+                request_id
+                    .as_str()
+                    .len(),
+                32
+            );
+            assert_eq!(
+                headers
+                    .get("x-request-id")
+                    .unwrap(),
+                request_id.as_str()
+            );
+        }
+
+        let mut headers = HeaderMap::new();
+        let first = RequestId::assign(&mut headers);
+        assert_eq!(
+            headers
+                .get("x-request-id")
+                .unwrap(),
+            first.as_str()
+        );
+        assert_ne!(RequestId::assign(&mut HeaderMap::new()).as_str(), first.as_str());
+    }
+
+    #[test]
+    fn test_access_log_format() {
+        let entry = AccessLogEntry {
+            remote_addr: "127.0.0.1:4000"
+                .parse()
+                .unwrap(),
+            listener: Some("public".into()),
+            method: Method::POST,
+            uri: "/submit?id=1"
+                .parse()
+                .unwrap(),
+            status: StatusCode::CREATED,
+            bytes: 42,
+            duration: Duration::from_millis(15),
+            request_id: request_id("trace-42"),
+            tags: LogTags::default(),
+        };
+
+        assert_eq!(
+            entry.format(
+                "%remote_addr \"%method %path\" %status %bytes %duration_ms %request_id 100%"
+            ),
+            "127.0.0.1:4000 \"POST /submit?id=1\" 201 42 15 trace-42 100%"
+        );
+
+        let entry = AccessLogEntry { listener: None, ..entry };
+        assert_eq!(entry.format("%listener %method %path %tags"), "- POST /submit?id=1 -");
+
+        let entry = AccessLogEntry {
+            tags: LogTags(vec![
+                ("user".to_string(), "42".to_string()),
+                ("route".to_string(), "submit".to_string()),
+            ]),
+            ..entry
+        };
+        assert_eq!(entry.format("%path %tags"), "/submit?id=1 user=42 route=submit");
+    }
+
+    fn connection_info(log_sampler: Option<Arc<LogSampler>>) -> ConnectionInfo {
+        ConnectionInfo {
+            client_addr: "127.0.0.1:4000"
+                .parse()
+                .unwrap(),
+            listener_name: None,
+            tls_info: None,
+            header_limits: HeaderLimits::default(),
+            keep_alive: true,
+            ambiguous_length: AmbiguousLengthPolicy::Reject,
+            log_sampler,
+        }
+    }
+
+    async fn do_access_log_toggle() -> Result<(), Box<dyn std::error::Error>> {
+        capture_logs();
+
+        for (enabled, marker) in [(true, "/access-log-enabled"), (false, "/access-log-disabled")] {
+            let config = VirtualHostConfig::builder()
+                .root_directory("src/tests")
+                .enable_logging(enabled)
+                .log_format("%method %path %status %bytes")
+                .build()?;
+
+            assert_eq!(access_log_format(&config).is_some(), enabled);
+
+            let response = http::Response::builder()
+                .status(StatusCode::OK)
+                .body(HttpBody::from_text("Hello, world!"))?;
+
+            let response = with_access_log(
+                &config,
+                response,
+                &connection_info(None),
+                Method::GET,
+                marker.parse()?,
+                &request_id("toggle"),
+                Instant::now(),
+            );
+
+            let body = response
+                .into_body()
+                .collect()
+                .await?
+                .to_bytes();
+
+            assert_eq!(body.as_ref(), b"Hello, world!");
+
+            if enabled {
+                assert_eq!(captured_logs(marker), vec![format!("GET {} 200 13", marker)]);
+            } else {
+                assert!(captured_logs(marker).is_empty());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_access_log_toggle() -> Result<(), Box<dyn std::error::Error>> {
+        do_access_log_toggle().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_access_log_toggle() -> Result<(), Box<dyn std::error::Error>> {
+        do_access_log_toggle().await
+    }
+
+    async fn do_access_log_sampling() -> Result<(), Box<dyn std::error::Error>> {
+        capture_logs();
+
+        let config = VirtualHostConfig::builder()
+            .root_directory("src/tests")
+            .enable_logging(true)
+            .log_format("%method %path %status")
+            .build()?;
+        let connection_info = connection_info(Some(Arc::new(LogSampler::new(10))));
+
+        for (status, marker, requests) in [
+            (StatusCode::OK, "/access-log-sampled", 100),
+            (StatusCode::INTERNAL_SERVER_ERROR, "/access-log-sampled-error", 20),
+        ] {
+            for _ in 0..requests {
+                let response = http::Response::builder()
+                    .status(status)
+                    .body(HttpBody::from_text("Hello, world!"))?;
+
+                let response = with_access_log(
+                    &config,
+                    response,
+                    &connection_info,
+                    Method::GET,
+                    marker.parse()?,
+                    &request_id("sampling"),
+                    Instant::now(),
+                );
+
+                response
+                    .into_body()
+                    .collect()
+                    .await?;
+            }
+        }
+
+        // One in ten successful requests is logged, every server error is
+        assert_eq!(captured_logs("/access-log-sampled ").len(), 10);
+        assert_eq!(captured_logs("/access-log-sampled-error ").len(), 20);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_access_log_sampling() -> Result<(), Box<dyn std::error::Error>> {
+        do_access_log_sampling().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_access_log_sampling() -> Result<(), Box<dyn std::error::Error>> {
+        do_access_log_sampling().await
+    }
+
+    async fn do_access_log_tags() -> Result<(), Box<dyn std::error::Error>> {
+        capture_logs();
+
+        let config = VirtualHostConfig::builder()
+            .root_directory("src/tests")
+            .enable_logging(true)
+            .log_format("%method %path %status %tags")
+            .build()?;
+
+        let response = crate::server::http::Response::builder()
+            .status(StatusCode::OK)
+            .text("Hello, world!")
+            .log_tag("user", "42")
+            .log_tag("route", "hello")
+            .into_inner();
+
+        let response = with_access_log(
+            &config,
+            response,
+            &connection_info(None),
+            Method::GET,
+            "/access-log-tags".parse()?,
+            &request_id("tags"),
+            Instant::now(),
+        );
+
+        response
+            .into_body()
+            .collect()
+            .await?;
+
+        assert_eq!(
+            captured_logs("/access-log-tags"),
+            vec!["GET /access-log-tags 200 user=42 route=hello".to_string()]
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_access_log_tags() -> Result<(), Box<dyn std::error::Error>> {
+        do_access_log_tags().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_access_log_tags() -> Result<(), Box<dyn std::error::Error>> {
+        do_access_log_tags().await
+    }
+}
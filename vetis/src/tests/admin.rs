@@ -0,0 +1,209 @@
+mod admin_tests {
+    use std::error::Error;
+
+    use deboa::{
+        errors::{DeboaError, ResponseError},
+        request,
+    };
+    use http::StatusCode;
+    #[cfg(feature = "smol-rt")]
+    use macro_rules_attribute::apply;
+    #[cfg(feature = "smol-rt")]
+    use smol_macros::test;
+
+    use crate::{
+        config::server::{
+            admin::AdminConfig,
+            virtual_host::{SecurityConfig, VirtualHostConfig},
+            ListenerConfig, ServerConfig,
+        },
+        errors::ConfigError,
+        server::virtual_host::{handler_fn, path::HandlerPath, VirtualHost},
+        tests::{default_protocol, CA_CERT, SERVER_CERT, SERVER_KEY},
+    };
+
+    #[test]
+    fn test_admin_config() -> Result<(), Box<dyn Error>> {
+        let admin_config = AdminConfig::builder()
+            .port(9900)
+            .build()?;
+        assert_eq!(admin_config.port(), 9900);
+        assert_eq!(admin_config.interface(), "127.0.0.1");
+
+        let admin_config = AdminConfig::builder().build();
+        assert_eq!(
+            admin_config.err(),
+            Some(ConfigError::Listener("Admin port is required".to_string()))
+        );
+
+        let server_config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9900)
+                    .build()?,
+            )
+            .admin(
+                AdminConfig::builder()
+                    .port(9900)
+                    .build()?,
+            )
+            .build();
+        assert_eq!(
+            server_config.err(),
+            Some(ConfigError::Server("Admin port 9900 is already used by a listener".to_string()))
+        );
+
+        Ok(())
+    }
+
+    async fn admin_server() -> Result<crate::Vetis, Box<dyn Error>> {
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .name("public")
+                    .port(9131)
+                    .protocol(default_protocol())
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9133)
+                    .protocol(default_protocol())
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .admin(
+                AdminConfig::builder()
+                    .port(9132)
+                    .build()?,
+            )
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+
+        for port in [9131, 9133] {
+            let security_config = SecurityConfig::builder()
+                .ca_cert_from_bytes(CA_CERT.to_vec())
+                .cert_from_bytes(SERVER_CERT.to_vec())
+                .key_from_bytes(SERVER_KEY.to_vec())
+                .build()?;
+
+            let host_config = VirtualHostConfig::builder()
+                .hostname("localhost")
+                .port(port)
+                .root_directory("src/tests")
+                .security(security_config)
+                .build()?;
+
+            let mut virtual_host = VirtualHost::new(host_config);
+            virtual_host.add_path(
+                HandlerPath::builder()
+                    .uri("/hello")
+                    .handler(handler_fn(|_request| async move {
+                        Ok(crate::server::http::Response::builder()
+                            .status(StatusCode::OK)
+                            .text("Hello"))
+                    }))
+                    .build()?,
+            );
+
+            server
+                .add_virtual_host(virtual_host)
+                .await;
+        }
+
+        Ok(server)
+    }
+
+    async fn do_admin_endpoints() -> Result<(), Box<dyn Error>> {
+        let mut server = admin_server().await?;
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::default();
+
+        let response = request::get("http://localhost:9132/admin/vhosts")?
+            .send_with(&client)
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let vhosts: serde_json::Value = serde_json::from_str(
+            &response
+                .text()
+                .await?,
+        )?;
+        assert_eq!(
+            vhosts,
+            serde_json::json!([
+                {
+                    "hostname": "localhost",
+                    "port": 9131,
+                    "secure": true,
+                    "paths": [{ "uri": "/hello", "kind": "handler" }]
+                },
+                {
+                    "hostname": "localhost",
+                    "port": 9133,
+                    "secure": true,
+                    "paths": [{ "uri": "/hello", "kind": "handler" }]
+                }
+            ])
+        );
+
+        // The admin endpoints also answer when addressed by IP
+        let response = request::get("http://127.0.0.1:9132/admin/metrics")?
+            .send_with(&client)
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let metrics: serde_json::Value = serde_json::from_str(
+            &response
+                .text()
+                .await?,
+        )?;
+        assert_eq!(metrics[0]["name"], "public");
+        assert_eq!(metrics[0]["port"], 9131);
+        assert_eq!(metrics[0]["active_connections"], 0);
+        assert_eq!(metrics[1]["name"], serde_json::Value::Null);
+        assert_eq!(metrics[1]["port"], 9133);
+
+        let response = request::post("http://localhost:9132/admin/vhosts")?
+            .send_with(&client)
+            .await;
+        assert!(matches!(
+            response.err(),
+            Some(DeboaError::Response(ResponseError::Receive { status_code, .. }))
+                if status_code == StatusCode::METHOD_NOT_ALLOWED
+        ));
+
+        let response = request::post("http://localhost:9132/admin/shutdown")?
+            .send_with(&client)
+            .await?;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        server
+            .shutdown_requested()
+            .await;
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_admin_endpoints() -> Result<(), Box<dyn Error>> {
+        do_admin_endpoints().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_admin_endpoints() -> Result<(), Box<dyn Error>> {
+        do_admin_endpoints().await
+    }
+}
@@ -0,0 +1,269 @@
+mod compression_tests {
+    use std::io::Read;
+
+    use http::StatusCode;
+    use http_body_util::BodyExt;
+    use hyper_body_utils::HttpBody;
+    #[cfg(feature = "smol-rt")]
+    use macro_rules_attribute::apply;
+    #[cfg(feature = "smol-rt")]
+    use smol_macros::test;
+
+    #[cfg(feature = "static-files")]
+    use crate::{
+        config::server::virtual_host::path::static_files::StaticPathConfig,
+        server::virtual_host::path::static_files::StaticPath,
+    };
+    use crate::{
+        config::server::virtual_host::VirtualHostConfig,
+        server::{
+            http::Request,
+            virtual_host::{
+                compression::{negotiate, ContentEncoding},
+                handler_fn, VirtualHost,
+            },
+        },
+    };
+
+    const HTML: &str = "<html><body><p>Compressed, compressed, compressed!</p></body></html>";
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(negotiate("br, gzip"), Some(ContentEncoding::Brotli));
+        assert_eq!(negotiate("gzip;q=1.0, br;q=0.5"), Some(ContentEncoding::Brotli));
+        assert_eq!(negotiate("*"), Some(ContentEncoding::Brotli));
+        assert_eq!(negotiate("br;q=0, gzip"), None);
+        assert_eq!(negotiate("*;q=0"), None);
+        assert_eq!(negotiate("identity"), None);
+        assert_eq!(negotiate(""), None);
+    }
+
+    async fn do_brotli_response() -> Result<(), Box<dyn std::error::Error>> {
+        let config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .root_directory("src/tests")
+            .build()?;
+
+        let virtual_host = VirtualHost::with_handler(
+            config,
+            handler_fn(|_request| async move {
+                Ok(crate::server::http::Response::builder()
+                    .status(StatusCode::OK)
+                    .header(
+                        http::header::CONTENT_TYPE,
+                        http::HeaderValue::from_static("text/html; charset=utf-8"),
+                    )
+                    .text(HTML))
+            }),
+        );
+
+        let request = http::Request::builder()
+            .uri("/")
+            .header(http::header::ACCEPT_ENCODING, "br, gzip")
+            .body(HttpBody::from_text(""))?;
+
+        let (parts, body) = request.into_parts();
+
+        let response = virtual_host
+            .route(Request::from_parts(parts, body))
+            .await?;
+
+        let (parts, body) = response
+            .into_inner()
+            .into_parts();
+
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(
+            parts
+                .headers
+                .get(http::header::CONTENT_ENCODING),
+            Some(&http::HeaderValue::from_static("br"))
+        );
+
+        let compressed = body
+            .collect()
+            .await?
+            .to_bytes();
+
+        let mut decompressed = String::new();
+        brotli::Decompressor::new(compressed.as_ref(), 4096).read_to_string(&mut decompressed)?;
+
+        assert_eq!(decompressed, HTML);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_brotli_response() -> Result<(), Box<dyn std::error::Error>> {
+        do_brotli_response().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_brotli_response() -> Result<(), Box<dyn std::error::Error>> {
+        do_brotli_response().await
+    }
+
+    async fn do_uncompressed_responses() -> Result<(), Box<dyn std::error::Error>> {
+        let config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .root_directory("src/tests")
+            .max_compressed_body_size(HTML.len() - 1)
+            .build()?;
+
+        let virtual_host = VirtualHost::with_handler(
+            config,
+            handler_fn(|request| async move {
+                let body = if request.uri().path() == "/small" { &HTML[..16] } else { HTML };
+                Ok(crate::server::http::Response::builder()
+                    .status(StatusCode::OK)
+                    .header(
+                        http::header::CONTENT_TYPE,
+                        http::HeaderValue::from_static("text/html; charset=utf-8"),
+                    )
+                    .text(body))
+            }),
+        );
+
+        let encoding_of = |method: http::Method, uri: &'static str| {
+            let virtual_host = &virtual_host;
+            async move {
+                let request = http::Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header(http::header::ACCEPT_ENCODING, "br")
+                    .body(HttpBody::from_text(""))?;
+
+                let (parts, body) = request.into_parts();
+
+                let response = virtual_host
+                    .route(Request::from_parts(parts, body))
+                    .await?;
+
+                Ok::<_, Box<dyn std::error::Error>>(
+                    response
+                        .into_inner()
+                        .headers()
+                        .get(http::header::CONTENT_ENCODING)
+                        .cloned(),
+                )
+            }
+        };
+
+        assert_eq!(
+            encoding_of(http::Method::GET, "/small").await?,
+            Some(http::HeaderValue::from_static("br"))
+        );
+        assert_eq!(
+            encoding_of(http::Method::HEAD, "/small").await?,
+            Some(http::HeaderValue::from_static("br"))
+        );
+        assert_eq!(encoding_of(http::Method::GET, "/").await?, None);
+        assert_eq!(encoding_of(http::Method::HEAD, "/").await?, None);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_uncompressed_responses() -> Result<(), Box<dyn std::error::Error>> {
+        do_uncompressed_responses().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_uncompressed_responses() -> Result<(), Box<dyn std::error::Error>> {
+        do_uncompressed_responses().await
+    }
+
+    async fn do_head_compressed_headers() -> Result<(), Box<dyn std::error::Error>> {
+        let config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .root_directory("src/tests")
+            .build()?;
+
+        #[cfg_attr(not(feature = "static-files"), allow(unused_mut))]
+        let mut virtual_host = VirtualHost::with_handler(
+            config,
+            handler_fn(|_request| async move {
+                Ok(crate::server::http::Response::builder()
+                    .status(StatusCode::OK)
+                    .header(
+                        http::header::CONTENT_TYPE,
+                        http::HeaderValue::from_static("text/html; charset=utf-8"),
+                    )
+                    .text(HTML))
+            }),
+        );
+
+        #[cfg(feature = "static-files")]
+        virtual_host.add_path(StaticPath::new(
+            StaticPathConfig::builder()
+                .uri("/static")
+                .directory("src/tests/files")
+                .extensions("\\.(html)$")
+                .build()?,
+        ));
+
+        let headers_of = |method: http::Method, uri: &'static str| {
+            let virtual_host = &virtual_host;
+            async move {
+                let request = http::Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header(http::header::ACCEPT_ENCODING, "br")
+                    .body(HttpBody::from_text(""))?;
+
+                let (parts, body) = request.into_parts();
+
+                let response = virtual_host
+                    .route(Request::from_parts(parts, body))
+                    .await?;
+
+                Ok::<_, Box<dyn std::error::Error>>(
+                    response
+                        .into_inner()
+                        .headers()
+                        .clone(),
+                )
+            }
+        };
+
+        // Handlers answering HEAD like GET send the headers of the compressed GET response
+        let get = headers_of(http::Method::GET, "/").await?;
+        let head = headers_of(http::Method::HEAD, "/").await?;
+        for name in
+            [http::header::CONTENT_ENCODING, http::header::VARY, http::header::CONTENT_LENGTH]
+        {
+            assert!(get.contains_key(&name), "{}", name);
+            assert_eq!(head.get(&name), get.get(&name), "{}", name);
+        }
+
+        // Files answer HEAD without reading them, so the compressed length is left out
+        #[cfg(feature = "static-files")]
+        {
+            let get = headers_of(http::Method::GET, "/static/index.html").await?;
+            let head = headers_of(http::Method::HEAD, "/static/index.html").await?;
+            for name in [http::header::CONTENT_ENCODING, http::header::VARY] {
+                assert!(get.contains_key(&name), "{}", name);
+                assert_eq!(head.get(&name), get.get(&name), "{}", name);
+            }
+            assert!(!head.contains_key(http::header::CONTENT_LENGTH));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_head_compressed_headers() -> Result<(), Box<dyn std::error::Error>> {
+        do_head_compressed_headers().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_head_compressed_headers() -> Result<(), Box<dyn std::error::Error>> {
+        do_head_compressed_headers().await
+    }
+}
@@ -1,9 +1,11 @@
-use std::error::Error;
+use std::{error::Error, time::Duration};
 
 use crate::{
     config::server::{
+        acl::{AclConfig, Cidr},
+        http3::Http3Config,
         virtual_host::{SecurityConfig, VirtualHostConfig},
-        ListenerConfig, Protocol, ServerConfig,
+        EmptyHostsPolicy, ListenerConfig, Protocol, ServerConfig,
     },
     errors::{ConfigError, VetisError},
 };
@@ -29,6 +31,330 @@ fn test_listener_config() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_listener_name() -> Result<(), Box<dyn Error>> {
+    let listener_config = ListenerConfig::builder()
+        .port(8080)
+        .name("public")
+        .build()?;
+    assert_eq!(listener_config.name(), &Some("public".to_string()));
+
+    let listener_config = ListenerConfig::builder()
+        .port(8080)
+        .build()?;
+    assert_eq!(listener_config.name(), &None);
+
+    let listener_config = ListenerConfig::builder()
+        .port(8080)
+        .name("")
+        .build();
+    assert_eq!(
+        listener_config.err(),
+        Some(ConfigError::Listener("Name cannot be empty".to_string()))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_listener_max_concurrent_connections() -> Result<(), Box<dyn Error>> {
+    let listener_config = ListenerConfig::builder()
+        .port(8080)
+        .max_concurrent_connections(16)
+        .build()?;
+    assert_eq!(listener_config.max_concurrent_connections(), Some(16));
+
+    let listener_config = ListenerConfig::builder()
+        .port(8080)
+        .max_concurrent_connections(0)
+        .build();
+    assert_eq!(
+        listener_config.err(),
+        Some(ConfigError::Listener("Max concurrent connections cannot be 0".to_string()))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_listener_header_limits() -> Result<(), Box<dyn Error>> {
+    let listener_config = ListenerConfig::builder()
+        .port(8080)
+        .max_headers(32)
+        .max_header_bytes(8192)
+        .build()?;
+    assert_eq!(listener_config.max_headers(), Some(32));
+    assert_eq!(listener_config.max_header_bytes(), Some(8192));
+
+    let listener_config = ListenerConfig::builder()
+        .port(8080)
+        .max_header_bytes(0)
+        .build();
+    assert_eq!(
+        listener_config.err(),
+        Some(ConfigError::Listener("Max header bytes cannot be 0".to_string()))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_listener_tcp_keepalive() -> Result<(), Box<dyn Error>> {
+    let listener_config = ListenerConfig::builder()
+        .port(8080)
+        .build()?;
+    assert_eq!(listener_config.tcp_keepalive(), None);
+
+    let listener_config = ListenerConfig::builder()
+        .port(8080)
+        .tcp_keepalive(Duration::from_secs(60))
+        .tcp_keepalive_interval(Duration::from_secs(10))
+        .tcp_keepalive_retries(4)
+        .build()?;
+    assert_eq!(listener_config.tcp_keepalive(), Some(Duration::from_secs(60)));
+    assert_eq!(listener_config.tcp_keepalive_interval(), Some(Duration::from_secs(10)));
+    assert_eq!(listener_config.tcp_keepalive_retries(), Some(4));
+
+    let listener_config = ListenerConfig::builder()
+        .port(8080)
+        .tcp_keepalive_retries(4)
+        .build();
+    assert_eq!(
+        listener_config.err(),
+        Some(ConfigError::Listener(
+            "TCP keepalive interval and retries require tcp_keepalive".to_string()
+        ))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_listener_log_sample_rate() -> Result<(), Box<dyn Error>> {
+    let listener_config = ListenerConfig::builder()
+        .port(8080)
+        .build()?;
+    assert_eq!(listener_config.log_sample_rate(), None);
+
+    let listener_config = ListenerConfig::builder()
+        .port(8080)
+        .log_sample_rate(100)
+        .build()?;
+    assert_eq!(listener_config.log_sample_rate(), Some(100));
+
+    let listener_config = ListenerConfig::builder()
+        .port(8080)
+        .log_sample_rate(0)
+        .build();
+    assert_eq!(
+        listener_config.err(),
+        Some(ConfigError::Listener("Log sample rate cannot be 0".to_string()))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_listener_config_from_file() -> Result<(), Box<dyn Error>> {
+    let listener_config: ListenerConfig = serde_yaml_ng::from_str(
+        "port: 8080\nprotocol: Http1\ninterface: 127.0.0.1\nlog_sample_rate: 100\n",
+    )?;
+    assert_eq!(listener_config.log_sample_rate(), Some(100));
+    assert!(listener_config.tcp_nodelay());
+
+    let listener_config = serde_yaml_ng::from_str::<ListenerConfig>(
+        "port: 8080\nprotocol: Http1\ninterface: 127.0.0.1\nlog_sample_rate: 0\n",
+    );
+    assert!(listener_config
+        .err()
+        .is_some_and(|e| e
+            .to_string()
+            .contains("Log sample rate cannot be 0")));
+
+    Ok(())
+}
+
+#[test]
+fn test_http3_config() -> Result<(), Box<dyn Error>> {
+    let http3_config = Http3Config::builder()
+        .max_idle_timeout(Duration::from_secs(10))
+        .max_concurrent_bidi_streams(32)
+        .receive_window(1024 * 1024)
+        .build()?;
+    assert_eq!(http3_config.max_idle_timeout(), Some(Duration::from_secs(10)));
+    assert_eq!(http3_config.max_concurrent_bidi_streams(), Some(32));
+    assert_eq!(http3_config.receive_window(), Some(1024 * 1024));
+
+    let listener_config = ListenerConfig::builder()
+        .port(8080)
+        .http3(http3_config)
+        .build()?;
+    assert!(listener_config
+        .http3()
+        .is_some());
+
+    let http3_config = Http3Config::builder()
+        .max_idle_timeout(Duration::ZERO)
+        .build();
+    assert_eq!(
+        http3_config.err(),
+        Some(ConfigError::Listener("Max idle timeout cannot be 0".to_string()))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_virtual_host_max_uri_length() -> Result<(), Box<dyn Error>> {
+    let virtual_host_config = VirtualHostConfig::builder()
+        .root_directory("src/tests")
+        .max_uri_length(2048)
+        .build()?;
+    assert_eq!(virtual_host_config.max_uri_length(), Some(2048));
+
+    let virtual_host_config = VirtualHostConfig::builder()
+        .root_directory("src/tests")
+        .max_uri_length(0)
+        .build();
+    assert!(virtual_host_config.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_virtual_host_allowed_methods() -> Result<(), Box<dyn Error>> {
+    let virtual_host_config = VirtualHostConfig::builder()
+        .root_directory("src/tests")
+        .allowed_methods(&["GET", "HEAD"])
+        .build()?;
+    assert_eq!(
+        virtual_host_config.allowed_methods(),
+        &Some(vec!["GET".to_string(), "HEAD".to_string()])
+    );
+    assert!(!virtual_host_config.allow_trace());
+
+    let virtual_host_config = VirtualHostConfig::builder()
+        .root_directory("src/tests")
+        .allowed_methods(&["GET", "NOT A METHOD"])
+        .build();
+    assert_eq!(
+        virtual_host_config.err(),
+        Some(VetisError::Config(ConfigError::VirtualHost(
+            "Invalid allowed method: NOT A METHOD".to_string()
+        )))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_virtual_host_base_path() -> Result<(), Box<dyn Error>> {
+    let virtual_host_config = VirtualHostConfig::builder()
+        .root_directory("src/tests")
+        .base_path("/app/")
+        .build()?;
+    assert_eq!(virtual_host_config.base_path(), &Some("/app".to_string()));
+
+    let virtual_host_config = VirtualHostConfig::builder()
+        .root_directory("src/tests")
+        .base_path("app")
+        .build();
+    assert_eq!(
+        virtual_host_config.err(),
+        Some(VetisError::Config(ConfigError::VirtualHost(
+            "Base path must start with /: app".to_string()
+        )))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_virtual_host_max_concurrent_requests() -> Result<(), Box<dyn Error>> {
+    let virtual_host_config = VirtualHostConfig::builder()
+        .root_directory("src/tests")
+        .max_concurrent_requests(8)
+        .build()?;
+    assert_eq!(virtual_host_config.max_concurrent_requests(), Some(8));
+
+    let virtual_host_config = VirtualHostConfig::builder()
+        .root_directory("src/tests")
+        .max_concurrent_requests(0)
+        .build();
+    assert_eq!(
+        virtual_host_config.err(),
+        Some(VetisError::Config(ConfigError::VirtualHost(
+            "Max concurrent requests cannot be 0".to_string()
+        )))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_virtual_host_require_tls() -> Result<(), Box<dyn Error>> {
+    let virtual_host_config = VirtualHostConfig::builder()
+        .root_directory("src/tests")
+        .build()?;
+    assert!(!virtual_host_config.require_tls());
+
+    let virtual_host_config = VirtualHostConfig::builder()
+        .root_directory("src/tests")
+        .require_tls(true)
+        .build();
+    assert_eq!(
+        virtual_host_config.err(),
+        Some(VetisError::Config(ConfigError::VirtualHost(
+            "Require TLS needs a security configuration".to_string()
+        )))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_acl_config() -> Result<(), Box<dyn Error>> {
+    let acl = AclConfig::builder()
+        .allow("127.0.0.1/32")
+        .allow("fd00::/8")
+        .deny("0.0.0.0/0")
+        .deny("::/0")
+        .build()?;
+
+    assert!(acl.is_allowed("127.0.0.1".parse()?));
+    assert!(acl.is_allowed("::ffff:127.0.0.1".parse()?));
+    assert!(acl.is_allowed("fd12::1".parse()?));
+    assert!(!acl.is_allowed("127.0.0.2".parse()?));
+    assert!(!acl.is_allowed("10.1.2.3".parse()?));
+    assert!(!acl.is_allowed("::1".parse()?));
+
+    let acl = AclConfig::builder()
+        .deny("192.168.0.0/16")
+        .build()?;
+
+    assert!(acl.is_allowed("10.0.0.1".parse()?));
+    assert!(!acl.is_allowed("192.168.10.1".parse()?));
+
+    let cidr: Cidr = "::1".parse()?;
+    assert_eq!(cidr.prefix(), 128);
+
+    assert!("10.0.0.0/33"
+        .parse::<Cidr>()
+        .is_err());
+    assert!("not-an-ip/8"
+        .parse::<Cidr>()
+        .is_err());
+    assert_eq!(
+        AclConfig::builder()
+            .allow("::/129")
+            .build()
+            .err(),
+        Some(ConfigError::Acl("Invalid prefix length: ::/129".to_string()))
+    );
+
+    Ok(())
+}
+
 #[test]
 fn test_server_config() -> Result<(), Box<dyn Error>> {
     let server_config = ServerConfig::builder()
@@ -44,6 +370,33 @@ fn test_server_config() -> Result<(), Box<dyn Error>> {
             .len(),
         1
     );
+    assert_eq!(server_config.max_buffered_bytes(), None);
+    assert_eq!(server_config.empty_hosts(), EmptyHostsPolicy::NotFound);
+
+    let server_config = ServerConfig::builder()
+        .add_listener(
+            ListenerConfig::builder()
+                .port(8080)
+                .build()?,
+        )
+        .max_buffered_bytes(64 * 1024 * 1024)
+        .empty_hosts(EmptyHostsPolicy::StopListeners)
+        .build()?;
+    assert_eq!(server_config.max_buffered_bytes(), Some(64 * 1024 * 1024));
+    assert_eq!(server_config.empty_hosts(), EmptyHostsPolicy::StopListeners);
+
+    let server_config = ServerConfig::builder()
+        .add_listener(
+            ListenerConfig::builder()
+                .port(8080)
+                .build()?,
+        )
+        .max_buffered_bytes(0)
+        .build();
+    assert_eq!(
+        server_config.err(),
+        Some(ConfigError::Server("Max buffered bytes cannot be 0".to_string()))
+    );
 
     Ok(())
 }
@@ -61,6 +414,108 @@ fn test_security_config() -> Result<(), Box<dyn Error>> {
         Some(VetisError::Config(ConfigError::Security("Missing certificate".to_string())))
     );
 
+    let security_config = SecurityConfig::builder()
+        .cert_from_bytes(vec![1])
+        .key_from_bytes(vec![1])
+        .client_auth(true)
+        .build();
+
+    assert_eq!(
+        security_config.err(),
+        Some(VetisError::Config(ConfigError::Security(
+            "Client authentication requires a CA certificate".to_string()
+        )))
+    );
+
+    let security_config = SecurityConfig::builder()
+        .cert_from_bytes(vec![1])
+        .key_from_bytes(vec![1])
+        .allowed_client_names(vec!["vetis-client".to_string()])
+        .build();
+
+    assert_eq!(
+        security_config.err(),
+        Some(VetisError::Config(ConfigError::Security(
+            "Allowed client names require client authentication".to_string()
+        )))
+    );
+
+    let security_config = SecurityConfig::builder()
+        .cert_from_bytes(vec![1])
+        .key_from_bytes(vec![1])
+        .build()?;
+    assert!(security_config.session_resumption());
+    assert_eq!(security_config.session_cache_size(), 256);
+
+    let security_config = SecurityConfig::builder()
+        .cert_from_bytes(vec![1])
+        .key_from_bytes(vec![1])
+        .session_cache_size(0)
+        .build();
+
+    assert_eq!(
+        security_config.err(),
+        Some(VetisError::Config(ConfigError::Security(
+            "Session cache size cannot be 0".to_string()
+        )))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_security_config_from_file() -> Result<(), Box<dyn Error>> {
+    let virtual_host_config: VirtualHostConfig = serde_yaml_ng::from_str(
+        "hostname: localhost\nport: 8443\nroot_directory: src/tests\nenable_logging: false\nsecurity:\n  cert_from_file: src/tests/certs/server.der\n  key_from_file: src/tests/certs/server.key.der\n  session_resumption: false\n  session_cache_size: 1024\n",
+    )?;
+
+    let security_config = virtual_host_config
+        .security()
+        .as_ref()
+        .ok_or("missing security config")?;
+    assert!(!security_config.session_resumption());
+    assert_eq!(security_config.session_cache_size(), 1024);
+
+    Ok(())
+}
+
+#[test]
+fn test_virtual_host_config_from_file() -> Result<(), Box<dyn Error>> {
+    let host = "hostname: localhost\nport: 8080\nroot_directory: src/tests\nenable_logging: false\nsecurity:\n  cert_from_file: src/tests/certs/server.der\n  key_from_file: src/tests/certs/server.key.der\n";
+
+    let virtual_host_config: VirtualHostConfig =
+        serde_yaml_ng::from_str(&format!("{}base_path: /api/\n", host))?;
+    assert_eq!(virtual_host_config.base_path(), &Some("/api".to_string()));
+
+    for (setting, error) in [
+        ("max_uri_length: 0", "Max URI length cannot be 0"),
+        ("request_timeout: 0", "Request timeout cannot be 0"),
+        ("max_concurrent_requests: 0", "Max concurrent requests cannot be 0"),
+        ("allowed_methods: [GET, NOT A METHOD]", "Invalid allowed method: NOT A METHOD"),
+        ("base_path: api/", "Base path must start with /: api/"),
+    ] {
+        let virtual_host_config =
+            serde_yaml_ng::from_str::<VirtualHostConfig>(&format!("{}{}\n", host, setting));
+        assert!(
+            virtual_host_config
+                .err()
+                .is_some_and(|e| e
+                    .to_string()
+                    .contains(error)),
+            "{}",
+            setting
+        );
+    }
+
+    let virtual_host_config = serde_yaml_ng::from_str::<VirtualHostConfig>(
+        "hostname: localhost\nport: 8080\nroot_directory: src/tests\nenable_logging: false\nrequire_tls: true\n",
+    );
+    assert!(virtual_host_config
+        .err()
+        .is_some_and(|e| e
+            .to_string()
+            .contains("Require TLS needs a security configuration")));
+
     Ok(())
 }
 
@@ -157,3 +612,64 @@ mod auth_tests {
         Ok(())
     }
 }
+
+mod runtime_tests {
+    use crate::{config::runtime::RuntimeConfig, errors::ConfigError};
+
+    #[test]
+    fn test_runtime_config() -> Result<(), Box<dyn std::error::Error>> {
+        let runtime_config = RuntimeConfig::builder()
+            .worker_threads(3)
+            .max_blocking_threads(8)
+            .build()?;
+        assert_eq!(runtime_config.worker_threads(), Some(3));
+        assert_eq!(runtime_config.max_blocking_threads(), Some(8));
+
+        assert_eq!(
+            RuntimeConfig::builder()
+                .worker_threads(0)
+                .build()
+                .err(),
+            Some(ConfigError::Runtime("Worker threads cannot be 0".to_string()))
+        );
+        assert_eq!(
+            RuntimeConfig::builder()
+                .max_blocking_threads(0)
+                .build()
+                .err(),
+            Some(ConfigError::Runtime("Max blocking threads cannot be 0".to_string()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_runtime_config_from_file() -> Result<(), Box<dyn std::error::Error>> {
+        let runtime_config: RuntimeConfig = serde_yaml_ng::from_str("worker_threads: 2\n")?;
+        assert_eq!(runtime_config.worker_threads(), Some(2));
+        assert_eq!(runtime_config.max_blocking_threads(), None);
+
+        let runtime_config = serde_yaml_ng::from_str::<RuntimeConfig>("worker_threads: 0\n");
+        assert!(runtime_config
+            .err()
+            .is_some_and(|e| e
+                .to_string()
+                .contains("Worker threads cannot be 0")));
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[test]
+    fn test_runtime_worker_threads() -> Result<(), Box<dyn std::error::Error>> {
+        let runtime_config = RuntimeConfig::builder()
+            .worker_threads(3)
+            .build()?;
+
+        let num_workers = runtime_config.block_on(async {
+            tokio::runtime::Handle::current()
+                .metrics()
+                .num_workers()
+        })?;
+        assert_eq!(num_workers, 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+mod config_watch_tests {
+    use std::{error::Error, path::Path, time::Duration};
+
+    use deboa::{cert::Certificate, request};
+    use http::StatusCode;
+    #[cfg(feature = "smol-rt")]
+    use macro_rules_attribute::apply;
+    #[cfg(feature = "smol-rt")]
+    use smol_macros::test;
+
+    use crate::{
+        config::server::{
+            virtual_host::{SecurityConfig, VirtualHostConfig},
+            ListenerConfig, ServerConfig,
+        },
+        server::virtual_host::{handler_fn, path::HandlerPath, VirtualHost},
+        tests::{default_protocol, CA_CERT, SERVER_CERT, SERVER_KEY},
+        utils::time::sleep,
+    };
+
+    /// Renders a config file listing the virtual host on port 9160, serving files if asked.
+    fn write_config(path: &Path, static_files: bool) -> Result<(), Box<dyn Error>> {
+        let mut file = String::from(
+            "virtual_hosts:\n  - hostname: localhost\n    port: 9160\n    root_directory: src/tests\n    enable_logging: false\n    security:\n      cert_from_file: src/tests/certs/server.der\n      key_from_file: src/tests/certs/server.key.der\n      ca_cert_from_file: src/tests/certs/ca.der\n",
+        );
+        if static_files {
+            file.push_str(
+                "    static_paths:\n      - uri: /\n        extensions: .html\n        directory: src/tests/files\n",
+            );
+        }
+        std::fs::write(path, file)?;
+        Ok(())
+    }
+
+    /// Returns a client trusting the test CA.
+    ///
+    /// Each request gets its own, as a pooled connection the client gave up on
+    /// after an error status would keep failing the requests sent over it.
+    fn client() -> deboa::Client {
+        deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, deboa::cert::ContentEncoding::DER))
+            .build()
+    }
+
+    /// Returns the status of a request for the index file, if it was answered with a success.
+    async fn get_index() -> Option<StatusCode> {
+        request::get("https://localhost:9160/index.html")
+            .ok()?
+            .send_with(&client())
+            .await
+            .ok()
+            .map(|response| response.status())
+    }
+
+    /// Polls the index file until it is served, or gives up after a few seconds.
+    async fn wait_for_index() -> Option<StatusCode> {
+        for _ in 0..50 {
+            if let Some(status) = get_index().await {
+                return Some(status);
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+        None
+    }
+
+    async fn do_config_watch() -> Result<(), Box<dyn Error>> {
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9160)
+                    .protocol(default_protocol())
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+
+        // The host registered in code, also listed by the file
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9160)
+            .root_directory("src/tests")
+            .security(security_config)
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/hello")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Hello"))
+                }))
+                .build()?,
+        );
+
+        server
+            .add_virtual_host(virtual_host)
+            .await;
+
+        let directory =
+            std::env::temp_dir().join(format!("vetis-config-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&directory)?;
+        let config_file = directory.join("vetis.yaml");
+        write_config(&config_file, false)?;
+
+        server
+            .watch_config(&config_file)
+            .await?;
+
+        server
+            .start()
+            .await?;
+
+        // Hosts already registered are kept as they are when watching starts
+        let response = request::get("https://localhost:9160/hello")?
+            .send_with(&client())
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(get_index().await, None);
+
+        // A changed entry replaces the host
+        write_config(&config_file, true)?;
+        assert_eq!(wait_for_index().await, Some(StatusCode::OK));
+        let response = request::get("https://localhost:9160/hello")?
+            .send_with(&client())
+            .await;
+        assert!(response.is_err());
+
+        // An invalid file keeps the current hosts
+        std::fs::write(&config_file, "virtual_hosts: [")?;
+        sleep(Duration::from_millis(500)).await;
+        assert_eq!(get_index().await, Some(StatusCode::OK));
+
+        server
+            .stop()
+            .await?;
+
+        std::fs::remove_dir_all(&directory)?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_config_watch() -> Result<(), Box<dyn Error>> {
+        do_config_watch().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_config_watch() -> Result<(), Box<dyn Error>> {
+        do_config_watch().await
+    }
+}
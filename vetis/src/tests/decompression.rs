@@ -0,0 +1,255 @@
+mod decompression_tests {
+    use std::{io::Write, sync::Arc};
+
+    use http::StatusCode;
+    use http_body_util::BodyExt;
+    use hyper_body_utils::HttpBody;
+    #[cfg(feature = "smol-rt")]
+    use macro_rules_attribute::apply;
+    #[cfg(feature = "smol-rt")]
+    use smol_macros::test;
+
+    use crate::{
+        config::server::virtual_host::VirtualHostConfig,
+        errors::VetisError,
+        server::{
+            conn::budget::MemoryBudget,
+            http::Request,
+            virtual_host::{decompression::decompress_request, handler_fn, VirtualHost},
+        },
+    };
+
+    const JSON: &str = r#"{"message": "Compressed, compressed, compressed!"}"#;
+
+    fn gzip(body: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(body)?;
+        encoder.finish()
+    }
+
+    fn encoded_request(encoding: &str, body: &[u8]) -> Result<Request, http::Error> {
+        let request = http::Request::builder()
+            .method(http::Method::POST)
+            .uri("/")
+            .header(http::header::CONTENT_ENCODING, encoding)
+            .header(http::header::CONTENT_LENGTH, body.len())
+            .body(HttpBody::from_bytes(body))?;
+
+        let (parts, body) = request.into_parts();
+        Ok(Request::from_parts(parts, body))
+    }
+
+    fn echo_virtual_host(
+        max_decompressed_body_size: usize,
+    ) -> Result<VirtualHost, Box<dyn std::error::Error>> {
+        let config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .root_directory("src/tests")
+            .max_decompressed_body_size(max_decompressed_body_size)
+            .build()?;
+
+        Ok(VirtualHost::with_handler(
+            config,
+            handler_fn(|request: Request| async move {
+                let encoded = request
+                    .headers()
+                    .contains_key(http::header::CONTENT_ENCODING);
+                let (_, body) = request.into_parts();
+                let body = body
+                    .collect()
+                    .await
+                    .map_err(|e| VetisError::Body(e.to_string()))?
+                    .to_bytes();
+
+                let status = if encoded { StatusCode::BAD_REQUEST } else { StatusCode::OK };
+                Ok(crate::server::http::Response::builder()
+                    .status(status)
+                    .bytes(body))
+            }),
+        ))
+    }
+
+    async fn do_gzip_request() -> Result<(), Box<dyn std::error::Error>> {
+        let virtual_host = echo_virtual_host(1024)?;
+
+        let response = virtual_host
+            .route(encoded_request("gzip", &gzip(JSON.as_bytes())?)?)
+            .await?;
+
+        let (parts, body) = response
+            .into_inner()
+            .into_parts();
+
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(
+            body.collect()
+                .await?
+                .to_bytes(),
+            JSON.as_bytes()
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_gzip_request() -> Result<(), Box<dyn std::error::Error>> {
+        do_gzip_request().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_gzip_request() -> Result<(), Box<dyn std::error::Error>> {
+        do_gzip_request().await
+    }
+
+    async fn do_decompression_bomb() -> Result<(), Box<dyn std::error::Error>> {
+        let virtual_host = echo_virtual_host(64 * 1024)?;
+
+        // A megabyte of zeros compresses to about a kilobyte
+        let bomb = gzip(&vec![0; 1024 * 1024])?;
+        assert!(bomb.len() < 64 * 1024);
+
+        let response = virtual_host
+            .route(encoded_request("gzip", &bomb)?)
+            .await?;
+
+        assert_eq!(
+            response
+                .into_inner()
+                .status(),
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_decompression_bomb() -> Result<(), Box<dyn std::error::Error>> {
+        do_decompression_bomb().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_decompression_bomb() -> Result<(), Box<dyn std::error::Error>> {
+        do_decompression_bomb().await
+    }
+
+    async fn do_decompress_request_codings() -> Result<(), Box<dyn std::error::Error>> {
+        let mut deflate =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        deflate.write_all(JSON.as_bytes())?;
+        let deflate = deflate.finish()?;
+
+        let mut brotli = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+        brotli.write_all(JSON.as_bytes())?;
+        let brotli = brotli.into_inner();
+
+        // Codings are listed in the order they were applied
+        let gzip_then_brotli = {
+            let mut writer = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+            writer.write_all(&gzip(JSON.as_bytes())?)?;
+            writer.into_inner()
+        };
+
+        for (encoding, body) in
+            [("deflate", deflate), ("br", brotli), ("gzip, br", gzip_then_brotli)]
+        {
+            let request = decompress_request(encoded_request(encoding, &body)?, 1024).await?;
+
+            assert!(!request
+                .headers()
+                .contains_key(http::header::CONTENT_ENCODING));
+            assert_eq!(request.content_length(), Some(JSON.len() as u64));
+
+            let (_, body) = request.into_parts();
+            assert_eq!(
+                body.collect()
+                    .await?
+                    .to_bytes(),
+                JSON.as_bytes()
+            );
+        }
+
+        let request = decompress_request(encoded_request("zstd", b"")?, 1024).await;
+        assert_eq!(request.err(), Some(VetisError::UnsupportedEncoding("zstd".to_string())));
+
+        let request = decompress_request(encoded_request("gzip", b"not gzip")?, 1024).await;
+        assert!(matches!(request.err(), Some(VetisError::Body(_))));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_decompress_request_codings() -> Result<(), Box<dyn std::error::Error>> {
+        do_decompress_request_codings().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_decompress_request_codings() -> Result<(), Box<dyn std::error::Error>> {
+        do_decompress_request_codings().await
+    }
+
+    async fn do_memory_budget() -> Result<(), Box<dyn std::error::Error>> {
+        let memory_budget = Arc::new(MemoryBudget::new(1024));
+        let mut virtual_host = echo_virtual_host(64 * 1024)?;
+        virtual_host.set_memory_budget(Some(memory_budget.clone()));
+
+        // A slow client sends most of the budget, then stalls without finishing its body
+        let first_chunk = futures_util::stream::iter([Ok::<_, std::io::Error>(
+            hyper::body::Frame::data(bytes::Bytes::from(vec![0; 1000])),
+        )]);
+        let stalled = futures_util::StreamExt::chain(first_chunk, futures_util::stream::pending());
+        let request = http::Request::builder()
+            .method(http::Method::POST)
+            .uri("/")
+            .header(http::header::CONTENT_ENCODING, "gzip")
+            .body(HttpBody::Stream(http_body_util::StreamBody::new(stalled).boxed()))?;
+        let (parts, body) = request.into_parts();
+
+        let mut stalled_response = virtual_host.route(Request::from_parts(parts, body));
+        assert!(futures_util::poll!(&mut stalled_response).is_pending());
+        assert_eq!(memory_budget.used(), 1000);
+
+        let response = virtual_host
+            .route(encoded_request("gzip", &gzip(JSON.as_bytes())?)?)
+            .await?;
+        assert_eq!(
+            response
+                .into_inner()
+                .status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        drop(stalled_response);
+        assert_eq!(memory_budget.used(), 0);
+
+        let response = virtual_host
+            .route(encoded_request("gzip", &gzip(JSON.as_bytes())?)?)
+            .await?;
+        assert_eq!(
+            response
+                .into_inner()
+                .status(),
+            StatusCode::OK
+        );
+        assert_eq!(memory_budget.used(), 0);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_memory_budget() -> Result<(), Box<dyn std::error::Error>> {
+        do_memory_budget().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_memory_budget() -> Result<(), Box<dyn std::error::Error>> {
+        do_memory_budget().await
+    }
+}
@@ -0,0 +1,132 @@
+mod errors_tests {
+    use std::io;
+
+    use http::StatusCode;
+
+    use crate::errors::{
+        ConfigError, FileError, HandlerError, HttpError, StartError, VetisError, VirtualHostError,
+    };
+
+    #[test]
+    fn test_io_error_conversion() {
+        let error = VetisError::from(io::Error::new(io::ErrorKind::NotFound, "missing file"));
+
+        assert_eq!(error, VetisError::Io("missing file".to_string()));
+    }
+
+    #[test]
+    fn test_http_error_conversion() {
+        let error = http::Response::builder()
+            .status(1000)
+            .body(())
+            .map_err(VetisError::from)
+            .unwrap_err();
+
+        assert!(matches!(error, VetisError::Http(_)));
+    }
+
+    #[test]
+    fn test_http_error_response_status() {
+        let error = VetisError::from(HttpError::new(StatusCode::FORBIDDEN).body("nope"));
+
+        assert_eq!(error.status_code(), StatusCode::FORBIDDEN);
+    }
+
+    #[cfg(feature = "rustls")]
+    #[test]
+    fn test_rustls_error_conversion() {
+        let error = VetisError::from(rustls::Error::General("handshake failed".to_string()));
+
+        assert_eq!(
+            error,
+            VetisError::Start(StartError::Tls(
+                rustls::Error::General("handshake failed".to_string()).to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_question_mark_with_box_dyn_error() {
+        fn read_missing() -> Result<(), VetisError> {
+            std::fs::read("src/tests/does-not-exist")?;
+            Ok(())
+        }
+
+        let result: Result<(), Box<dyn std::error::Error>> = read_missing().map_err(Into::into);
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .starts_with("I/O error:"));
+    }
+
+    #[test]
+    fn test_status_code_mapping() {
+        let cases = [
+            (
+                VetisError::Config(ConfigError::Server("invalid".to_string())),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+            (VetisError::Bind("in use".to_string()), StatusCode::INTERNAL_SERVER_ERROR),
+            (
+                VetisError::Start(StartError::Tls("no cert".to_string())),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+            (VetisError::Stop("stuck".to_string()), StatusCode::INTERNAL_SERVER_ERROR),
+            (VetisError::Handler("failed".to_string()), StatusCode::INTERNAL_SERVER_ERROR),
+            (VetisError::Tls("bad key".to_string()), StatusCode::INTERNAL_SERVER_ERROR),
+            (VetisError::NoInstances, StatusCode::SERVICE_UNAVAILABLE),
+            (VetisError::Io("broken pipe".to_string()), StatusCode::INTERNAL_SERVER_ERROR),
+            (VetisError::Http("invalid header".to_string()), StatusCode::INTERNAL_SERVER_ERROR),
+            (VetisError::Body("truncated".to_string()), StatusCode::BAD_REQUEST),
+            (VetisError::Timeout, StatusCode::GATEWAY_TIMEOUT),
+            (VetisError::PayloadTooLarge, StatusCode::PAYLOAD_TOO_LARGE),
+            (
+                VetisError::UnsupportedEncoding("zstd".to_string()),
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ),
+            (VetisError::UpstreamUnavailable, StatusCode::SERVICE_UNAVAILABLE),
+            (VetisError::VirtualHost(VirtualHostError::NoVirtualHosts), StatusCode::NOT_FOUND),
+            (
+                VetisError::VirtualHost(VirtualHostError::Handler(HandlerError::Uri(
+                    "empty".to_string(),
+                ))),
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                VetisError::VirtualHost(VirtualHostError::Handler(HandlerError::Handler(
+                    "failed".to_string(),
+                ))),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+            (
+                VetisError::VirtualHost(VirtualHostError::File(FileError::NotFound)),
+                StatusCode::NOT_FOUND,
+            ),
+            (
+                VetisError::VirtualHost(VirtualHostError::File(FileError::InvalidMetadata)),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+            (
+                VetisError::VirtualHost(VirtualHostError::File(FileError::InvalidRange)),
+                StatusCode::RANGE_NOT_SATISFIABLE,
+            ),
+            (
+                VetisError::VirtualHost(VirtualHostError::Proxy("refused".to_string())),
+                StatusCode::BAD_GATEWAY,
+            ),
+            (
+                VetisError::VirtualHost(VirtualHostError::Interface("crashed".to_string())),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+            (
+                VetisError::VirtualHost(VirtualHostError::Auth("denied".to_string())),
+                StatusCode::UNAUTHORIZED,
+            ),
+        ];
+
+        for (error, status) in cases {
+            assert_eq!(error.status_code(), status, "{}", error);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+mod metrics_tests {
+    use std::sync::Arc;
+
+    use http::StatusCode;
+    use http_body_util::BodyExt;
+    use hyper_body_utils::HttpBody;
+    #[cfg(feature = "smol-rt")]
+    use macro_rules_attribute::apply;
+    #[cfg(feature = "smol-rt")]
+    use smol_macros::test;
+
+    use crate::server::conn::metrics::ListenerMetrics;
+
+    #[test]
+    fn test_request_gauge() {
+        let metrics = Arc::new(ListenerMetrics::default());
+
+        let requests: Vec<_> = (0..3)
+            .map(|_| metrics.track_request())
+            .collect();
+
+        assert_eq!(metrics.in_flight_requests(), 3);
+
+        drop(requests);
+
+        assert_eq!(metrics.in_flight_requests(), 0);
+    }
+
+    #[cfg(feature = "http3")]
+    #[test]
+    fn test_connection_streams() {
+        let metrics = Arc::new(ListenerMetrics::default());
+
+        let mut first = metrics
+            .track_connection(None)
+            .unwrap();
+        let mut second = metrics
+            .track_connection(None)
+            .unwrap();
+
+        first.add_stream();
+        first.add_stream();
+        second.add_stream();
+
+        assert_eq!(metrics.active_connections(), 2);
+        assert_eq!(first.streams(), 2);
+        assert_eq!(second.streams(), 1);
+        assert_eq!(metrics.total_streams(), 3);
+
+        drop(first);
+
+        assert_eq!(metrics.active_connections(), 1);
+        assert_eq!(metrics.total_streams(), 3);
+    }
+
+    #[test]
+    fn test_max_concurrent_connections() {
+        let metrics = Arc::new(ListenerMetrics::default());
+
+        let first = metrics.track_connection(Some(2));
+        let second = metrics.track_connection(Some(2));
+        let third = metrics.track_connection(Some(2));
+
+        assert!(first.is_some());
+        assert!(second.is_some());
+        assert!(third.is_none());
+        assert_eq!(metrics.active_connections(), 2);
+
+        drop(first);
+
+        assert!(metrics
+            .track_connection(Some(2))
+            .is_some());
+    }
+
+    #[test]
+    fn test_refused_connections() {
+        let metrics = ListenerMetrics::default();
+
+        // The first refusal is reported right away, the next ones wait for the following warning
+        assert_eq!(metrics.refuse_connection(), Some(1));
+        assert_eq!(metrics.refuse_connection(), None);
+        assert_eq!(metrics.refuse_connection(), None);
+        assert_eq!(metrics.refused_connections(), 3);
+    }
+
+    async fn do_bytes_sent() -> Result<(), Box<dyn std::error::Error>> {
+        let metrics = Arc::new(ListenerMetrics::default());
+
+        let response = http::Response::builder()
+            .status(StatusCode::OK)
+            .body(HttpBody::from_text("Hello, world!"))?;
+
+        let response = metrics.count_response(response);
+        assert_eq!(metrics.bytes_sent(), 0);
+
+        let body = response
+            .into_body()
+            .collect()
+            .await?
+            .to_bytes();
+
+        assert_eq!(body.as_ref(), b"Hello, world!");
+        assert_eq!(metrics.bytes_sent(), 13);
+
+        // A response dropped before being sent adds nothing
+        let response = http::Response::builder()
+            .status(StatusCode::OK)
+            .body(HttpBody::from_text("Never sent"))?;
+
+        drop(metrics.count_response(response));
+        assert_eq!(metrics.bytes_sent(), 13);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_bytes_sent() -> Result<(), Box<dyn std::error::Error>> {
+        do_bytes_sent().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_bytes_sent() -> Result<(), Box<dyn std::error::Error>> {
+        do_bytes_sent().await
+    }
+}
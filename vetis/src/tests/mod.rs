@@ -10,6 +10,12 @@ pub(crate) const SERVER_KEY: &[u8] = include_bytes!("certs/server.key.der");
 pub(crate) const IP6_SERVER_CERT: &[u8] = include_bytes!("certs/ip6-server.der");
 pub(crate) const IP6_SERVER_KEY: &[u8] = include_bytes!("certs/ip6-server.key.der");
 
+pub(crate) const CLIENT_CERT: &[u8] = include_bytes!("certs/client.der");
+pub(crate) const CLIENT_KEY: &[u8] = include_bytes!("certs/client.key.der");
+
+pub(crate) const GUEST_CERT: &[u8] = include_bytes!("certs/guest.der");
+pub(crate) const GUEST_KEY: &[u8] = include_bytes!("certs/guest.key.der");
+
 pub(crate) const fn default_protocol() -> Protocol {
     #[cfg(feature = "http1")]
     {
@@ -25,10 +31,88 @@ pub(crate) const fn default_protocol() -> Protocol {
     }
 }
 
+#[cfg(test)]
+static LOG_LINES: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+
+#[cfg(test)]
+struct CapturingLogger;
+
+#[cfg(test)]
+impl log::Log for CapturingLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        // Debug and trace messages are only kept from vetis itself
+        metadata.level() <= log::Level::Info
+            || metadata
+                .target()
+                .starts_with("vetis")
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        LOG_LINES
+            .lock()
+            .unwrap()
+            .push(
+                record
+                    .args()
+                    .to_string(),
+            );
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs a logger recording info messages and vetis traces, shared by every test of the process.
+#[cfg(test)]
+pub(crate) fn capture_logs() {
+    static LOGGER: CapturingLogger = CapturingLogger;
+    static INIT: std::sync::Once = std::sync::Once::new();
+
+    INIT.call_once(|| {
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Trace);
+    });
+}
+
+/// Returns the captured log lines containing `marker`.
+#[cfg(test)]
+pub(crate) fn captured_logs(marker: &str) -> Vec<String> {
+    LOG_LINES
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|line| line.contains(marker))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod access_log;
+#[cfg(all(test, feature = "admin"))]
+mod admin;
+#[cfg(all(test, feature = "compression-brotli"))]
+mod compression;
 #[cfg(test)]
 mod config;
+#[cfg(all(test, feature = "config-watch"))]
+mod config_watch;
+#[cfg(all(test, feature = "request-decompression"))]
+mod decompression;
+#[cfg(test)]
+mod errors;
+#[cfg(test)]
+mod metrics;
 #[cfg(test)]
 mod paths;
+#[cfg(all(test, feature = "proxy-protocol"))]
+mod proxy_protocol;
+#[cfg(test)]
+mod request;
+#[cfg(test)]
+mod response;
 #[cfg(test)]
 mod server;
 #[cfg(test)]
@@ -111,7 +111,8 @@ mod static_files {
     use crate::{
         config::server::{
             virtual_host::{
-                path::static_files::StaticPathConfig, SecurityConfig, VirtualHostConfig,
+                path::static_files::{DotfilePolicy, StaticPathConfig},
+                SecurityConfig, VirtualHostConfig,
             },
             ListenerConfig, ServerConfig,
         },
@@ -131,6 +132,7 @@ mod static_files {
         assert_eq!(path_config.uri(), "/test");
         assert_eq!(path_config.directory(), "./test");
         assert_eq!(path_config.extensions(), ".html");
+        assert!(path_config.accept_ranges());
 
         Ok(())
     }
@@ -333,19 +335,90 @@ mod static_files {
         do_not_found().await
     }
 
-    #[cfg(feature = "auth")]
-    async fn do_basic_auth(
-        username: Option<String>,
-        password: Option<String>,
-    ) -> Result<(), Box<dyn Error>> {
-        use crate::server::virtual_host::path::auth::{basic_auth::BasicAuth, AuthType};
+    async fn do_ranges_disabled() -> Result<(), Box<dyn Error>> {
+        let listener = ListenerConfig::builder()
+            .port(9101)
+            .protocol(default_protocol())
+            .interface("0.0.0.0")
+            .build()?;
 
-        let has_auth = username.is_some() && password.is_some();
+        let config = ServerConfig::builder()
+            .add_listener(listener)
+            .build()?;
 
-        let port = if has_auth { 9200 } else { 9201 };
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9101)
+            .root_directory("src/tests")
+            .security(security_config.clone())
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(StaticPath::new(
+            StaticPathConfig::builder()
+                .uri("/")
+                .directory("src/tests/files")
+                .accept_ranges(false)
+                .build()?,
+        ));
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, deboa::cert::ContentEncoding::DER))
+            .build();
+
+        let response = request::get("https://localhost:9101/index.html")?
+            .header(http::header::RANGE, "bytes=0-5")
+            .send_with(&client)
+            .await?;
+
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert!(!response
+            .headers()
+            .contains_key(http::header::ACCEPT_RANGES));
+        assert_eq!(response
+            .text()
+            .await?,
+            "<html>\n<head>\n  <title>\n    Tested!\n  </title>\n</head>\n<body>\n  <p>\n    Tested!\n  </p>\n</body>\n</html>");
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_ranges_disabled() -> Result<(), Box<dyn Error>> {
+        do_ranges_disabled().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_ranges_disabled() -> Result<(), Box<dyn Error>> {
+        do_ranges_disabled().await
+    }
+
+    async fn do_range_content_length() -> Result<(), Box<dyn Error>> {
+        const INDEX: &str = "<html>\n<head>\n  <title>\n    Tested!\n  </title>\n</head>\n<body>\n  <p>\n    Tested!\n  </p>\n</body>\n</html>";
 
         let listener = ListenerConfig::builder()
-            .port(port)
+            .port(9174)
             .protocol(default_protocol())
             .interface("0.0.0.0")
             .build()?;
@@ -362,29 +435,2272 @@ mod static_files {
 
         let host_config = VirtualHostConfig::builder()
             .hostname("localhost")
-            .port(port)
+            .port(9174)
             .root_directory("src/tests")
-            .security(security_config.clone())
+            .security(security_config)
             .build()?;
 
         let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(StaticPath::new(
+            StaticPathConfig::builder()
+                .uri("/")
+                .directory("src/tests/files")
+                .build()?,
+        ));
 
-        let auth_config = BasicAuthConfig::builder()
-            .htpasswd(Some("src/tests/files/.htpasswd".to_string()))
-            .cache_users()
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, deboa::cert::ContentEncoding::DER))
+            .build();
+
+        for (start, end) in [(0, 5), (7, 12), (20, 20)] {
+            let response = request::get("https://localhost:9174/index.html")?
+                .header(http::header::RANGE, &format!("bytes={}-{}", start, end))
+                .send_with(&client)
+                .await?;
+
+            assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+            assert_eq!(
+                response.headers()[http::header::CONTENT_LENGTH],
+                (end - start + 1).to_string()
+            );
+            let body = response
+                .text()
+                .await?;
+            assert_eq!(body.len(), end - start + 1);
+            assert_eq!(body, INDEX[start..=end]);
+        }
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_range_content_length() -> Result<(), Box<dyn Error>> {
+        do_range_content_length().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_range_content_length() -> Result<(), Box<dyn Error>> {
+        do_range_content_length().await
+    }
+
+    async fn do_mime_overrides() -> Result<(), Box<dyn Error>> {
+        let listener = ListenerConfig::builder()
+            .port(9102)
+            .protocol(default_protocol())
+            .interface("0.0.0.0")
+            .build()?;
+
+        let config = ServerConfig::builder()
+            .add_listener(listener)
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9102)
+            .root_directory("src/tests")
+            .security(security_config.clone())
             .build()?;
 
+        let mut mime_overrides = HashMap::new();
+        mime_overrides.insert("mjs".to_string(), "text/javascript".to_string());
+
+        let mut virtual_host = VirtualHost::new(host_config);
         virtual_host.add_path(StaticPath::new(
             StaticPathConfig::builder()
                 .uri("/")
+                .extensions(r"\.(html|mjs)$")
                 .directory("src/tests/files")
-                .auth(AuthType::Basic(BasicAuth::new(auth_config)))
+                .mime_overrides(mime_overrides)
                 .build()?,
         ));
 
         let mut server = crate::Vetis::new(config);
         server
-            .add_virtual_host(virtual_host)
+            .add_virtual_host(virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, deboa::cert::ContentEncoding::DER))
+            .build();
+
+        let response = request::get("https://localhost:9102/app.mjs")?
+            .send_with(&client)
+            .await?;
+
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(
+            response
+                .headers()
+                .get(http::header::CONTENT_TYPE),
+            Some(&http::HeaderValue::from_static("text/javascript"))
+        );
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_mime_overrides() -> Result<(), Box<dyn Error>> {
+        do_mime_overrides().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_mime_overrides() -> Result<(), Box<dyn Error>> {
+        do_mime_overrides().await
+    }
+
+    async fn get_static(
+        static_path: &StaticPath,
+        method: http::Method,
+        uri: &str,
+        range: Option<&str>,
+    ) -> Result<(http::response::Parts, String), Box<dyn Error>> {
+        let headers: Vec<(http::header::HeaderName, &str)> = range
+            .map(|range| (http::header::RANGE, range))
+            .into_iter()
+            .collect();
+
+        get_static_with(static_path, method, uri, &headers).await
+    }
+
+    async fn get_static_with(
+        static_path: &impl crate::server::virtual_host::path::Path,
+        method: http::Method,
+        uri: &str,
+        headers: &[(http::header::HeaderName, &str)],
+    ) -> Result<(http::response::Parts, String), Box<dyn Error>> {
+        use http_body_util::BodyExt;
+
+        use crate::server::http::Request;
+
+        let mut request = http::Request::builder()
+            .method(method)
+            .uri(uri);
+        for (name, value) in headers {
+            request = request.header(name, *value);
+        }
+        let (parts, body) = request
+            .body(hyper_body_utils::HttpBody::from_text(""))?
+            .into_parts();
+
+        let response = static_path
+            .handle(Request::from_parts(parts, body), std::sync::Arc::new(uri.to_string()))
+            .await?;
+        let (parts, body) = response
+            .into_inner()
+            .into_parts();
+        let body = body
+            .collect()
+            .await?
+            .to_bytes();
+
+        Ok((parts, String::from_utf8(body.to_vec())?))
+    }
+
+    async fn do_fallback_directories() -> Result<(), Box<dyn Error>> {
+        let static_path = StaticPath::new(
+            StaticPathConfig::builder()
+                .uri("/")
+                .extensions(".*")
+                .directory("src/tests/files/overrides")
+                .add_directory("src/tests/files")
+                .build()?,
+        );
+
+        let (parts, body) =
+            get_static(&static_path, http::Method::GET, "/index.html", None).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(body, "<html>\n<body>\n  <p>\n    Overridden!\n  </p>\n</body>\n</html>");
+
+        let (parts, body) = get_static(&static_path, http::Method::GET, "/app.mjs", None).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(body, "export const tested = true;\n");
+
+        let result = get_static(&static_path, http::Method::GET, "/missing.html", None).await;
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_fallback_directories() -> Result<(), Box<dyn Error>> {
+        do_fallback_directories().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_fallback_directories() -> Result<(), Box<dyn Error>> {
+        do_fallback_directories().await
+    }
+
+    async fn do_serve_dotfiles() -> Result<(), Box<dyn Error>> {
+        let static_path = |policy| {
+            StaticPathConfig::builder()
+                .uri("/")
+                .extensions(".*")
+                .directory("src/tests/files")
+                .serve_dotfiles(policy)
+                .build()
+                .map(StaticPath::new)
+        };
+
+        let config = StaticPathConfig::builder().build()?;
+        assert_eq!(config.serve_dotfiles(), DotfilePolicy::AllowWellKnown);
+
+        let static_path_well_known = static_path(DotfilePolicy::AllowWellKnown)?;
+        let result = get_static(&static_path_well_known, http::Method::GET, "/.env", None).await;
+        assert!(result.is_err());
+
+        let (parts, body) =
+            get_static(&static_path_well_known, http::Method::GET, "/.well-known/x", None).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(body, "acme-challenge-token\n");
+
+        let static_path_deny = static_path(DotfilePolicy::Deny)?;
+        let result = get_static(&static_path_deny, http::Method::GET, "/.well-known/x", None).await;
+        assert!(result.is_err());
+
+        let static_path_allow = static_path(DotfilePolicy::Allow)?;
+        let (parts, body) =
+            get_static(&static_path_allow, http::Method::GET, "/.env", None).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(body, "SECRET=do-not-serve\n");
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_serve_dotfiles() -> Result<(), Box<dyn Error>> {
+        do_serve_dotfiles().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_serve_dotfiles() -> Result<(), Box<dyn Error>> {
+        do_serve_dotfiles().await
+    }
+
+    #[cfg(unix)]
+    async fn do_follow_symlinks() -> Result<(), Box<dyn Error>> {
+        let directory =
+            std::env::temp_dir().join(format!("vetis-follow-symlinks-{}", std::process::id()));
+        let root = directory.join("root");
+        std::fs::create_dir_all(&root)?;
+        std::fs::write(directory.join("secret.txt"), "outside the root\n")?;
+        std::fs::write(root.join("inside.txt"), "inside the root\n")?;
+        std::os::unix::fs::symlink(directory.join("secret.txt"), root.join("secret.txt"))?;
+        std::os::unix::fs::symlink(root.join("inside.txt"), root.join("alias.txt"))?;
+
+        let static_path = |follow_symlinks| {
+            StaticPathConfig::builder()
+                .uri("/")
+                .extensions(".*")
+                .directory(
+                    root.to_string_lossy()
+                        .as_ref(),
+                )
+                .follow_symlinks(follow_symlinks)
+                .build()
+                .map(StaticPath::new)
+        };
+
+        let config = StaticPathConfig::builder().build()?;
+        assert!(!config.follow_symlinks());
+
+        let static_path_strict = static_path(false)?;
+        let result = get_static(&static_path_strict, http::Method::GET, "/secret.txt", None).await;
+        assert!(result.is_err());
+
+        // Symlinks staying inside the root are still followed
+        let (parts, body) =
+            get_static(&static_path_strict, http::Method::GET, "/alias.txt", None).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(body, "inside the root\n");
+
+        let static_path_follow = static_path(true)?;
+        let (parts, body) =
+            get_static(&static_path_follow, http::Method::GET, "/secret.txt", None).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(body, "outside the root\n");
+
+        std::fs::remove_dir_all(&directory)?;
+
+        Ok(())
+    }
+
+    #[cfg(all(unix, feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_follow_symlinks() -> Result<(), Box<dyn Error>> {
+        do_follow_symlinks().await
+    }
+
+    #[cfg(all(unix, feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_follow_symlinks() -> Result<(), Box<dyn Error>> {
+        do_follow_symlinks().await
+    }
+
+    async fn do_directory_template() -> Result<(), Box<dyn Error>> {
+        let static_path = StaticPath::new(
+            StaticPathConfig::builder()
+                .uri("/")
+                .extensions(".*")
+                .directory("src/tests/files")
+                .index_files(vec!["index.html".to_string()])
+                .directory_template("src/tests/templates/directory.html")
+                .build()?,
+        );
+
+        let (parts, body) = get_static(&static_path, http::Method::GET, "/", None).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(
+            parts
+                .headers
+                .get(http::header::CONTENT_TYPE),
+            Some(&http::HeaderValue::from_static("text/html; charset=utf-8"))
+        );
+        assert!(body.contains("<h1>Index of /</h1>"));
+        assert!(body.contains("<li><a href=\"/app.mjs\">app.mjs</a></li>"));
+        assert!(body.contains("<li><a href=\"/python/\">python/</a></li>"));
+        assert!(body.contains("<li><a href=\"/.well-known/\">.well-known/</a></li>"));
+        assert!(!body.contains(".env"));
+
+        let (parts, body) = get_static(&static_path, http::Method::GET, "/python", None).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert!(body.contains("<h1>Index of /python</h1>"));
+        assert!(body.contains("<li><a href=\"/python/main.py\">main.py</a></li>"));
+
+        // Without a template, directories are served their index file
+        let static_path = StaticPath::new(
+            StaticPathConfig::builder()
+                .uri("/")
+                .extensions(".*")
+                .directory("src/tests/files")
+                .index_files(vec!["index.html".to_string()])
+                .build()?,
+        );
+
+        let (parts, body) = get_static(&static_path, http::Method::GET, "/", None).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert!(!body.contains("Index of"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_directory_template() -> Result<(), Box<dyn Error>> {
+        do_directory_template().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_directory_template() -> Result<(), Box<dyn Error>> {
+        do_directory_template().await
+    }
+
+    async fn do_static_attachment() -> Result<(), Box<dyn Error>> {
+        let static_path = StaticPath::new(
+            StaticPathConfig::builder()
+                .uri("/")
+                .extensions(".*")
+                .directory("src/tests/files")
+                .attachment(true)
+                .build()?,
+        );
+
+        let (parts, _) = get_static(&static_path, http::Method::GET, "/app.mjs", None).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(
+            parts
+                .headers
+                .get(http::header::CONTENT_DISPOSITION),
+            Some(&http::HeaderValue::from_static("attachment; filename=\"app.mjs\""))
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_static_attachment() -> Result<(), Box<dyn Error>> {
+        do_static_attachment().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_static_attachment() -> Result<(), Box<dyn Error>> {
+        do_static_attachment().await
+    }
+
+    async fn do_if_range() -> Result<(), Box<dyn Error>> {
+        const INDEX: &str = "<html>\n<head>\n  <title>\n    Tested!\n  </title>\n</head>\n<body>\n  <p>\n    Tested!\n  </p>\n</body>\n</html>";
+
+        let static_path = || -> Result<StaticPath, Box<dyn Error>> {
+            Ok(StaticPath::new(
+                StaticPathConfig::builder()
+                    .uri("/")
+                    .extensions(".*")
+                    .directory("src/tests/files")
+                    .build()?,
+            ))
+        };
+
+        let (parts, _) =
+            get_static(&static_path()?, http::Method::GET, "/index.html", None).await?;
+        let last_modified = parts
+            .headers
+            .get(http::header::LAST_MODIFIED)
+            .ok_or("missing last-modified")?
+            .to_str()?
+            .to_string();
+
+        let (parts, body) = get_static_with(
+            &static_path()?,
+            http::Method::GET,
+            "/index.html",
+            &[(http::header::RANGE, "bytes=7-12"), (http::header::IF_RANGE, &last_modified)],
+        )
+        .await?;
+        assert_eq!(parts.status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            parts.headers[http::header::CONTENT_RANGE],
+            format!("bytes 7-12/{}", INDEX.len())
+        );
+        assert_eq!(body, INDEX[7..=12]);
+
+        // The file changed since the validator was issued, or was validated by an entity tag
+        for if_range in ["Mon, 01 Jan 2001 00:00:00 +0000", "\"abc\""] {
+            let (parts, body) = get_static_with(
+                &static_path()?,
+                http::Method::GET,
+                "/index.html",
+                &[(http::header::RANGE, "bytes=7-12"), (http::header::IF_RANGE, if_range)],
+            )
+            .await?;
+            assert_eq!(parts.status, StatusCode::OK);
+            assert_eq!(body, INDEX);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_if_range() -> Result<(), Box<dyn Error>> {
+        do_if_range().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_if_range() -> Result<(), Box<dyn Error>> {
+        do_if_range().await
+    }
+
+    async fn do_range_of_large_file() -> Result<(), Box<dyn Error>> {
+        let directory =
+            std::env::temp_dir().join(format!("vetis-large-range-{}", std::process::id()));
+        std::fs::create_dir_all(&directory)?;
+
+        // Larger than a read chunk, with each byte telling its offset apart from its neighbours
+        let contents: String = (0..1024 * 1024)
+            .map(|offset| char::from(b'a' + (offset % 26) as u8))
+            .collect();
+        std::fs::write(directory.join("large.txt"), &contents)?;
+
+        let static_path = StaticPath::new(
+            StaticPathConfig::builder()
+                .uri("/")
+                .extensions(".*")
+                .directory(
+                    directory
+                        .to_string_lossy()
+                        .as_ref(),
+                )
+                .build()?,
+        );
+
+        let (parts, body) =
+            get_static(&static_path, http::Method::GET, "/large.txt", Some("bytes=1000-1099"))
+                .await?;
+        assert_eq!(parts.status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            parts.headers[http::header::CONTENT_RANGE],
+            format!("bytes 1000-1099/{}", contents.len())
+        );
+        assert_eq!(parts.headers[http::header::CONTENT_LENGTH], "100");
+        assert_eq!(body, contents[1000..1100]);
+
+        // A range spanning several read chunks
+        let (parts, body) =
+            get_static(&static_path, http::Method::GET, "/large.txt", Some("bytes=0-199999"))
+                .await?;
+        assert_eq!(parts.status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(body, contents[..200000]);
+
+        // Ranges running past the end of the file stop at its last byte
+        let start = contents.len() - 10;
+        let (parts, body) = get_static(
+            &static_path,
+            http::Method::GET,
+            "/large.txt",
+            Some(&format!("bytes={}-{}", start, contents.len() + 100)),
+        )
+        .await?;
+        assert_eq!(parts.status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            parts.headers[http::header::CONTENT_RANGE],
+            format!("bytes {}-{}/{}", start, contents.len() - 1, contents.len())
+        );
+        assert_eq!(body, contents[start..]);
+
+        let (parts, body) = get_static(&static_path, http::Method::GET, "/large.txt", None).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(body, contents);
+
+        std::fs::remove_dir_all(&directory)?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_range_of_large_file() -> Result<(), Box<dyn Error>> {
+        do_range_of_large_file().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_range_of_large_file() -> Result<(), Box<dyn Error>> {
+        do_range_of_large_file().await
+    }
+
+    async fn do_head_metadata() -> Result<(), Box<dyn Error>> {
+        let static_path = StaticPath::new(
+            StaticPathConfig::builder()
+                .uri("/")
+                .extensions(".*")
+                .directory("src/tests/files")
+                .build()?,
+        );
+
+        let (parts, body) =
+            get_static(&static_path, http::Method::HEAD, "/index.html", None).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(
+            parts
+                .headers
+                .get(http::header::CONTENT_LENGTH),
+            Some(&http::HeaderValue::from(std::fs::metadata("src/tests/files/index.html")?.len()))
+        );
+        assert!(parts
+            .headers
+            .contains_key(http::header::LAST_MODIFIED));
+        assert_eq!(
+            parts
+                .headers
+                .get(http::header::CONTENT_TYPE),
+            Some(&http::HeaderValue::from_static("text/html"))
+        );
+        assert_eq!(
+            parts
+                .headers
+                .get(http::header::ACCEPT_RANGES),
+            Some(&http::HeaderValue::from_static("bytes"))
+        );
+        assert!(body.is_empty());
+
+        // HEAD announces the same download as GET
+        let static_path = StaticPath::new(
+            StaticPathConfig::builder()
+                .uri("/")
+                .extensions(".*")
+                .directory("src/tests/files")
+                .attachment(true)
+                .build()?,
+        );
+
+        for method in [http::Method::GET, http::Method::HEAD] {
+            let (parts, _) = get_static(&static_path, method, "/index.html", None).await?;
+            assert_eq!(
+                parts
+                    .headers
+                    .get(http::header::CONTENT_DISPOSITION),
+                Some(&http::HeaderValue::from_static("attachment; filename=\"index.html\""))
+            );
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_head_metadata() -> Result<(), Box<dyn Error>> {
+        do_head_metadata().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_head_metadata() -> Result<(), Box<dyn Error>> {
+        do_head_metadata().await
+    }
+
+    async fn do_unknown_content_type() -> Result<(), Box<dyn Error>> {
+        for method in [http::Method::GET, http::Method::HEAD] {
+            let static_path = StaticPath::new(
+                StaticPathConfig::builder()
+                    .uri("/")
+                    .extensions(".*")
+                    .directory("src/tests/files")
+                    .build()?,
+            );
+
+            let (parts, _) = get_static(&static_path, method, "/NOTICE", None).await?;
+            assert_eq!(parts.status, StatusCode::OK);
+            assert_eq!(
+                parts
+                    .headers
+                    .get(http::header::CONTENT_TYPE),
+                Some(&http::HeaderValue::from_static("application/octet-stream"))
+            );
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_unknown_content_type() -> Result<(), Box<dyn Error>> {
+        do_unknown_content_type().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_unknown_content_type() -> Result<(), Box<dyn Error>> {
+        do_unknown_content_type().await
+    }
+
+    async fn do_single_file() -> Result<(), Box<dyn Error>> {
+        use http_body_util::BodyExt;
+
+        use crate::server::http::Request;
+
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .root_directory("src/tests")
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(StaticPath::new(
+            StaticPathConfig::builder()
+                .uri("/favicon.ico")
+                .file("src/tests/files/NOTICE")
+                .build()?,
+        ));
+
+        let request = |uri: &str| -> Result<Request, http::Error> {
+            let (parts, body) = http::Request::builder()
+                .uri(uri)
+                .body(hyper_body_utils::HttpBody::from_text(""))?
+                .into_parts();
+            Ok(Request::from_parts(parts, body))
+        };
+
+        let (parts, body) = virtual_host
+            .route(request("/favicon.ico")?)
+            .await?
+            .into_inner()
+            .into_parts();
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(
+            parts
+                .headers
+                .get(http::header::CONTENT_LENGTH),
+            Some(&http::HeaderValue::from(std::fs::metadata("src/tests/files/NOTICE")?.len()))
+        );
+        assert_eq!(
+            body.collect()
+                .await?
+                .to_bytes(),
+            std::fs::read("src/tests/files/NOTICE")?
+        );
+
+        // Paths below the file's URI don't exist
+        let response = virtual_host
+            .route(request("/favicon.ico/NOTICE")?)
+            .await?;
+        assert_eq!(
+            response
+                .into_inner()
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+
+        let some_path = StaticPathConfig::builder()
+            .file("")
+            .build();
+        assert_eq!(
+            some_path.err(),
+            Some(VetisError::Config(ConfigError::Path("File cannot be empty".to_string())))
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_single_file() -> Result<(), Box<dyn Error>> {
+        do_single_file().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_single_file() -> Result<(), Box<dyn Error>> {
+        do_single_file().await
+    }
+
+    #[test]
+    fn test_invalid_fallback_directory() {
+        let some_path = StaticPathConfig::builder()
+            .add_directory("")
+            .build();
+
+        assert!(some_path.is_err());
+        assert_eq!(
+            some_path.err(),
+            Some(VetisError::Config(ConfigError::Path("Directory cannot be empty".into())))
+        );
+    }
+
+    #[cfg(feature = "static-cache")]
+    async fn do_static_cache() -> Result<(), Box<dyn Error>> {
+        const INDEX: &str = "<html>\n<head>\n  <title>\n    Tested!\n  </title>\n</head>\n<body>\n  <p>\n    Tested!\n  </p>\n</body>\n</html>";
+
+        let static_path = StaticPath::new(
+            StaticPathConfig::builder()
+                .uri("/")
+                .directory("src/tests/files")
+                .cache_size(1024 * 1024)
+                .cache_revalidate_interval(std::time::Duration::from_secs(60))
+                .build()?,
+        );
+        let content_cache = static_path
+            .content_cache()
+            .ok_or("cache not enabled")?;
+
+        let (parts, body) =
+            get_static(&static_path, http::Method::GET, "/index.html", None).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(body, INDEX);
+        assert_eq!(content_cache.disk_accesses(), 1);
+
+        let (parts, body) =
+            get_static(&static_path, http::Method::GET, "/index.html", None).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(
+            parts
+                .headers
+                .get(http::header::CONTENT_LENGTH),
+            Some(&http::HeaderValue::from(INDEX.len()))
+        );
+        assert_eq!(body, INDEX);
+        assert_eq!(content_cache.disk_accesses(), 1);
+
+        let (parts, body) =
+            get_static(&static_path, http::Method::GET, "/index.html", Some("bytes=7-12")).await?;
+        assert_eq!(parts.status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(body, INDEX[7..=12]);
+
+        let (parts, _) =
+            get_static(&static_path, http::Method::GET, "/index.html", Some("bytes=500-600"))
+                .await?;
+        assert_eq!(parts.status, StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(parts.headers[http::header::CONTENT_RANGE], format!("bytes */{}", INDEX.len()));
+
+        let (parts, _) = get_static(&static_path, http::Method::HEAD, "/index.html", None).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert!(parts
+            .headers
+            .contains_key(http::header::LAST_MODIFIED));
+        assert_eq!(content_cache.disk_accesses(), 1);
+
+        let directory =
+            std::env::temp_dir().join(format!("vetis-static-cache-{}", std::process::id()));
+        std::fs::create_dir_all(&directory)?;
+        let file = directory.join("cached.txt");
+        std::fs::write(&file, "first")?;
+
+        let static_path = StaticPath::new(
+            StaticPathConfig::builder()
+                .uri("/")
+                .extensions(".txt")
+                .directory(
+                    directory
+                        .to_str()
+                        .ok_or("invalid temp dir")?,
+                )
+                .cache_size(1024)
+                .cache_revalidate_interval(std::time::Duration::from_millis(1))
+                .build()?,
+        );
+
+        let (_, body) = get_static(&static_path, http::Method::GET, "/cached.txt", None).await?;
+        assert_eq!(body, "first");
+
+        std::fs::write(&file, "second")?;
+        std::fs::File::options()
+            .write(true)
+            .open(&file)?
+            .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(10))?;
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        let (_, body) = get_static(&static_path, http::Method::GET, "/cached.txt", None).await?;
+        assert_eq!(body, "second");
+
+        std::fs::remove_dir_all(&directory)?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "tokio-rt", feature = "static-cache"))]
+    #[tokio::test]
+    async fn test_static_cache() -> Result<(), Box<dyn Error>> {
+        do_static_cache().await
+    }
+
+    #[cfg(all(feature = "smol-rt", feature = "static-cache"))]
+    #[apply(test!)]
+    async fn test_static_cache() -> Result<(), Box<dyn Error>> {
+        do_static_cache().await
+    }
+
+    #[cfg(feature = "static-cache")]
+    #[test]
+    fn test_invalid_cache_size() {
+        let static_path_config = StaticPathConfig::builder()
+            .cache_size(0)
+            .build();
+        assert!(static_path_config.is_err());
+        assert_eq!(
+            static_path_config.err(),
+            Some(VetisError::Config(ConfigError::Path("Cache size cannot be 0".to_string())))
+        );
+    }
+
+    #[cfg(feature = "auth")]
+    async fn do_basic_auth(
+        username: Option<String>,
+        password: Option<String>,
+    ) -> Result<(), Box<dyn Error>> {
+        use crate::server::virtual_host::path::auth::{basic_auth::BasicAuth, AuthType};
+
+        let has_auth = username.is_some() && password.is_some();
+
+        let port = if has_auth { 9200 } else { 9201 };
+
+        let listener = ListenerConfig::builder()
+            .port(port)
+            .protocol(default_protocol())
+            .interface("0.0.0.0")
+            .build()?;
+
+        let config = ServerConfig::builder()
+            .add_listener(listener)
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(port)
+            .root_directory("src/tests")
+            .security(security_config.clone())
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(host_config);
+
+        let auth_config = BasicAuthConfig::builder()
+            .htpasswd(Some("src/tests/files/.htpasswd".to_string()))
+            .cache_users()
+            .build()?;
+
+        virtual_host.add_path(StaticPath::new(
+            StaticPathConfig::builder()
+                .uri("/")
+                .directory("src/tests/files")
+                .auth(AuthType::Basic(BasicAuth::new(auth_config)))
+                .build()?,
+        ));
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, deboa::cert::ContentEncoding::DER))
+            .build();
+
+        let request = request::get(format!("https://localhost:{}/index.html", port))?;
+
+        let request = if let Some(username) = username {
+            if let Some(password) = password {
+                request.basic_auth(&username, &password)
+            } else {
+                request
+            }
+        } else {
+            request
+        };
+
+        let response = request
+            .send_with(&client)
+            .await;
+
+        if !has_auth {
+            assert_eq!(
+                response.err(),
+                Some(deboa::errors::DeboaError::Response(deboa::errors::ResponseError::Receive {
+                    status_code: StatusCode::UNAUTHORIZED,
+                    message: "Could not process request (401 Unauthorized): Unauthorized"
+                        .to_string()
+                }))
+            );
+        } else {
+            assert_eq!(response?.status(), StatusCode::OK);
+        }
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "embed")]
+    async fn do_embedded_static_path() -> Result<(), Box<dyn Error>> {
+        use crate::server::virtual_host::path::static_files::embedded::EmbeddedStaticPath;
+
+        static FILES: include_dir::Dir =
+            include_dir::include_dir!("$CARGO_MANIFEST_DIR/src/tests/files");
+
+        let index = include_str!("files/index.html");
+
+        let embedded_path = EmbeddedStaticPath::from_dir(
+            StaticPathConfig::builder()
+                .uri("/")
+                .index_files(vec!["index.html".to_string()])
+                .build()?,
+            &FILES,
+        );
+
+        let (parts, body) =
+            get_static_with(&embedded_path, http::Method::GET, "/index.html", &[]).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(parts.headers[http::header::CONTENT_TYPE], "text/html");
+        assert_eq!(
+            parts.headers[http::header::CONTENT_LENGTH],
+            index
+                .len()
+                .to_string()
+        );
+        assert_eq!(body, index);
+
+        // Directories are answered with their index file
+        let (parts, body) = get_static_with(&embedded_path, http::Method::GET, "/", &[]).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(body, index);
+
+        let (parts, body) = get_static_with(
+            &embedded_path,
+            http::Method::GET,
+            "/index.html",
+            &[(http::header::RANGE, "bytes=7-12")],
+        )
+        .await?;
+        assert_eq!(parts.status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(body, index[7..=12]);
+
+        let (parts, body) =
+            get_static_with(&embedded_path, http::Method::HEAD, "/index.html", &[]).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(
+            parts.headers[http::header::CONTENT_LENGTH],
+            index
+                .len()
+                .to_string()
+        );
+        assert_eq!(parts.headers[http::header::ACCEPT_RANGES], "bytes");
+        assert!(body.is_empty());
+
+        let result = get_static_with(&embedded_path, http::Method::GET, "/missing.html", &[]).await;
+        assert!(result.is_err());
+
+        let files =
+            HashMap::from([("/notice.txt".to_string(), include_bytes!("files/NOTICE").as_slice())]);
+        let embedded_path = EmbeddedStaticPath::from_files(
+            StaticPathConfig::builder()
+                .uri("/")
+                .build()?,
+            files,
+        );
+
+        let (parts, body) =
+            get_static_with(&embedded_path, http::Method::GET, "/notice.txt", &[]).await?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(parts.headers[http::header::CONTENT_TYPE], "text/plain");
+        assert_eq!(body, include_str!("files/NOTICE"));
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "embed", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_embedded_static_path() -> Result<(), Box<dyn Error>> {
+        do_embedded_static_path().await
+    }
+
+    #[cfg(all(feature = "embed", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_embedded_static_path() -> Result<(), Box<dyn Error>> {
+        do_embedded_static_path().await
+    }
+
+    #[cfg(all(feature = "auth", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_invalid_basic_auth() -> Result<(), Box<dyn Error>> {
+        do_basic_auth(None, None).await
+    }
+
+    #[cfg(all(feature = "auth", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_invalid_basic_auth() -> Result<(), Box<dyn Error>> {
+        do_basic_auth(None, None).await
+    }
+
+    #[cfg(all(feature = "auth", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_valid_basic_auth() -> Result<(), Box<dyn Error>> {
+        do_basic_auth(Some("rogerio".to_string()), Some("rpa78@rio!".to_string())).await
+    }
+
+    #[cfg(all(feature = "auth", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_valid_basic_auth() -> Result<(), Box<dyn Error>> {
+        do_basic_auth(Some("rogerio".to_string()), Some("rpa78@rio!".to_string())).await
+    }
+}
+
+#[cfg(feature = "reverse-proxy")]
+mod reverse_proxy {
+    use std::error::Error;
+
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    use deboa::{cert::Certificate, request};
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    use http::StatusCode;
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    use http_body_util::BodyExt;
+
+    #[cfg(feature = "smol-rt")]
+    use macro_rules_attribute::apply;
+    #[cfg(feature = "smol-rt")]
+    use smol_macros::test;
+
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    use crate::{
+        config::server::{
+            virtual_host::{SecurityConfig, VirtualHostConfig},
+            ListenerConfig, ServerConfig,
+        },
+        server::virtual_host::{
+            handler_fn,
+            path::{proxy::ProxyPath, HandlerPath},
+            VirtualHost,
+        },
+        tests::{CA_CERT, SERVER_CERT, SERVER_KEY},
+    };
+
+    use crate::{
+        config::server::virtual_host::path::proxy::ProxyPathConfig,
+        errors::{ConfigError, VetisError},
+    };
+
+    #[test]
+    fn test_proxy_path() -> Result<(), Box<dyn Error>> {
+        let some_path = ProxyPathConfig::builder()
+            .uri("/test")
+            .target("http://localhost:8080")
+            .build()?;
+
+        assert_eq!(some_path.uri(), "/test");
+        assert_eq!(some_path.target(), "http://localhost:8080");
+        assert!(some_path.via());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_proxy_path() -> Result<(), Box<dyn Error>> {
+        let some_path = ProxyPathConfig::builder()
+            .uri("")
+            .target("http://localhost:8080")
+            .build();
+
+        assert!(some_path.is_err());
+        assert_eq!(
+            some_path.err(),
+            Some(VetisError::Config(ConfigError::Path("URI cannot be empty".into(),)))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_proxy_path_target() -> Result<(), Box<dyn Error>> {
+        let some_path = ProxyPathConfig::builder()
+            .uri("/test")
+            .target("")
+            .build();
+
+        assert!(some_path.is_err());
+        assert_eq!(
+            some_path.err(),
+            Some(VetisError::Config(ConfigError::Path("Target cannot be empty".into(),)))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_proxy_upstream() -> Result<(), Box<dyn Error>> {
+        let some_path = ProxyPathConfig::builder()
+            .uri("/test")
+            .target("http://localhost:8080")
+            .add_upstream("")
+            .build();
+
+        assert_eq!(
+            some_path.err(),
+            Some(VetisError::Config(ConfigError::Path("Upstream cannot be empty".into(),)))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_proxy_header() {
+        let some_path = ProxyPathConfig::builder()
+            .uri("/test")
+            .target("http://localhost:8080")
+            .add_response_header("x-bad header", "value")
+            .build();
+
+        assert_eq!(
+            some_path.err(),
+            Some(VetisError::Config(ConfigError::Path("Invalid header name: x-bad header".into())))
+        );
+    }
+
+    #[test]
+    fn test_invalid_proxy_retries() {
+        let some_path = ProxyPathConfig::builder()
+            .max_retries(0)
+            .build();
+
+        assert_eq!(
+            some_path.err(),
+            Some(VetisError::Config(ConfigError::Path("Max retries cannot be 0".into())))
+        );
+
+        let some_path = ProxyPathConfig::builder()
+            .max_retries(1)
+            .add_retry_status(1000)
+            .build();
+
+        assert_eq!(
+            some_path.err(),
+            Some(VetisError::Config(ConfigError::Path("Invalid retry status: 1000".into())))
+        );
+    }
+
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    async fn do_get_proxy_to_target() -> Result<(), Box<dyn Error>> {
+        use crate::tests::default_protocol;
+
+        let source_listener = ListenerConfig::builder()
+            .port(8084)
+            .protocol(default_protocol())
+            .interface("0.0.0.0")
+            .build()?;
+
+        let target_listener = ListenerConfig::builder()
+            .port(8085)
+            .protocol(default_protocol())
+            .interface("0.0.0.0")
+            .build()?;
+
+        let config = ServerConfig::builder()
+            .add_listener(source_listener)
+            .add_listener(target_listener)
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let source_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(8084)
+            .root_directory("src/tests")
+            .security(security_config.clone())
+            .build()?;
+
+        let mut source_virtual_host = VirtualHost::new(source_config);
+        source_virtual_host.add_path(ProxyPath::new(
+            ProxyPathConfig::builder()
+                .uri("/")
+                .target("http://localhost:8085")
+                .build()?,
+        ));
+
+        let target_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(8085)
+            .root_directory("src/tests")
+            .build()?;
+
+        let mut target_virtual_host = VirtualHost::new(target_config);
+        target_virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Hello, world!"))
+                }))
+                .build()?,
+        );
+
+        assert_eq!(
+            target_virtual_host
+                .config()
+                .hostname(),
+            "localhost"
+        );
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(source_virtual_host)
+            .await;
+        server
+            .add_virtual_host(target_virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, deboa::cert::ContentEncoding::DER))
+            .build();
+
+        let request = request::get("https://localhost:8085/")?
+            .send_with(&client)
+            .await?;
+
+        assert_eq!(request.status(), StatusCode::OK);
+        assert_eq!(
+            request
+                .text()
+                .await?,
+            "Hello, world!"
+        );
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "tokio-rt", any(feature = "http1", feature = "http2")))]
+    #[tokio::test]
+    async fn test_get_proxy_to_target() -> Result<(), Box<dyn Error>> {
+        do_get_proxy_to_target().await
+    }
+
+    #[cfg(all(feature = "smol-rt", any(feature = "http1", feature = "http2")))]
+    #[apply(test!)]
+    async fn test_get_proxy_to_target() -> Result<(), Box<dyn Error>> {
+        do_get_proxy_to_target().await
+    }
+
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    async fn do_post_proxy_to_target() -> Result<(), Box<dyn Error>> {
+        use crate::tests::default_protocol;
+
+        let source_listener = ListenerConfig::builder()
+            .port(9093)
+            .protocol(default_protocol())
+            .interface("0.0.0.0")
+            .build()?;
+
+        let target_listener = ListenerConfig::builder()
+            .port(9094)
+            .protocol(default_protocol())
+            .interface("0.0.0.0")
+            .build()?;
+
+        let config = ServerConfig::builder()
+            .add_listener(source_listener)
+            .add_listener(target_listener)
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let source_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9093)
+            .root_directory("src/tests")
+            .security(security_config.clone())
+            .build()?;
+
+        let mut source_virtual_host = VirtualHost::new(source_config);
+        source_virtual_host.add_path(ProxyPath::new(
+            ProxyPathConfig::builder()
+                .uri("/")
+                .target("http://localhost:9094")
+                .build()?,
+        ));
+
+        let target_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9094)
+            .root_directory("src/tests")
+            .build()?;
+
+        let mut target_virtual_host = VirtualHost::new(target_config);
+        target_virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/")
+                .handler(handler_fn(|request| async move {
+                    let (_parts, body) = request.into_parts();
+                    let text = body
+                        .collect()
+                        .await
+                        .unwrap()
+                        .to_bytes();
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .bytes(text))
+                }))
+                .build()?,
+        );
+
+        assert_eq!(
+            target_virtual_host
+                .config()
+                .hostname(),
+            "localhost"
+        );
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(source_virtual_host)
+            .await;
+        server
+            .add_virtual_host(target_virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, deboa::cert::ContentEncoding::DER))
+            .build();
+
+        let response = request::post("https://localhost:9093/")?
+            .text("Something cool!")
+            .send_with(&client)
+            .await?;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response
+                .text()
+                .await?,
+            "Something cool!"
+        );
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "tokio-rt", any(feature = "http1", feature = "http2")))]
+    #[tokio::test]
+    async fn test_post_proxy_to_target() -> Result<(), Box<dyn Error>> {
+        do_post_proxy_to_target().await
+    }
+
+    #[cfg(all(feature = "smol-rt", any(feature = "http1", feature = "http2")))]
+    #[apply(test!)]
+    async fn test_post_proxy_to_target() -> Result<(), Box<dyn Error>> {
+        do_post_proxy_to_target().await
+    }
+
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    fn chunked_body(chunks: &[&'static str]) -> hyper_body_utils::HttpBody {
+        use futures_util::{stream, TryStreamExt};
+        use http_body_util::StreamBody;
+        use hyper::body::Frame;
+
+        let chunks: Vec<Result<bytes::Bytes, std::io::Error>> = chunks
+            .iter()
+            .map(|chunk| Ok(bytes::Bytes::from_static(chunk.as_bytes())))
+            .collect();
+
+        hyper_body_utils::HttpBody::Stream(
+            StreamBody::new(stream::iter(chunks).map_ok(Frame::data)).boxed(),
+        )
+    }
+
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    async fn do_proxy_buffering() -> Result<(), Box<dyn Error>> {
+        use crate::tests::default_protocol;
+
+        let source_listener = ListenerConfig::builder()
+            .port(9095)
+            .protocol(default_protocol())
+            .interface("0.0.0.0")
+            .build()?;
+
+        let target_listener = ListenerConfig::builder()
+            .port(9096)
+            .protocol(default_protocol())
+            .interface("0.0.0.0")
+            .build()?;
+
+        let config = ServerConfig::builder()
+            .add_listener(source_listener)
+            .add_listener(target_listener)
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let source_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9095)
+            .root_directory("src/tests")
+            .security(security_config.clone())
+            .build()?;
+
+        let mut source_virtual_host = VirtualHost::new(source_config);
+        source_virtual_host.add_path(ProxyPath::new(
+            ProxyPathConfig::builder()
+                .uri("/streamed")
+                .target("http://127.0.0.1:9096")
+                .build()?,
+        ));
+        source_virtual_host.add_path(ProxyPath::new(
+            ProxyPathConfig::builder()
+                .uri("/buffered")
+                .target("http://127.0.0.1:9096")
+                .buffer_response(true)
+                .build()?,
+        ));
+        source_virtual_host.add_path(ProxyPath::new(
+            ProxyPathConfig::builder()
+                .uri("/limited")
+                .target("http://127.0.0.1:9096")
+                .buffer_response(true)
+                .max_buffer_size(8)
+                .build()?,
+        ));
+        source_virtual_host.add_path(ProxyPath::new(
+            ProxyPathConfig::builder()
+                .uri("/upload")
+                .target("http://127.0.0.1:9096/upload")
+                .buffer_request(true)
+                .build()?,
+        ));
+
+        // The proxy client pools connections per host, so keep this upstream
+        // apart from the ones used by the other proxy tests
+        let target_config = VirtualHostConfig::builder()
+            .hostname("127.0.0.1")
+            .port(9096)
+            .root_directory("src/tests")
+            .build()?;
+
+        let mut target_virtual_host = VirtualHost::new(target_config);
+        target_virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .body(chunked_body(&["chunk-1;", "chunk-2;", "chunk-3;"])))
+                }))
+                .build()?,
+        );
+        target_virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/upload")
+                .handler(handler_fn(|request| async move {
+                    let content_length = request
+                        .headers()
+                        .get(http::header::CONTENT_LENGTH)
+                        .and_then(|value| value.to_str().ok())
+                        .unwrap_or("none")
+                        .to_string();
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text(&content_length))
+                }))
+                .build()?,
+        );
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(source_virtual_host)
+            .await;
+        server
+            .add_virtual_host(target_virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, deboa::cert::ContentEncoding::DER))
+            .build();
+
+        let response = request::get("https://localhost:9095/streamed")?
+            .send_with(&client)
+            .await?;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response
+            .headers()
+            .contains_key(http::header::CONTENT_LENGTH));
+        assert_eq!(
+            response
+                .text()
+                .await?,
+            "chunk-1;chunk-2;chunk-3;"
+        );
+
+        let response = request::get("https://localhost:9095/buffered")?
+            .send_with(&client)
+            .await?;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response
+                .headers()
+                .get(http::header::CONTENT_LENGTH),
+            Some(&http::HeaderValue::from(24))
+        );
+        assert_eq!(
+            response
+                .text()
+                .await?,
+            "chunk-1;chunk-2;chunk-3;"
+        );
+
+        let response = request::post("https://localhost:9095/upload")?
+            .body(chunked_body(&["Something ", "cool!"]))
+            .send_with(&client)
+            .await?;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response
+                .text()
+                .await?,
+            "15"
+        );
+
+        // Runs last: the abandoned upstream body leaves its pooled connection unusable
+        let response = request::get("https://localhost:9095/limited")?
+            .send_with(&client)
+            .await;
+
+        assert!(matches!(
+            response.err(),
+            Some(deboa::errors::DeboaError::Response(deboa::errors::ResponseError::Receive {
+                status_code: StatusCode::BAD_GATEWAY,
+                ..
+            }))
+        ));
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "tokio-rt", any(feature = "http1", feature = "http2")))]
+    #[tokio::test]
+    async fn test_proxy_buffering() -> Result<(), Box<dyn Error>> {
+        do_proxy_buffering().await
+    }
+
+    #[cfg(all(feature = "smol-rt", any(feature = "http1", feature = "http2")))]
+    #[apply(test!)]
+    async fn test_proxy_buffering() -> Result<(), Box<dyn Error>> {
+        do_proxy_buffering().await
+    }
+
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    async fn do_sticky_sessions() -> Result<(), Box<dyn Error>> {
+        use deboa::errors::{DeboaError, ResponseError};
+
+        use crate::tests::default_protocol;
+
+        let mut config = ServerConfig::builder();
+        for port in [9097, 9098, 9099] {
+            config = config.add_listener(
+                ListenerConfig::builder()
+                    .port(port)
+                    .protocol(default_protocol())
+                    .interface("0.0.0.0")
+                    .build()?,
+            );
+        }
+        let config = config.build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let source_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9097)
+            .root_directory("src/tests")
+            .security(security_config.clone())
+            .build()?;
+
+        let mut source_virtual_host = VirtualHost::new(source_config);
+        source_virtual_host.add_path(ProxyPath::new(
+            ProxyPathConfig::builder()
+                .uri("/")
+                .target("http://127.0.0.2:9098")
+                .add_upstream("http://127.0.0.3:9099")
+                .sticky_sessions(true)
+                .build()?,
+        ));
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(source_virtual_host)
+            .await;
+
+        // Distinct loopback hosts, since the proxy client pools connections per host
+        for (hostname, port, backend) in
+            [("127.0.0.2", 9098, "first"), ("127.0.0.3", 9099, "second")]
+        {
+            let backend_config = VirtualHostConfig::builder()
+                .hostname(hostname)
+                .port(port)
+                .root_directory("src/tests")
+                .build()?;
+
+            let backend_virtual_host = VirtualHost::with_handler(
+                backend_config,
+                handler_fn(move |request| async move {
+                    let status = if request
+                        .headers()
+                        .contains_key("x-missing")
+                    {
+                        StatusCode::NOT_FOUND
+                    } else {
+                        StatusCode::OK
+                    };
+                    Ok(crate::server::http::Response::builder()
+                        .status(status)
+                        .text(backend))
+                }),
+            );
+
+            server
+                .add_virtual_host(backend_virtual_host)
+                .await;
+        }
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, deboa::cert::ContentEncoding::DER))
+            .build();
+
+        let response = request::get("https://localhost:9097/")?
+            .send_with(&client)
+            .await?;
+
+        let cookie = response
+            .headers()
+            .get(http::header::SET_COOKIE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| {
+                value
+                    .split(';')
+                    .next()
+            })
+            .map(str::to_string)
+            .ok_or("missing sticky session cookie")?;
+
+        assert!(cookie.starts_with("VETIS_UPSTREAM="));
+        assert_eq!(
+            response
+                .text()
+                .await?,
+            "first"
+        );
+
+        // Error statuses are forwarded, without taking the pinned upstream out of rotation
+        let response = request::get("https://localhost:9097/")?
+            .header(http::header::COOKIE, &cookie)
+            .header(http::HeaderName::from_static("x-missing"), "1")
+            .send_with(&client)
+            .await;
+
+        assert!(matches!(
+            response.err(),
+            Some(DeboaError::Response(ResponseError::Receive { status_code, message }))
+                if status_code == StatusCode::NOT_FOUND && message.ends_with("first")
+        ));
+
+        for _ in 0..2 {
+            let response = request::get("https://localhost:9097/")?
+                .header(http::header::COOKIE, &cookie)
+                .send_with(&client)
+                .await?;
+
+            assert!(!response
+                .headers()
+                .contains_key(http::header::SET_COOKIE));
+            assert_eq!(
+                response
+                    .text()
+                    .await?,
+                "first"
+            );
+        }
+
+        let response = request::get("https://localhost:9097/")?
+            .send_with(&client)
+            .await?;
+
+        assert_eq!(
+            response
+                .text()
+                .await?,
+            "second"
+        );
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "tokio-rt", any(feature = "http1", feature = "http2")))]
+    #[tokio::test]
+    async fn test_sticky_sessions() -> Result<(), Box<dyn Error>> {
+        do_sticky_sessions().await
+    }
+
+    #[cfg(all(feature = "smol-rt", any(feature = "http1", feature = "http2")))]
+    #[apply(test!)]
+    async fn test_sticky_sessions() -> Result<(), Box<dyn Error>> {
+        do_sticky_sessions().await
+    }
+
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    async fn do_health_checks() -> Result<(), Box<dyn Error>> {
+        use std::time::Duration;
+
+        use crate::{tests::default_protocol, utils::time::sleep};
+
+        let mut config = ServerConfig::builder();
+        for port in [9110, 9111, 9112] {
+            config = config.add_listener(
+                ListenerConfig::builder()
+                    .port(port)
+                    .protocol(default_protocol())
+                    .interface("0.0.0.0")
+                    .build()?,
+            );
+        }
+        let config = config.build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let source_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9112)
+            .root_directory("src/tests")
+            .security(security_config.clone())
+            .build()?;
+
+        let mut source_virtual_host = VirtualHost::new(source_config);
+        source_virtual_host.add_path(ProxyPath::new(
+            ProxyPathConfig::builder()
+                .uri("/")
+                .target("http://127.0.0.4:9110")
+                .add_upstream("http://127.0.0.5:9111")
+                .health_check("/health")
+                .health_check_interval(Duration::from_millis(50))
+                .health_check_timeout(Duration::from_secs(1))
+                .build()?,
+        ));
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(source_virtual_host)
+            .await;
+
+        for (hostname, port, backend) in
+            [("127.0.0.4", 9110, "healthy"), ("127.0.0.5", 9111, "failing")]
+        {
+            let backend_config = VirtualHostConfig::builder()
+                .hostname(hostname)
+                .port(port)
+                .root_directory("src/tests")
+                .build()?;
+
+            let backend_virtual_host = VirtualHost::with_handler(
+                backend_config,
+                handler_fn(move |request| async move {
+                    let status = if backend == "failing" && request.uri().path() == "/health" {
+                        StatusCode::SERVICE_UNAVAILABLE
+                    } else {
+                        StatusCode::OK
+                    };
+                    Ok(crate::server::http::Response::builder()
+                        .status(status)
+                        .text(backend))
+                }),
+            );
+
+            server
+                .add_virtual_host(backend_virtual_host)
+                .await;
+        }
+
+        server
+            .start()
+            .await?;
+
+        sleep(Duration::from_millis(300)).await;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, deboa::cert::ContentEncoding::DER))
+            .build();
+
+        for _ in 0..4 {
+            let response = request::get("https://localhost:9112/")?
+                .send_with(&client)
+                .await?;
+
+            assert_eq!(
+                response
+                    .text()
+                    .await?,
+                "healthy"
+            );
+        }
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "tokio-rt", any(feature = "http1", feature = "http2")))]
+    #[tokio::test]
+    async fn test_health_checks() -> Result<(), Box<dyn Error>> {
+        do_health_checks().await
+    }
+
+    #[cfg(all(feature = "smol-rt", any(feature = "http1", feature = "http2")))]
+    #[apply(test!)]
+    async fn test_health_checks() -> Result<(), Box<dyn Error>> {
+        do_health_checks().await
+    }
+
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    async fn do_proxy_header_rewriting() -> Result<(), Box<dyn Error>> {
+        use crate::tests::default_protocol;
+
+        let mut config = ServerConfig::builder();
+        for port in [9113, 9114] {
+            config = config.add_listener(
+                ListenerConfig::builder()
+                    .port(port)
+                    .protocol(default_protocol())
+                    .interface("0.0.0.0")
+                    .build()?,
+            );
+        }
+        let config = config.build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let source_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9113)
+            .root_directory("src/tests")
+            .security(security_config.clone())
+            .build()?;
+
+        let mut source_virtual_host = VirtualHost::new(source_config);
+        source_virtual_host.add_path(ProxyPath::new(
+            ProxyPathConfig::builder()
+                .uri("/")
+                .target("http://127.0.0.6:9114")
+                .remove_request_header("x-client-secret")
+                .remove_response_header("keep-alive")
+                .remove_response_header("x-backend-internal")
+                .add_response_header("x-proxied-by", "vetis")
+                .build()?,
+        ));
+
+        let target_config = VirtualHostConfig::builder()
+            .hostname("127.0.0.6")
+            .port(9114)
+            .root_directory("src/tests")
+            .build()?;
+
+        let target_virtual_host = VirtualHost::with_handler(
+            target_config,
+            handler_fn(|request| async move {
+                let secret_forwarded = request
+                    .headers()
+                    .contains_key("x-client-secret");
+                Ok(crate::server::http::Response::builder()
+                    .status(StatusCode::OK)
+                    .header(
+                        http::header::HeaderName::from_static("keep-alive"),
+                        http::HeaderValue::from_static("timeout=5"),
+                    )
+                    .header(
+                        http::header::HeaderName::from_static("x-backend-internal"),
+                        http::HeaderValue::from_static("node-7"),
+                    )
+                    .text(&secret_forwarded.to_string()))
+            }),
+        );
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(source_virtual_host)
+            .await;
+        server
+            .add_virtual_host(target_virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, deboa::cert::ContentEncoding::DER))
+            .build();
+
+        let response = request::get("https://localhost:9113/")?
+            .header(http::header::HeaderName::from_static("x-client-secret"), "hunter2")
+            .send_with(&client)
+            .await?;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response
+            .headers()
+            .contains_key("keep-alive"));
+        assert!(!response
+            .headers()
+            .contains_key("x-backend-internal"));
+        assert_eq!(
+            response
+                .headers()
+                .get("x-proxied-by"),
+            Some(&http::HeaderValue::from_static("vetis"))
+        );
+        assert_eq!(
+            response
+                .text()
+                .await?,
+            "false"
+        );
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "tokio-rt", any(feature = "http1", feature = "http2")))]
+    #[tokio::test]
+    async fn test_proxy_header_rewriting() -> Result<(), Box<dyn Error>> {
+        do_proxy_header_rewriting().await
+    }
+
+    #[cfg(all(feature = "smol-rt", any(feature = "http1", feature = "http2")))]
+    #[apply(test!)]
+    async fn test_proxy_header_rewriting() -> Result<(), Box<dyn Error>> {
+        do_proxy_header_rewriting().await
+    }
+
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    async fn do_hop_by_hop_headers() -> Result<(), Box<dyn Error>> {
+        use crate::tests::default_protocol;
+
+        let mut config = ServerConfig::builder();
+        for port in [9117, 9118] {
+            config = config.add_listener(
+                ListenerConfig::builder()
+                    .port(port)
+                    .protocol(default_protocol())
+                    .interface("0.0.0.0")
+                    .build()?,
+            );
+        }
+        let config = config.build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let source_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9117)
+            .root_directory("src/tests")
+            .security(security_config.clone())
+            .build()?;
+
+        let mut source_virtual_host = VirtualHost::new(source_config);
+        source_virtual_host.add_path(ProxyPath::new(
+            ProxyPathConfig::builder()
+                .uri("/")
+                .target("http://127.0.0.8:9118")
+                .build()?,
+        ));
+
+        let target_config = VirtualHostConfig::builder()
+            .hostname("127.0.0.8")
+            .port(9118)
+            .root_directory("src/tests")
+            .build()?;
+
+        let target_virtual_host = VirtualHost::with_handler(
+            target_config,
+            handler_fn(|request| async move {
+                let forwarded: Vec<&str> = ["connection", "x-custom", "proxy-trace"]
+                    .into_iter()
+                    .filter(|name| {
+                        request
+                            .headers()
+                            .contains_key(*name)
+                    })
+                    .collect();
+                Ok(crate::server::http::Response::builder()
+                    .status(StatusCode::OK)
+                    .header(
+                        http::header::CONNECTION,
+                        http::HeaderValue::from_static("x-upstream-hop"),
+                    )
+                    .header(
+                        http::header::HeaderName::from_static("x-upstream-hop"),
+                        http::HeaderValue::from_static("1"),
+                    )
+                    .text(&forwarded.join(",")))
+            }),
+        );
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(source_virtual_host)
+            .await;
+        server
+            .add_virtual_host(target_virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, deboa::cert::ContentEncoding::DER))
+            .build();
+
+        let response = request::get("https://localhost:9117/")?
+            .header(http::header::CONNECTION, "X-Custom")
+            .header(http::header::HeaderName::from_static("x-custom"), "value")
+            .header(http::header::HeaderName::from_static("proxy-trace"), "1")
+            .send_with(&client)
+            .await?;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response
+            .headers()
+            .contains_key("x-upstream-hop"));
+        assert_eq!(
+            response
+                .text()
+                .await?,
+            ""
+        );
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    async fn do_proxy_via() -> Result<(), Box<dyn Error>> {
+        use deboa::errors::{DeboaError, ResponseError};
+
+        use crate::tests::default_protocol;
+
+        let mut config = ServerConfig::builder();
+        for port in [9145, 9146] {
+            config = config.add_listener(
+                ListenerConfig::builder()
+                    .port(port)
+                    .protocol(default_protocol())
+                    .interface("0.0.0.0")
+                    .build()?,
+            );
+        }
+        let config = config.build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let source_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9145)
+            .root_directory("src/tests")
+            .security(security_config)
+            .build()?;
+
+        let mut source_virtual_host = VirtualHost::new(source_config);
+        source_virtual_host.add_path(ProxyPath::new(
+            ProxyPathConfig::builder()
+                .uri("/")
+                .target("http://127.0.0.11:9146")
+                .build()?,
+        ));
+
+        let target_config = VirtualHostConfig::builder()
+            .hostname("127.0.0.11")
+            .port(9146)
+            .root_directory("src/tests")
+            .build()?;
+
+        // Answers with the Via header of the forwarded request
+        let target_virtual_host = VirtualHost::with_handler(
+            target_config,
+            handler_fn(|request| async move {
+                let via = request
+                    .headers()
+                    .get(http::header::VIA)
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string();
+                Ok(crate::server::http::Response::builder()
+                    .status(StatusCode::OK)
+                    .header(http::header::VIA, http::HeaderValue::from_static("1.1 upstream"))
+                    .text(&via))
+            }),
+        );
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(source_virtual_host)
+            .await;
+        server
+            .add_virtual_host(target_virtual_host)
             .await;
 
         server
@@ -395,34 +2711,36 @@ mod static_files {
             .certificate(Certificate::from_slice(CA_CERT, deboa::cert::ContentEncoding::DER))
             .build();
 
-        let request = request::get(format!("https://localhost:{}/index.html", port))?;
+        let response = request::get("https://localhost:9145/")?
+            .header(http::header::VIA, "1.0 client-proxy")
+            .send_with(&client)
+            .await?;
 
-        let request = if let Some(username) = username {
-            if let Some(password) = password {
-                request.basic_auth(&username, &password)
-            } else {
-                request
-            }
-        } else {
-            request
-        };
+        assert_eq!(response.status(), StatusCode::OK);
+        let via = response
+            .headers()
+            .get(http::header::VIA)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        assert!(via.is_some_and(|via| via.starts_with("1.1 upstream, ") && via.ends_with(" vetis")));
+
+        let forwarded = response
+            .text()
+            .await?;
+        assert!(forwarded.starts_with("1.0 client-proxy, "));
+        assert!(forwarded.ends_with(" vetis"));
 
-        let response = request
+        // A request that went through this proxy before is a loop
+        let response = request::get("https://localhost:9145/")?
+            .header(http::header::VIA, "1.1 vetis")
             .send_with(&client)
             .await;
 
-        if !has_auth {
-            assert_eq!(
-                response.err(),
-                Some(deboa::errors::DeboaError::Response(deboa::errors::ResponseError::Receive {
-                    status_code: StatusCode::UNAUTHORIZED,
-                    message: "Could not process request (401 Unauthorized): Unauthorized"
-                        .to_string()
-                }))
-            );
-        } else {
-            assert_eq!(response?.status(), StatusCode::OK);
-        }
+        assert!(matches!(
+            response.err(),
+            Some(DeboaError::Response(ResponseError::Receive { status_code, .. }))
+                if status_code == StatusCode::LOOP_DETECTED
+        ));
 
         server
             .stop()
@@ -431,131 +2749,37 @@ mod static_files {
         Ok(())
     }
 
-    #[cfg(all(feature = "auth", feature = "tokio-rt"))]
-    #[tokio::test]
-    async fn test_invalid_basic_auth() -> Result<(), Box<dyn Error>> {
-        do_basic_auth(None, None).await
-    }
-
-    #[cfg(all(feature = "auth", feature = "smol-rt"))]
-    #[apply(test!)]
-    async fn test_invalid_basic_auth() -> Result<(), Box<dyn Error>> {
-        do_basic_auth(None, None).await
-    }
-
-    #[cfg(all(feature = "auth", feature = "tokio-rt"))]
+    #[cfg(all(feature = "tokio-rt", any(feature = "http1", feature = "http2")))]
     #[tokio::test]
-    async fn test_valid_basic_auth() -> Result<(), Box<dyn Error>> {
-        do_basic_auth(Some("rogerio".to_string()), Some("rpa78@rio!".to_string())).await
+    async fn test_proxy_via() -> Result<(), Box<dyn Error>> {
+        do_proxy_via().await
     }
 
-    #[cfg(all(feature = "auth", feature = "smol-rt"))]
+    #[cfg(all(feature = "smol-rt", any(feature = "http1", feature = "http2")))]
     #[apply(test!)]
-    async fn test_valid_basic_auth() -> Result<(), Box<dyn Error>> {
-        do_basic_auth(Some("rogerio".to_string()), Some("rpa78@rio!".to_string())).await
-    }
-}
-
-#[cfg(feature = "reverse-proxy")]
-mod reverse_proxy {
-    use std::error::Error;
-
-    #[cfg(any(feature = "http1", feature = "http2"))]
-    use deboa::{cert::Certificate, request};
-    #[cfg(any(feature = "http1", feature = "http2"))]
-    use http::StatusCode;
-    #[cfg(any(feature = "http1", feature = "http2"))]
-    use http_body_util::BodyExt;
-
-    #[cfg(feature = "smol-rt")]
-    use macro_rules_attribute::apply;
-    #[cfg(feature = "smol-rt")]
-    use smol_macros::test;
-
-    #[cfg(any(feature = "http1", feature = "http2"))]
-    use crate::{
-        config::server::{
-            virtual_host::{SecurityConfig, VirtualHostConfig},
-            ListenerConfig, ServerConfig,
-        },
-        server::virtual_host::{
-            handler_fn,
-            path::{proxy::ProxyPath, HandlerPath},
-            VirtualHost,
-        },
-        tests::{CA_CERT, SERVER_CERT, SERVER_KEY},
-    };
-
-    use crate::{
-        config::server::virtual_host::path::proxy::ProxyPathConfig,
-        errors::{ConfigError, VetisError},
-    };
-
-    #[test]
-    fn test_proxy_path() -> Result<(), Box<dyn Error>> {
-        let some_path = ProxyPathConfig::builder()
-            .uri("/test")
-            .target("http://localhost:8080")
-            .build()?;
-
-        assert_eq!(some_path.uri(), "/test");
-        assert_eq!(some_path.target(), "http://localhost:8080");
-
-        Ok(())
-    }
-
-    #[test]
-    fn test_invalid_proxy_path() -> Result<(), Box<dyn Error>> {
-        let some_path = ProxyPathConfig::builder()
-            .uri("")
-            .target("http://localhost:8080")
-            .build();
-
-        assert!(some_path.is_err());
-        assert_eq!(
-            some_path.err(),
-            Some(VetisError::Config(ConfigError::Path("URI cannot be empty".into(),)))
-        );
-
-        Ok(())
-    }
-
-    #[test]
-    fn test_invalid_proxy_path_target() -> Result<(), Box<dyn Error>> {
-        let some_path = ProxyPathConfig::builder()
-            .uri("/test")
-            .target("")
-            .build();
-
-        assert!(some_path.is_err());
-        assert_eq!(
-            some_path.err(),
-            Some(VetisError::Config(ConfigError::Path("Target cannot be empty".into(),)))
-        );
-
-        Ok(())
+    async fn test_proxy_via() -> Result<(), Box<dyn Error>> {
+        do_proxy_via().await
     }
 
     #[cfg(any(feature = "http1", feature = "http2"))]
-    async fn do_get_proxy_to_target() -> Result<(), Box<dyn Error>> {
-        use crate::tests::default_protocol;
+    async fn do_proxy_deadline() -> Result<(), Box<dyn Error>> {
+        use std::time::{Duration, Instant};
 
-        let source_listener = ListenerConfig::builder()
-            .port(8084)
-            .protocol(default_protocol())
-            .interface("0.0.0.0")
-            .build()?;
+        use deboa::errors::{DeboaError, ResponseError};
 
-        let target_listener = ListenerConfig::builder()
-            .port(8085)
-            .protocol(default_protocol())
-            .interface("0.0.0.0")
-            .build()?;
+        use crate::{tests::default_protocol, utils::time::sleep};
 
-        let config = ServerConfig::builder()
-            .add_listener(source_listener)
-            .add_listener(target_listener)
-            .build()?;
+        let mut config = ServerConfig::builder();
+        for port in [9142, 9143] {
+            config = config.add_listener(
+                ListenerConfig::builder()
+                    .port(port)
+                    .protocol(default_protocol())
+                    .interface("0.0.0.0")
+                    .build()?,
+            );
+        }
+        let config = config.build()?;
 
         let security_config = SecurityConfig::builder()
             .ca_cert_from_bytes(CA_CERT.to_vec())
@@ -565,42 +2789,34 @@ mod reverse_proxy {
 
         let source_config = VirtualHostConfig::builder()
             .hostname("localhost")
-            .port(8084)
+            .port(9142)
             .root_directory("src/tests")
-            .security(security_config.clone())
+            .security(security_config)
+            .request_timeout(Duration::from_millis(300))
             .build()?;
 
         let mut source_virtual_host = VirtualHost::new(source_config);
         source_virtual_host.add_path(ProxyPath::new(
             ProxyPathConfig::builder()
                 .uri("/")
-                .target("http://localhost:8085")
+                .target("http://127.0.0.10:9143")
                 .build()?,
         ));
 
         let target_config = VirtualHostConfig::builder()
-            .hostname("localhost")
-            .port(8085)
+            .hostname("127.0.0.10")
+            .port(9143)
             .root_directory("src/tests")
             .build()?;
 
-        let mut target_virtual_host = VirtualHost::new(target_config);
-        target_virtual_host.add_path(
-            HandlerPath::builder()
-                .uri("/")
-                .handler(handler_fn(|_request| async move {
-                    Ok(crate::server::http::Response::builder()
-                        .status(StatusCode::OK)
-                        .text("Hello, world!"))
-                }))
-                .build()?,
-        );
-
-        assert_eq!(
-            target_virtual_host
-                .config()
-                .hostname(),
-            "localhost"
+        let target_virtual_host = VirtualHost::with_handler(
+            target_config,
+            handler_fn(|_request| async move {
+                sleep(Duration::from_secs(3)).await;
+                Ok(crate::server::http::Response::builder()
+                    .status(StatusCode::OK)
+                    .text("Too late"))
+            }),
         );
 
         let mut server = crate::Vetis::new(config);
@@ -619,17 +2835,17 @@ mod reverse_proxy {
             .certificate(Certificate::from_slice(CA_CERT, deboa::cert::ContentEncoding::DER))
             .build();
 
-        let request = request::get("https://localhost:8085/")?
+        let started = Instant::now();
+        let response = request::get("https://localhost:9142/")?
             .send_with(&client)
-            .await?;
+            .await;
 
-        assert_eq!(request.status(), StatusCode::OK);
-        assert_eq!(
-            request
-                .text()
-                .await?,
-            "Hello, world!"
-        );
+        assert!(matches!(
+            response.err(),
+            Some(DeboaError::Response(ResponseError::Receive { status_code, .. }))
+                if status_code == StatusCode::GATEWAY_TIMEOUT
+        ));
+        assert!(started.elapsed() < Duration::from_secs(2));
 
         server
             .stop()
@@ -640,36 +2856,38 @@ mod reverse_proxy {
 
     #[cfg(all(feature = "tokio-rt", any(feature = "http1", feature = "http2")))]
     #[tokio::test]
-    async fn test_get_proxy_to_target() -> Result<(), Box<dyn Error>> {
-        do_get_proxy_to_target().await
+    async fn test_proxy_deadline() -> Result<(), Box<dyn Error>> {
+        do_proxy_deadline().await
     }
 
     #[cfg(all(feature = "smol-rt", any(feature = "http1", feature = "http2")))]
     #[apply(test!)]
-    async fn test_get_proxy_to_target() -> Result<(), Box<dyn Error>> {
-        do_get_proxy_to_target().await
+    async fn test_proxy_deadline() -> Result<(), Box<dyn Error>> {
+        do_proxy_deadline().await
     }
 
     #[cfg(any(feature = "http1", feature = "http2"))]
-    async fn do_post_proxy_to_target() -> Result<(), Box<dyn Error>> {
-        use crate::tests::default_protocol;
+    async fn do_proxy_retries() -> Result<(), Box<dyn Error>> {
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
 
-        let source_listener = ListenerConfig::builder()
-            .port(9093)
-            .protocol(default_protocol())
-            .interface("0.0.0.0")
-            .build()?;
+        use deboa::errors::{DeboaError, ResponseError};
 
-        let target_listener = ListenerConfig::builder()
-            .port(9094)
-            .protocol(default_protocol())
-            .interface("0.0.0.0")
-            .build()?;
+        use crate::tests::default_protocol;
 
-        let config = ServerConfig::builder()
-            .add_listener(source_listener)
-            .add_listener(target_listener)
-            .build()?;
+        let mut config = ServerConfig::builder();
+        for port in [9156, 9157] {
+            config = config.add_listener(
+                ListenerConfig::builder()
+                    .port(port)
+                    .protocol(default_protocol())
+                    .interface("0.0.0.0")
+                    .build()?,
+            );
+        }
+        let config = config.build()?;
 
         let security_config = SecurityConfig::builder()
             .ca_cert_from_bytes(CA_CERT.to_vec())
@@ -679,48 +2897,46 @@ mod reverse_proxy {
 
         let source_config = VirtualHostConfig::builder()
             .hostname("localhost")
-            .port(9093)
+            .port(9156)
             .root_directory("src/tests")
-            .security(security_config.clone())
+            .security(security_config)
             .build()?;
 
         let mut source_virtual_host = VirtualHost::new(source_config);
         source_virtual_host.add_path(ProxyPath::new(
             ProxyPathConfig::builder()
                 .uri("/")
-                .target("http://localhost:9094")
+                .target("http://127.0.0.12:9157")
+                .max_retries(1)
+                .add_retry_status(503)
+                .retry_connection_errors(false)
                 .build()?,
         ));
 
         let target_config = VirtualHostConfig::builder()
-            .hostname("localhost")
-            .port(9094)
+            .hostname("127.0.0.12")
+            .port(9157)
             .root_directory("src/tests")
             .build()?;
 
-        let mut target_virtual_host = VirtualHost::new(target_config);
-        target_virtual_host.add_path(
-            HandlerPath::builder()
-                .uri("/")
-                .handler(handler_fn(|request| async move {
-                    let (_parts, body) = request.into_parts();
-                    let text = body
-                        .collect()
-                        .await
-                        .unwrap()
-                        .to_bytes();
+        // Fails every other request, starting with the first
+        let requests = Arc::new(AtomicUsize::new(0));
+        let backend_requests = requests.clone();
+        let target_virtual_host = VirtualHost::with_handler(
+            target_config,
+            handler_fn(move |_request| {
+                let attempt = backend_requests.fetch_add(1, Ordering::Relaxed);
+                async move {
+                    let status = if attempt % 2 == 0 {
+                        StatusCode::SERVICE_UNAVAILABLE
+                    } else {
+                        StatusCode::OK
+                    };
                     Ok(crate::server::http::Response::builder()
-                        .status(StatusCode::OK)
-                        .bytes(text.as_ref()))
-                }))
-                .build()?,
-        );
-
-        assert_eq!(
-            target_virtual_host
-                .config()
-                .hostname(),
-            "localhost"
+                        .status(status)
+                        .text("backend"))
+                }
+            }),
         );
 
         let mut server = crate::Vetis::new(config);
@@ -739,8 +2955,7 @@ mod reverse_proxy {
             .certificate(Certificate::from_slice(CA_CERT, deboa::cert::ContentEncoding::DER))
             .build();
 
-        let response = request::post("https://localhost:9093/")?
-            .text("Something cool!")
+        let response = request::get("https://localhost:9156/")?
             .send_with(&client)
             .await?;
 
@@ -749,8 +2964,22 @@ mod reverse_proxy {
             response
                 .text()
                 .await?,
-            "Something cool!"
+            "backend"
         );
+        assert_eq!(requests.load(Ordering::Relaxed), 2);
+
+        // POST isn't idempotent, so the failure isn't retried and reaches the client
+        let response = request::post("https://localhost:9156/")?
+            .text("payload")
+            .send_with(&client)
+            .await;
+
+        assert!(matches!(
+            response.err(),
+            Some(DeboaError::Response(ResponseError::Receive { status_code, .. }))
+                if status_code == StatusCode::SERVICE_UNAVAILABLE
+        ));
+        assert_eq!(requests.load(Ordering::Relaxed), 3);
 
         server
             .stop()
@@ -761,14 +2990,26 @@ mod reverse_proxy {
 
     #[cfg(all(feature = "tokio-rt", any(feature = "http1", feature = "http2")))]
     #[tokio::test]
-    async fn test_post_proxy_to_target() -> Result<(), Box<dyn Error>> {
-        do_post_proxy_to_target().await
+    async fn test_proxy_retries() -> Result<(), Box<dyn Error>> {
+        do_proxy_retries().await
     }
 
     #[cfg(all(feature = "smol-rt", any(feature = "http1", feature = "http2")))]
     #[apply(test!)]
-    async fn test_post_proxy_to_target() -> Result<(), Box<dyn Error>> {
-        do_post_proxy_to_target().await
+    async fn test_proxy_retries() -> Result<(), Box<dyn Error>> {
+        do_proxy_retries().await
+    }
+
+    #[cfg(all(feature = "tokio-rt", any(feature = "http1", feature = "http2")))]
+    #[tokio::test]
+    async fn test_hop_by_hop_headers() -> Result<(), Box<dyn Error>> {
+        do_hop_by_hop_headers().await
+    }
+
+    #[cfg(all(feature = "smol-rt", any(feature = "http1", feature = "http2")))]
+    #[apply(test!)]
+    async fn test_hop_by_hop_headers() -> Result<(), Box<dyn Error>> {
+        do_hop_by_hop_headers().await
     }
 }
 
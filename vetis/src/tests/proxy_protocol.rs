@@ -0,0 +1,111 @@
+mod proxy_protocol_tests {
+    use std::net::SocketAddr;
+
+    #[cfg(feature = "smol-rt")]
+    use macro_rules_attribute::apply;
+    #[cfg(feature = "smol-rt")]
+    use smol_macros::test;
+
+    use crate::{errors::VetisError, server::conn::proxy_protocol::read_proxy_header};
+
+    const V2_SIGNATURE: &[u8] = b"\r\n\r\n\0\r\nQUIT\n";
+
+    fn v2_header(command: u8, family: u8, addresses: &[u8]) -> Vec<u8> {
+        let mut header = V2_SIGNATURE.to_vec();
+        header.push(0x20 | command);
+        header.push(family);
+        header.extend_from_slice(&(addresses.len() as u16).to_be_bytes());
+        header.extend_from_slice(addresses);
+        header
+    }
+
+    async fn do_proxy_protocol_v1() -> Result<(), Box<dyn std::error::Error>> {
+        let mut stream: &[u8] =
+            b"PROXY TCP4 203.0.113.7 198.51.100.1 56324 443\r\nGET / HTTP/1.1\r\n";
+        let source = read_proxy_header(&mut stream).await?;
+        assert_eq!(source, Some("203.0.113.7:56324".parse::<SocketAddr>()?));
+        // Only the header is consumed
+        assert_eq!(stream, b"GET / HTTP/1.1\r\n");
+
+        let mut stream: &[u8] = b"PROXY TCP6 2001:db8::7 2001:db8::1 56324 443\r\n";
+        let source = read_proxy_header(&mut stream).await?;
+        assert_eq!(source, Some("[2001:db8::7]:56324".parse::<SocketAddr>()?));
+
+        let mut stream: &[u8] = b"PROXY UNKNOWN\r\n";
+        assert_eq!(read_proxy_header(&mut stream).await?, None);
+
+        for header in [
+            &b"PROXY TCP4 2001:db8::7 198.51.100.1 56324 443\r\n"[..],
+            b"PROXY TCP4 203.0.113.7 56324\r\n",
+            b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n",
+        ] {
+            let mut stream = header;
+            assert!(matches!(
+                read_proxy_header(&mut stream).await,
+                Err(VetisError::ProxyProtocol(_))
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_proxy_protocol_v1() -> Result<(), Box<dyn std::error::Error>> {
+        do_proxy_protocol_v1().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_proxy_protocol_v1() -> Result<(), Box<dyn std::error::Error>> {
+        do_proxy_protocol_v1().await
+    }
+
+    async fn do_proxy_protocol_v2() -> Result<(), Box<dyn std::error::Error>> {
+        let mut header =
+            v2_header(0x1, 0x11, &[203, 0, 113, 7, 198, 51, 100, 1, 0xdc, 0x04, 0x01, 0xbb]);
+        header.extend_from_slice(b"GET / HTTP/1.1\r\n");
+        let mut stream = header.as_slice();
+        let source = read_proxy_header(&mut stream).await?;
+        assert_eq!(source, Some("203.0.113.7:56324".parse::<SocketAddr>()?));
+        assert_eq!(stream, b"GET / HTTP/1.1\r\n");
+
+        let mut addresses = "2001:db8::7"
+            .parse::<std::net::Ipv6Addr>()?
+            .octets()
+            .to_vec();
+        addresses.extend_from_slice(
+            &"2001:db8::1"
+                .parse::<std::net::Ipv6Addr>()?
+                .octets(),
+        );
+        addresses.extend_from_slice(&[0xdc, 0x04, 0x01, 0xbb]);
+        let header = v2_header(0x1, 0x21, &addresses);
+        let mut stream = header.as_slice();
+        let source = read_proxy_header(&mut stream).await?;
+        assert_eq!(source, Some("[2001:db8::7]:56324".parse::<SocketAddr>()?));
+
+        // LOCAL commands keep the peer address
+        let header = v2_header(0x0, 0x00, &[]);
+        let mut stream = header.as_slice();
+        assert_eq!(read_proxy_header(&mut stream).await?, None);
+
+        let header = v2_header(0x1, 0x11, &[203, 0, 113, 7]);
+        let mut stream = header.as_slice();
+        assert!(matches!(read_proxy_header(&mut stream).await, Err(VetisError::ProxyProtocol(_))));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_proxy_protocol_v2() -> Result<(), Box<dyn std::error::Error>> {
+        do_proxy_protocol_v2().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_proxy_protocol_v2() -> Result<(), Box<dyn std::error::Error>> {
+        do_proxy_protocol_v2().await
+    }
+}
@@ -0,0 +1,151 @@
+mod request_tests {
+    use std::sync::Arc;
+
+    use http::{header, HeaderValue};
+    use hyper_body_utils::HttpBody;
+
+    use crate::server::http::{Request, TlsInfo};
+
+    fn request_with(headers: &[(header::HeaderName, HeaderValue)]) -> Request {
+        let mut request = http::Request::builder().uri("/");
+        for (name, value) in headers {
+            request = request.header(name, value.clone());
+        }
+
+        let (parts, body) = request
+            .body(HttpBody::from_text(""))
+            .unwrap()
+            .into_parts();
+
+        Request::from_parts(parts, body)
+    }
+
+    #[test]
+    fn test_header() {
+        let request =
+            request_with(&[(header::USER_AGENT, HeaderValue::from_static("vetis-test/1.0"))]);
+        assert_eq!(request.header("user-agent"), Some("vetis-test/1.0"));
+        assert_eq!(request.header("User-Agent"), Some("vetis-test/1.0"));
+        assert_eq!(request.header(header::USER_AGENT), Some("vetis-test/1.0"));
+        assert_eq!(request.header(String::from("user-agent")), Some("vetis-test/1.0"));
+
+        let request = request_with(&[]);
+        assert_eq!(request.header("user-agent"), None);
+
+        let request = request_with(&[(
+            header::USER_AGENT,
+            HeaderValue::from_bytes(b"vetis-\xfftest").unwrap(),
+        )]);
+        assert_eq!(request.header("user-agent"), None);
+    }
+
+    #[test]
+    fn test_content_type() {
+        let request = request_with(&[(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json; charset=utf-8"),
+        )]);
+        assert_eq!(request.content_type(), Some("application/json; charset=utf-8"));
+
+        let request = request_with(&[]);
+        assert_eq!(request.content_type(), None);
+
+        let request = request_with(&[(
+            header::CONTENT_TYPE,
+            HeaderValue::from_bytes(b"text/\xffplain").unwrap(),
+        )]);
+        assert_eq!(request.content_type(), None);
+    }
+
+    #[test]
+    fn test_content_length() {
+        let request = request_with(&[(header::CONTENT_LENGTH, HeaderValue::from_static("42"))]);
+        assert_eq!(request.content_length(), Some(42));
+
+        let request = request_with(&[]);
+        assert_eq!(request.content_length(), None);
+
+        for malformed in ["abc", "-1", "", "18446744073709551616"] {
+            let request =
+                request_with(&[(header::CONTENT_LENGTH, HeaderValue::from_static(malformed))]);
+            assert_eq!(request.content_length(), None, "{malformed:?}");
+        }
+    }
+
+    #[test]
+    fn test_accepts() {
+        let request = request_with(&[(
+            header::ACCEPT,
+            HeaderValue::from_static("text/html;q=0.9, application/json, image/*;q=0.5, */*;q=0"),
+        )]);
+        assert!(request.accepts("text/html"));
+        assert!(request.accepts("application/json"));
+        assert!(request.accepts("image/png"));
+        assert!(!request.accepts("text/plain"));
+
+        let request = request_with(&[]);
+        assert!(request.accepts("application/xml"));
+    }
+
+    #[test]
+    fn test_preferred() {
+        let request = request_with(&[(
+            header::ACCEPT,
+            HeaderValue::from_static("text/html;q=0.9, application/json"),
+        )]);
+        assert_eq!(request.preferred(&["text/html", "application/json"]), Some("application/json"));
+        assert_eq!(request.preferred(&["text/html", "text/plain"]), Some("text/html"));
+        assert_eq!(request.preferred(&["text/plain"]), None);
+
+        let request =
+            request_with(&[(header::ACCEPT, HeaderValue::from_static("*/*, text/html;q=0"))]);
+        assert_eq!(request.preferred(&["text/html", "application/json"]), Some("application/json"));
+
+        let request = request_with(&[]);
+        assert_eq!(request.preferred(&["text/html", "application/json"]), Some("text/html"));
+    }
+
+    /// Builds a request for the URI, as if it arrived on a TLS connection when `secure`.
+    fn request_to(uri: &str, host: Option<&'static str>, secure: bool) -> Request {
+        let mut request = http::Request::builder().uri(uri);
+        if let Some(host) = host {
+            request = request.header(header::HOST, HeaderValue::from_static(host));
+        }
+        if secure {
+            request = request.extension(Arc::new(TlsInfo {
+                sni: Some("localhost".to_string()),
+                alpn: None,
+                peer_certificates: None,
+            }));
+        }
+
+        let (parts, body) = request
+            .body(HttpBody::from_text(""))
+            .unwrap()
+            .into_parts();
+
+        Request::from_parts(parts, body)
+    }
+
+    #[test]
+    fn test_full_url() {
+        let request = request_to("/search?q=vetis", Some("localhost:8443"), true);
+        assert_eq!(request.scheme(), "https");
+        assert_eq!(request.host(), Some("localhost:8443"));
+        assert_eq!(request.full_url(), Some("https://localhost:8443/search?q=vetis".to_string()));
+
+        let request = request_to("/", Some("example.com"), false);
+        assert_eq!(request.scheme(), "http");
+        assert_eq!(request.host(), Some("example.com"));
+        assert_eq!(request.full_url(), Some("http://example.com/".to_string()));
+
+        // The URI authority wins over the host header
+        let request = request_to("http://example.org/docs", Some("example.com"), false);
+        assert_eq!(request.host(), Some("example.org"));
+        assert_eq!(request.full_url(), Some("http://example.org/docs".to_string()));
+
+        let request = request_to("/", None, false);
+        assert_eq!(request.host(), None);
+        assert_eq!(request.full_url(), None);
+    }
+}
@@ -0,0 +1,196 @@
+mod response_tests {
+    use bytes::Bytes;
+    use http::{header, HeaderValue, StatusCode};
+    use http_body_util::BodyExt;
+    use hyper_body_utils::HttpBody;
+    #[cfg(feature = "smol-rt")]
+    use macro_rules_attribute::apply;
+    #[cfg(feature = "smol-rt")]
+    use smol_macros::test;
+    use std::error::Error;
+
+    use crate::server::http::Response;
+
+    #[test]
+    fn test_attachment() {
+        let response = Response::builder()
+            .status(StatusCode::OK)
+            .attachment("report.csv")
+            .text("id,name\n");
+
+        assert_eq!(
+            response
+                .into_inner()
+                .headers()
+                .get(header::CONTENT_DISPOSITION),
+            Some(&HeaderValue::from_static("attachment; filename=\"report.csv\""))
+        );
+    }
+
+    #[test]
+    fn test_attachment_utf8() {
+        let response = Response::builder()
+            .status(StatusCode::OK)
+            .attachment("relatório \"final\".pdf")
+            .text("");
+
+        assert_eq!(
+            response
+                .into_inner()
+                .headers()
+                .get(header::CONTENT_DISPOSITION),
+            Some(&HeaderValue::from_static(
+                "attachment; filename=\"relat_rio _final_.pdf\"; filename*=UTF-8''relat%C3%B3rio%20%22final%22.pdf"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_status_shortcuts() {
+        for (response, status) in [
+            (Response::builder().no_content(), StatusCode::NO_CONTENT),
+            (Response::builder().not_modified(), StatusCode::NOT_MODIFIED),
+            (Response::builder().accepted(), StatusCode::ACCEPTED),
+        ] {
+            let response = response.into_inner();
+            assert_eq!(response.status(), status);
+            assert!(!response
+                .headers()
+                .contains_key(header::LOCATION));
+        }
+    }
+
+    #[test]
+    fn test_content_length() {
+        let response = Response::builder()
+            .status(StatusCode::OK)
+            .text("hello, world")
+            .into_inner();
+        assert_eq!(
+            response
+                .headers()
+                .get(header::CONTENT_LENGTH),
+            Some(&HeaderValue::from(12))
+        );
+
+        let response = Response::builder()
+            .status(StatusCode::OK)
+            .bytes(vec![0u8; 64])
+            .into_inner();
+        assert_eq!(
+            response
+                .headers()
+                .get(header::CONTENT_LENGTH),
+            Some(&HeaderValue::from(64))
+        );
+
+        // A length set by the handler is kept
+        let response = Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_LENGTH, HeaderValue::from(5))
+            .text("")
+            .into_inner();
+        assert_eq!(
+            response
+                .headers()
+                .get_all(header::CONTENT_LENGTH)
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![&HeaderValue::from(5)]
+        );
+
+        for response in [Response::builder().no_content(), Response::builder().not_modified()] {
+            assert!(!response
+                .into_inner()
+                .headers()
+                .contains_key(header::CONTENT_LENGTH));
+        }
+    }
+
+    #[test]
+    fn test_created() -> Result<(), Box<dyn std::error::Error>> {
+        let response = Response::builder()
+            .created("/users/42")?
+            .into_inner();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(
+            response
+                .headers()
+                .get(header::LOCATION),
+            Some(&HeaderValue::from_static("/users/42"))
+        );
+
+        assert!(Response::builder()
+            .created("/users/\n42")
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_status_code() -> Result<(), Box<dyn std::error::Error>> {
+        let response = Response::builder()
+            .status_code(418)?
+            .text("")
+            .into_inner();
+        assert_eq!(response.status(), StatusCode::IM_A_TEAPOT);
+
+        for code in [0, 99, 1000] {
+            assert!(
+                Response::builder()
+                    .status_code(code)
+                    .is_err(),
+                "{}",
+                code
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn do_map_body() -> Result<(), Box<dyn Error>> {
+        let response = Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, HeaderValue::from_static("text/plain"))
+            .text("hello, world")
+            .map_body(|body| {
+                HttpBody::Stream(
+                    body.map_frame(|frame| {
+                        frame.map_data(|data| Bytes::from(data.to_ascii_uppercase()))
+                    })
+                    .boxed(),
+                )
+            })
+            .into_inner();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response
+                .headers()
+                .get(header::CONTENT_TYPE),
+            Some(&HeaderValue::from_static("text/plain"))
+        );
+
+        let body = response
+            .into_body()
+            .collect()
+            .await?
+            .to_bytes();
+        assert_eq!(body, Bytes::from_static(b"HELLO, WORLD"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_map_body() -> Result<(), Box<dyn Error>> {
+        do_map_body().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_map_body() -> Result<(), Box<dyn Error>> {
+        do_map_body().await
+    }
+}
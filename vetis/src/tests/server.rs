@@ -1,23 +1,40 @@
 mod server_tests {
     use deboa::{
-        cert::{Certificate, ContentEncoding},
+        cert::{Certificate, ContentEncoding, Identity},
+        errors::{DeboaError, ResponseError},
         request,
     };
+    use futures_util::future::join;
     use http::StatusCode;
     #[cfg(feature = "smol-rt")]
     use macro_rules_attribute::apply;
     #[cfg(feature = "smol-rt")]
     use smol_macros::test;
-    use std::error::Error;
+    use std::{
+        error::Error,
+        future::poll_fn,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
+        task::{Poll, Waker},
+    };
 
+    #[cfg(feature = "http1")]
+    use crate::config::server::{AmbiguousLengthPolicy, EmptyHostsPolicy};
     use crate::{
         config::server::{
+            acl::AclConfig,
             virtual_host::{SecurityConfig, VirtualHostConfig},
             ListenerConfig, ServerConfig,
         },
-        server::virtual_host::{handler_fn, path::HandlerPath, VirtualHost},
+        server::{
+            conn::shutdown::shutdown_channel,
+            virtual_host::{handler_fn, path::HandlerPath, VirtualHost},
+        },
         tests::{
-            default_protocol, CA_CERT, IP6_SERVER_CERT, IP6_SERVER_KEY, SERVER_CERT, SERVER_KEY,
+            capture_logs, captured_logs, default_protocol, CA_CERT, CLIENT_CERT, CLIENT_KEY,
+            GUEST_CERT, GUEST_KEY, IP6_SERVER_CERT, IP6_SERVER_KEY, SERVER_CERT, SERVER_KEY,
         },
     };
 
@@ -158,4 +175,3573 @@ mod server_tests {
     async fn test_multiple_interfaces_smol() -> Result<(), Box<dyn Error>> {
         do_multiple_interfaces().await
     }
+
+    /// One-shot latch whose wait future is `Sync`, as required by handlers.
+    #[derive(Default)]
+    struct Latch {
+        open: AtomicBool,
+        wakers: Mutex<Vec<Waker>>,
+    }
+
+    impl Latch {
+        fn open(&self) {
+            self.open
+                .store(true, Ordering::SeqCst);
+            for waker in self
+                .wakers
+                .lock()
+                .unwrap()
+                .drain(..)
+            {
+                waker.wake();
+            }
+        }
+
+        async fn wait(&self) {
+            poll_fn(|cx| {
+                let mut wakers = self
+                    .wakers
+                    .lock()
+                    .unwrap();
+                if self
+                    .open
+                    .load(Ordering::SeqCst)
+                {
+                    return Poll::Ready(());
+                }
+                wakers.push(cx.waker().clone());
+                Poll::Pending
+            })
+            .await
+        }
+    }
+
+    async fn do_graceful_shutdown() -> Result<(), Box<dyn Error>> {
+        let listener = ListenerConfig::builder()
+            .port(8090)
+            .protocol(default_protocol())
+            .interface("0.0.0.0")
+            .build()?;
+
+        let config = ServerConfig::builder()
+            .add_listener(listener)
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let localhost_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(8090)
+            .root_directory("src/tests")
+            .security(security_config)
+            .build()?;
+
+        let started = Arc::new(Latch::default());
+        let release = Arc::new(Latch::default());
+        let handler_started = started.clone();
+        let handler_release = release.clone();
+
+        let slow_path = HandlerPath::builder()
+            .uri("/slow")
+            .handler(handler_fn(move |_request| {
+                let started = handler_started.clone();
+                let release = handler_release.clone();
+                async move {
+                    started.open();
+                    release.wait().await;
+                    let response = crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Finished after shutdown");
+                    Ok(response)
+                }
+            }))
+            .build()?;
+
+        let mut localhost_virtual_host = VirtualHost::new(localhost_config);
+        localhost_virtual_host.add_path(slow_path);
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(localhost_virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, ContentEncoding::DER))
+            .build();
+
+        let in_flight = async {
+            request::get("https://localhost:8090/slow")?
+                .send_with(&client)
+                .await
+        };
+
+        let shutdown = async {
+            started.wait().await;
+            server
+                .stop()
+                .await?;
+            release.open();
+            Ok::<(), Box<dyn Error>>(())
+        };
+
+        let (response, stopped) = join(in_flight, shutdown).await;
+        stopped?;
+
+        let response = response?;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response
+                .text()
+                .await?,
+            "Finished after shutdown"
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_graceful_shutdown() -> Result<(), Box<dyn Error>> {
+        do_graceful_shutdown().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_graceful_shutdown_smol() -> Result<(), Box<dyn Error>> {
+        do_graceful_shutdown().await
+    }
+
+    async fn do_listener_acl() -> Result<(), Box<dyn Error>> {
+        let allowed = ListenerConfig::builder()
+            .port(8091)
+            .protocol(default_protocol())
+            .interface("127.0.0.1")
+            .acl(
+                AclConfig::builder()
+                    .allow("127.0.0.1/32")
+                    .deny("0.0.0.0/0")
+                    .build()?,
+            )
+            .build()?;
+
+        let denied = ListenerConfig::builder()
+            .port(8092)
+            .protocol(default_protocol())
+            .interface("127.0.0.1")
+            .acl(
+                AclConfig::builder()
+                    .allow("10.0.0.0/8")
+                    .build()?,
+            )
+            .build()?;
+
+        let config = ServerConfig::builder()
+            .add_listener(allowed)
+            .add_listener(denied)
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+
+        for port in [8091, 8092] {
+            let security_config = SecurityConfig::builder()
+                .ca_cert_from_bytes(CA_CERT.to_vec())
+                .cert_from_bytes(SERVER_CERT.to_vec())
+                .key_from_bytes(SERVER_KEY.to_vec())
+                .build()?;
+
+            let localhost_config = VirtualHostConfig::builder()
+                .hostname("localhost")
+                .port(port)
+                .root_directory("src/tests")
+                .security(security_config)
+                .build()?;
+
+            let mut localhost_virtual_host = VirtualHost::new(localhost_config);
+            localhost_virtual_host.add_path(
+                HandlerPath::builder()
+                    .uri("/hello")
+                    .handler(handler_fn(|_request| async move {
+                        let response = crate::server::http::Response::builder()
+                            .status(StatusCode::OK)
+                            .text("Hello from an allowed client");
+                        Ok(response)
+                    }))
+                    .build()?,
+            );
+
+            server
+                .add_virtual_host(localhost_virtual_host)
+                .await;
+        }
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, ContentEncoding::DER))
+            .build();
+
+        let response = request::get("https://localhost:8091/hello")?
+            .send_with(&client)
+            .await?;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response
+                .text()
+                .await?,
+            "Hello from an allowed client"
+        );
+
+        // A fresh client, so the pooled connection to the allowed listener isn't reused
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, ContentEncoding::DER))
+            .build();
+
+        let response = request::get("https://localhost:8092/hello")?
+            .send_with(&client)
+            .await;
+
+        assert!(response.is_err());
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_listener_acl() -> Result<(), Box<dyn Error>> {
+        do_listener_acl().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_listener_acl_smol() -> Result<(), Box<dyn Error>> {
+        do_listener_acl().await
+    }
+
+    async fn hello_server(ports: &[u16]) -> Result<crate::Vetis, Box<dyn Error>> {
+        let mut config = ServerConfig::builder();
+        for port in ports {
+            config = config.add_listener(
+                ListenerConfig::builder()
+                    .port(*port)
+                    .protocol(default_protocol())
+                    .interface("127.0.0.1")
+                    .build()?,
+            );
+        }
+
+        let mut server = crate::Vetis::new(config.build()?);
+
+        for port in ports {
+            let security_config = SecurityConfig::builder()
+                .ca_cert_from_bytes(CA_CERT.to_vec())
+                .cert_from_bytes(SERVER_CERT.to_vec())
+                .key_from_bytes(SERVER_KEY.to_vec())
+                .build()?;
+
+            let localhost_config = VirtualHostConfig::builder()
+                .hostname("localhost")
+                .port(*port)
+                .root_directory("src/tests")
+                .security(security_config)
+                .build()?;
+
+            let mut localhost_virtual_host = VirtualHost::new(localhost_config);
+            localhost_virtual_host.add_path(
+                HandlerPath::builder()
+                    .uri("/hello")
+                    .handler(handler_fn(|_request| async move {
+                        let response = crate::server::http::Response::builder()
+                            .status(StatusCode::OK)
+                            .text("Ready");
+                        Ok(response)
+                    }))
+                    .build()?,
+            );
+
+            server
+                .add_virtual_host(localhost_virtual_host)
+                .await;
+        }
+
+        Ok(server)
+    }
+
+    async fn do_ready_after_start() -> Result<(), Box<dyn Error>> {
+        // Restart a few times, connecting right after each start without retrying
+        for _ in 0..5 {
+            let mut server = hello_server(&[9119]).await?;
+
+            server
+                .start()
+                .await?;
+
+            let client = deboa::Client::builder()
+                .certificate(Certificate::from_slice(CA_CERT, ContentEncoding::DER))
+                .build();
+
+            let response = request::get("https://localhost:9119/hello")?
+                .send_with(&client)
+                .await?;
+
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                response
+                    .text()
+                    .await?,
+                "Ready"
+            );
+
+            server
+                .stop()
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_ready_after_start() -> Result<(), Box<dyn Error>> {
+        do_ready_after_start().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_ready_after_start_smol() -> Result<(), Box<dyn Error>> {
+        do_ready_after_start().await
+    }
+
+    async fn do_binary_body() -> Result<(), Box<dyn Error>> {
+        let payload: Vec<u8> = (0..=255u8)
+            .cycle()
+            .take(4096)
+            .collect();
+        let expected = payload.clone();
+
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9124)
+                    .protocol(default_protocol())
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let localhost_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9124)
+            .root_directory("src/tests")
+            .security(security_config)
+            .build()?;
+
+        let mut localhost_virtual_host = VirtualHost::new(localhost_config);
+        localhost_virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/binary")
+                .handler(handler_fn(move |_request| {
+                    let payload = payload.clone();
+                    async move {
+                        let response = crate::server::http::Response::builder()
+                            .status(StatusCode::OK)
+                            .header(
+                                http::header::CONTENT_TYPE,
+                                http::HeaderValue::from_static("application/octet-stream"),
+                            )
+                            .bytes(payload);
+                        Ok(response)
+                    }
+                }))
+                .build()?,
+        );
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(localhost_virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, ContentEncoding::DER))
+            .build();
+
+        let response = request::get("https://localhost:9124/binary")?
+            .send_with(&client)
+            .await?;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response
+                .bytes()
+                .await,
+            expected
+        );
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_binary_body() -> Result<(), Box<dyn Error>> {
+        do_binary_body().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_binary_body_smol() -> Result<(), Box<dyn Error>> {
+        do_binary_body().await
+    }
+
+    async fn do_client_certificate() -> Result<(), Box<dyn Error>> {
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9125)
+                    .protocol(default_protocol())
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .client_auth(true)
+            .build()?;
+
+        let localhost_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9125)
+            .root_directory("src/tests")
+            .security(security_config)
+            .build()?;
+
+        let mut localhost_virtual_host = VirtualHost::new(localhost_config);
+        localhost_virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/whoami")
+                .handler(handler_fn(|request| async move {
+                    // The subject common name is stored as-is in the DER encoding
+                    let subject = request
+                        .peer_certificates()
+                        .and_then(|chain| chain.first())
+                        .is_some_and(|certificate| {
+                            certificate
+                                .windows(b"vetis-client".len())
+                                .any(|window| window == b"vetis-client")
+                        });
+                    let text = format!(
+                        "{} {} {}",
+                        request
+                            .sni()
+                            .unwrap_or("-"),
+                        request
+                            .alpn()
+                            .is_some(),
+                        subject
+                    );
+                    let response = crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text(&text);
+                    Ok(response)
+                }))
+                .build()?,
+        );
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(localhost_virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, ContentEncoding::DER))
+            .identity(Identity::from_pkcs8(CLIENT_CERT, CLIENT_KEY, ContentEncoding::DER))
+            .build();
+
+        let response = request::get("https://localhost:9125/whoami")?
+            .send_with(&client)
+            .await?;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response
+                .text()
+                .await?,
+            "localhost true true"
+        );
+
+        // Clients without a certificate are refused by the virtual host
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, ContentEncoding::DER))
+            .build();
+
+        let response = request::get("https://localhost:9125/whoami")?
+            .send_with(&client)
+            .await;
+
+        assert_eq!(
+            response.err(),
+            Some(DeboaError::Response(ResponseError::Receive {
+                status_code: StatusCode::FORBIDDEN,
+                message: "Could not process request (403 Forbidden): Forbidden".to_string()
+            }))
+        );
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_client_certificate() -> Result<(), Box<dyn Error>> {
+        do_client_certificate().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_client_certificate_smol() -> Result<(), Box<dyn Error>> {
+        do_client_certificate().await
+    }
+
+    async fn do_allowed_client_names() -> Result<(), Box<dyn Error>> {
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9126)
+                    .protocol(default_protocol())
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .client_auth(true)
+            .allowed_client_names(vec![
+                "vetis-client".to_string(),
+                "*.internal.example.com".to_string(),
+            ])
+            .build()?;
+
+        let localhost_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9126)
+            .root_directory("src/tests")
+            .security(security_config)
+            .build()?;
+
+        let mut localhost_virtual_host = VirtualHost::new(localhost_config);
+        localhost_virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/")
+                .handler(handler_fn(|_request| async move {
+                    let response = crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Authorized");
+                    Ok(response)
+                }))
+                .build()?,
+        );
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(localhost_virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, ContentEncoding::DER))
+            .identity(Identity::from_pkcs8(CLIENT_CERT, CLIENT_KEY, ContentEncoding::DER))
+            .build();
+
+        let response = request::get("https://localhost:9126/")?
+            .send_with(&client)
+            .await?;
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The guest certificate is valid, but neither its name nor its SAN is allowed
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, ContentEncoding::DER))
+            .identity(Identity::from_pkcs8(GUEST_CERT, GUEST_KEY, ContentEncoding::DER))
+            .build();
+
+        let response = request::get("https://localhost:9126/")?
+            .send_with(&client)
+            .await;
+
+        assert_eq!(
+            response.err(),
+            Some(DeboaError::Response(ResponseError::Receive {
+                status_code: StatusCode::FORBIDDEN,
+                message: "Could not process request (403 Forbidden): Forbidden".to_string()
+            }))
+        );
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_allowed_client_names() -> Result<(), Box<dyn Error>> {
+        do_allowed_client_names().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_allowed_client_names_smol() -> Result<(), Box<dyn Error>> {
+        do_allowed_client_names().await
+    }
+
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    async fn do_failed_start_releases_listeners() -> Result<(), Box<dyn Error>> {
+        let occupied = std::net::TcpListener::bind("127.0.0.1:9121")?;
+
+        let mut server = hello_server(&[9120, 9121]).await?;
+
+        let result = server.start().await;
+        assert!(matches!(result, Err(crate::errors::VetisError::Bind(_))));
+
+        // The listener bound before the failure must have been released
+        drop(std::net::TcpListener::bind("127.0.0.1:9120")?);
+        drop(occupied);
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "tokio-rt", any(feature = "http1", feature = "http2")))]
+    #[tokio::test]
+    async fn test_failed_start_releases_listeners() -> Result<(), Box<dyn Error>> {
+        do_failed_start_releases_listeners().await
+    }
+
+    #[cfg(all(feature = "smol-rt", any(feature = "http1", feature = "http2")))]
+    #[apply(test!)]
+    async fn test_failed_start_releases_listeners_smol() -> Result<(), Box<dyn Error>> {
+        do_failed_start_releases_listeners().await
+    }
+
+    #[cfg(all(feature = "http3", feature = "__rustls_awc_lc_rs"))]
+    async fn do_h3_survives_bad_clients() -> Result<(), Box<dyn Error>> {
+        use h3_quinn::quinn::{self, crypto::rustls::QuicClientConfig};
+        use rustls::{pki_types::CertificateDer, RootCertStore};
+
+        use crate::config::server::Protocol;
+
+        let listener = ListenerConfig::builder()
+            .port(8093)
+            .protocol(Protocol::Http3)
+            .interface("127.0.0.1")
+            .build()?;
+
+        let config = ServerConfig::builder()
+            .add_listener(listener)
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let localhost_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(8093)
+            .root_directory("src/tests")
+            .security(security_config)
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(VirtualHost::new(localhost_config))
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        // Datagrams that aren't QUIC at all
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0")?;
+        for garbage in [&b"\x00"[..], &[0xff; 64][..], &[0x16, 0x03, 0x01, 0x00][..]] {
+            socket.send_to(garbage, "127.0.0.1:8093")?;
+        }
+
+        let mut roots = RootCertStore::empty();
+        roots.add(CertificateDer::from(CA_CERT.to_vec()))?;
+
+        let mut tls_config = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::aws_lc_rs::default_provider(),
+        ))
+        .with_protocol_versions(&[&rustls::version::TLS13])?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+        tls_config.alpn_protocols = vec![b"h3".to_vec()];
+
+        let mut endpoint = quinn::Endpoint::client("127.0.0.1:0".parse()?)?;
+        endpoint.set_default_client_config(quinn::ClientConfig::new(Arc::new(
+            QuicClientConfig::try_from(tls_config)?,
+        )));
+
+        // A QUIC client that breaks the HTTP/3 handshake with a bogus control stream
+        let connection = endpoint
+            .connect("127.0.0.1:8093".parse()?, "localhost")?
+            .await?;
+        let mut stream = connection
+            .open_uni()
+            .await?;
+        stream
+            .write_all(&[0x00, 0xff, 0xff, 0xff, 0xff])
+            .await?;
+        let _ = stream.finish();
+        connection
+            .closed()
+            .await;
+
+        // The listener must still accept new connections
+        let connection = endpoint
+            .connect("127.0.0.1:8093".parse()?, "localhost")?
+            .await?;
+        assert!(connection
+            .close_reason()
+            .is_none());
+        connection.close(0u32.into(), b"done");
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "tokio-rt", feature = "http3", feature = "__rustls_awc_lc_rs"))]
+    #[tokio::test]
+    async fn test_h3_survives_bad_clients() -> Result<(), Box<dyn Error>> {
+        do_h3_survives_bad_clients().await
+    }
+
+    #[cfg(all(feature = "smol-rt", feature = "http3", feature = "__rustls_awc_lc_rs"))]
+    #[apply(test!)]
+    async fn test_h3_survives_bad_clients_smol() -> Result<(), Box<dyn Error>> {
+        do_h3_survives_bad_clients().await
+    }
+
+    #[cfg(all(feature = "http3", feature = "__rustls_awc_lc_rs"))]
+    async fn do_h3_malformed_frames() -> Result<(), Box<dyn Error>> {
+        use h3_quinn::quinn::{self, crypto::rustls::QuicClientConfig};
+        use rustls::{pki_types::CertificateDer, RootCertStore};
+        use std::time::Duration;
+
+        use crate::{config::server::Protocol, utils::time::timeout};
+
+        let listener = ListenerConfig::builder()
+            .port(9167)
+            .protocol(Protocol::Http3)
+            .interface("127.0.0.1")
+            .build()?;
+
+        let config = ServerConfig::builder()
+            .add_listener(listener)
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let localhost_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9167)
+            .root_directory("src/tests")
+            .security(security_config)
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(VirtualHost::new(localhost_config))
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let mut roots = RootCertStore::empty();
+        roots.add(CertificateDer::from(CA_CERT.to_vec()))?;
+
+        let mut tls_config = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::aws_lc_rs::default_provider(),
+        ))
+        .with_protocol_versions(&[&rustls::version::TLS13])?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+        tls_config.alpn_protocols = vec![b"h3".to_vec()];
+
+        let mut endpoint = quinn::Endpoint::client("127.0.0.1:0".parse()?)?;
+        endpoint.set_default_client_config(quinn::ClientConfig::new(Arc::new(
+            QuicClientConfig::try_from(tls_config)?,
+        )));
+
+        let frames: [&[u8]; 3] = [
+            // DATA before HEADERS
+            &[0x00, 0x05, b'h', b'e', b'l', b'l', b'o'],
+            // HEADERS announcing a 1 GiB header section
+            &[0x01, 0xc0, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x00],
+            // HEADERS that aren't valid QPACK
+            &[0x01, 0x04, 0xff, 0xff, 0xff, 0xff],
+        ];
+
+        for frame in frames {
+            let connection = endpoint
+                .connect("127.0.0.1:9167".parse()?, "localhost")?
+                .await?;
+            let (mut send, mut recv) = connection
+                .open_bi()
+                .await?;
+            send.write_all(frame)
+                .await?;
+            let _ = send.finish();
+
+            // The stream is reset or the connection closed, without any response
+            let response = timeout(Duration::from_secs(5), recv.read_to_end(64 * 1024))
+                .await
+                .ok_or("malformed frame left the stream open")?;
+            assert!(response.is_err(), "{:?}", response);
+
+            connection.close(0u32.into(), b"done");
+        }
+
+        // The listener must still serve well-formed connections
+        let connection = endpoint
+            .connect("127.0.0.1:9167".parse()?, "localhost")?
+            .await?;
+        assert!(connection
+            .close_reason()
+            .is_none());
+        connection.close(0u32.into(), b"done");
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "tokio-rt", feature = "http3", feature = "__rustls_awc_lc_rs"))]
+    #[tokio::test]
+    async fn test_h3_malformed_frames() -> Result<(), Box<dyn Error>> {
+        do_h3_malformed_frames().await
+    }
+
+    #[cfg(all(feature = "smol-rt", feature = "http3", feature = "__rustls_awc_lc_rs"))]
+    #[apply(test!)]
+    async fn test_h3_malformed_frames_smol() -> Result<(), Box<dyn Error>> {
+        do_h3_malformed_frames().await
+    }
+
+    #[cfg(all(feature = "http3", feature = "__rustls_awc_lc_rs"))]
+    async fn do_h3_idle_timeout() -> Result<(), Box<dyn Error>> {
+        use h3_quinn::quinn::{self, crypto::rustls::QuicClientConfig, ConnectionError};
+        use rustls::{pki_types::CertificateDer, RootCertStore};
+        use std::time::{Duration, Instant};
+
+        use crate::{
+            config::server::{http3::Http3Config, Protocol},
+            utils::time::timeout,
+        };
+
+        let http3 = Http3Config::builder()
+            .max_idle_timeout(Duration::from_millis(300))
+            .build()?;
+
+        let listener = ListenerConfig::builder()
+            .port(8094)
+            .protocol(Protocol::Http3)
+            .interface("127.0.0.1")
+            .http3(http3)
+            .build()?;
+
+        let config = ServerConfig::builder()
+            .add_listener(listener)
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let localhost_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(8094)
+            .root_directory("src/tests")
+            .security(security_config)
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(VirtualHost::new(localhost_config))
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let mut roots = RootCertStore::empty();
+        roots.add(CertificateDer::from(CA_CERT.to_vec()))?;
+
+        let mut tls_config = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::aws_lc_rs::default_provider(),
+        ))
+        .with_protocol_versions(&[&rustls::version::TLS13])?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+        tls_config.alpn_protocols = vec![b"h3".to_vec()];
+
+        let mut endpoint = quinn::Endpoint::client("127.0.0.1:0".parse()?)?;
+        endpoint.set_default_client_config(quinn::ClientConfig::new(Arc::new(
+            QuicClientConfig::try_from(tls_config)?,
+        )));
+
+        let connection = endpoint
+            .connect("127.0.0.1:8094".parse()?, "localhost")?
+            .await?;
+
+        // The client announces the default 30s timeout, so the server's 300ms wins
+        let started = Instant::now();
+        let reason = timeout(Duration::from_secs(5), connection.closed()).await;
+        assert!(matches!(reason, Some(ConnectionError::TimedOut)));
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "tokio-rt", feature = "http3", feature = "__rustls_awc_lc_rs"))]
+    #[tokio::test]
+    async fn test_h3_idle_timeout() -> Result<(), Box<dyn Error>> {
+        do_h3_idle_timeout().await
+    }
+
+    #[cfg(all(feature = "smol-rt", feature = "http3", feature = "__rustls_awc_lc_rs"))]
+    #[apply(test!)]
+    async fn test_h3_idle_timeout_smol() -> Result<(), Box<dyn Error>> {
+        do_h3_idle_timeout().await
+    }
+
+    #[cfg(not(all(feature = "http1", feature = "http2", feature = "http3")))]
+    async fn do_disabled_protocol() -> Result<(), Box<dyn Error>> {
+        use crate::{config::server::Protocol, errors::ConfigError, errors::VetisError};
+
+        #[cfg(not(feature = "http2"))]
+        let (protocol, feature) = (Protocol::Http2, "http2");
+        #[cfg(all(feature = "http2", not(feature = "http3")))]
+        let (protocol, feature) = (Protocol::Http3, "http3");
+        #[cfg(all(feature = "http2", feature = "http3"))]
+        let (protocol, feature) = (Protocol::Http1, "http1");
+
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9127)
+                    .protocol(protocol.clone())
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let localhost_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9127)
+            .root_directory("src/tests")
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(VirtualHost::new(localhost_config))
+            .await;
+
+        let result = server.start().await;
+
+        assert_eq!(
+            result.err(),
+            Some(VetisError::Config(ConfigError::Listener(format!(
+                "Listener on port 9127 uses {:?}, which requires the `{}` feature",
+                protocol, feature
+            ))))
+        );
+
+        Ok(())
+    }
+
+    #[cfg(all(
+        feature = "tokio-rt",
+        not(all(feature = "http1", feature = "http2", feature = "http3"))
+    ))]
+    #[tokio::test]
+    async fn test_disabled_protocol() -> Result<(), Box<dyn Error>> {
+        do_disabled_protocol().await
+    }
+
+    #[cfg(all(
+        feature = "smol-rt",
+        not(all(feature = "http1", feature = "http2", feature = "http3"))
+    ))]
+    #[apply(test!)]
+    async fn test_disabled_protocol_smol() -> Result<(), Box<dyn Error>> {
+        do_disabled_protocol().await
+    }
+
+    #[cfg(feature = "http3")]
+    async fn do_h3_plaintext_virtual_host() -> Result<(), Box<dyn Error>> {
+        use crate::{config::server::Protocol, errors::ConfigError, errors::VetisError};
+
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9128)
+                    .protocol(Protocol::Http3)
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let localhost_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9128)
+            .root_directory("src/tests")
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(VirtualHost::new(localhost_config))
+            .await;
+
+        let result = server.start().await;
+
+        assert_eq!(
+            result.err(),
+            Some(VetisError::Config(ConfigError::Listener(
+                "Virtual host localhost on port 9128 has no security configuration, which HTTP/3 requires"
+                    .to_string()
+            )))
+        );
+
+        Ok(())
+    }
+
+    async fn do_invalid_tls_fails_start() -> Result<(), Box<dyn Error>> {
+        use crate::errors::{StartError, VetisError};
+
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9153)
+                    .protocol(default_protocol())
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9154)
+                    .protocol(default_protocol())
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+        for port in [9153, 9154] {
+            let security_config = SecurityConfig::builder()
+                .cert_from_bytes(SERVER_CERT.to_vec())
+                .key_from_bytes(vec![0x01, 0x02, 0x03])
+                .build()?;
+
+            let host_config = VirtualHostConfig::builder()
+                .hostname("localhost")
+                .port(port)
+                .root_directory("src/tests")
+                .security(security_config)
+                .build()?;
+
+            server
+                .add_virtual_host(VirtualHost::new(host_config))
+                .await;
+        }
+
+        let result = server.start().await;
+        assert_eq!(
+            result.err(),
+            Some(VetisError::Start(StartError::Tls("Failed to parse private key".to_string())))
+        );
+
+        // The error is reported before any listener binds its port
+        for port in [9153, 9154] {
+            std::net::TcpListener::bind(("127.0.0.1", port))?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_invalid_tls_fails_start() -> Result<(), Box<dyn Error>> {
+        do_invalid_tls_fails_start().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_invalid_tls_fails_start_smol() -> Result<(), Box<dyn Error>> {
+        do_invalid_tls_fails_start().await
+    }
+
+    #[cfg(feature = "http3")]
+    async fn do_h3_requires_tls() -> Result<(), Box<dyn Error>> {
+        use crate::{
+            config::server::Protocol,
+            errors::{StartError, VetisError},
+        };
+
+        // The only virtual host lives on another port, none has a certificate for the listener
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9138)
+                    .protocol(Protocol::Http3)
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let localhost_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9139)
+            .root_directory("src/tests")
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(VirtualHost::new(localhost_config))
+            .await;
+
+        let result = server.start().await;
+
+        assert_eq!(
+            result.err(),
+            Some(VetisError::Start(StartError::Tls("HTTP/3 requires TLS".to_string())))
+        );
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "tokio-rt", feature = "http3"))]
+    #[tokio::test]
+    async fn test_h3_requires_tls() -> Result<(), Box<dyn Error>> {
+        do_h3_requires_tls().await
+    }
+
+    #[cfg(all(feature = "smol-rt", feature = "http3"))]
+    #[apply(test!)]
+    async fn test_h3_requires_tls_smol() -> Result<(), Box<dyn Error>> {
+        do_h3_requires_tls().await
+    }
+
+    #[cfg(all(feature = "tokio-rt", feature = "http3"))]
+    #[tokio::test]
+    async fn test_h3_plaintext_virtual_host() -> Result<(), Box<dyn Error>> {
+        do_h3_plaintext_virtual_host().await
+    }
+
+    #[cfg(all(feature = "smol-rt", feature = "http3"))]
+    #[apply(test!)]
+    async fn test_h3_plaintext_virtual_host_smol() -> Result<(), Box<dyn Error>> {
+        do_h3_plaintext_virtual_host().await
+    }
+
+    #[cfg(all(feature = "http3", feature = "__rustls_awc_lc_rs"))]
+    async fn h3_request(
+        connection: h3_quinn::quinn::Connection,
+        request: http::Request<()>,
+    ) -> Result<(http::Response<()>, String), Box<dyn Error>> {
+        use bytes::BufMut;
+        use futures_util::future::{select, Either};
+        use std::pin::pin;
+
+        let (mut driver, mut send_request) =
+            h3::client::new(h3_quinn::Connection::new(connection)).await?;
+
+        let request = async move {
+            let mut stream = send_request
+                .send_request(request)
+                .await?;
+            stream
+                .finish()
+                .await?;
+            let response = stream
+                .recv_response()
+                .await?;
+
+            let mut body = Vec::new();
+            while let Some(chunk) = stream
+                .recv_data()
+                .await?
+            {
+                body.put(chunk);
+            }
+            Ok::<_, Box<dyn Error>>((response, String::from_utf8(body)?))
+        };
+
+        match select(pin!(request), pin!(poll_fn(|cx| driver.poll_close(cx)))).await {
+            Either::Left((response, _)) => response,
+            Either::Right((err, _)) => Err(err.into()),
+        }
+    }
+
+    #[cfg(all(feature = "http3", feature = "__rustls_awc_lc_rs"))]
+    async fn h3_get(
+        connection: h3_quinn::quinn::Connection,
+        uri: &str,
+    ) -> Result<String, Box<dyn Error>> {
+        let (_, body) = h3_request(connection, http::Request::get(uri).body(())?).await?;
+        Ok(body)
+    }
+
+    #[cfg(all(feature = "http3", feature = "__rustls_awc_lc_rs"))]
+    async fn do_h3_early_data() -> Result<(), Box<dyn Error>> {
+        use h3_quinn::quinn::{self, crypto::rustls::QuicClientConfig};
+        use rustls::{pki_types::CertificateDer, RootCertStore};
+
+        use crate::config::server::Protocol;
+
+        let listener = ListenerConfig::builder()
+            .port(8095)
+            .protocol(Protocol::Http3)
+            .interface("127.0.0.1")
+            .build()?;
+
+        let config = ServerConfig::builder()
+            .add_listener(listener)
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .max_early_data_size(16 * 1024)
+            .build()?;
+
+        let localhost_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(8095)
+            .root_directory("src/tests")
+            .security(security_config)
+            .build()?;
+
+        let mut localhost_virtual_host = VirtualHost::new(localhost_config);
+        localhost_virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/early")
+                .handler(handler_fn(|request| async move {
+                    let response = crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text(
+                            &request
+                                .is_early_data()
+                                .to_string(),
+                        );
+                    Ok(response)
+                }))
+                .build()?,
+        );
+
+        // A host on the same listener that leaves early data disabled
+        let other_security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(IP6_SERVER_CERT.to_vec())
+            .key_from_bytes(IP6_SERVER_KEY.to_vec())
+            .build()?;
+
+        let other_config = VirtualHostConfig::builder()
+            .hostname("ip6-localhost")
+            .port(8095)
+            .root_directory("src/tests")
+            .security(other_security_config)
+            .build()?;
+
+        let mut other_virtual_host = VirtualHost::new(other_config);
+        other_virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/early")
+                .handler(handler_fn(|request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text(
+                            &request
+                                .is_early_data()
+                                .to_string(),
+                        ))
+                }))
+                .build()?,
+        );
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(localhost_virtual_host)
+            .await;
+        server
+            .add_virtual_host(other_virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let mut roots = RootCertStore::empty();
+        roots.add(CertificateDer::from(CA_CERT.to_vec()))?;
+
+        let mut tls_config = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::aws_lc_rs::default_provider(),
+        ))
+        .with_protocol_versions(&[&rustls::version::TLS13])?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+        tls_config.alpn_protocols = vec![b"h3".to_vec()];
+        tls_config.enable_early_data = true;
+
+        let mut endpoint = quinn::Endpoint::client("127.0.0.1:0".parse()?)?;
+        endpoint.set_default_client_config(quinn::ClientConfig::new(Arc::new(
+            QuicClientConfig::try_from(tls_config)?,
+        )));
+
+        // A full handshake, which also hands the client a session ticket
+        let connection = endpoint
+            .connect("127.0.0.1:8095".parse()?, "localhost")?
+            .await?;
+        let body = h3_get(connection.clone(), "https://localhost:8095/early").await?;
+        assert_eq!(body, "false");
+        connection.close(0u32.into(), b"done");
+
+        // A resumed session sending the request as 0-RTT data
+        let (connection, _) = endpoint
+            .connect("127.0.0.1:8095".parse()?, "localhost")?
+            .into_0rtt()
+            .map_err(|_| "Session cannot be resumed with 0-RTT")?;
+        let body = h3_get(connection.clone(), "https://localhost:8095/early").await?;
+        assert_eq!(body, "true");
+        connection.close(0u32.into(), b"done");
+
+        // Early data reaching a host that didn't opt in is answered with 425
+        let (connection, _) = endpoint
+            .connect("127.0.0.1:8095".parse()?, "localhost")?
+            .into_0rtt()
+            .map_err(|_| "Session cannot be resumed with 0-RTT")?;
+        let (response, _) = h3_request(
+            connection.clone(),
+            http::Request::get("https://ip6-localhost:8095/early").body(())?,
+        )
+        .await?;
+        assert_eq!(response.status(), StatusCode::TOO_EARLY);
+        connection.close(0u32.into(), b"done");
+
+        // The same request after the handshake reaches it
+        let connection = endpoint
+            .connect("127.0.0.1:8095".parse()?, "localhost")?
+            .await?;
+        let body = h3_get(connection.clone(), "https://ip6-localhost:8095/early").await?;
+        assert_eq!(body, "false");
+        connection.close(0u32.into(), b"done");
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "tokio-rt", feature = "http3", feature = "__rustls_awc_lc_rs"))]
+    #[tokio::test]
+    async fn test_h3_early_data() -> Result<(), Box<dyn Error>> {
+        do_h3_early_data().await
+    }
+
+    #[cfg(all(feature = "smol-rt", feature = "http3", feature = "__rustls_awc_lc_rs"))]
+    #[apply(test!)]
+    async fn test_h3_early_data_smol() -> Result<(), Box<dyn Error>> {
+        do_h3_early_data().await
+    }
+
+    #[cfg(all(feature = "http3", feature = "__rustls_awc_lc_rs", feature = "static-files"))]
+    async fn do_h3_range() -> Result<(), Box<dyn Error>> {
+        use h3_quinn::quinn::{self, crypto::rustls::QuicClientConfig};
+        use rustls::{pki_types::CertificateDer, RootCertStore};
+
+        use crate::{
+            config::server::{virtual_host::path::static_files::StaticPathConfig, Protocol},
+            server::virtual_host::path::static_files::StaticPath,
+        };
+
+        let index = std::fs::read_to_string("src/tests/files/index.html")?;
+
+        let listener = ListenerConfig::builder()
+            .port(9173)
+            .protocol(Protocol::Http3)
+            .interface("127.0.0.1")
+            .build()?;
+
+        let config = ServerConfig::builder()
+            .add_listener(listener)
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let localhost_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9173)
+            .root_directory("src/tests")
+            .security(security_config)
+            .build()?;
+
+        let mut localhost_virtual_host = VirtualHost::new(localhost_config);
+        localhost_virtual_host.add_path(StaticPath::new(
+            StaticPathConfig::builder()
+                .uri("/")
+                .extensions(".*")
+                .directory("src/tests/files")
+                .build()?,
+        ));
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(localhost_virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let mut roots = RootCertStore::empty();
+        roots.add(CertificateDer::from(CA_CERT.to_vec()))?;
+
+        let mut tls_config = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::aws_lc_rs::default_provider(),
+        ))
+        .with_protocol_versions(&[&rustls::version::TLS13])?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+        tls_config.alpn_protocols = vec![b"h3".to_vec()];
+
+        let mut endpoint = quinn::Endpoint::client("127.0.0.1:0".parse()?)?;
+        endpoint.set_default_client_config(quinn::ClientConfig::new(Arc::new(
+            QuicClientConfig::try_from(tls_config)?,
+        )));
+
+        // The last bytes of the file
+        let start = index.len() - 10;
+        let connection = endpoint
+            .connect("127.0.0.1:9173".parse()?, "localhost")?
+            .await?;
+        let request = http::Request::get("https://localhost:9173/index.html")
+            .header(http::header::RANGE, format!("bytes={}-{}", start, index.len() - 1))
+            .body(())?;
+        let (response, body) = h3_request(connection.clone(), request).await?;
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            response.headers()[http::header::CONTENT_RANGE],
+            format!("bytes {}-{}/{}", start, index.len() - 1, index.len())
+        );
+        assert_eq!(body, index[start..]);
+        connection.close(0u32.into(), b"done");
+
+        // Each request sets up its own HTTP/3 session, so it needs its own connection
+        let connection = endpoint
+            .connect("127.0.0.1:9173".parse()?, "localhost")?
+            .await?;
+        let request = http::Request::get("https://localhost:9173/index.html")
+            .header(http::header::RANGE, format!("bytes={}-{}", index.len(), index.len() + 10))
+            .body(())?;
+        let (response, body) = h3_request(connection.clone(), request).await?;
+        assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(
+            response.headers()[http::header::CONTENT_RANGE],
+            format!("bytes */{}", index.len())
+        );
+        assert!(body.is_empty());
+
+        connection.close(0u32.into(), b"done");
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(
+        feature = "tokio-rt",
+        feature = "http3",
+        feature = "__rustls_awc_lc_rs",
+        feature = "static-files"
+    ))]
+    #[tokio::test]
+    async fn test_h3_range() -> Result<(), Box<dyn Error>> {
+        do_h3_range().await
+    }
+
+    #[cfg(all(
+        feature = "smol-rt",
+        feature = "http3",
+        feature = "__rustls_awc_lc_rs",
+        feature = "static-files"
+    ))]
+    #[apply(test!)]
+    async fn test_h3_range_smol() -> Result<(), Box<dyn Error>> {
+        do_h3_range().await
+    }
+
+    async fn do_named_listener() -> Result<(), Box<dyn Error>> {
+        capture_logs();
+
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .name("public-listener")
+                    .port(9129)
+                    .protocol(default_protocol())
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let localhost_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9129)
+            .root_directory("src/tests")
+            .security(security_config)
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(VirtualHost::new(localhost_config))
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        assert_eq!(
+            captured_logs(":9129"),
+            vec!["Server listening on port 127.0.0.1:9129 (public-listener)".to_string()]
+        );
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_named_listener() -> Result<(), Box<dyn Error>> {
+        do_named_listener().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_named_listener_smol() -> Result<(), Box<dyn Error>> {
+        do_named_listener().await
+    }
+
+    async fn do_startup_summary() -> Result<(), Box<dyn Error>> {
+        capture_logs();
+
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .name("summary-listener")
+                    .port(9144)
+                    .protocol(default_protocol())
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let localhost_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9144)
+            .root_directory("src/tests")
+            .security(security_config)
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(localhost_config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/hello")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Hello"))
+                }))
+                .build()?,
+        );
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        assert_eq!(
+            captured_logs("port=9144"),
+            vec![
+                format!(
+                    "Startup summary: listener name=summary-listener interface=127.0.0.1 port=9144 protocol={:?} tls=yes",
+                    default_protocol()
+                ),
+                "Startup summary: virtual_host hostname=localhost port=9144 paths=1 secure=yes"
+                    .to_string(),
+            ]
+        );
+        assert!(captured_logs("Startup summary: features=")
+            .iter()
+            .any(|line| line.contains("http")));
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_startup_summary() -> Result<(), Box<dyn Error>> {
+        do_startup_summary().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_startup_summary_smol() -> Result<(), Box<dyn Error>> {
+        do_startup_summary().await
+    }
+
+    async fn do_header_limits() -> Result<(), Box<dyn Error>> {
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9134)
+                    .protocol(default_protocol())
+                    .interface("127.0.0.1")
+                    .max_headers(20)
+                    .max_header_bytes(2048)
+                    .build()?,
+            )
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let localhost_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9134)
+            .root_directory("src/tests")
+            .security(security_config)
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(VirtualHost::with_handler(
+                localhost_config,
+                handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Hello"))
+                }),
+            ))
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        // A new client per request, since connections answered with a 431 are closed
+        let client = || {
+            deboa::Client::builder()
+                .certificate(Certificate::from_slice(CA_CERT, ContentEncoding::DER))
+                .build()
+        };
+
+        let response = request::get("https://localhost:9134/")?
+            .header(http::HeaderName::from_static("x-small"), "small")
+            .send_with(&client())
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut many_headers = request::get("https://localhost:9134/")?;
+        for index in 0..30 {
+            many_headers = many_headers.header(
+                http::HeaderName::from_bytes(format!("x-header-{}", index).as_bytes())?,
+                "value",
+            );
+        }
+        let response = many_headers
+            .send_with(&client())
+            .await;
+        assert!(matches!(
+            response.err(),
+            Some(DeboaError::Response(ResponseError::Receive { status_code, .. }))
+                if status_code == StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+        ));
+
+        let response = request::get("https://localhost:9134/")?
+            .header(http::HeaderName::from_static("x-large"), &"a".repeat(4096))
+            .send_with(&client())
+            .await;
+        assert!(matches!(
+            response.err(),
+            Some(DeboaError::Response(ResponseError::Receive { status_code, .. }))
+                if status_code == StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+        ));
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_header_limits() -> Result<(), Box<dyn Error>> {
+        do_header_limits().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_header_limits_smol() -> Result<(), Box<dyn Error>> {
+        do_header_limits().await
+    }
+
+    async fn do_run_until() -> Result<(), Box<dyn Error>> {
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9137)
+                    .protocol(default_protocol())
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let localhost_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9137)
+            .root_directory("src/tests")
+            .security(security_config)
+            .build()?;
+
+        let mut localhost_virtual_host = VirtualHost::new(localhost_config);
+        localhost_virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/hello")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Hello"))
+                }))
+                .build()?,
+        );
+
+        let mut server = crate::Vetis::new(config);
+        server
+            .add_virtual_host(localhost_virtual_host)
+            .await;
+
+        let (stop, stopped) = shutdown_channel();
+
+        let client = async move {
+            let client = deboa::Client::builder()
+                .certificate(Certificate::from_slice(CA_CERT, ContentEncoding::DER))
+                .build();
+
+            let status = request::get("https://localhost:9137/hello")?
+                .send_with(&client)
+                .await
+                .map(|response| response.status());
+
+            stop.trigger();
+            status
+        };
+
+        let (served, status) =
+            join(server.run_until(async move { stopped.wait().await }), client).await;
+        served?;
+        assert_eq!(status?, StatusCode::OK);
+
+        assert!(request::get("https://localhost:9137/hello")?
+            .send_with(deboa::Client::default())
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_run_until() -> Result<(), Box<dyn Error>> {
+        do_run_until().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_run_until_smol() -> Result<(), Box<dyn Error>> {
+        do_run_until().await
+    }
+
+    /// Sends a raw HTTP/1 request over plain TCP and reads until the server closes the connection.
+    #[cfg(all(feature = "http1", feature = "tokio-rt"))]
+    async fn raw_http1_request(addr: &str, request: &[u8]) -> Result<String, std::io::Error> {
+        let stream = tokio::net::TcpStream::connect(addr).await?;
+
+        let mut written = 0;
+        while written < request.len() {
+            stream
+                .writable()
+                .await?;
+            match stream.try_write(&request[written..]) {
+                Ok(n) => written += n,
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let mut response = Vec::new();
+        let mut buffer = [0; 1024];
+        loop {
+            stream
+                .readable()
+                .await?;
+            match stream.try_read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => response.extend_from_slice(&buffer[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(String::from_utf8_lossy(&response).into_owned())
+    }
+
+    /// Sends a raw HTTP/1 request over plain TCP and reads until the server closes the connection.
+    #[cfg(all(feature = "http1", feature = "smol-rt"))]
+    async fn raw_http1_request(addr: &str, request: &[u8]) -> Result<String, std::io::Error> {
+        use smol::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut stream = smol::net::TcpStream::connect(addr).await?;
+        stream
+            .write_all(request)
+            .await?;
+
+        let mut response = Vec::new();
+        stream
+            .read_to_end(&mut response)
+            .await?;
+
+        Ok(String::from_utf8_lossy(&response).into_owned())
+    }
+
+    #[cfg(feature = "http1")]
+    async fn do_response_version() -> Result<(), Box<dyn Error>> {
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9140)
+                    .protocol(crate::config::server::Protocol::Http1)
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9140)
+            .root_directory("src/tests")
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/version")
+                .handler(handler_fn(|request| async move {
+                    let version = format!("{:?}", request.version());
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text(&version))
+                }))
+                .build()?,
+        );
+
+        server
+            .add_virtual_host(virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let response = request::get("http://localhost:9140/version")?
+            .send_with(deboa::Client::default())
+            .await?;
+        let (parts, _) = response.into_parts();
+        assert_eq!(parts.version, http::Version::HTTP_11);
+
+        // HTTP/1.0 clients are answered in kind, rather than with an HTTP/1.1 status line
+        let response = raw_http1_request(
+            "127.0.0.1:9140",
+            b"GET /version HTTP/1.0\r\nHost: localhost\r\n\r\n",
+        )
+        .await?;
+        assert!(response.starts_with("HTTP/1.0 200 OK\r\n"), "{}", response);
+        assert!(response.ends_with("HTTP/1.0"), "{}", response);
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "http1", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_response_version() -> Result<(), Box<dyn Error>> {
+        do_response_version().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_response_version_smol() -> Result<(), Box<dyn Error>> {
+        do_response_version().await
+    }
+
+    #[cfg(feature = "http1")]
+    async fn do_text_content_length() -> Result<(), Box<dyn Error>> {
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9170)
+                    .protocol(crate::config::server::Protocol::Http1)
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9170)
+            .root_directory("src/tests")
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/hello")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Hello, World!"))
+                }))
+                .build()?,
+        );
+
+        server
+            .add_virtual_host(virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let response = raw_http1_request(
+            "127.0.0.1:9170",
+            b"GET /hello HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        )
+        .await?;
+        let response = response.to_ascii_lowercase();
+        assert!(response.starts_with("http/1.1 200 ok\r\n"), "{}", response);
+        assert!(response.contains("\r\ncontent-length: 13\r\n"), "{}", response);
+        assert!(!response.contains("transfer-encoding"), "{}", response);
+        assert!(response.ends_with("\r\n\r\nhello, world!"), "{}", response);
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "http1", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_text_content_length() -> Result<(), Box<dyn Error>> {
+        do_text_content_length().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_text_content_length_smol() -> Result<(), Box<dyn Error>> {
+        do_text_content_length().await
+    }
+
+    #[cfg(feature = "http1")]
+    async fn do_remove_last_virtual_host() -> Result<(), Box<dyn Error>> {
+        for (port, empty_hosts) in
+            [(9171, EmptyHostsPolicy::NotFound), (9172, EmptyHostsPolicy::StopListeners)]
+        {
+            let config = ServerConfig::builder()
+                .add_listener(
+                    ListenerConfig::builder()
+                        .port(port)
+                        .protocol(crate::config::server::Protocol::Http1)
+                        .interface("127.0.0.1")
+                        .build()?,
+                )
+                .empty_hosts(empty_hosts)
+                .build()?;
+
+            let mut server = crate::Vetis::new(config);
+
+            let host_config = VirtualHostConfig::builder()
+                .hostname("localhost")
+                .port(port)
+                .root_directory("src/tests")
+                .build()?;
+
+            let mut virtual_host = VirtualHost::new(host_config);
+            virtual_host.add_path(
+                HandlerPath::builder()
+                    .uri("/hello")
+                    .handler(handler_fn(|_request| async move {
+                        Ok(crate::server::http::Response::builder()
+                            .status(StatusCode::OK)
+                            .text("Hello"))
+                    }))
+                    .build()?,
+            );
+
+            server
+                .add_virtual_host(virtual_host)
+                .await;
+
+            server
+                .start()
+                .await?;
+
+            let addr = format!("127.0.0.1:{}", port);
+            let request = b"GET /hello HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+
+            let response = raw_http1_request(&addr, request).await?;
+            assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+
+            assert!(server
+                .remove_virtual_host("localhost", 9999)
+                .await?
+                .is_none());
+            assert!(server
+                .remove_virtual_host("localhost", port)
+                .await?
+                .is_some());
+
+            match empty_hosts {
+                EmptyHostsPolicy::NotFound => {
+                    let response = raw_http1_request(&addr, request).await?;
+                    assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"), "{}", response);
+                }
+                EmptyHostsPolicy::StopListeners => {
+                    assert!(raw_http1_request(&addr, request)
+                        .await
+                        .is_err());
+                }
+            }
+
+            server
+                .stop()
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "http1", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_remove_last_virtual_host() -> Result<(), Box<dyn Error>> {
+        do_remove_last_virtual_host().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_remove_last_virtual_host_smol() -> Result<(), Box<dyn Error>> {
+        do_remove_last_virtual_host().await
+    }
+
+    #[cfg(feature = "http1")]
+    async fn do_require_tls() -> Result<(), Box<dyn Error>> {
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9147)
+                    .protocol(crate::config::server::Protocol::Http1)
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9147)
+            .root_directory("src/tests")
+            .security(security_config)
+            .require_tls(true)
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/hello")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Hello"))
+                }))
+                .build()?,
+        );
+
+        server
+            .add_virtual_host(virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, ContentEncoding::DER))
+            .build();
+
+        let response = request::get("https://localhost:9147/hello")?
+            .send_with(&client)
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The listener sniffs TLS, so plaintext reaches the host and is sent to https://
+        let response = raw_http1_request(
+            "127.0.0.1:9147",
+            b"GET /hello?name=vetis HTTP/1.1\r\nHost: localhost:9147\r\n\r\n",
+        )
+        .await?;
+        assert!(response.starts_with("HTTP/1.1 308 Permanent Redirect\r\n"), "{}", response);
+        assert!(
+            response.contains("location: https://localhost:9147/hello?name=vetis\r\n"),
+            "{}",
+            response
+        );
+        assert!(response.contains("connection: close\r\n"), "{}", response);
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "http1", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_require_tls() -> Result<(), Box<dyn Error>> {
+        do_require_tls().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_require_tls_smol() -> Result<(), Box<dyn Error>> {
+        do_require_tls().await
+    }
+
+    #[cfg(feature = "http1")]
+    async fn do_request_is_secure() -> Result<(), Box<dyn Error>> {
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9148)
+                    .protocol(crate::config::server::Protocol::Http1)
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+
+        let security_config = SecurityConfig::builder()
+            .ca_cert_from_bytes(CA_CERT.to_vec())
+            .cert_from_bytes(SERVER_CERT.to_vec())
+            .key_from_bytes(SERVER_KEY.to_vec())
+            .build()?;
+
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9148)
+            .root_directory("src/tests")
+            .security(security_config)
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/secure")
+                .handler(handler_fn(|request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text(
+                            &request
+                                .is_secure()
+                                .to_string(),
+                        ))
+                }))
+                .build()?,
+        );
+
+        server
+            .add_virtual_host(virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let client = deboa::Client::builder()
+            .certificate(Certificate::from_slice(CA_CERT, ContentEncoding::DER))
+            .build();
+
+        let response = request::get("https://localhost:9148/secure")?
+            .send_with(&client)
+            .await?;
+        assert_eq!(
+            response
+                .text()
+                .await?,
+            "true"
+        );
+
+        let response = raw_http1_request(
+            "127.0.0.1:9148",
+            b"GET /secure HTTP/1.1\r\nHost: localhost:9148\r\nConnection: close\r\n\r\n",
+        )
+        .await?;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.ends_with("\r\n\r\nfalse"), "{}", response);
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "http1", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_request_is_secure() -> Result<(), Box<dyn Error>> {
+        do_request_is_secure().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_request_is_secure_smol() -> Result<(), Box<dyn Error>> {
+        do_request_is_secure().await
+    }
+
+    #[cfg(feature = "http1")]
+    async fn do_malformed_host() -> Result<(), Box<dyn Error>> {
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9149)
+                    .protocol(crate::config::server::Protocol::Http1)
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9149)
+            .root_directory("src/tests")
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/hello")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Hello"))
+                }))
+                .build()?,
+        );
+
+        server
+            .add_virtual_host(virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        // Hosts are matched case-insensitively
+        let response = raw_http1_request(
+            "127.0.0.1:9149",
+            b"GET /hello HTTP/1.1\r\nHost: LocalHost:9149\r\nConnection: close\r\n\r\n",
+        )
+        .await?;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+
+        for host in ["", ":9149", "   "] {
+            let request =
+                format!("GET /hello HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n", host);
+            let response = raw_http1_request("127.0.0.1:9149", request.as_bytes()).await?;
+            assert!(
+                response.starts_with("HTTP/1.1 400 Bad Request\r\n"),
+                "{:?}: {}",
+                host,
+                response
+            );
+        }
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "http1", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_malformed_host() -> Result<(), Box<dyn Error>> {
+        do_malformed_host().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_malformed_host_smol() -> Result<(), Box<dyn Error>> {
+        do_malformed_host().await
+    }
+
+    #[cfg(feature = "http1")]
+    async fn do_absolute_form_request() -> Result<(), Box<dyn Error>> {
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9150)
+                    .protocol(crate::config::server::Protocol::Http1)
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+
+        for hostname in ["localhost", "127.0.0.1"] {
+            let host_config = VirtualHostConfig::builder()
+                .hostname(hostname)
+                .port(9150)
+                .root_directory("src/tests")
+                .build()?;
+
+            let mut virtual_host = VirtualHost::new(host_config);
+            let hostname = hostname.to_string();
+            virtual_host.add_path(
+                HandlerPath::builder()
+                    .uri("/hello")
+                    .handler(handler_fn(move |request| {
+                        let body = format!("{} {}", hostname, request.uri().path());
+                        async move {
+                            Ok(crate::server::http::Response::builder()
+                                .status(StatusCode::OK)
+                                .text(&body))
+                        }
+                    }))
+                    .build()?,
+            );
+
+            server
+                .add_virtual_host(virtual_host)
+                .await;
+        }
+
+        server
+            .start()
+            .await?;
+
+        // The URI authority wins over the host header, and paths match without it
+        let response = raw_http1_request(
+            "127.0.0.1:9150",
+            b"GET http://localhost:9150/hello?name=vetis HTTP/1.1\r\nHost: 127.0.0.1:9150\r\nConnection: close\r\n\r\n",
+        )
+        .await?;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.ends_with("\r\n\r\nlocalhost /hello"), "{}", response);
+
+        let response = raw_http1_request(
+            "127.0.0.1:9150",
+            b"GET http://example.com/hello HTTP/1.1\r\nHost: localhost:9150\r\nConnection: close\r\n\r\n",
+        )
+        .await?;
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"), "{}", response);
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "http1", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_absolute_form_request() -> Result<(), Box<dyn Error>> {
+        do_absolute_form_request().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_absolute_form_request_smol() -> Result<(), Box<dyn Error>> {
+        do_absolute_form_request().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "request-decompression"))]
+    async fn do_rejected_body_keep_alive() -> Result<(), Box<dyn Error>> {
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9141)
+                    .protocol(crate::config::server::Protocol::Http1)
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9141)
+            .root_directory("src/tests")
+            .max_decompressed_body_size(1024)
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/hello")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Hello"))
+                }))
+                .build()?,
+        );
+
+        server
+            .add_virtual_host(virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        // The body of a request for an unknown host is read, so the next request is still served
+        let response = raw_http1_request(
+            "127.0.0.1:9141",
+            b"POST /hello HTTP/1.1\r\nHost: unknown\r\nContent-Length: 11\r\n\r\nhello world\
+              GET /hello HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        )
+        .await?;
+        let statuses: Vec<&str> = response
+            .lines()
+            .filter(|line| line.starts_with("HTTP/1.1 "))
+            .collect();
+        assert_eq!(statuses, ["HTTP/1.1 404 Not Found", "HTTP/1.1 200 OK"], "{}", response);
+
+        // A body rejected half way closes the connection instead
+        let mut request = b"POST /hello HTTP/1.1\r\nHost: localhost\r\nContent-Encoding: gzip\r\n\
+                            Content-Length: 2000\r\n\r\n"
+            .to_vec();
+        request.extend_from_slice(&[0; 2000]);
+        request.extend_from_slice(b"GET /hello HTTP/1.1\r\nHost: localhost\r\n\r\n");
+
+        let response = raw_http1_request("127.0.0.1:9141", &request).await?;
+        let statuses: Vec<&str> = response
+            .lines()
+            .filter(|line| line.starts_with("HTTP/1.1 "))
+            .collect();
+        assert_eq!(statuses, ["HTTP/1.1 413 Payload Too Large"], "{}", response);
+        assert!(response
+            .to_ascii_lowercase()
+            .contains("connection: close"));
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "http1", feature = "request-decompression", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_rejected_body_keep_alive() -> Result<(), Box<dyn Error>> {
+        do_rejected_body_keep_alive().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "request-decompression", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_rejected_body_keep_alive_smol() -> Result<(), Box<dyn Error>> {
+        do_rejected_body_keep_alive().await
+    }
+
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    #[test]
+    fn test_tcp_keepalive() -> Result<(), Box<dyn Error>> {
+        use std::time::Duration;
+
+        use socket2::SockRef;
+
+        use crate::server::conn::listener::tcp::tcp_keepalive;
+
+        let disabled = ListenerConfig::builder().build()?;
+        assert!(tcp_keepalive(&disabled).is_none());
+
+        let config = ListenerConfig::builder()
+            .tcp_keepalive(Duration::from_secs(60))
+            .tcp_keepalive_interval(Duration::from_secs(10))
+            .tcp_keepalive_retries(4)
+            .build()?;
+        let keepalive = tcp_keepalive(&config).ok_or("TCP keepalive is disabled")?;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let _client = std::net::TcpStream::connect(listener.local_addr()?)?;
+        let (accepted, _) = listener.accept()?;
+
+        let socket = SockRef::from(&accepted);
+        assert!(!socket.keepalive()?);
+
+        socket.set_tcp_keepalive(&keepalive)?;
+        assert!(socket.keepalive()?);
+        assert_eq!(socket.tcp_keepalive_time()?, Duration::from_secs(60));
+        assert_eq!(socket.tcp_keepalive_interval()?, Duration::from_secs(10));
+        assert_eq!(socket.tcp_keepalive_retries()?, 4);
+
+        Ok(())
+    }
+
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    #[test]
+    fn test_tcp_nodelay() -> Result<(), Box<dyn Error>> {
+        use socket2::SockRef;
+
+        use crate::server::conn::listener::tcp::apply_socket_options;
+
+        let config = ListenerConfig::builder().build()?;
+        assert!(config.tcp_nodelay());
+
+        let config = ListenerConfig::builder()
+            .tcp_nodelay(false)
+            .build()?;
+        assert!(!config.tcp_nodelay());
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let _client = std::net::TcpStream::connect(listener.local_addr()?)?;
+        let (accepted, client_addr) = listener.accept()?;
+        let socket = SockRef::from(&accepted);
+
+        apply_socket_options(SockRef::from(&accepted), true, None, client_addr);
+        assert!(socket.tcp_nodelay()?);
+
+        apply_socket_options(SockRef::from(&accepted), config.tcp_nodelay(), None, client_addr);
+        assert!(!socket.tcp_nodelay()?);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "http1")]
+    async fn do_http1_pipelining() -> Result<(), Box<dyn Error>> {
+        use std::time::Duration;
+
+        use crate::utils::time::sleep;
+
+        let mut servers = Vec::new();
+        for (port, pipelining) in [(9151, true), (9152, false)] {
+            let config = ServerConfig::builder()
+                .add_listener(
+                    ListenerConfig::builder()
+                        .port(port)
+                        .protocol(crate::config::server::Protocol::Http1)
+                        .interface("127.0.0.1")
+                        .http1_pipelining(pipelining)
+                        .build()?,
+                )
+                .build()?;
+
+            let mut server = crate::Vetis::new(config);
+
+            let host_config = VirtualHostConfig::builder()
+                .hostname("localhost")
+                .port(port)
+                .root_directory("src/tests")
+                .build()?;
+
+            let mut virtual_host = VirtualHost::new(host_config);
+            virtual_host.add_path(
+                HandlerPath::builder()
+                    .uri("/slow")
+                    .handler(handler_fn(|_request| async move {
+                        sleep(Duration::from_millis(200)).await;
+                        Ok(crate::server::http::Response::builder()
+                            .status(StatusCode::OK)
+                            .text("first-response"))
+                    }))
+                    .build()?,
+            );
+            virtual_host.add_path(
+                HandlerPath::builder()
+                    .uri("/fast")
+                    .handler(handler_fn(|_request| async move {
+                        Ok(crate::server::http::Response::builder()
+                            .status(StatusCode::OK)
+                            .text("second-response"))
+                    }))
+                    .build()?,
+            );
+
+            server
+                .add_virtual_host(virtual_host)
+                .await;
+
+            server
+                .start()
+                .await?;
+
+            servers.push(server);
+        }
+
+        // Both requests are written at once, the slow one first
+        let pipelined = b"GET /slow HTTP/1.1\r\nHost: localhost\r\n\r\n\
+            GET /fast HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+
+        let response = raw_http1_request("127.0.0.1:9151", pipelined).await?;
+        let first = response
+            .find("first-response")
+            .ok_or("missing first response")?;
+        let second = response
+            .find("second-response")
+            .ok_or("missing second response")?;
+        assert!(first < second, "{}", response);
+        assert_eq!(
+            response
+                .matches("HTTP/1.1 200 OK\r\n")
+                .count(),
+            2
+        );
+
+        // Without pipelining the connection closes after the first response
+        let response = raw_http1_request("127.0.0.1:9152", pipelined).await?;
+        assert!(response.contains("first-response"), "{}", response);
+        assert!(!response.contains("second-response"), "{}", response);
+        assert!(response.contains("connection: close\r\n"), "{}", response);
+
+        for mut server in servers {
+            server
+                .stop()
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "http1", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_http1_pipelining() -> Result<(), Box<dyn Error>> {
+        do_http1_pipelining().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_http1_pipelining_smol() -> Result<(), Box<dyn Error>> {
+        do_http1_pipelining().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "proxy-protocol"))]
+    async fn do_proxy_protocol_client_addr() -> Result<(), Box<dyn Error>> {
+        capture_logs();
+
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9155)
+                    .protocol(crate::config::server::Protocol::Http1)
+                    .interface("127.0.0.1")
+                    .proxy_protocol(true)
+                    .build()?,
+            )
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9155)
+            .root_directory("src/tests")
+            .enable_logging(true)
+            .log_format("%remote_addr %path")
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/proxy-protocol")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Hello"))
+                }))
+                .build()?,
+        );
+
+        server
+            .add_virtual_host(virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        // A client that connects and never sends its header doesn't hold up other connections
+        let _stalled = std::net::TcpStream::connect("127.0.0.1:9155")?;
+
+        let request =
+            b"GET /proxy-protocol?v1 HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+        let mut v1 = b"PROXY TCP4 203.0.113.7 127.0.0.1 56324 9155\r\n".to_vec();
+        v1.extend_from_slice(request);
+        let response = crate::utils::time::timeout(
+            std::time::Duration::from_secs(2),
+            raw_http1_request("127.0.0.1:9155", &v1),
+        )
+        .await
+        .ok_or("request blocked by a stalled connection")??;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+
+        let request =
+            b"GET /proxy-protocol?v2 HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+        let mut v2 = b"\r\n\r\n\0\r\nQUIT\n\x21\x11\x00\x0c".to_vec();
+        v2.extend_from_slice(&[198, 51, 100, 9, 127, 0, 0, 1, 0xdc, 0x05, 0x23, 0xc3]);
+        v2.extend_from_slice(request);
+        let response = raw_http1_request("127.0.0.1:9155", &v2).await?;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+
+        // Connections without a header are closed without a response
+        let response = raw_http1_request(
+            "127.0.0.1:9155",
+            b"GET /proxy-protocol?none HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        )
+        .await
+        .unwrap_or_default();
+        assert!(response.is_empty(), "{}", response);
+
+        server
+            .stop()
+            .await?;
+
+        assert_eq!(
+            captured_logs("/proxy-protocol?v1"),
+            vec!["203.0.113.7:56324 /proxy-protocol?v1".to_string()]
+        );
+        assert_eq!(
+            captured_logs("/proxy-protocol?v2"),
+            vec!["198.51.100.9:56325 /proxy-protocol?v2".to_string()]
+        );
+        assert!(captured_logs("/proxy-protocol?none").is_empty());
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "http1", feature = "proxy-protocol", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_proxy_protocol_client_addr() -> Result<(), Box<dyn Error>> {
+        do_proxy_protocol_client_addr().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "proxy-protocol", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_proxy_protocol_client_addr_smol() -> Result<(), Box<dyn Error>> {
+        do_proxy_protocol_client_addr().await
+    }
+
+    #[cfg(feature = "http1")]
+    async fn do_connection_cap_refusal() -> Result<(), Box<dyn Error>> {
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9175)
+                    .protocol(crate::config::server::Protocol::Http1)
+                    .interface("127.0.0.1")
+                    .max_concurrent_connections(1)
+                    .build()?,
+            )
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9175)
+            .root_directory("src/tests")
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Hello"))
+                }))
+                .build()?,
+        );
+
+        server
+            .add_virtual_host(virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        // Takes the only slot, and keeps it while open
+        let _idle = std::net::TcpStream::connect("127.0.0.1:9175")?;
+
+        // Requests racing the idle connection for the slot may still be served
+        let mut response = String::new();
+        for _ in 0..20 {
+            response = raw_http1_request(
+                "127.0.0.1:9175",
+                b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+            )
+            .await?;
+            if !response.starts_with("HTTP/1.1 200 OK\r\n") {
+                break;
+            }
+            crate::utils::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+
+        assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"), "{}", response);
+        assert!(response
+            .to_ascii_lowercase()
+            .contains("connection: close"));
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "http1", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_connection_cap_refusal() -> Result<(), Box<dyn Error>> {
+        do_connection_cap_refusal().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_connection_cap_refusal_smol() -> Result<(), Box<dyn Error>> {
+        do_connection_cap_refusal().await
+    }
+
+    #[cfg(feature = "http1")]
+    async fn do_request_id_logs() -> Result<(), Box<dyn Error>> {
+        capture_logs();
+
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9158)
+                    .protocol(crate::config::server::Protocol::Http1)
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9158)
+            .root_directory("src/tests")
+            .enable_logging(true)
+            .log_format("%request_id %path %status")
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/request-id")
+                .handler(handler_fn(|_request| async move {
+                    Err(crate::errors::VetisError::UpstreamUnavailable)
+                }))
+                .build()?,
+        );
+
+        server
+            .add_virtual_host(virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let generated = raw_http1_request(
+            "127.0.0.1:9158",
+            b"GET /request-id?generated HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        )
+        .await?;
+
+        let forwarded = raw_http1_request(
+            "127.0.0.1:9158",
+            b"GET /request-id?forwarded HTTP/1.1\r\nHost: localhost\r\nX-Request-Id: trace-9158\r\nConnection: close\r\n\r\n",
+        )
+        .await?;
+
+        server
+            .stop()
+            .await?;
+
+        // The generated id is echoed to the client and shared by both log lines
+        let access_log = captured_logs("/request-id?generated");
+        assert_eq!(access_log.len(), 1, "{:?}", access_log);
+        let (request_id, _) = access_log[0]
+            .split_once(' ')
+            .ok_or("missing request id")?;
+        assert_eq!(request_id.len(), 32);
+        assert!(generated.contains(&format!("x-request-id: {}\r\n", request_id)), "{}", generated);
+        assert_eq!(captured_logs(&format!("[{}] Request failed", request_id)).len(), 1);
+
+        assert!(forwarded.contains("x-request-id: trace-9158\r\n"), "{}", forwarded);
+        let lines = captured_logs("trace-9158");
+        assert_eq!(lines.len(), 2, "{:?}", lines);
+        assert!(lines.contains(&"trace-9158 /request-id?forwarded 503".to_string()), "{:?}", lines);
+        assert!(
+            lines
+                .iter()
+                .any(|line| line.starts_with("[trace-9158] Request failed")),
+            "{:?}",
+            lines
+        );
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "http1", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_request_id_logs() -> Result<(), Box<dyn Error>> {
+        do_request_id_logs().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_request_id_logs_smol() -> Result<(), Box<dyn Error>> {
+        do_request_id_logs().await
+    }
+
+    #[cfg(feature = "http1")]
+    async fn do_routing_traces() -> Result<(), Box<dyn Error>> {
+        capture_logs();
+
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9164)
+                    .protocol(crate::config::server::Protocol::Http1)
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9164)
+            .root_directory("src/tests")
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/traced")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Traced"))
+                }))
+                .build()?,
+        );
+
+        server
+            .add_virtual_host(virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        let matched = raw_http1_request(
+            "127.0.0.1:9164",
+            b"GET /traced/matched HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        )
+        .await?;
+        assert!(matched.starts_with("HTTP/1.1 200 OK\r\n"), "{}", matched);
+
+        let unmatched = raw_http1_request(
+            "127.0.0.1:9164",
+            b"GET /untraced HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        )
+        .await?;
+        assert!(unmatched.starts_with("HTTP/1.1 404 Not Found\r\n"), "{}", unmatched);
+
+        let unknown_host = raw_http1_request(
+            "127.0.0.1:9164",
+            b"GET /traced HTTP/1.1\r\nHost: unknown.test\r\nConnection: close\r\n\r\n",
+        )
+        .await?;
+        assert!(unknown_host.starts_with("HTTP/1.1 404 Not Found\r\n"), "{}", unknown_host);
+
+        server
+            .stop()
+            .await?;
+
+        assert_eq!(
+            captured_logs("/traced/matched on"),
+            vec!["Path /traced/matched on localhost:9164 matched handler path /traced".to_string()]
+        );
+        assert_eq!(
+            captured_logs("/untraced on"),
+            vec!["Path /untraced on localhost:9164 matched no path".to_string()]
+        );
+        assert_eq!(
+            captured_logs("Host localhost:9164"),
+            vec!["Host localhost:9164 matched a virtual host".to_string(); 2]
+        );
+        assert_eq!(
+            captured_logs("Host unknown.test:9164"),
+            vec!["Host unknown.test:9164 matched no virtual host".to_string()]
+        );
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "http1", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_routing_traces() -> Result<(), Box<dyn Error>> {
+        do_routing_traces().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_routing_traces_smol() -> Result<(), Box<dyn Error>> {
+        do_routing_traces().await
+    }
+
+    #[cfg(feature = "http1")]
+    async fn do_ambiguous_length() -> Result<(), Box<dyn Error>> {
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9165)
+                    .protocol(crate::config::server::Protocol::Http1)
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9166)
+                    .protocol(crate::config::server::Protocol::Http1)
+                    .interface("127.0.0.1")
+                    .ambiguous_length(AmbiguousLengthPolicy::PreferTransferEncoding)
+                    .build()?,
+            )
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+
+        for port in [9165, 9166] {
+            let host_config = VirtualHostConfig::builder()
+                .hostname("localhost")
+                .port(port)
+                .root_directory("src/tests")
+                .build()?;
+
+            let mut virtual_host = VirtualHost::new(host_config);
+            virtual_host.add_path(
+                HandlerPath::builder()
+                    .uri("/upload")
+                    .handler(handler_fn(|request| {
+                        let framing = match request
+                            .headers()
+                            .get(http::header::CONTENT_LENGTH)
+                        {
+                            Some(_) => "content-length",
+                            None => "transfer-encoding",
+                        };
+                        async move {
+                            Ok(crate::server::http::Response::builder()
+                                .status(StatusCode::OK)
+                                .text(framing))
+                        }
+                    }))
+                    .build()?,
+            );
+
+            server
+                .add_virtual_host(virtual_host)
+                .await;
+        }
+
+        server
+            .start()
+            .await?;
+
+        let smuggled = b"POST /upload HTTP/1.1\r\nHost: localhost\r\nContent-Length: 3\r\n\
+                         Transfer-Encoding: chunked\r\nConnection: close\r\n\r\n\
+                         5\r\nhello\r\n0\r\n\r\n";
+
+        let response = raw_http1_request("127.0.0.1:9165", smuggled).await?;
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"), "{}", response);
+        assert!(response.contains("\r\nconnection: close\r\n"), "{}", response);
+
+        let response = raw_http1_request("127.0.0.1:9166", smuggled).await?;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.ends_with("\r\n\r\ntransfer-encoding"), "{}", response);
+
+        // Conflicting lengths are rejected whatever the policy
+        for (addr, request) in [
+            (
+                "127.0.0.1:9166",
+                b"POST /upload HTTP/1.1\r\nHost: localhost\r\nContent-Length: 3, 5\r\n\
+                  Connection: close\r\n\r\nhello"
+                    .as_slice(),
+            ),
+            (
+                "127.0.0.1:9166",
+                b"POST /upload HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5\r\n\
+                  Content-Length: 3\r\nConnection: close\r\n\r\nhello"
+                    .as_slice(),
+            ),
+        ] {
+            let response = raw_http1_request(addr, request).await?;
+            assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"), "{}", response);
+        }
+
+        // Repeated lengths that agree are accepted
+        let response = raw_http1_request(
+            "127.0.0.1:9165",
+            b"POST /upload HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5\r\n\
+              Content-Length: 5\r\nConnection: close\r\n\r\nhello",
+        )
+        .await?;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.ends_with("\r\n\r\ncontent-length"), "{}", response);
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "http1", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_ambiguous_length() -> Result<(), Box<dyn Error>> {
+        do_ambiguous_length().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_ambiguous_length_smol() -> Result<(), Box<dyn Error>> {
+        do_ambiguous_length().await
+    }
+
+    #[cfg(feature = "http1")]
+    async fn do_max_body_size() -> Result<(), Box<dyn Error>> {
+        let config = ServerConfig::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9168)
+                    .protocol(crate::config::server::Protocol::Http1)
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9168)
+            .root_directory("src/tests")
+            .max_body_size(1024)
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/upload")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Uploaded"))
+                }))
+                .build()?,
+        );
+
+        server
+            .add_virtual_host(virtual_host)
+            .await;
+
+        server
+            .start()
+            .await?;
+
+        // Refused from the headers alone, the client never has to send the body
+        let response = raw_http1_request(
+            "127.0.0.1:9168",
+            b"PUT /upload HTTP/1.1\r\nHost: localhost\r\nContent-Length: 10485760\r\n\
+              Expect: 100-continue\r\n\r\n",
+        )
+        .await?;
+        assert!(response.starts_with("HTTP/1.1 413 Payload Too Large\r\n"), "{}", response);
+        assert!(!response.contains("100 Continue"), "{}", response);
+        assert!(response.contains("\r\nconnection: close\r\n"), "{}", response);
+
+        let response = raw_http1_request(
+            "127.0.0.1:9168",
+            b"PUT /upload HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5\r\n\
+              Connection: close\r\n\r\nhello",
+        )
+        .await?;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+
+        server
+            .stop()
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "http1", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_max_body_size() -> Result<(), Box<dyn Error>> {
+        do_max_body_size().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_max_body_size_smol() -> Result<(), Box<dyn Error>> {
+        do_max_body_size().await
+    }
+
+    #[cfg(all(unix, feature = "http1"))]
+    async fn do_listener_from_std() -> Result<(), Box<dyn Error>> {
+        let listener = std::net::TcpListener::bind("127.0.0.1:9169")?;
+
+        let listener_config = ListenerConfig::from_std(listener)?
+            .protocol(crate::config::server::Protocol::Http1)
+            .build()?;
+        assert_eq!(listener_config.port(), 9169);
+        assert_eq!(listener_config.interface(), "127.0.0.1");
+        assert!(listener_config
+            .fd()
+            .is_some());
+
+        let config = ServerConfig::builder()
+            .add_listener(listener_config)
+            .build()?;
+
+        let mut server = crate::Vetis::new(config);
+
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9169)
+            .root_directory("src/tests")
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/hello")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Inherited"))
+                }))
+                .build()?,
+        );
+
+        server
+            .add_virtual_host(virtual_host)
+            .await;
+
+        // The socket is served as it is, and again after a restart
+        for _ in 0..2 {
+            server
+                .start()
+                .await?;
+
+            let response = raw_http1_request(
+                "127.0.0.1:9169",
+                b"GET /hello HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+            )
+            .await?;
+            assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+            assert!(response.ends_with("\r\n\r\nInherited"), "{}", response);
+
+            server
+                .stop()
+                .await?;
+        }
+
+        // The socket is closed along with the configuration owning it
+        drop(server);
+        std::net::TcpListener::bind("127.0.0.1:9169")?;
+
+        Ok(())
+    }
+
+    #[cfg(all(unix, feature = "http1", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_listener_from_std() -> Result<(), Box<dyn Error>> {
+        do_listener_from_std().await
+    }
+
+    #[cfg(all(unix, feature = "http1", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_listener_from_std_smol() -> Result<(), Box<dyn Error>> {
+        do_listener_from_std().await
+    }
+
+    #[cfg(feature = "http1")]
+    async fn do_builder() -> Result<(), Box<dyn Error>> {
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9162)
+            .root_directory("src/tests")
+            .header("X-Frame-Options", "SAMEORIGIN")
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(host_config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/hello")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Hello"))
+                }))
+                .build()?,
+        );
+
+        let mut server = crate::Vetis::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9162)
+                    .protocol(crate::config::server::Protocol::Http1)
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .add_virtual_host(virtual_host)
+            .header("X-Served-By", "vetis")
+            .header("X-Frame-Options", "DENY")
+            .build()?;
+
+        server
+            .start()
+            .await?;
+
+        let response = request::get("http://localhost:9162/hello")?
+            .send_with(deboa::Client::default())
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response
+                .headers()
+                .get("x-served-by")
+                .and_then(|value| value.to_str().ok()),
+            Some("vetis")
+        );
+        // The virtual host's own default headers win over the server-wide ones
+        assert_eq!(
+            response
+                .headers()
+                .get("x-frame-options")
+                .and_then(|value| value.to_str().ok()),
+            Some("SAMEORIGIN")
+        );
+
+        server
+            .stop()
+            .await?;
+
+        // Without listeners there is nothing to serve
+        let server = crate::Vetis::builder().build();
+        assert!(matches!(
+            server.err(),
+            Some(crate::errors::VetisError::Config(crate::errors::ConfigError::Server(_)))
+        ));
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "http1", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_builder() -> Result<(), Box<dyn Error>> {
+        do_builder().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_builder_smol() -> Result<(), Box<dyn Error>> {
+        do_builder().await
+    }
+
+    #[cfg(feature = "http1")]
+    async fn do_builder_shutdown_on() -> Result<(), Box<dyn Error>> {
+        let host_config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .port(9163)
+            .root_directory("src/tests")
+            .build()?;
+
+        let mut server = crate::Vetis::builder()
+            .add_listener(
+                ListenerConfig::builder()
+                    .port(9163)
+                    .protocol(crate::config::server::Protocol::Http1)
+                    .interface("127.0.0.1")
+                    .build()?,
+            )
+            .add_virtual_host(VirtualHost::new(host_config))
+            .shutdown_on(async {})
+            .build()?;
+
+        // `run` returns once the shutdown future completes instead of waiting for a signal
+        server.run().await?;
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "http1", feature = "tokio-rt"))]
+    #[tokio::test]
+    async fn test_builder_shutdown_on() -> Result<(), Box<dyn Error>> {
+        do_builder_shutdown_on().await
+    }
+
+    #[cfg(all(feature = "http1", feature = "smol-rt"))]
+    #[apply(test!)]
+    async fn test_builder_shutdown_on_smol() -> Result<(), Box<dyn Error>> {
+        do_builder_shutdown_on().await
+    }
 }
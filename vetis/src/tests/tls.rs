@@ -17,10 +17,10 @@ mod tls_tests {
         config::server::virtual_host::{SecurityConfig, VirtualHostConfig},
         errors::VetisError,
         server::{
-            tls::TlsFactory,
+            tls::{certificate_names, name_matches, TlsFactory},
             virtual_host::{handler_fn, path::HandlerPath, VirtualHost},
         },
-        tests::{CA_CERT, SERVER_CERT, SERVER_KEY},
+        tests::{CA_CERT, CLIENT_CERT, GUEST_CERT, SERVER_CERT, SERVER_KEY},
         VetisVirtualHosts,
     };
 
@@ -138,7 +138,7 @@ mod tls_tests {
 
         let config = tls_config.unwrap();
         assert_eq!(config.alpn_protocols, vec![b"h2".to_vec(), b"http/1.1".to_vec()]);
-        assert_eq!(config.max_early_data_size, u32::MAX);
+        assert_eq!(config.max_early_data_size, 0);
     }
 
     #[cfg(feature = "tokio-rt")]
@@ -344,6 +344,178 @@ mod tls_tests {
         do_create_tls_config_with_ca_cert().await;
     }
 
+    async fn do_create_tls_config_early_data() {
+        let mut hosts = std::collections::HashMap::new();
+
+        for (port, max_early_data_size) in [(8443, 1024), (8444, 4096)] {
+            let security_config = SecurityConfig::builder()
+                .cert_from_bytes(SERVER_CERT.to_vec())
+                .key_from_bytes(SERVER_KEY.to_vec())
+                .max_early_data_size(max_early_data_size)
+                .build()
+                .expect("Failed to create security config");
+
+            let vhost_config = VirtualHostConfig::builder()
+                .hostname("localhost")
+                .port(port)
+                .root_directory("src/tests")
+                .security(security_config)
+                .build()
+                .expect("Failed to create virtual host config");
+
+            hosts.insert((Arc::from("localhost"), port), VirtualHost::new(vhost_config));
+        }
+
+        let virtual_hosts = Arc::new(RwLock::new(hosts));
+        let alpn_protocols = vec![b"http/1.1".to_vec()];
+
+        let result = TlsFactory::create_tls_config(virtual_hosts, alpn_protocols).await;
+
+        let config = result
+            .expect("TLS config creation should succeed")
+            .expect("TLS config should be Some");
+        assert_eq!(config.max_early_data_size, 4096);
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_create_tls_config_early_data() {
+        do_create_tls_config_early_data().await;
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_create_tls_config_early_data() {
+        do_create_tls_config_early_data().await;
+    }
+
+    /// Sends what one side of an in-memory TLS connection has to write to the other.
+    #[cfg(feature = "__rustls_awc_lc_rs")]
+    fn transfer(
+        from: &mut rustls::Connection,
+        to: &mut rustls::Connection,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut buffer = Vec::new();
+        while from.wants_write() {
+            from.write_tls(&mut buffer)?;
+        }
+
+        let mut reader = buffer.as_slice();
+        while !reader.is_empty() {
+            to.read_tls(&mut reader)?;
+            to.process_new_packets()?;
+        }
+
+        Ok(())
+    }
+
+    /// Connects a client to the server in memory, returning whether the session was resumed.
+    #[cfg(feature = "__rustls_awc_lc_rs")]
+    fn handshake(
+        client_config: Arc<rustls::ClientConfig>,
+        server_config: Arc<rustls::ServerConfig>,
+    ) -> Result<Option<rustls::HandshakeKind>, Box<dyn std::error::Error>> {
+        let mut client = rustls::Connection::Client(rustls::ClientConnection::new(
+            client_config,
+            "localhost".try_into()?,
+        )?);
+        let mut server = rustls::Connection::Server(rustls::ServerConnection::new(server_config)?);
+
+        // Session tickets are sent once the handshake completes
+        while client.is_handshaking() || server.is_handshaking() || server.wants_write() {
+            transfer(&mut client, &mut server)?;
+            transfer(&mut server, &mut client)?;
+        }
+
+        Ok(client.handshake_kind())
+    }
+
+    #[cfg(feature = "__rustls_awc_lc_rs")]
+    async fn do_session_resumption() -> Result<(), Box<dyn std::error::Error>> {
+        for session_resumption in [true, false] {
+            let security_config = SecurityConfig::builder()
+                .cert_from_bytes(SERVER_CERT.to_vec())
+                .key_from_bytes(SERVER_KEY.to_vec())
+                .ca_cert_from_bytes(CA_CERT.to_vec())
+                .session_resumption(session_resumption)
+                .build()?;
+
+            let vhost_config = VirtualHostConfig::builder()
+                .hostname("localhost")
+                .port(8443)
+                .root_directory("src/tests")
+                .security(security_config)
+                .build()?;
+
+            let mut hosts = std::collections::HashMap::new();
+            hosts.insert((Arc::from("localhost"), 8443u16), VirtualHost::new(vhost_config));
+
+            let server_config = TlsFactory::create_tls_config(Arc::new(RwLock::new(hosts)), vec![])
+                .await?
+                .ok_or("TLS config should be Some")?;
+            let server_config = Arc::new(server_config);
+
+            let mut roots = rustls::RootCertStore::empty();
+            roots.add(rustls::pki_types::CertificateDer::from(CA_CERT.to_vec()))?;
+            let client_config = Arc::new(
+                rustls::ClientConfig::builder_with_provider(Arc::new(
+                    rustls::crypto::aws_lc_rs::default_provider(),
+                ))
+                .with_protocol_versions(&[&rustls::version::TLS13])?
+                .with_root_certificates(roots)
+                .with_no_client_auth(),
+            );
+
+            assert_eq!(
+                handshake(client_config.clone(), server_config.clone())?,
+                Some(rustls::HandshakeKind::Full)
+            );
+
+            // The client offers the ticket it was issued, if any
+            let expected = if session_resumption {
+                rustls::HandshakeKind::Resumed
+            } else {
+                rustls::HandshakeKind::Full
+            };
+            assert_eq!(handshake(client_config, server_config)?, Some(expected));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "tokio-rt", feature = "__rustls_awc_lc_rs"))]
+    #[tokio::test]
+    async fn test_session_resumption() -> Result<(), Box<dyn std::error::Error>> {
+        do_session_resumption().await
+    }
+
+    #[cfg(all(feature = "smol-rt", feature = "__rustls_awc_lc_rs"))]
+    #[apply(test!)]
+    async fn test_session_resumption() -> Result<(), Box<dyn std::error::Error>> {
+        do_session_resumption().await
+    }
+
+    #[test]
+    fn test_certificate_names() {
+        assert_eq!(certificate_names(CLIENT_CERT), vec!["vetis-client".to_string()]);
+        assert_eq!(
+            certificate_names(GUEST_CERT),
+            vec!["vetis-guest".to_string(), "guest.example.com".to_string()]
+        );
+        assert!(certificate_names(b"not a certificate").is_empty());
+    }
+
+    #[test]
+    fn test_name_matches() {
+        assert!(name_matches("vetis-client", "vetis-client"));
+        assert!(name_matches("Vetis-Client", "vetis-client"));
+        assert!(!name_matches("vetis-client", "vetis-guest"));
+        assert!(name_matches("*.example.com", "guest.example.com"));
+        assert!(!name_matches("*.example.com", "example.com"));
+        assert!(!name_matches("*.example.com", "a.guest.example.com"));
+        assert!(!name_matches("*.example.com", ".example.com"));
+    }
+
     #[test]
     fn test_tls_factory_struct_exists() {
         // This test ensures the TlsFactory struct is accessible
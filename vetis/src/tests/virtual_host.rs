@@ -1,5 +1,15 @@
 mod virtual_host_tests {
 
+    use std::{
+        future::Future,
+        pin::Pin,
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc,
+        },
+        time::{Duration, Instant},
+    };
+
     use http::StatusCode;
     use http_body_util::BodyExt;
     use hyper_body_utils::HttpBody;
@@ -8,11 +18,23 @@ mod virtual_host_tests {
     #[cfg(feature = "smol-rt")]
     use smol_macros::test;
 
+    #[cfg(feature = "static-files")]
+    use crate::{
+        config::server::virtual_host::path::static_files::StaticPathConfig,
+        server::virtual_host::path::static_files::StaticPath,
+    };
     use crate::{
         config::server::virtual_host::VirtualHostConfig,
+        errors::VetisError,
         server::{
-            http::Request,
-            virtual_host::{handler_fn, path::HandlerPath, VirtualHost},
+            http::{Request, Response},
+            virtual_host::{
+                handler_fn,
+                hook::ServerTiming,
+                path::{redirect::RedirectPath, HandlerPath},
+                transform::InjectBeforeClosingBody,
+                Handler, VirtualHost,
+            },
         },
     };
 
@@ -127,4 +149,1402 @@ mod virtual_host_tests {
     async fn test_handle_request() -> Result<(), Box<dyn std::error::Error>> {
         do_handle_request().await
     }
+
+    async fn do_max_uri_length() -> Result<(), Box<dyn std::error::Error>> {
+        let config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .root_directory("src/tests")
+            .max_uri_length(16)
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Hello, world!"))
+                }))
+                .build()?,
+        );
+
+        // "/?q=" plus 12 characters is exactly 16 bytes long
+        for (uri, status) in
+            [("/?q=aaaaaaaaaaaa", StatusCode::OK), ("/?q=aaaaaaaaaaaaa", StatusCode::URI_TOO_LONG)]
+        {
+            let request = http::Request::builder()
+                .uri(uri)
+                .body(HttpBody::from_text(""))?;
+
+            let (parts, body) = request.into_parts();
+
+            let response = virtual_host
+                .route(Request::from_parts(parts, body))
+                .await?;
+
+            assert_eq!(
+                response
+                    .into_inner()
+                    .status(),
+                status
+            );
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_max_uri_length() -> Result<(), Box<dyn std::error::Error>> {
+        do_max_uri_length().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_max_uri_length() -> Result<(), Box<dyn std::error::Error>> {
+        do_max_uri_length().await
+    }
+
+    async fn do_request_timeout() -> Result<(), Box<dyn std::error::Error>> {
+        struct DropFlag(Arc<AtomicBool>);
+
+        impl Drop for DropFlag {
+            fn drop(&mut self) {
+                self.0
+                    .store(true, Ordering::SeqCst);
+            }
+        }
+
+        let config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .root_directory("src/tests")
+            .request_timeout(Duration::from_millis(100))
+            .build()?;
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let handler_cancelled = cancelled.clone();
+
+        let mut virtual_host = VirtualHost::new(config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/slow")
+                .handler(handler_fn(move |_request| {
+                    let flag = DropFlag(handler_cancelled.clone());
+                    async move {
+                        crate::utils::time::sleep(Duration::from_secs(10)).await;
+                        drop(flag);
+                        Ok(crate::server::http::Response::builder()
+                            .status(StatusCode::OK)
+                            .text("Too late"))
+                    }
+                }))
+                .build()?,
+        );
+
+        let request = http::Request::builder()
+            .uri("/slow")
+            .body(HttpBody::from_text(""))?;
+
+        let (parts, body) = request.into_parts();
+
+        let started = Instant::now();
+        let response = virtual_host
+            .route(Request::from_parts(parts, body))
+            .await?;
+
+        assert_eq!(
+            response
+                .into_inner()
+                .status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(cancelled.load(Ordering::SeqCst));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_request_timeout() -> Result<(), Box<dyn std::error::Error>> {
+        do_request_timeout().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_request_timeout() -> Result<(), Box<dyn std::error::Error>> {
+        do_request_timeout().await
+    }
+
+    #[cfg(feature = "reverse-proxy")]
+    async fn do_proxy_request_timeout() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::{
+            config::server::virtual_host::path::proxy::ProxyPathConfig,
+            server::virtual_host::path::proxy::ProxyPath,
+        };
+
+        // Accepts connections through the backlog, but never answers
+        let _upstream = std::net::TcpListener::bind("127.0.0.9:9123")?;
+
+        let config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .root_directory("src/tests")
+            .request_timeout(Duration::from_millis(200))
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(config);
+        virtual_host.add_path(ProxyPath::new(
+            ProxyPathConfig::builder()
+                .uri("/api")
+                .target("http://127.0.0.9:9123")
+                .build()?,
+        ));
+
+        let request = http::Request::builder()
+            .uri("/api/hang")
+            .body(HttpBody::from_text(""))?;
+
+        let (parts, body) = request.into_parts();
+
+        let response = virtual_host
+            .route(Request::from_parts(parts, body))
+            .await?;
+
+        assert_eq!(
+            response
+                .into_inner()
+                .status(),
+            StatusCode::GATEWAY_TIMEOUT
+        );
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "tokio-rt", feature = "reverse-proxy"))]
+    #[tokio::test]
+    async fn test_proxy_request_timeout() -> Result<(), Box<dyn std::error::Error>> {
+        do_proxy_request_timeout().await
+    }
+
+    #[cfg(all(feature = "smol-rt", feature = "reverse-proxy"))]
+    #[apply(test!)]
+    async fn test_proxy_request_timeout() -> Result<(), Box<dyn std::error::Error>> {
+        do_proxy_request_timeout().await
+    }
+
+    async fn do_inject_before_closing_body() -> Result<(), Box<dyn std::error::Error>> {
+        let config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .root_directory("src/tests")
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/page")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .header(
+                            http::header::CONTENT_TYPE,
+                            http::HeaderValue::from_static("text/html; charset=utf-8"),
+                        )
+                        .text("<html><body><h1>Hello</h1></body></html>"))
+                }))
+                .build()?,
+        );
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/data")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .header(
+                            http::header::CONTENT_TYPE,
+                            http::HeaderValue::from_static("application/json"),
+                        )
+                        .text(r#"{"body":"</body>"}"#))
+                }))
+                .build()?,
+        );
+        virtual_host.add_transform(InjectBeforeClosingBody::new("<script>reload()</script>"));
+
+        let expected = [
+            ("/page", "<html><body><h1>Hello</h1><script>reload()</script></body></html>"),
+            ("/data", r#"{"body":"</body>"}"#),
+        ];
+
+        for (uri, expected_body) in expected {
+            let request = http::Request::builder()
+                .uri(uri)
+                .body(HttpBody::from_text(""))?;
+
+            let (parts, body) = request.into_parts();
+
+            let response = virtual_host
+                .route(Request::from_parts(parts, body))
+                .await?;
+
+            let (parts, body) = response
+                .into_inner()
+                .into_parts();
+
+            let content_length = parts
+                .headers
+                .get(http::header::CONTENT_LENGTH)
+                .map(|value| value.to_str())
+                .transpose()?;
+
+            // Transformed or not, the length matches the body sent
+            assert_eq!(
+                content_length,
+                Some(
+                    expected_body
+                        .len()
+                        .to_string()
+                        .as_str()
+                )
+            );
+
+            assert_eq!(
+                body.collect()
+                    .await?
+                    .to_bytes()
+                    .as_ref(),
+                expected_body.as_bytes()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_inject_before_closing_body() -> Result<(), Box<dyn std::error::Error>> {
+        do_inject_before_closing_body().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_inject_before_closing_body() -> Result<(), Box<dyn std::error::Error>> {
+        do_inject_before_closing_body().await
+    }
+
+    async fn do_with_handler() -> Result<(), Box<dyn std::error::Error>> {
+        let config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .root_directory("src/tests")
+            .build()?;
+
+        let virtual_host = VirtualHost::with_handler(
+            config,
+            handler_fn(|request| async move {
+                Ok(crate::server::http::Response::builder()
+                    .status(StatusCode::OK)
+                    .text(request.uri().path()))
+            }),
+        );
+
+        for uri in ["/", "/index.html", "/api/v1/users?page=2"] {
+            let request = http::Request::builder()
+                .uri(uri)
+                .body(HttpBody::from_text(""))?;
+
+            let (parts, body) = request.into_parts();
+
+            let response = virtual_host
+                .route(Request::from_parts(parts, body))
+                .await?;
+
+            let (parts, body) = response
+                .into_inner()
+                .into_parts();
+            assert_eq!(parts.status, StatusCode::OK);
+            assert_eq!(
+                body.collect()
+                    .await?
+                    .to_bytes()
+                    .as_ref(),
+                uri.split('?')
+                    .next()
+                    .unwrap()
+                    .as_bytes()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_with_handler() -> Result<(), Box<dyn std::error::Error>> {
+        do_with_handler().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_with_handler() -> Result<(), Box<dyn std::error::Error>> {
+        do_with_handler().await
+    }
+
+    #[test]
+    fn test_header_value_sanitizer() {
+        use crate::utils::headers::{is_safe_value, parse_header};
+
+        assert!(is_safe_value(b"text/html; charset=utf-8"));
+        assert!(is_safe_value(b"a\tb"));
+        assert!(!is_safe_value(b"vetis\r\nset-cookie: session=stolen"));
+        assert!(!is_safe_value(b"vetis\x7f"));
+
+        assert!(parse_header("x-served-by", "vetis").is_some());
+        assert!(parse_header("x-injected", "vetis\r\n").is_none());
+        assert!(parse_header("bad name", "vetis").is_none());
+    }
+
+    fn get(uri: &str) -> Result<Request, http::Error> {
+        let request = http::Request::builder()
+            .uri(uri)
+            .body(HttpBody::from_text(""))?;
+
+        let (parts, body) = request.into_parts();
+        Ok(Request::from_parts(parts, body))
+    }
+
+    async fn do_dispatch() -> Result<(), Box<dyn std::error::Error>> {
+        let config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .root_directory("src/tests")
+            .header("x-served-by", "vetis")
+            .header("x-injected", "vetis\r\nset-cookie: session=stolen")
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/hello")
+                .handler(handler_fn(|_request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Hello, world!"))
+                }))
+                .build()?,
+        );
+        #[cfg(feature = "static-files")]
+        virtual_host.add_path(StaticPath::new(
+            StaticPathConfig::builder()
+                .uri("/static")
+                .directory("src/tests/files")
+                .extensions("\\.(html)$")
+                .build()?,
+        ));
+
+        // Handler responses get the default headers, like responses sent by listeners
+        let (parts, body) = virtual_host
+            .dispatch(get("/hello")?)
+            .await?
+            .into_inner()
+            .into_parts();
+
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(
+            parts
+                .headers
+                .get("x-served-by"),
+            Some(&http::HeaderValue::from_static("vetis"))
+        );
+        // Default headers with CR/LF are skipped rather than splitting the response
+        assert!(!parts
+            .headers
+            .contains_key("x-injected"));
+        assert!(!parts
+            .headers
+            .contains_key(http::header::SET_COOKIE));
+        assert_eq!(
+            body.collect()
+                .await?
+                .to_bytes()
+                .as_ref(),
+            b"Hello, world!"
+        );
+
+        #[cfg(feature = "static-files")]
+        {
+            let (parts, body) = virtual_host
+                .dispatch(get("/static/index.html")?)
+                .await?
+                .into_inner()
+                .into_parts();
+
+            assert_eq!(parts.status, StatusCode::OK);
+            assert_eq!(
+                body.collect()
+                    .await?
+                    .to_bytes()
+                    .as_ref(),
+                std::fs::read("src/tests/files/index.html")?
+            );
+        }
+
+        let response = virtual_host
+            .dispatch(get("/missing")?)
+            .await?
+            .into_inner();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response
+                .headers()
+                .get("x-served-by"),
+            Some(&http::HeaderValue::from_static("vetis"))
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_dispatch() -> Result<(), Box<dyn std::error::Error>> {
+        do_dispatch().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_dispatch() -> Result<(), Box<dyn std::error::Error>> {
+        do_dispatch().await
+    }
+
+    async fn do_error_handler() -> Result<(), Box<dyn std::error::Error>> {
+        let virtual_host = || -> Result<VirtualHost, Box<dyn std::error::Error>> {
+            let config = VirtualHostConfig::builder()
+                .hostname("localhost")
+                .root_directory("src/tests")
+                .build()?;
+
+            let mut virtual_host = VirtualHost::new(config);
+            virtual_host.add_path(
+                HandlerPath::builder()
+                    .uri("/orders")
+                    .handler(handler_fn(|_request| async move {
+                        Err(VetisError::Handler("database unavailable".to_string()))
+                    }))
+                    .build()?,
+            );
+            Ok(virtual_host)
+        };
+
+        // Without an error handler the status page of the error is served
+        let response = virtual_host()?
+            .dispatch(get("/orders/42")?)
+            .await?
+            .into_inner();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let mut virtual_host = virtual_host()?;
+        virtual_host.set_error_handler(|error, request| {
+            crate::server::http::Response::builder()
+                .status(StatusCode::SERVICE_UNAVAILABLE)
+                .header(
+                    http::header::CONTENT_TYPE,
+                    http::HeaderValue::from_static("application/json"),
+                )
+                .text(&format!(r#"{{"error":"{}","path":"{}"}}"#, error, request.uri().path()))
+        });
+
+        let (parts, body) = virtual_host
+            .dispatch(get("/orders/42")?)
+            .await?
+            .into_inner()
+            .into_parts();
+        assert_eq!(parts.status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            parts
+                .headers
+                .get(http::header::CONTENT_TYPE),
+            Some(&http::HeaderValue::from_static("application/json"))
+        );
+        assert_eq!(
+            body.collect()
+                .await?
+                .to_bytes()
+                .as_ref(),
+            format!(
+                r#"{{"error":"{}","path":"/orders/42"}}"#,
+                VetisError::Handler("database unavailable".to_string())
+            )
+            .as_bytes()
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_error_handler() -> Result<(), Box<dyn std::error::Error>> {
+        do_error_handler().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_error_handler() -> Result<(), Box<dyn std::error::Error>> {
+        do_error_handler().await
+    }
+
+    async fn do_method_handlers() -> Result<(), Box<dyn std::error::Error>> {
+        let config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .root_directory("src/tests")
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/items")
+                .method(
+                    http::Method::GET,
+                    handler_fn(|_request| async move {
+                        Ok(crate::server::http::Response::builder()
+                            .status(StatusCode::OK)
+                            .text("Items"))
+                    }),
+                )
+                .method(
+                    http::Method::POST,
+                    handler_fn(|_request| async move {
+                        Ok(crate::server::http::Response::builder()
+                            .status(StatusCode::CREATED)
+                            .text("Created"))
+                    }),
+                )
+                .build()?,
+        );
+
+        let response = virtual_host
+            .dispatch(request(http::Method::OPTIONS, "/items")?)
+            .await?;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response
+                .headers()
+                .get(http::header::ALLOW),
+            Some(&http::HeaderValue::from_static("GET, POST, OPTIONS"))
+        );
+
+        let response = virtual_host
+            .dispatch(request(http::Method::DELETE, "/items")?)
+            .await?;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(
+            response
+                .headers()
+                .get(http::header::ALLOW),
+            Some(&http::HeaderValue::from_static("GET, POST, OPTIONS"))
+        );
+
+        let response = virtual_host
+            .dispatch(request(http::Method::GET, "/items")?)
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = virtual_host
+            .dispatch(request(http::Method::POST, "/items")?)
+            .await?;
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        // HEAD is served by the GET handler
+        let response = virtual_host
+            .dispatch(request(http::Method::HEAD, "/items")?)
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_method_handlers() -> Result<(), Box<dyn std::error::Error>> {
+        do_method_handlers().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_method_handlers() -> Result<(), Box<dyn std::error::Error>> {
+        do_method_handlers().await
+    }
+
+    async fn do_server_timing() -> Result<(), Box<dyn std::error::Error>> {
+        let config = VirtualHostConfig::builder()
+            .hostname("localhost")
+            .root_directory("src/tests")
+            .build()?;
+
+        let mut virtual_host = VirtualHost::new(config);
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/slow")
+                .handler(handler_fn(|_request| async move {
+                    crate::utils::time::sleep(Duration::from_millis(50)).await;
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .header("server-timing", http::HeaderValue::from_static("db;dur=1"))
+                        .text("Slow"))
+                }))
+                .build()?,
+        );
+        virtual_host.add_response_hook(ServerTiming::new("app"));
+
+        let response = virtual_host
+            .dispatch(get("/slow")?)
+            .await?;
+
+        // The handler's own entry is kept, the hook's is appended after it
+        let timings: Vec<&str> = response
+            .headers()
+            .get_all("server-timing")
+            .iter()
+            .map(|value| value.to_str())
+            .collect::<Result<_, _>>()?;
+        assert_eq!(timings.len(), 2, "{:?}", timings);
+        assert_eq!(timings[0], "db;dur=1");
+
+        let duration: f64 = timings[1]
+            .strip_prefix("app;dur=")
+            .ok_or("missing app metric")?
+            .parse()?;
+        assert!((50.0..10_000.0).contains(&duration), "{}", duration);
+
+        // Responses served without reaching a path go through the hooks too
+        let response = virtual_host
+            .dispatch(get("/missing")?)
+            .await?;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(response
+            .headers()
+            .contains_key("server-timing"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_server_timing() -> Result<(), Box<dyn std::error::Error>> {
+        do_server_timing().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_server_timing() -> Result<(), Box<dyn std::error::Error>> {
+        do_server_timing().await
+    }
+
+    fn request(method: http::Method, uri: &str) -> Result<Request, http::Error> {
+        let request = http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(HttpBody::from_text(""))?;
+
+        let (parts, body) = request.into_parts();
+        Ok(Request::from_parts(parts, body))
+    }
+
+    fn method_echo_virtual_host(config: VirtualHostConfig) -> VirtualHost {
+        VirtualHost::with_handler(
+            config,
+            handler_fn(|request: Request| async move {
+                Ok(crate::server::http::Response::builder()
+                    .status(StatusCode::OK)
+                    .text(
+                        request
+                            .method()
+                            .as_str(),
+                    ))
+            }),
+        )
+    }
+
+    async fn do_server_methods() -> Result<(), Box<dyn std::error::Error>> {
+        let virtual_host = method_echo_virtual_host(
+            VirtualHostConfig::builder()
+                .hostname("localhost")
+                .root_directory("src/tests")
+                .build()?,
+        );
+
+        let response = virtual_host
+            .route(request(http::Method::OPTIONS, "*")?)
+            .await?
+            .into_inner();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response
+                .headers()
+                .get(http::header::ALLOW),
+            Some(&http::HeaderValue::from_static("GET, HEAD, POST, PUT, PATCH, DELETE, OPTIONS"))
+        );
+
+        // TRACE is blocked by default to prevent Cross-Site Tracing
+        let response = virtual_host
+            .route(request(http::Method::TRACE, "/")?)
+            .await?
+            .into_inner();
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert!(response
+            .headers()
+            .contains_key(http::header::ALLOW));
+
+        // OPTIONS on a path is still answered by the path handler
+        let response = virtual_host
+            .route(request(http::Method::OPTIONS, "/hello")?)
+            .await?
+            .into_inner();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let virtual_host = method_echo_virtual_host(
+            VirtualHostConfig::builder()
+                .hostname("localhost")
+                .root_directory("src/tests")
+                .allowed_methods(&["GET", "OPTIONS"])
+                .allow_trace(true)
+                .build()?,
+        );
+
+        let response = virtual_host
+            .route(request(http::Method::OPTIONS, "*")?)
+            .await?
+            .into_inner();
+        assert_eq!(
+            response
+                .headers()
+                .get(http::header::ALLOW),
+            Some(&http::HeaderValue::from_static("GET, OPTIONS, TRACE"))
+        );
+
+        let (parts, body) = virtual_host
+            .route(request(http::Method::TRACE, "/")?)
+            .await?
+            .into_inner()
+            .into_parts();
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(
+            body.collect()
+                .await?
+                .to_bytes()
+                .as_ref(),
+            b"TRACE"
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_server_methods() -> Result<(), Box<dyn std::error::Error>> {
+        do_server_methods().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_server_methods() -> Result<(), Box<dyn std::error::Error>> {
+        do_server_methods().await
+    }
+
+    async fn do_base_path() -> Result<(), Box<dyn std::error::Error>> {
+        let mut virtual_host = VirtualHost::new(
+            VirtualHostConfig::builder()
+                .hostname("localhost")
+                .root_directory("src/tests")
+                .base_path("/app")
+                .build()?,
+        );
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/hello")
+                .handler(handler_fn(|request: Request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text(request.uri().path()))
+                }))
+                .build()?,
+        );
+
+        let (parts, body) = virtual_host
+            .route(request(http::Method::GET, "/app/hello")?)
+            .await?
+            .into_inner()
+            .into_parts();
+        assert_eq!(parts.status, StatusCode::OK);
+        // Handlers still see the full request path
+        assert_eq!(
+            body.collect()
+                .await?
+                .to_bytes()
+                .as_ref(),
+            b"/app/hello"
+        );
+
+        for uri in ["/hello", "/application/hello", "/app"] {
+            let response = virtual_host
+                .route(request(http::Method::GET, uri)?)
+                .await?
+                .into_inner();
+            assert_eq!(response.status(), StatusCode::NOT_FOUND, "{}", uri);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_base_path() -> Result<(), Box<dyn std::error::Error>> {
+        do_base_path().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_base_path() -> Result<(), Box<dyn std::error::Error>> {
+        do_base_path().await
+    }
+
+    async fn do_unmatched_path_status_page() -> Result<(), Box<dyn std::error::Error>> {
+        let mut virtual_host = VirtualHost::new(
+            VirtualHostConfig::builder()
+                .hostname("localhost")
+                .root_directory("src/tests")
+                .status_pages(std::collections::HashMap::from([(
+                    404,
+                    "files/404.html".to_string(),
+                )]))
+                .build()?,
+        );
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/hello")
+                .handler(handler_fn(|_request: Request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Hello"))
+                }))
+                .build()?,
+        );
+
+        let (parts, body) = virtual_host
+            .route(request(http::Method::GET, "/missing")?)
+            .await?
+            .into_inner()
+            .into_parts();
+        assert_eq!(parts.status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            body.collect()
+                .await?
+                .to_bytes()
+                .as_ref(),
+            std::fs::read("src/tests/files/404.html")?
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_unmatched_path_status_page() -> Result<(), Box<dyn std::error::Error>> {
+        do_unmatched_path_status_page().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_unmatched_path_status_page() -> Result<(), Box<dyn std::error::Error>> {
+        do_unmatched_path_status_page().await
+    }
+
+    /// Reports taking the first request, then holds it until released.
+    struct HoldingHandler {
+        held: std::sync::Mutex<
+            Option<(crossfire::oneshot::TxOneshot<()>, crossfire::oneshot::RxOneshot<()>)>,
+        >,
+    }
+
+    impl Handler for HoldingHandler {
+        fn handle(
+            &self,
+            _request: Request,
+        ) -> Pin<Box<dyn Future<Output = Result<Response, VetisError>> + Send + '_>> {
+            let held = self
+                .held
+                .lock()
+                .unwrap()
+                .take();
+            Box::pin(async move {
+                if let Some((started, release)) = held {
+                    started.send(());
+                    let _ = release.await;
+                }
+                Ok(Response::builder()
+                    .status(StatusCode::OK)
+                    .text("Done"))
+            })
+        }
+    }
+
+    async fn do_max_concurrent_requests() -> Result<(), Box<dyn std::error::Error>> {
+        use crossfire::oneshot;
+
+        let (started_tx, started_rx) = oneshot::oneshot::<()>();
+        let (release_tx, release_rx) = oneshot::oneshot::<()>();
+
+        let mut limited = VirtualHost::new(
+            VirtualHostConfig::builder()
+                .hostname("api.localhost")
+                .root_directory("src/tests")
+                .max_concurrent_requests(1)
+                .build()?,
+        );
+        limited.add_path(
+            HandlerPath::builder()
+                .uri("/")
+                .handler(HoldingHandler {
+                    held: std::sync::Mutex::new(Some((started_tx, release_rx))),
+                })
+                .build()?,
+        );
+        let other = VirtualHost::with_handler(
+            VirtualHostConfig::builder()
+                .hostname("localhost")
+                .root_directory("src/tests")
+                .build()?,
+            handler_fn(|_request: Request| async move {
+                Ok(crate::server::http::Response::builder()
+                    .status(StatusCode::OK)
+                    .text("Done"))
+            }),
+        );
+
+        let first = limited.route(request(http::Method::GET, "/")?);
+        let others = async {
+            started_rx.await?;
+
+            let busy = limited
+                .route(request(http::Method::GET, "/")?)
+                .await?
+                .into_inner();
+            let unaffected = other
+                .route(request(http::Method::GET, "/")?)
+                .await?
+                .into_inner();
+
+            release_tx.send(());
+            Ok::<_, Box<dyn std::error::Error>>((busy, unaffected))
+        };
+
+        let (first, others) = futures_util::future::join(first, others).await;
+        let (busy, unaffected) = others?;
+
+        assert_eq!(
+            first?
+                .into_inner()
+                .status(),
+            StatusCode::OK
+        );
+        assert_eq!(busy.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            busy.headers()
+                .get(http::header::RETRY_AFTER),
+            Some(&http::HeaderValue::from_static("1"))
+        );
+        assert_eq!(unaffected.status(), StatusCode::OK);
+
+        // The slot is given back once the request completes
+        let response = limited
+            .route(request(http::Method::GET, "/")?)
+            .await?
+            .into_inner();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_max_concurrent_requests() -> Result<(), Box<dyn std::error::Error>> {
+        do_max_concurrent_requests().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_max_concurrent_requests() -> Result<(), Box<dyn std::error::Error>> {
+        do_max_concurrent_requests().await
+    }
+
+    async fn do_handler_http_error() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::errors::HttpError;
+
+        let virtual_host = VirtualHost::with_handler(
+            VirtualHostConfig::builder()
+                .hostname("localhost")
+                .root_directory("src/tests")
+                .status_pages(std::collections::HashMap::from([(
+                    403,
+                    "files/404.html".to_string(),
+                )]))
+                .build()?,
+            handler_fn(|_request: Request| async move {
+                Err(HttpError::new(StatusCode::FORBIDDEN)
+                    .header(
+                        http::header::CONTENT_TYPE,
+                        http::HeaderValue::from_static("text/plain"),
+                    )
+                    .body("nope")
+                    .into())
+            }),
+        );
+
+        // The handler's response is sent as is, rather than the status page
+        let (parts, body) = virtual_host
+            .route(request(http::Method::GET, "/")?)
+            .await?
+            .into_inner()
+            .into_parts();
+        assert_eq!(parts.status, StatusCode::FORBIDDEN);
+        assert_eq!(
+            parts
+                .headers
+                .get(http::header::CONTENT_TYPE),
+            Some(&http::HeaderValue::from_static("text/plain"))
+        );
+        assert_eq!(
+            body.collect()
+                .await?
+                .to_bytes()
+                .as_ref(),
+            b"nope"
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_handler_http_error() -> Result<(), Box<dyn std::error::Error>> {
+        do_handler_http_error().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_handler_http_error() -> Result<(), Box<dyn std::error::Error>> {
+        do_handler_http_error().await
+    }
+
+    async fn do_error_status_pages() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::errors::{FileError, HandlerError, VirtualHostError};
+
+        let virtual_host = VirtualHost::with_handler(
+            VirtualHostConfig::builder()
+                .hostname("localhost")
+                .root_directory("src/tests")
+                .build()?,
+            handler_fn(|request: Request| async move {
+                Err(match request.uri().path() {
+                    "/range" => {
+                        VetisError::VirtualHost(VirtualHostError::File(FileError::InvalidRange))
+                    }
+                    "/encoding" => VetisError::UnsupportedEncoding("zstd".to_string()),
+                    "/uri" => VetisError::VirtualHost(VirtualHostError::Handler(
+                        HandlerError::Uri("empty".to_string()),
+                    )),
+                    _ => VetisError::Io("broken pipe".to_string()),
+                })
+            }),
+        );
+
+        // Every error is answered with its status, rather than dropping the connection
+        for (uri, status) in [
+            ("/range", StatusCode::RANGE_NOT_SATISFIABLE),
+            ("/encoding", StatusCode::UNSUPPORTED_MEDIA_TYPE),
+            ("/uri", StatusCode::BAD_REQUEST),
+            ("/io", StatusCode::INTERNAL_SERVER_ERROR),
+        ] {
+            let response = virtual_host
+                .route(request(http::Method::GET, uri)?)
+                .await?
+                .into_inner();
+            assert_eq!(response.status(), status, "{}", uri);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_error_status_pages() -> Result<(), Box<dyn std::error::Error>> {
+        do_error_status_pages().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_error_status_pages() -> Result<(), Box<dyn std::error::Error>> {
+        do_error_status_pages().await
+    }
+
+    async fn do_head_for_handlers() -> Result<(), Box<dyn std::error::Error>> {
+        let virtual_host = method_echo_virtual_host(
+            VirtualHostConfig::builder()
+                .hostname("localhost")
+                .root_directory("src/tests")
+                .build()?,
+        );
+
+        let get = virtual_host
+            .route(request(http::Method::GET, "/hello")?)
+            .await?
+            .into_inner();
+
+        // The handler is invoked as for GET, and its body dropped
+        let (parts, body) = virtual_host
+            .route(request(http::Method::HEAD, "/hello")?)
+            .await?
+            .into_inner()
+            .into_parts();
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(
+            parts
+                .headers
+                .get(http::header::CONTENT_LENGTH),
+            Some(&http::HeaderValue::from(3))
+        );
+        assert_eq!(
+            parts
+                .headers
+                .get(http::header::CONTENT_TYPE),
+            get.headers()
+                .get(http::header::CONTENT_TYPE)
+        );
+        assert!(body
+            .collect()
+            .await?
+            .to_bytes()
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_head_for_handlers() -> Result<(), Box<dyn std::error::Error>> {
+        do_head_for_handlers().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_head_for_handlers() -> Result<(), Box<dyn std::error::Error>> {
+        do_head_for_handlers().await
+    }
+
+    struct CountingHandler {
+        hits: Arc<AtomicUsize>,
+    }
+
+    impl Handler for CountingHandler {
+        fn handle(
+            &self,
+            _request: Request,
+        ) -> Pin<Box<dyn Future<Output = Result<Response, VetisError>> + Send + '_>> {
+            Box::pin(async move {
+                let hits = self
+                    .hits
+                    .fetch_add(1, Ordering::SeqCst)
+                    + 1;
+                Ok(Response::builder()
+                    .status(StatusCode::OK)
+                    .text(&hits.to_string()))
+            })
+        }
+    }
+
+    async fn do_struct_handler() -> Result<(), Box<dyn std::error::Error>> {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let boxed: Box<dyn Handler> = Box::new(CountingHandler { hits: hits.clone() });
+
+        let mut virtual_host = VirtualHost::new(
+            VirtualHostConfig::builder()
+                .hostname("localhost")
+                .root_directory("src/tests")
+                .build()?,
+        );
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/hits")
+                .handler(CountingHandler { hits: hits.clone() })
+                .build()?,
+        );
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/boxed")
+                .handler(boxed)
+                .build()?,
+        );
+
+        for (uri, expected) in [("/hits", b"1"), ("/hits", b"2"), ("/boxed", b"3")] {
+            let (parts, body) = virtual_host
+                .route(request(http::Method::GET, uri)?)
+                .await?
+                .into_inner()
+                .into_parts();
+            assert_eq!(parts.status, StatusCode::OK);
+            assert_eq!(
+                body.collect()
+                    .await?
+                    .to_bytes()
+                    .as_ref(),
+                expected
+            );
+        }
+
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_struct_handler() -> Result<(), Box<dyn std::error::Error>> {
+        do_struct_handler().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_struct_handler() -> Result<(), Box<dyn std::error::Error>> {
+        do_struct_handler().await
+    }
+
+    async fn do_request_counters() -> Result<(), Box<dyn std::error::Error>> {
+        let mut virtual_host = VirtualHost::new(
+            VirtualHostConfig::builder()
+                .hostname("localhost")
+                .root_directory("src/tests")
+                .build()?,
+        );
+        virtual_host.add_path(
+            HandlerPath::builder()
+                .uri("/hello")
+                .handler(handler_fn(|_request: Request| async move {
+                    Ok(crate::server::http::Response::builder()
+                        .status(StatusCode::OK)
+                        .text("Hello"))
+                }))
+                .build()?,
+        );
+
+        for _ in 0..5 {
+            virtual_host
+                .route(request(http::Method::GET, "/hello")?)
+                .await?;
+        }
+
+        let response = virtual_host
+            .route(request(http::Method::GET, "/missing")?)
+            .await?
+            .into_inner();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        assert_eq!(virtual_host.path_request_count("/hello"), Some(5));
+        assert_eq!(virtual_host.path_request_count("/missing"), None);
+        assert_eq!(virtual_host.request_count(), 6);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_request_counters() -> Result<(), Box<dyn std::error::Error>> {
+        do_request_counters().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_request_counters() -> Result<(), Box<dyn std::error::Error>> {
+        do_request_counters().await
+    }
+
+    async fn do_redirect_path() -> Result<(), Box<dyn std::error::Error>> {
+        let mut virtual_host = VirtualHost::new(
+            VirtualHostConfig::builder()
+                .hostname("localhost")
+                .root_directory("src/tests")
+                .build()?,
+        );
+        virtual_host.add_path(
+            RedirectPath::builder()
+                .uri("/old/*")
+                .target("/new/*")
+                .build()?,
+        );
+        virtual_host.add_path(
+            RedirectPath::builder()
+                .uri("/docs")
+                .target("https://docs.rs/vetis")
+                .status(StatusCode::FOUND)
+                .build()?,
+        );
+
+        // The matched suffix and query are carried over to the new location
+        let response = virtual_host
+            .route(request(http::Method::GET, "/old/guide/intro.html?lang=en")?)
+            .await?
+            .into_inner();
+        assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(
+            response
+                .headers()
+                .get(http::header::LOCATION),
+            Some(&http::HeaderValue::from_static("/new/guide/intro.html?lang=en"))
+        );
+
+        let response = virtual_host
+            .route(request(http::Method::GET, "/old")?)
+            .await?
+            .into_inner();
+        assert_eq!(
+            response
+                .headers()
+                .get(http::header::LOCATION),
+            Some(&http::HeaderValue::from_static("/new/"))
+        );
+
+        // Targets without a wildcard are sent as they are
+        let response = virtual_host
+            .route(request(http::Method::GET, "/docs/latest?q=path")?)
+            .await?
+            .into_inner();
+        assert_eq!(response.status(), StatusCode::FOUND);
+        assert_eq!(
+            response
+                .headers()
+                .get(http::header::LOCATION),
+            Some(&http::HeaderValue::from_static("https://docs.rs/vetis"))
+        );
+
+        assert!(RedirectPath::builder()
+            .uri("/old")
+            .target("/new")
+            .status(StatusCode::OK)
+            .build()
+            .is_err());
+        assert!(RedirectPath::builder()
+            .uri("/old")
+            .build()
+            .is_err());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_redirect_path() -> Result<(), Box<dyn std::error::Error>> {
+        do_redirect_path().await
+    }
+
+    #[cfg(feature = "smol-rt")]
+    #[apply(test!)]
+    async fn test_redirect_path() -> Result<(), Box<dyn std::error::Error>> {
+        do_redirect_path().await
+    }
 }
@@ -0,0 +1,39 @@
+use http::HeaderValue;
+
+/// Returns whether `c` can appear as is in a quoted `filename` parameter.
+fn is_plain_char(c: char) -> bool {
+    c == ' ' || (c.is_ascii_graphic() && c != '"' && c != '\\')
+}
+
+/// Builds a `content-disposition: attachment` header value suggesting `filename`.
+///
+/// Names that aren't plain printable ASCII get an ASCII `filename` fallback
+/// plus an RFC 5987 encoded `filename*=UTF-8''...` parameter.
+pub fn attachment(filename: &str) -> HeaderValue {
+    let is_plain = filename
+        .chars()
+        .all(is_plain_char);
+
+    let value = if is_plain {
+        format!("attachment; filename=\"{}\"", filename)
+    } else {
+        let fallback: String = filename
+            .chars()
+            .map(|c| if is_plain_char(c) { c } else { '_' })
+            .collect();
+
+        let mut encoded = String::new();
+        for byte in filename.bytes() {
+            if byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte) {
+                encoded.push(byte as char);
+            } else {
+                encoded.push_str(&format!("%{:02X}", byte));
+            }
+        }
+
+        format!("attachment; filename=\"{}\"; filename*=UTF-8''{}", fallback, encoded)
+    };
+
+    // Every character is printable ASCII at this point
+    HeaderValue::from_str(&value).unwrap_or_else(|_| HeaderValue::from_static("attachment"))
+}
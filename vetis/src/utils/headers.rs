@@ -0,0 +1,62 @@
+use http::{header::HeaderName, HeaderValue};
+
+/// Returns whether `byte` is a control character other than horizontal tab.
+fn is_control(byte: u8) -> bool {
+    (byte < b' ' && byte != b'\t') || byte == 0x7f
+}
+
+/// Returns whether a header value is free of control characters.
+///
+/// CR and LF in a value would let it end the header line early and inject
+/// headers, or a whole response, of its own.
+pub(crate) fn is_safe_value(value: &[u8]) -> bool {
+    !value
+        .iter()
+        .copied()
+        .any(is_control)
+}
+
+/// Builds a header from configured strings, rejecting values with control characters.
+///
+/// # Arguments
+///
+/// * `key` - The header name
+/// * `value` - The header value
+///
+/// # Returns
+///
+/// * `Option<(HeaderName, HeaderValue)>` - The header, or `None` if the name or value is invalid.
+pub(crate) fn parse_header(key: &str, value: &str) -> Option<(HeaderName, HeaderValue)> {
+    if !is_safe_value(value.as_bytes()) {
+        return None;
+    }
+
+    let name = HeaderName::from_bytes(key.as_bytes()).ok()?;
+    let value = HeaderValue::from_str(value).ok()?;
+    Some((name, value))
+}
+
+#[cfg(feature = "reverse-proxy")]
+/// Removes every header with a value holding control characters.
+///
+/// # Arguments
+///
+/// * `headers` - The headers to sanitize
+///
+/// # Returns
+///
+/// * `Vec<HeaderName>` - The names of the removed headers.
+pub(crate) fn strip_unsafe_values(headers: &mut http::HeaderMap) -> Vec<HeaderName> {
+    let mut unsafe_names: Vec<HeaderName> = Vec::new();
+    for (name, value) in headers.iter() {
+        if !is_safe_value(value.as_bytes()) && !unsafe_names.contains(name) {
+            unsafe_names.push(name.clone());
+        }
+    }
+
+    for name in &unsafe_names {
+        headers.remove(name);
+    }
+
+    unsafe_names
+}
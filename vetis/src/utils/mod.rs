@@ -1 +1,4 @@
 pub(crate) mod date;
+pub(crate) mod disposition;
+pub(crate) mod headers;
+pub(crate) mod time;
@@ -0,0 +1,25 @@
+use std::{future::Future, pin::pin, time::Duration};
+
+use futures_util::future::{select, Either};
+
+/// Waits for the given duration on the active runtime.
+pub async fn sleep(duration: Duration) {
+    #[cfg(feature = "tokio-rt")]
+    tokio::time::sleep(duration).await;
+
+    #[cfg(feature = "smol-rt")]
+    smol::Timer::after(duration).await;
+}
+
+/// Runs a future, giving up once the duration elapses.
+///
+/// Returns `None` if the future did not complete in time.
+pub async fn timeout<F>(duration: Duration, future: F) -> Option<F::Output>
+where
+    F: Future,
+{
+    match select(pin!(future), pin!(sleep(duration))).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(_) => None,
+    }
+}